@@ -0,0 +1,165 @@
+//! Arithmetic of the AES field, and the S-box and MixColumns built on it.
+//!
+//! AES reduces modulo `x^8 + x^4 + x^3 + x + 1` (`0x11b`) rather than this
+//! crate's `0x11d`. The functions here take and return bytes in the AES
+//! representation and compute in [`Galois`] through the isomorphism between
+//! the two fields.
+//!
+//! A state is 16 bytes in the order of the standard, column by column.
+
+use std::sync::OnceLock;
+
+use crate::isomorphism::FieldMap;
+use crate::Galois;
+
+/// The AES field polynomial, with its `x^8` term.
+pub const AES_POLYNOMIAL: u16 = 0x11b;
+
+const AFFINE_CONSTANT: u8 = 0x63;
+const MIX: [u8; 4] = [0x02, 0x03, 0x01, 0x01];
+const INV_MIX: [u8; 4] = [0x0e, 0x0b, 0x0d, 0x09];
+
+// forward: AES representation -> crate representation
+fn field() -> &'static FieldMap {
+    static FIELD: OnceLock<FieldMap> = OnceLock::new();
+    FIELD.get_or_init(|| FieldMap::new(AES_POLYNOMIAL).expect("0x11b is irreducible"))
+}
+
+/// The product of two elements of the AES field.
+pub fn mul(a: u8, b: u8) -> u8 {
+    let f = field();
+    f.inverse(u8::from(
+        Galois::new(f.forward(a)) * Galois::new(f.forward(b)),
+    ))
+}
+
+/// The multiplicative inverse in the AES field, with zero mapped to zero
+/// as the S-box requires.
+pub fn inv(a: u8) -> u8 {
+    if a == 0 {
+        return 0;
+    }
+    let f = field();
+    f.inverse(u8::from(Galois::new(f.forward(a)).inv()))
+}
+
+/// The affine transform over GF(2) that follows the inversion in the
+/// S-box: `b ^ rotl(b, 1) ^ rotl(b, 2) ^ rotl(b, 3) ^ rotl(b, 4) ^ 0x63`.
+pub fn affine(b: u8) -> u8 {
+    b ^ b.rotate_left(1) ^ b.rotate_left(2) ^ b.rotate_left(3) ^ b.rotate_left(4) ^ AFFINE_CONSTANT
+}
+
+/// The inverse of [`affine`]: `rotl(s, 1) ^ rotl(s, 3) ^ rotl(s, 6) ^ 0x05`.
+pub fn inv_affine(s: u8) -> u8 {
+    s.rotate_left(1) ^ s.rotate_left(3) ^ s.rotate_left(6) ^ 0x05
+}
+
+/// The AES S-box applied to one byte.
+pub fn sub_byte(b: u8) -> u8 {
+    affine(inv(b))
+}
+
+/// The inverse S-box applied to one byte.
+pub fn inv_sub_byte(s: u8) -> u8 {
+    inv(inv_affine(s))
+}
+
+/// The full S-box table.
+pub fn sbox() -> [u8; 256] {
+    std::array::from_fn(|b| sub_byte(b as u8))
+}
+
+/// The full inverse S-box table.
+pub fn inv_sbox() -> [u8; 256] {
+    std::array::from_fn(|s| inv_sub_byte(s as u8))
+}
+
+/// Multiplies `column` by the circulant matrix whose first row is `row`,
+/// each row being the one above rotated right by one.
+pub fn circulant(row: [u8; 4], column: [u8; 4]) -> [u8; 4] {
+    std::array::from_fn(|i| (0..4).fold(0, |acc, j| acc ^ mul(row[(j + 4 - i) % 4], column[j])))
+}
+
+/// MixColumns on one column.
+pub fn mix_column(column: &mut [u8; 4]) {
+    *column = circulant(MIX, *column);
+}
+
+/// InvMixColumns on one column.
+pub fn inv_mix_column(column: &mut [u8; 4]) {
+    *column = circulant(INV_MIX, *column);
+}
+
+/// MixColumns on every column of a state.
+pub fn mix_columns(state: &mut [u8; 16]) {
+    for chunk in state.chunks_exact_mut(4) {
+        mix_column(chunk.try_into().unwrap());
+    }
+}
+
+/// InvMixColumns on every column of a state.
+pub fn inv_mix_columns(state: &mut [u8; 16]) {
+    for chunk in state.chunks_exact_mut(4) {
+        inv_mix_column(chunk.try_into().unwrap());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field() {
+        // the worked example of FIPS 197: {57} * {83} = {c1}
+        assert_eq!(mul(0x57, 0x83), 0xc1);
+        assert_eq!(mul(0x57, 0x13), 0xfe);
+        for a in 1..=255u8 {
+            assert_eq!(mul(a, inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn test_sbox() {
+        let sbox = sbox();
+        let inv_sbox = inv_sbox();
+        // the first row and some entries of the FIPS 197 table
+        assert_eq!(
+            sbox[..16],
+            [
+                0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7,
+                0xab, 0x76
+            ]
+        );
+        assert_eq!(sbox[0x53], 0xed);
+        assert_eq!(sbox[0xff], 0x16);
+        assert_eq!(inv_sbox[0x00], 0x52);
+        for b in 0..=255u8 {
+            assert_eq!(inv_sbox[sbox[b as usize] as usize], b);
+            assert_eq!(inv_affine(affine(b)), b);
+        }
+    }
+
+    #[test]
+    fn test_mix_columns() {
+        let mut column = [0xdb, 0x13, 0x53, 0x45];
+        mix_column(&mut column);
+        assert_eq!(column, [0x8e, 0x4d, 0xa1, 0xbc]);
+        inv_mix_column(&mut column);
+        assert_eq!(column, [0xdb, 0x13, 0x53, 0x45]);
+
+        // round 1 of the FIPS 197 appendix B example
+        let before = [
+            0xd4, 0xbf, 0x5d, 0x30, 0xe0, 0xb4, 0x52, 0xae, 0xb8, 0x41, 0x11, 0xf1, 0x1e, 0x27,
+            0x98, 0xe5,
+        ];
+        let after = [
+            0x04, 0x66, 0x81, 0xe5, 0xe0, 0xcb, 0x19, 0x9a, 0x48, 0xf8, 0xd3, 0x7a, 0x28, 0x06,
+            0x26, 0x4c,
+        ];
+        let mut state = before;
+        mix_columns(&mut state);
+        assert_eq!(state, after);
+        inv_mix_columns(&mut state);
+        assert_eq!(state, before);
+    }
+}
@@ -0,0 +1,210 @@
+//! Weight distributions, minimum distance and the MDS property of linear
+//! codes over GF(256).
+//!
+//! Every function takes a `k × n` generator matrix whose rows span the
+//! code. The encoding matrix of a [`ReedSolomon`](crate::rs::ReedSolomon)
+//! codec is its transpose, so `rs.matrix().transpose()` is the generator
+//! of the code it stores across its shards.
+//!
+//! The exact functions enumerate the code or all its `k`-column subsets and
+//! are only practical for short codes; the sampled ones give evidence, not
+//! proof, for long ones.
+
+use crate::fountain::SplitMix;
+use crate::{Galois, GfMatrix};
+
+/// The number of codewords of each weight `0..=n`.
+///
+/// Codewords that are scalar multiples share a weight, so this enumerates
+/// about `256^(k-1)` of them, which limits it to `k <= 4` or so.
+pub fn weight_distribution(g: &GfMatrix) -> Vec<u64> {
+    let (k, n) = (g.rows(), g.cols());
+    let mut dist = vec![0u64; n + 1];
+    dist[0] = 1;
+
+    // messages whose first nonzero symbol is a one at `lead`
+    for lead in 0..k {
+        let mut msg = vec![0u8; k - lead - 1];
+        let mut codeword = g.row(lead).to_vec();
+        loop {
+            dist[weight(&codeword)] += 255;
+            // advance the free symbols like an odometer, updating the
+            // codeword by the change in each symbol
+            let Some(i) = msg.iter().position(|m| *m != 255) else {
+                break;
+            };
+            for (j, m) in msg[..=i].iter_mut().enumerate() {
+                let old = Galois::new(*m);
+                *m = if j == i { *m + 1 } else { 0 };
+                let delta = old + Galois::new(*m);
+                for (c, r) in codeword.iter_mut().zip(g.row(lead + 1 + j)) {
+                    *c += delta * *r;
+                }
+            }
+        }
+    }
+    dist
+}
+
+/// The smallest weight of a nonzero codeword, from
+/// [`weight_distribution`], or zero if the rows are dependent.
+pub fn minimum_distance(g: &GfMatrix) -> usize {
+    let dist = weight_distribution(g);
+    if dist[0] > 1 {
+        return 0;
+    }
+    (1..dist.len()).find(|w| dist[*w] > 0).unwrap_or(0)
+}
+
+/// The weights of `samples` random nonzero codewords, drawn from `seed`, as
+/// counts per weight `0..=n`.
+///
+/// The smallest weight seen is an upper bound on the minimum distance.
+pub fn sampled_weight_distribution(g: &GfMatrix, samples: usize, seed: u64) -> Vec<u64> {
+    let (k, n) = (g.rows(), g.cols());
+    let mut rng = SplitMix(seed);
+    let mut dist = vec![0u64; n + 1];
+    for _ in 0..samples {
+        let msg: Vec<Galois> = loop {
+            let msg: Vec<Galois> = (0..k).map(|_| Galois::new(rng.next() as u8)).collect();
+            if msg.iter().any(|m| *m != Galois::zero()) {
+                break msg;
+            }
+        };
+        let mut codeword = vec![Galois::zero(); n];
+        for (m, r) in msg.iter().zip(0..k) {
+            for (c, v) in codeword.iter_mut().zip(g.row(r)) {
+                *c += *m * *v;
+            }
+        }
+        dist[weight(&codeword)] += 1;
+    }
+    dist
+}
+
+/// Whether the code is MDS, i.e. every `k` columns of `g` are linearly
+/// independent and the minimum distance is `n - k + 1`.
+///
+/// This checks all `C(n, k)` subsets.
+pub fn is_mds(g: &GfMatrix) -> bool {
+    let (k, n) = (g.rows(), g.cols());
+    if k == 0 || k > n {
+        return false;
+    }
+    let mut cols: Vec<usize> = (0..k).collect();
+    loop {
+        if rank(&select_cols(g, &cols)) < k {
+            return false;
+        }
+        // the next k-subset in lexicographic order
+        let Some(i) = (0..k).rev().find(|i| cols[*i] < n - k + i) else {
+            return true;
+        };
+        cols[i] += 1;
+        for j in i + 1..k {
+            cols[j] = cols[j - 1] + 1;
+        }
+    }
+}
+
+/// Looks for `k` linearly dependent columns of `g` among `samples` random
+/// subsets drawn from `seed`, returning the first found, which proves the
+/// code is not MDS.
+pub fn find_dependent_columns(g: &GfMatrix, samples: usize, seed: u64) -> Option<Vec<usize>> {
+    let (k, n) = (g.rows(), g.cols());
+    if k > n {
+        return None;
+    }
+    let mut rng = SplitMix(seed);
+    for _ in 0..samples {
+        // a partial Fisher-Yates shuffle picks k distinct columns
+        let mut perm: Vec<usize> = (0..n).collect();
+        for i in 0..k {
+            let j = i + (rng.next() % (n - i) as u64) as usize;
+            perm.swap(i, j);
+        }
+        let mut cols = perm[..k].to_vec();
+        cols.sort_unstable();
+        if rank(&select_cols(g, &cols)) < k {
+            return Some(cols);
+        }
+    }
+    None
+}
+
+fn weight(v: &[Galois]) -> usize {
+    v.iter().filter(|c| **c != Galois::zero()).count()
+}
+
+fn select_cols(g: &GfMatrix, cols: &[usize]) -> GfMatrix {
+    GfMatrix::from_fn(g.rows(), cols.len(), |r, c| g[(r, cols[c])])
+}
+
+fn rank(m: &GfMatrix) -> usize {
+    let mut m = m.clone();
+    let mut rank = 0;
+    for col in 0..m.cols() {
+        let Some(p) = (rank..m.rows()).find(|r| m[(*r, col)] != Galois::zero()) else {
+            continue;
+        };
+        for c in 0..m.cols() {
+            let (a, b) = (m[(rank, c)], m[(p, c)]);
+            m[(rank, c)] = b;
+            m[(p, c)] = a;
+        }
+        let inv = m[(rank, col)].inv();
+        for r in rank + 1..m.rows() {
+            let f = m[(r, col)] * inv;
+            if f != Galois::zero() {
+                for c in col..m.cols() {
+                    let v = m[(rank, c)];
+                    m[(r, c)] -= f * v;
+                }
+            }
+        }
+        rank += 1;
+    }
+    rank
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rs::ReedSolomon;
+
+    #[test]
+    fn test_mds_weight_distribution() {
+        // an MDS [5, 2] code has A_4 = 5 * 255 and A_5 = 256^2 - 1 - A_4
+        let g = ReedSolomon::new(2, 3).unwrap().matrix().transpose();
+        assert_eq!(weight_distribution(&g), vec![1, 0, 0, 0, 1275, 64260]);
+        assert_eq!(minimum_distance(&g), 4);
+        assert!(is_mds(&g));
+        assert_eq!(find_dependent_columns(&g, 100, 1), None);
+
+        let g = ReedSolomon::new(3, 4).unwrap().matrix().transpose();
+        let dist = weight_distribution(&g);
+        assert_eq!(dist.iter().sum::<u64>(), 1 << 24);
+        assert_eq!(minimum_distance(&g), 5);
+        let sampled = sampled_weight_distribution(&g, 1000, 3);
+        assert_eq!(sampled.iter().sum::<u64>(), 1000);
+        assert!(sampled[..5].iter().all(|c| *c == 0));
+    }
+
+    #[test]
+    fn test_not_mds() {
+        // columns 1 and 3 are equal, so they are dependent for k = 2 and the
+        // difference of their unit messages has weight below n - k + 1
+        let g = GfMatrix::from_fn(2, 6, |r, c| {
+            let point = Galois::new([1, 2, 3, 2, 5, 6][c]);
+            point.exp(r as u32)
+        });
+        assert!(!is_mds(&g));
+        assert_eq!(find_dependent_columns(&g, 1000, 2), Some(vec![1, 3]));
+        assert_eq!(minimum_distance(&g), 4);
+
+        // dependent rows give a zero codeword
+        let g = GfMatrix::from_fn(2, 4, |_, c| Galois::new(c as u8 + 1));
+        assert_eq!(minimum_distance(&g), 0);
+        assert!(!is_mds(&g));
+    }
+}
@@ -0,0 +1,206 @@
+//! AONT-RS: dispersal that keeps data secret from anyone holding fewer
+//! than `k` shards.
+//!
+//! Following Resch and Plank, the data is first put through an
+//! all-or-nothing transform: it is encrypted under a random key, and the key
+//! is appended masked by the hash of the ciphertext. Recovering the key
+//! needs the whole package, so the `k` data shards of a
+//! [`ReedSolomon`] code over it, or any `k` of its shards, and without it
+//! the ciphertext reveals nothing useful. A zero canary inside the
+//! encryption detects a package that does not decrypt.
+//!
+//! The cipher is SHA-256 in counter mode, and SHA-256 is also the hash.
+//! Unlike Shamir sharing, the secrecy is computational, but the shards are
+//! only `1 / k` of the data each.
+
+use std::fmt;
+
+use crate::rs::{self, ReedSolomon};
+
+mod sha256;
+use sha256::sha256;
+
+const KEY_LEN: usize = 32;
+const CANARY_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AontError {
+    /// The shards could not be decoded.
+    Codec(rs::Error),
+    /// The decoded package failed its integrity check.
+    Corrupt,
+}
+
+impl fmt::Display for AontError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AontError::Codec(e) => write!(f, "{e}"),
+            AontError::Corrupt => write!(f, "package failed integrity check"),
+        }
+    }
+}
+
+impl std::error::Error for AontError {}
+
+impl From<rs::Error> for AontError {
+    fn from(e: rs::Error) -> Self {
+        AontError::Codec(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AontRs {
+    codec: ReedSolomon,
+}
+
+impl AontRs {
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<Self, rs::Error> {
+        Ok(AontRs {
+            codec: ReedSolomon::new(data_shards, parity_shards)?,
+        })
+    }
+
+    pub fn codec(&self) -> &ReedSolomon {
+        &self.codec
+    }
+
+    /// Transforms `data` under a key drawn from `rng` and returns all
+    /// `k + m` shards, data first.
+    ///
+    /// The package is the data length as a little-endian `u64`, the data,
+    /// the canary and zero padding, encrypted, followed by the masked key,
+    /// in all `8 + len + 16 + 32` bytes rounded up to a multiple of `k`.
+    pub fn encode<R>(&self, data: &[u8], mut rng: R) -> Vec<Vec<u8>>
+    where
+        R: FnMut(&mut [u8]),
+    {
+        let k = self.codec.data_shards();
+        let shard_len = (8 + data.len() + CANARY_LEN + KEY_LEN).div_ceil(k);
+        let mut package = vec![0u8; k * shard_len];
+        let body_len = package.len() - KEY_LEN;
+        package[..8].copy_from_slice(&(data.len() as u64).to_le_bytes());
+        package[8..8 + data.len()].copy_from_slice(data);
+
+        let mut key = [0u8; KEY_LEN];
+        rng(&mut key);
+        let (body, tail) = package.split_at_mut(body_len);
+        apply_keystream(&key, body);
+        let mask = sha256(&[body]);
+        for ((t, k), m) in tail.iter_mut().zip(key).zip(mask) {
+            *t = k ^ m;
+        }
+
+        let mut shards = self.codec.split(&package);
+        let data_refs: Vec<&[u8]> = shards[..k].iter().map(|s| &s[..]).collect();
+        let parity = self
+            .codec
+            .encode(&data_refs)
+            .expect("split shards match the codec");
+        shards.truncate(k);
+        shards.extend(parity);
+        shards
+    }
+
+    /// Recovers the data from any `k` of the shards, given in order with the
+    /// missing ones `None`. Missing data shards are filled in.
+    pub fn decode(&self, shards: &mut [Option<Vec<u8>>]) -> Result<Vec<u8>, AontError> {
+        let k = self.codec.data_shards();
+        let wanted: Vec<usize> = (0..k).collect();
+        self.codec.reconstruct_only(shards, &wanted)?;
+        let mut package: Vec<u8> = shards[..k]
+            .iter()
+            .flat_map(|s| s.as_deref().unwrap_or_default())
+            .copied()
+            .collect();
+        if package.len() < 8 + CANARY_LEN + KEY_LEN {
+            return Err(AontError::Corrupt);
+        }
+
+        let body_len = package.len() - KEY_LEN;
+        let (body, tail) = package.split_at_mut(body_len);
+        let mask = sha256(&[body]);
+        let mut key = [0u8; KEY_LEN];
+        for ((k, t), m) in key.iter_mut().zip(tail).zip(mask) {
+            *k = *t ^ m;
+        }
+        apply_keystream(&key, body);
+
+        let len = u64::from_le_bytes(body[..8].try_into().unwrap());
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_add(8 + CANARY_LEN))
+            .filter(|end| *end <= body_len)
+            .ok_or(AontError::Corrupt)?;
+        if body[end - CANARY_LEN..end].iter().any(|b| *b != 0) {
+            return Err(AontError::Corrupt);
+        }
+        Ok(body[8..end - CANARY_LEN].to_vec())
+    }
+}
+
+// XORs `data` with SHA-256(key || counter) for counter = 0, 1, ...
+fn apply_keystream(key: &[u8; KEY_LEN], data: &mut [u8]) {
+    for (i, chunk) in data.chunks_mut(32).enumerate() {
+        let block = sha256(&[key, &(i as u64).to_le_bytes()]);
+        for (d, b) in chunk.iter_mut().zip(block) {
+            *d ^= b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::tests::test_rng;
+
+    #[test]
+    fn test_round_trip() {
+        let aont = AontRs::new(4, 2).unwrap();
+        for len in [0usize, 1, 7, 100, 1000] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 37 + 11) as u8).collect();
+            let shards = aont.encode(&data, test_rng(len as u64));
+            assert_eq!(shards.len(), 6);
+            assert_eq!(shards[0].len(), (len + 56).div_ceil(4));
+
+            let mut received: Vec<Option<Vec<u8>>> = shards.into_iter().map(Some).collect();
+            received[0] = None;
+            received[3] = None;
+            assert_eq!(aont.decode(&mut received).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_hides_data() {
+        let aont = AontRs::new(3, 1).unwrap();
+        let data = vec![b'A'; 300];
+        let first = aont.encode(&data, test_rng(1));
+        let second = aont.encode(&data, test_rng(2));
+        assert_ne!(first, second);
+        for shard in &first {
+            assert!(!shard.windows(4).any(|w| w == b"AAAA"));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let aont = AontRs::new(3, 2).unwrap();
+        let shards = aont.encode(b"integrity", test_rng(3));
+
+        // a changed byte anywhere in the package scrambles the key
+        for shard in 0..3 {
+            let mut received: Vec<Option<Vec<u8>>> = shards.iter().cloned().map(Some).collect();
+            received[shard].as_mut().unwrap()[1] ^= 4;
+            assert_eq!(aont.decode(&mut received), Err(AontError::Corrupt));
+        }
+
+        let mut received: Vec<Option<Vec<u8>>> = shards.into_iter().map(Some).collect();
+        received[0] = None;
+        received[2] = None;
+        received[4] = None;
+        assert_eq!(
+            aont.decode(&mut received),
+            Err(AontError::Codec(rs::Error::TooFewShards))
+        );
+        assert_eq!(AontRs::new(0, 2), Err(rs::Error::InvalidShardCount));
+    }
+}
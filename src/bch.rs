@@ -0,0 +1,241 @@
+//! Binary BCH codes of length up to 255.
+//!
+//! A narrow-sense primitive BCH code of designed distance `d` is the binary
+//! cyclic code of length 255 whose generator has the roots `α^1 .. α^(d-1)`
+//! in GF(256). Its generator is the product of the minimal polynomials of
+//! those roots, one per cyclotomic coset `{i, 2i, 4i, ..} mod 255`, so it
+//! has binary coefficients even though the roots do not. Shorter codes are
+//! the full one shortened at the front, as with [`RsCode::shortened`].
+//!
+//! Codewords hold one bit per byte, each 0 or 1: the message bits followed
+//! by the parity bits, with the first bit as the highest-degree coefficient.
+//!
+//! [`RsCode::shortened`]: crate::rs::RsCode::shortened
+
+use crate::lfsr::berlekamp_massey;
+use crate::poly::Poly;
+use crate::rs::CodeError;
+use crate::Galois;
+
+/// A binary BCH(n, k) code that corrects up to `(d - 1) / 2` bit errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BchCode {
+    n: usize,
+    k: usize,
+    distance: usize,
+    generator: Poly,
+}
+
+impl BchCode {
+    /// The length-255 code of designed distance `distance`, shortened to
+    /// `n` bits.
+    pub fn new(n: usize, distance: usize) -> Result<Self, CodeError> {
+        if n > 255 || !(2..=255).contains(&distance) {
+            return Err(CodeError::InvalidParameters);
+        }
+
+        let mut exponents: Vec<usize> = Vec::new();
+        for i in 1..distance {
+            if !exponents.contains(&i) {
+                exponents.extend(cyclotomic_coset(i));
+            }
+        }
+        let roots: Vec<Galois> = exponents.iter().map(|e| alpha(*e)).collect();
+        let generator = Poly::from_roots(&roots);
+        debug_assert!(generator.coeffs().iter().all(|c| u8::from(*c) <= 1));
+
+        if roots.len() >= n {
+            return Err(CodeError::InvalidParameters);
+        }
+        Ok(BchCode {
+            n,
+            k: n - roots.len(),
+            distance,
+            generator,
+        })
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn parity_len(&self) -> usize {
+        self.n - self.k
+    }
+
+    /// The designed distance.
+    pub fn distance(&self) -> usize {
+        self.distance
+    }
+
+    /// The number of bit errors the code can always correct.
+    pub fn capability(&self) -> usize {
+        (self.distance - 1) / 2
+    }
+
+    /// The generator polynomial, whose coefficients are all 0 or 1.
+    pub fn generator(&self) -> &Poly {
+        &self.generator
+    }
+
+    /// Returns the codeword for the `k` bits of `msg`: the message followed
+    /// by its parity.
+    pub fn encode(&self, msg: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if msg.len() != self.k {
+            return Err(CodeError::WrongLength);
+        }
+        let mut codeword = vec![0u8; self.n];
+        codeword[..self.k].copy_from_slice(msg);
+        self.encode_parity(msg, &mut codeword[self.k..])?;
+        Ok(codeword)
+    }
+
+    /// Computes the parity bits for `msg` as the remainder of
+    /// `msg(x) * x^(n-k)` divided by the generator.
+    pub fn encode_parity(&self, msg: &[u8], parity: &mut [u8]) -> Result<(), CodeError> {
+        if msg.len() != self.k || parity.len() != self.parity_len() {
+            return Err(CodeError::WrongLength);
+        }
+
+        // the same LFSR as `RsCode`, with every tap 0 or 1
+        let g = self.generator.coeffs();
+        let p = parity.len();
+        parity.fill(0);
+        for m in msg {
+            let feedback = (m ^ parity[0]) & 1;
+            parity.copy_within(1.., 0);
+            parity[p - 1] = 0;
+            if feedback == 1 {
+                for (j, r) in parity.iter_mut().enumerate() {
+                    *r ^= u8::from(g[p - 1 - j]);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `S_j = r(α^j)` for `j in 1..d`.
+    pub fn syndromes(&self, codeword: &[u8]) -> Vec<Galois> {
+        (1..self.distance)
+            .map(|j| {
+                let x = alpha(j);
+                codeword
+                    .iter()
+                    .fold(Galois::zero(), |acc, c| acc * x + Galois::new(*c & 1))
+            })
+            .collect()
+    }
+
+    /// Corrects `codeword` in place, returning the number of bits flipped.
+    pub fn decode(&self, codeword: &mut [u8]) -> Result<usize, CodeError> {
+        if codeword.len() != self.n {
+            return Err(CodeError::WrongLength);
+        }
+        let syndromes = self.syndromes(codeword);
+        if syndromes.iter().all(|s| *s == Galois::zero()) {
+            return Ok(0);
+        }
+
+        let locator = berlekamp_massey(&syndromes);
+        let degree = locator.degree().unwrap_or(0);
+        if degree > self.capability() {
+            return Err(CodeError::TooManyErrors);
+        }
+
+        // Chien search: bit `i` has the locator `X = α^(n-1-i)`, and in a
+        // binary code every located error is a flip
+        let positions: Vec<usize> = (0..self.n)
+            .filter(|i| locator.eval(alpha(255 - (self.n - 1 - i))) == Galois::zero())
+            .collect();
+        if positions.len() != degree {
+            return Err(CodeError::TooManyErrors);
+        }
+        for pos in &positions {
+            codeword[*pos] ^= 1;
+        }
+        Ok(positions.len())
+    }
+}
+
+// α^e for the crate's primitive element α = 2.
+fn alpha(e: usize) -> Galois {
+    Galois::new(2).exp((e % 255) as u32)
+}
+
+// The exponents of the conjugates of α^s, `{s, 2s, 4s, ..} mod 255`.
+fn cyclotomic_coset(s: usize) -> Vec<usize> {
+    let mut coset = vec![s % 255];
+    let mut e = 2 * s % 255;
+    while e != coset[0] {
+        coset.push(e);
+        e = 2 * e % 255;
+    }
+    coset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(k: usize, seed: usize) -> Vec<u8> {
+        (0..k)
+            .map(|i| ((i * 37 + seed * 11) >> 3) as u8 & 1)
+            .collect()
+    }
+
+    #[test]
+    fn test_parameters() {
+        // the standard primitive BCH codes of length 255
+        for (distance, k) in [(3, 247), (5, 239), (7, 231), (17, 191), (45, 107)] {
+            let code = BchCode::new(255, distance).unwrap();
+            assert_eq!(code.k(), k, "d={distance}");
+            assert!(code.generator().coeffs().iter().all(|c| u8::from(*c) <= 1));
+        }
+        // the designed distance 4 code is the distance 5 code
+        assert_eq!(BchCode::new(255, 4).unwrap().k(), 239);
+        assert_eq!(BchCode::new(16, 7), Err(CodeError::InvalidParameters));
+        assert_eq!(BchCode::new(256, 3), Err(CodeError::InvalidParameters));
+    }
+
+    #[test]
+    fn test_correct_errors() {
+        for (n, distance) in [(255, 17), (255, 5), (100, 9), (40, 7)] {
+            let code = BchCode::new(n, distance).unwrap();
+            let t = code.capability();
+            for seed in 0..6 {
+                let original = code.encode(&message(code.k(), seed)).unwrap();
+                assert!(code
+                    .syndromes(&original)
+                    .iter()
+                    .all(|s| *s == Galois::zero()));
+                for errors in 0..=t {
+                    let mut received = original.clone();
+                    for e in 0..errors {
+                        received[(seed * 13 + e * (n / t)) % n] ^= 1;
+                    }
+                    assert_eq!(code.decode(&mut received), Ok(errors));
+                    assert_eq!(received, original);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_too_many_errors() {
+        let code = BchCode::new(63, 5).unwrap();
+        let original = code.encode(&message(code.k(), 1)).unwrap();
+        let mut received = original.clone();
+        for pos in [3, 20, 41] {
+            received[pos] ^= 1;
+        }
+        // no codeword is within two bits of this word
+        let corrupt = received.clone();
+        assert_eq!(code.decode(&mut received), Err(CodeError::TooManyErrors));
+        assert_eq!(received, corrupt);
+        assert_eq!(code.decode(&mut [0u8; 62]), Err(CodeError::WrongLength));
+    }
+}
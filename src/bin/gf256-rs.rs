@@ -0,0 +1,99 @@
+//! Protects files with Reed-Solomon shard files.
+//!
+//! ```text
+//! gf256-rs split --data 4 --parity 2 [--block 65536] [--out DIR] FILE
+//! gf256-rs join [--out FILE] MANIFEST
+//! ```
+
+use std::fs::File;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use gf256_lite::rs::{decode_file, encode_file, Manifest};
+
+const USAGE: &str = "usage:
+  gf256-rs split --data K --parity M [--block BYTES] [--out DIR] FILE
+  gf256-rs join [--out FILE] MANIFEST";
+
+struct Args {
+    data: Option<usize>,
+    parity: Option<usize>,
+    block: usize,
+    out: Option<PathBuf>,
+    path: PathBuf,
+}
+
+fn parse(args: &[String]) -> Result<Args, String> {
+    let mut parsed = Args {
+        data: None,
+        parity: None,
+        block: 64 * 1024,
+        out: None,
+        path: PathBuf::new(),
+    };
+    let mut path = None;
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        let mut value = || it.next().ok_or(format!("{arg} needs a value"));
+        let number = |v: &String| v.parse().map_err(|_| format!("bad value for {arg}: {v}"));
+        match arg.as_str() {
+            "--data" => parsed.data = Some(number(value()?)?),
+            "--parity" => parsed.parity = Some(number(value()?)?),
+            "--block" => parsed.block = number(value()?)?,
+            "--out" => parsed.out = Some(value()?.into()),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+            _ if path.is_none() => path = Some(arg.into()),
+            _ => return Err(format!("unexpected argument {arg}")),
+        }
+    }
+    parsed.path = path.ok_or("missing file argument")?;
+    Ok(parsed)
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let (command, rest) = args.split_first().ok_or(USAGE)?;
+    let args = parse(rest)?;
+    match command.as_str() {
+        "split" => {
+            let (Some(k), Some(m)) = (args.data, args.parity) else {
+                return Err("split needs --data and --parity".into());
+            };
+            if args.block == 0 {
+                return Err("--block must be positive".into());
+            }
+            let out = args.out.unwrap_or_else(|| PathBuf::from("."));
+            let manifest = encode_file(&args.path, &out, k, m, args.block)
+                .map_err(|e| format!("{}: {e}", args.path.display()))?;
+            println!("{}", manifest.manifest_path(&out).display());
+        }
+        "join" => {
+            let out = match args.out {
+                Some(out) => out,
+                None => {
+                    let file = File::open(&args.path)
+                        .map_err(|e| format!("{}: {e}", args.path.display()))?;
+                    let manifest = Manifest::read(file)
+                        .map_err(|e| format!("{}: {e}", args.path.display()))?;
+                    // Manifest::read only accepts a plain file name, which
+                    // stays in the working directory
+                    PathBuf::from(manifest.name)
+                }
+            };
+            decode_file(&args.path, &out).map_err(|e| format!("{}: {e}", args.path.display()))?;
+            println!("{}", out.display());
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("gf256-rs: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
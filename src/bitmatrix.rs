@@ -0,0 +1,402 @@
+use std::ops::Index;
+
+use crate::slice::add_slice;
+use crate::{Galois, GfMatrix};
+
+/// A matrix over GF(2), as produced by [`GfMatrix::to_bitmatrix`].
+///
+/// Each GF(256) element `e` becomes the 8×8 block whose column `x` holds the
+/// bits of `e * 2^x`, so multiplying by the block is the same as multiplying
+/// by `e`. This matches Jerasure's `jerasure_matrix_to_bitmatrix` layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    rows: usize,
+    cols: usize,
+    bits: Vec<bool>,
+}
+
+impl BitMatrix {
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        BitMatrix {
+            rows,
+            cols,
+            bits: vec![false; rows * cols],
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn set(&mut self, r: usize, c: usize, v: bool) {
+        assert!(r < self.rows && c < self.cols, "index out of bounds");
+        self.bits[r * self.cols + c] = v;
+    }
+
+    /// Number of ones in the matrix, i.e. an upper bound on the XORs needed to apply it.
+    pub fn ones(&self) -> usize {
+        self.bits.iter().filter(|b| **b).count()
+    }
+
+    /// Applies the matrix to `data` with XORs only, writing `coding`.
+    ///
+    /// Every device is split into groups of `8 * packet_size` bytes, and
+    /// packet `x` of a group plays the role of bit `x` of a symbol. Device
+    /// lengths must be equal and a multiple of `8 * packet_size`.
+    pub fn apply(&self, data: &[&[u8]], coding: &mut [&mut [u8]], packet_size: usize) {
+        assert_eq!(self.cols, data.len() * 8, "data device count mismatch");
+        assert_eq!(self.rows, coding.len() * 8, "coding device count mismatch");
+        assert!(packet_size > 0, "packet size must be positive");
+
+        let len = data.first().map_or(0, |d| d.len());
+        let group = 8 * packet_size;
+        assert_eq!(
+            len % group,
+            0,
+            "device size must be a multiple of 8 * packet_size"
+        );
+        assert!(data.iter().all(|d| d.len() == len), "device size mismatch");
+        assert!(
+            coding.iter().all(|d| d.len() == len),
+            "device size mismatch"
+        );
+
+        for g in (0..len).step_by(group) {
+            for r in 0..self.rows {
+                let (dev, bit) = (r / 8, r % 8);
+                let dst = &mut coding[dev][g + bit * packet_size..g + (bit + 1) * packet_size];
+                dst.fill(0);
+                for c in (0..self.cols).filter(|c| self[(r, *c)]) {
+                    let off = g + (c % 8) * packet_size;
+                    add_slice(&data[c / 8][off..off + packet_size], dst);
+                }
+            }
+        }
+    }
+
+    /// Turns the matrix into a schedule that computes each coding packet from
+    /// the data packets on its own, like Jerasure's
+    /// `jerasure_dumb_bitmatrix_to_schedule`.
+    pub fn dumb_schedule(&self) -> Schedule {
+        let k = self.cols / 8;
+        let mut ops = Vec::new();
+        for r in 0..self.rows {
+            let dst = (k + r / 8, r % 8);
+            let srcs = (0..self.cols).filter(|c| self[(r, *c)]);
+            for (i, c) in srcs.enumerate() {
+                ops.push(ScheduleOp {
+                    src: (c / 8, c % 8),
+                    dst,
+                    xor: i > 0,
+                });
+            }
+        }
+        Schedule {
+            data_devices: k,
+            coding_devices: self.rows / 8,
+            ops,
+        }
+    }
+
+    /// Like [`dumb_schedule`](BitMatrix::dumb_schedule), but starts a coding
+    /// packet from an already computed one whenever their rows differ in
+    /// fewer bits than the row has, as in Jerasure's
+    /// `jerasure_smart_bitmatrix_to_schedule`.
+    pub fn smart_schedule(&self) -> Schedule {
+        let k = self.cols / 8;
+        let row = |r: usize| (0..self.cols).filter(move |c| self[(r, *c)]);
+        let distance = |a: usize, b: usize| {
+            (0..self.cols)
+                .filter(|c| self[(a, *c)] != self[(b, *c)])
+                .count()
+        };
+
+        // the XORs needed for each row so far, and the row it would start from
+        let mut cost: Vec<usize> = (0..self.rows).map(|r| row(r).count()).collect();
+        let mut from: Vec<Option<usize>> = vec![None; self.rows];
+        let mut remaining: Vec<usize> = (0..self.rows).collect();
+        let mut ops = Vec::new();
+
+        while !remaining.is_empty() {
+            let (i, r) = remaining
+                .iter()
+                .copied()
+                .enumerate()
+                .min_by_key(|(_, r)| cost[*r])
+                .expect("rows remain");
+            remaining.remove(i);
+
+            let dst = (k + r / 8, r % 8);
+            match from[r] {
+                None => {
+                    for (i, c) in row(r).enumerate() {
+                        ops.push(ScheduleOp {
+                            src: (c / 8, c % 8),
+                            dst,
+                            xor: i > 0,
+                        });
+                    }
+                }
+                Some(f) => {
+                    ops.push(ScheduleOp {
+                        src: (k + f / 8, f % 8),
+                        dst,
+                        xor: false,
+                    });
+                    for c in (0..self.cols).filter(|c| self[(r, *c)] != self[(f, *c)]) {
+                        ops.push(ScheduleOp {
+                            src: (c / 8, c % 8),
+                            dst,
+                            xor: true,
+                        });
+                    }
+                }
+            }
+
+            for other in &remaining {
+                let d = distance(r, *other) + 1;
+                if d < cost[*other] {
+                    cost[*other] = d;
+                    from[*other] = Some(r);
+                }
+            }
+        }
+        Schedule {
+            data_devices: k,
+            coding_devices: self.rows / 8,
+            ops,
+        }
+    }
+}
+
+/// One step of a [`Schedule`]: copy or XOR packet `src` into packet `dst`.
+///
+/// Packets are `(device, packet)` pairs where devices `0..k` are data and
+/// `k..k + m` are coding, as in Jerasure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleOp {
+    pub src: (usize, usize),
+    pub dst: (usize, usize),
+    pub xor: bool,
+}
+
+/// A list of packet copies and XORs that applies a [`BitMatrix`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    data_devices: usize,
+    coding_devices: usize,
+    ops: Vec<ScheduleOp>,
+}
+
+impl Schedule {
+    pub fn ops(&self) -> &[ScheduleOp] {
+        &self.ops
+    }
+
+    /// The number of packet XORs per group.
+    pub fn xor_count(&self) -> usize {
+        self.ops.iter().filter(|op| op.xor).count()
+    }
+
+    /// Runs the schedule on every group of `8 * packet_size` bytes, with
+    /// the same layout as [`BitMatrix::apply`]. As in Jerasure, packets whose
+    /// bitmatrix row is all zero are not written.
+    pub fn apply(&self, data: &[&[u8]], coding: &mut [&mut [u8]], packet_size: usize) {
+        assert_eq!(data.len(), self.data_devices, "data device count mismatch");
+        assert_eq!(
+            coding.len(),
+            self.coding_devices,
+            "coding device count mismatch"
+        );
+        assert!(packet_size > 0, "packet size must be positive");
+        let len = data.first().map_or(0, |d| d.len());
+        let group = 8 * packet_size;
+        assert_eq!(
+            len % group,
+            0,
+            "device size must be a multiple of 8 * packet_size"
+        );
+        assert!(data.iter().all(|d| d.len() == len), "device size mismatch");
+        assert!(
+            coding.iter().all(|d| d.len() == len),
+            "device size mismatch"
+        );
+
+        let k = self.data_devices;
+        for g in (0..len).step_by(group) {
+            for op in &self.ops {
+                let (dev, packet) = op.dst;
+                let dst_off = g + packet * packet_size;
+                let src_off = g + op.src.1 * packet_size;
+                if op.src.0 < k {
+                    let src = &data[op.src.0][src_off..src_off + packet_size];
+                    let dst = &mut coding[dev - k][dst_off..dst_off + packet_size];
+                    packet_op(src, dst, op.xor);
+                } else {
+                    let (dst, src) = two_packets(
+                        coding,
+                        (dev - k, dst_off),
+                        (op.src.0 - k, src_off),
+                        packet_size,
+                    );
+                    packet_op(src, dst, op.xor);
+                }
+            }
+        }
+    }
+}
+
+fn packet_op(src: &[u8], dst: &mut [u8], xor: bool) {
+    if xor {
+        add_slice(src, dst);
+    } else {
+        dst.copy_from_slice(src);
+    }
+}
+
+// Borrows two distinct coding packets, given as (device, offset), mutably
+// and immutably at once.
+fn two_packets<'a>(
+    coding: &'a mut [&mut [u8]],
+    dst: (usize, usize),
+    src: (usize, usize),
+    len: usize,
+) -> (&'a mut [u8], &'a [u8]) {
+    if dst.0 == src.0 {
+        let dev = &mut *coding[dst.0];
+        return if dst.1 < src.1 {
+            let (a, b) = dev.split_at_mut(src.1);
+            (&mut a[dst.1..dst.1 + len], &b[..len])
+        } else {
+            let (a, b) = dev.split_at_mut(dst.1);
+            (&mut b[..len], &a[src.1..src.1 + len])
+        };
+    }
+    if dst.0 < src.0 {
+        let (a, b) = coding.split_at_mut(src.0);
+        (&mut a[dst.0][dst.1..dst.1 + len], &b[0][src.1..src.1 + len])
+    } else {
+        let (a, b) = coding.split_at_mut(dst.0);
+        (&mut b[0][dst.1..dst.1 + len], &a[src.0][src.1..src.1 + len])
+    }
+}
+
+impl Index<(usize, usize)> for BitMatrix {
+    type Output = bool;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(r < self.rows && c < self.cols, "index out of bounds");
+        &self.bits[r * self.cols + c]
+    }
+}
+
+impl GfMatrix {
+    /// Expands every element into its 8×8 GF(2) companion block.
+    pub fn to_bitmatrix(&self) -> BitMatrix {
+        let mut bm = BitMatrix::zero(self.rows() * 8, self.cols() * 8);
+        for i in 0..self.rows() {
+            for j in 0..self.cols() {
+                let mut elt = self[(i, j)];
+                for x in 0..8 {
+                    for l in 0..8 {
+                        bm.set(i * 8 + l, j * 8 + x, u8::from(elt) & (1 << l) != 0);
+                    }
+                    elt *= Galois::new(2);
+                }
+            }
+        }
+        bm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads byte `o`, bit `b` of every packet in a group as the bits of one symbol.
+    fn symbol(dev: &[u8], packet_size: usize, o: usize, b: usize) -> Galois {
+        let mut v = 0u8;
+        for x in 0..8 {
+            v |= ((dev[x * packet_size + o] >> b) & 1) << x;
+        }
+        Galois::new(v)
+    }
+
+    #[test]
+    fn test_companion_block() {
+        let m = GfMatrix::from_vec(1, 1, vec![Galois::new(0x53)]);
+        let bm = m.to_bitmatrix();
+        for v in 0..=255u8 {
+            let mut out = 0u8;
+            for l in 0..8 {
+                let mut bit = false;
+                for x in 0..8 {
+                    bit ^= bm[(l, x)] && (v >> x) & 1 == 1;
+                }
+                out |= (bit as u8) << l;
+            }
+            assert_eq!(Galois::new(out), Galois::new(0x53) * Galois::new(v));
+        }
+    }
+
+    #[test]
+    fn test_schedules() {
+        let rs = crate::rs::ReedSolomon::new_cauchy(4, 3).unwrap();
+        let bm = rs.matrix().submatrix(4, 0, 3, 4).to_bitmatrix();
+        let dumb = bm.dumb_schedule();
+        let smart = bm.smart_schedule();
+        assert_eq!(dumb.xor_count(), bm.ones() - bm.rows());
+        assert!(smart.xor_count() < dumb.xor_count());
+
+        let packet_size = 8;
+        let data: Vec<Vec<u8>> = (0..4)
+            .map(|d| (0..128).map(|i| (i * 13 + d * 41) as u8).collect())
+            .collect();
+        let data: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let mut expected = vec![vec![0u8; 128]; 3];
+        let mut outs: Vec<&mut [u8]> = expected.iter_mut().map(|d| &mut d[..]).collect();
+        bm.apply(&data, &mut outs, packet_size);
+
+        for schedule in [dumb, smart] {
+            let mut coding = vec![vec![0xeeu8; 128]; 3];
+            let mut outs: Vec<&mut [u8]> = coding.iter_mut().map(|d| &mut d[..]).collect();
+            schedule.apply(&data, &mut outs, packet_size);
+            assert_eq!(coding, expected);
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let (k, m, packet_size) = (3, 2, 4);
+        let gm = GfMatrix::from_fn(m, k, |r, c| Galois::new((r * 37 + c * 11 + 1) as u8));
+        let bm = gm.to_bitmatrix();
+
+        let data: Vec<Vec<u8>> = (0..k)
+            .map(|d| (0..64).map(|i| (i * 29 + d * 71) as u8).collect())
+            .collect();
+        let mut coding = vec![vec![0u8; 64]; m];
+        {
+            let data: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+            let mut coding: Vec<&mut [u8]> = coding.iter_mut().map(|d| &mut d[..]).collect();
+            bm.apply(&data, &mut coding, packet_size);
+        }
+
+        for g in (0..64).step_by(8 * packet_size) {
+            for o in 0..packet_size {
+                for b in 0..8 {
+                    for (r, out) in coding.iter().enumerate() {
+                        let mut expected = Galois::zero();
+                        for (c, d) in data.iter().enumerate() {
+                            expected += gm[(r, c)] * symbol(&d[g..], packet_size, o, b);
+                        }
+                        assert_eq!(symbol(&out[g..], packet_size, o, b), expected);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,376 @@
+//! Composite fields GF(q^n) over any [`Field`] of order `q`.
+//!
+//! [`tower`](crate::tower) hard-codes GF((2^4)^2) for AES; a
+//! [`CompositeField`] is the general GF((2^m)^n) = F[z] / (f) for a
+//! subfield `F` and an irreducible `f` of degree `n` over it, with every
+//! operation delegated to `F`. An element is its `n` coefficients in `z`,
+//! lowest first.
+//!
+//! Inversion goes through the norm, as tower-field hardware does: with
+//! `r = (q^n - 1) / (q - 1)`, the product of the conjugates
+//! `a^r = a a^q .. a^(q^(n-1))` lies in `F`, so
+//! `a^-1 = a^(r-1) (a^r)^-1` costs one inversion in `F`, with the
+//! Frobenius powers and products around it. [`CompositeElement::norm`]
+//! and [`CompositeElement::frobenius`] expose the pieces.
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::Field;
+
+/// GF(q^n) as `F[z] / (f)`, for an irreducible `f` of degree `n` over a
+/// field `F` of order `q`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositeField<F: Field> {
+    // f, monic, lowest coefficient first
+    modulus: Vec<F>,
+}
+
+impl<F: Field> CompositeField<F> {
+    /// Returns `None` unless `modulus`, lowest coefficient first, is
+    /// irreducible over `F` of degree at least 1. It is made monic.
+    pub fn new(modulus: Vec<F>) -> Option<Self> {
+        let modulus = trim(modulus);
+        if modulus.len() < 2 {
+            return None;
+        }
+        let lead = modulus[modulus.len() - 1].inv();
+        let modulus: Vec<F> = modulus.into_iter().map(|c| c * lead).collect();
+        let field = CompositeField { modulus };
+        field.is_irreducible().then_some(field)
+    }
+
+    /// The modulus `f`, monic, lowest coefficient first.
+    pub fn modulus(&self) -> &[F] {
+        &self.modulus
+    }
+
+    /// The degree `n` of the extension.
+    pub fn degree(&self) -> usize {
+        self.modulus.len() - 1
+    }
+
+    /// The number of elements, `q^n`, if it fits.
+    pub fn order(&self) -> Option<u128> {
+        (F::ORDER as u128).checked_pow(self.degree() as u32)
+    }
+
+    /// The element with the coefficients `coeffs` in `z`, lowest first,
+    /// reduced modulo `f`.
+    pub fn elem(&self, coeffs: &[F]) -> CompositeElement<'_, F> {
+        CompositeElement {
+            field: self,
+            coeffs: self.reduce(coeffs.to_vec()),
+        }
+    }
+
+    /// The element whose coefficients are the base-`q` digits of `i`, the
+    /// lowest digit that of `z^0`: for GF((2^4)^2), the byte `h << 4 | l`
+    /// of [`Tower`](crate::tower::Tower).
+    ///
+    /// Panics unless `i` is below the order.
+    pub fn from_index(&self, i: u128) -> CompositeElement<'_, F> {
+        let q = F::ORDER as u128;
+        assert!(
+            self.order().is_none_or(|order| i < order),
+            "index out of range"
+        );
+        let coeffs = (0..self.degree())
+            .scan(i, |rest, _| {
+                let digit = *rest % q;
+                *rest /= q;
+                Some(F::from_index(digit as usize))
+            })
+            .collect();
+        CompositeElement {
+            field: self,
+            coeffs,
+        }
+    }
+
+    pub fn zero(&self) -> CompositeElement<'_, F> {
+        self.elem(&[])
+    }
+
+    pub fn one(&self) -> CompositeElement<'_, F> {
+        self.elem(&[F::one()])
+    }
+
+    /// The class of `z`, a root of the modulus.
+    pub fn z(&self) -> CompositeElement<'_, F> {
+        self.elem(&[F::zero(), F::one()])
+    }
+
+    /// The element of the subfield `c`.
+    pub fn constant(&self, c: F) -> CompositeElement<'_, F> {
+        self.elem(&[c])
+    }
+
+    // Ben-Or: f has no factor of degree d <= n / 2, gcd(f, z^(q^d) - z) = 1.
+    fn is_irreducible(&self) -> bool {
+        let mut z_pow = self.z();
+        for _ in 0..self.degree() / 2 {
+            z_pow = z_pow.frobenius();
+            let g = trim((z_pow.clone() - self.z()).coeffs);
+            if gcd(self.modulus.clone(), g).len() != 1 {
+                return false;
+            }
+        }
+        true
+    }
+
+    // The remainder of a modulo f, padded to n coefficients.
+    fn reduce(&self, a: Vec<F>) -> Vec<F> {
+        let mut r = rem(a, &self.modulus);
+        r.resize(self.degree(), F::zero());
+        r
+    }
+}
+
+/// An element of a [`CompositeField`], from [`CompositeField::elem`].
+///
+/// Operators between elements of different fields panic.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CompositeElement<'a, F: Field> {
+    field: &'a CompositeField<F>,
+    // n coefficients, lowest first
+    coeffs: Vec<F>,
+}
+
+impl<'a, F: Field> CompositeElement<'a, F> {
+    /// The `n` coefficients in `z`, lowest first.
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    /// The inverse of [`CompositeField::from_index`].
+    pub fn index(&self) -> u128 {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(0, |acc, c| acc * F::ORDER as u128 + c.to_index() as u128)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.iter().all(|&c| c == F::zero())
+    }
+
+    /// The Frobenius map `a^q`, an automorphism fixing the subfield.
+    pub fn frobenius(&self) -> CompositeElement<'a, F> {
+        // q is a power of two, so a^q is log2(q) squarings
+        (0..F::ORDER.trailing_zeros()).fold(self.clone(), |a, _| a.clone() * a)
+    }
+
+    /// The norm to the subfield, `a^((q^n - 1) / (q - 1))`, the product of
+    /// the conjugates of `a`.
+    pub fn norm(&self) -> F {
+        (self.clone() * self.conjugate_product()).coeffs[0]
+    }
+
+    /// The inverse through the norm, panicking on zero.
+    pub fn inv(&self) -> CompositeElement<'a, F> {
+        assert!(!self.is_zero(), "divide by zero");
+        let rest = self.conjugate_product();
+        let norm = (self.clone() * rest.clone()).coeffs[0];
+        rest * self.field.constant(norm.inv())
+    }
+
+    pub fn pow(&self, n: u128) -> CompositeElement<'a, F> {
+        let (mut base, mut result) = (self.clone(), self.field.one());
+        let mut n = n;
+        while n != 0 {
+            if n & 1 == 1 {
+                result = result * base.clone();
+            }
+            base = base.clone() * base;
+            n >>= 1;
+        }
+        result
+    }
+
+    // a^(r - 1) = a^q a^(q^2) .. a^(q^(n-1))
+    fn conjugate_product(&self) -> CompositeElement<'a, F> {
+        let mut conjugate = self.clone();
+        let mut product = self.field.one();
+        for _ in 1..self.field.degree() {
+            conjugate = conjugate.frobenius();
+            product = product * conjugate.clone();
+        }
+        product
+    }
+
+    fn check_field(&self, rhs: &Self) {
+        assert!(
+            std::ptr::eq(self.field, rhs.field) || self.field == rhs.field,
+            "elements of different fields"
+        );
+    }
+}
+
+impl<F: Field> fmt::Debug for CompositeElement<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(&self.coeffs).finish()
+    }
+}
+
+impl<'a, F: Field> Add for CompositeElement<'a, F> {
+    type Output = CompositeElement<'a, F>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.check_field(&rhs);
+        let coeffs = self
+            .coeffs
+            .iter()
+            .zip(&rhs.coeffs)
+            .map(|(&a, &b)| a + b)
+            .collect();
+        CompositeElement {
+            field: self.field,
+            coeffs,
+        }
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<'a, F: Field> Sub for CompositeElement<'a, F> {
+    type Output = CompositeElement<'a, F>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + rhs
+    }
+}
+
+impl<'a, F: Field> Mul for CompositeElement<'a, F> {
+    type Output = CompositeElement<'a, F>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.check_field(&rhs);
+        let mut product = vec![F::zero(); 2 * self.coeffs.len()];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            for (j, &b) in rhs.coeffs.iter().enumerate() {
+                product[i + j] += a * b;
+            }
+        }
+        CompositeElement {
+            field: self.field,
+            coeffs: self.field.reduce(product),
+        }
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<'a, F: Field> Div for CompositeElement<'a, F> {
+    type Output = CompositeElement<'a, F>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.check_field(&rhs);
+        self * rhs.inv()
+    }
+}
+
+fn trim<F: Field>(mut a: Vec<F>) -> Vec<F> {
+    while a.last() == Some(&F::zero()) {
+        a.pop();
+    }
+    a
+}
+
+// The remainder of a by a nonzero, trimmed b.
+fn rem<F: Field>(a: Vec<F>, b: &[F]) -> Vec<F> {
+    let mut r = trim(a);
+    let lead = b[b.len() - 1].inv();
+    while r.len() >= b.len() {
+        let shift = r.len() - b.len();
+        let factor = r[r.len() - 1] * lead;
+        for (i, &c) in b.iter().enumerate() {
+            r[shift + i] -= factor * c;
+        }
+        r = trim(r);
+    }
+    r
+}
+
+fn gcd<F: Field>(mut a: Vec<F>, mut b: Vec<F>) -> Vec<F> {
+    while !b.is_empty() {
+        let r = rem(a, &b);
+        a = std::mem::replace(&mut b, r);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tower::{Tower, LAMBDA};
+    use crate::{Galois, Gf16};
+
+    #[test]
+    fn test_tower() {
+        // z^2 + z + λ over GF(16) is the field of tower
+        let field = CompositeField::new(vec![Gf16::new(LAMBDA), Gf16::one(), Gf16::one()]).unwrap();
+        assert_eq!(field.order(), Some(256));
+        for a in 0..=255u8 {
+            let x = field.from_index(a as u128);
+            assert_eq!(x.index(), a as u128);
+            if a != 0 {
+                assert_eq!(x.inv().index(), u8::from(Tower::new(a).inv()) as u128);
+                // the norm lies in GF(16): it is its own 16th power
+                assert_eq!(x.norm().exp(16), x.norm());
+            }
+            for b in (0..=255u8).step_by(3) {
+                let product = Tower::new(a) * Tower::new(b);
+                let y = field.from_index(b as u128);
+                assert_eq!((x.clone() * y).index(), u8::from(product) as u128);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quadratic_over_gf256() {
+        // z^2 + z + c is irreducible for the c outside {y^2 + y}
+        let reducible: Vec<Galois> = (0..=255u8)
+            .map(|y| Galois::new(y) * Galois::new(y) + Galois::new(y))
+            .collect();
+        let c = (0..=255u8)
+            .map(Galois::new)
+            .find(|c| !reducible.contains(c))
+            .unwrap();
+        assert!(CompositeField::new(vec![reducible[7], Galois::one(), Galois::one()]).is_none());
+        let field = CompositeField::new(vec![c, Galois::one(), Galois::one()]).unwrap();
+        assert_eq!(field.order(), Some(65536));
+
+        let z = field.z();
+        assert!((z.clone() * z.clone() + z.clone() + field.constant(c)).is_zero());
+        for i in (1..65536u128).step_by(251) {
+            let a = field.from_index(i);
+            assert_eq!(a.clone() * a.inv(), field.one());
+            assert_eq!(a.frobenius().frobenius(), a);
+            assert_eq!(field.constant(a.norm()), a.pow(257));
+            assert_eq!(a.pow(65535), field.one());
+        }
+    }
+
+    #[test]
+    fn test_construction() {
+        // (z + 1)(z + 2) and constants are rejected, and the modulus made monic
+        let g = |v: u8| Galois::new(v);
+        assert!(CompositeField::new(vec![g(2), g(3), g(1)]).is_none());
+        assert!(CompositeField::new(vec![g(5), g(0)]).is_none());
+        let field = CompositeField::new(vec![g(2), g(3), g(0)]).unwrap();
+        assert_eq!(field.degree(), 1);
+        assert_eq!(field.modulus(), [g(2) / g(3), g(1)]);
+        assert_eq!(field.z().coeffs(), [g(2) / g(3)]);
+
+        // a cubic over GF(16), with 4096 elements
+        let cubic = (0..16u8)
+            .find_map(|c| {
+                CompositeField::new(vec![Gf16::new(c), Gf16::one(), Gf16::zero(), Gf16::one()])
+            })
+            .unwrap();
+        assert_eq!(cubic.order(), Some(4096));
+        for i in 1..4096 {
+            let a = cubic.from_index(i);
+            assert_eq!((a.clone() / a.clone()), cubic.one());
+        }
+    }
+}
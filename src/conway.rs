@@ -0,0 +1,197 @@
+//! Conway polynomials and the fields GF(2^m) built on them.
+//!
+//! The Conway polynomial `C_m` of degree `m` over GF(2) is the least
+//! primitive polynomial, comparing coefficients from `x^(m-1)` down, whose
+//! root `α_m` is compatible with those of the subfields: for each `d`
+//! dividing `m`, `α_m^((2^m - 1) / (2^d - 1))` is a root of `C_d`. That
+//! makes the representation canonical, and it is the one computer algebra
+//! systems use by default: an element of a [`ConwayField`] is the bit
+//! vector of its coefficients in `α`, Sage's `integer_representation` of
+//! `GF(2^m, modulus="conway")`, and [`ConwayField::exp`]`(k)` is GAP's
+//! `Z(2^m)^k`.
+//!
+//! `C_8` is `0x11d`, so [`Galois`](crate::Galois) is itself the Conway
+//! field of degree 8, with `α = 2`.
+
+use crate::rabin::mul_mod;
+
+/// The largest degree in the table.
+pub const MAX_DEGREE: u8 = 16;
+
+// C_m without its x^m term, from Lübeck's tables
+const CONWAY: [u64; MAX_DEGREE as usize] = [
+    0x1, 0x3, 0x3, 0x3, 0x5, 0x1b, 0x3, 0x1d, 0x11, 0x6f, 0x5, 0xeb, 0x1b, 0xa9, 0x35, 0x2d,
+];
+
+/// The Conway polynomial of degree `degree`, without its `x^degree` term
+/// as in [`lfsr`](crate::lfsr), or `None` past [`MAX_DEGREE`].
+pub fn polynomial(degree: u8) -> Option<u64> {
+    (1..=MAX_DEGREE)
+        .contains(&degree)
+        .then(|| CONWAY[degree as usize - 1])
+}
+
+/// GF(2^m) for `m <= 16` as GF(2)[x] modulo the Conway polynomial, with
+/// elements given as `u16` bit vectors and exp and log tables to the base
+/// of `α = x`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConwayField {
+    degree: u8,
+    // exp[i] = α^i, doubled so that the sum of two logs needs no reduction
+    exp: Vec<u16>,
+    log: Vec<u16>,
+}
+
+impl ConwayField {
+    /// Returns `None` unless `1 <= degree <= 16`.
+    pub fn new(degree: u8) -> Option<Self> {
+        let poly = polynomial(degree)?;
+        let order = 1usize << degree;
+        let top = 1u128 << degree | poly as u128;
+        let mut exp = vec![0u16; 2 * (order - 1)];
+        let mut log = vec![0u16; order];
+        let mut v = 1u128;
+        for i in 0..order - 1 {
+            exp[i] = v as u16;
+            exp[i + order - 1] = v as u16;
+            log[v as usize] = i as u16;
+            v = mul_mod(v, 2 % top, top, degree);
+        }
+        Some(ConwayField { degree, exp, log })
+    }
+
+    pub fn degree(&self) -> u8 {
+        self.degree
+    }
+
+    /// The Conway polynomial, without its `x^m` term.
+    pub fn poly(&self) -> u64 {
+        CONWAY[self.degree as usize - 1]
+    }
+
+    /// The number of elements, `2^m`.
+    pub fn order(&self) -> usize {
+        1 << self.degree
+    }
+
+    pub fn add(&self, a: u16, b: u16) -> u16 {
+        a ^ b
+    }
+
+    pub fn mul(&self, a: u16, b: u16) -> u16 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+    }
+
+    /// Panics if `b` is zero.
+    pub fn div(&self, a: u16, b: u16) -> u16 {
+        assert!(b != 0, "divide by zero");
+        if a == 0 {
+            return 0;
+        }
+        let n = self.order() - 1;
+        self.exp[self.log[a as usize] as usize + n - self.log[b as usize] as usize]
+    }
+
+    pub fn inv(&self, a: u16) -> u16 {
+        self.div(1, a)
+    }
+
+    pub fn pow(&self, a: u16, n: u64) -> u16 {
+        if n == 0 {
+            return 1;
+        }
+        if a == 0 {
+            return 0;
+        }
+        let group = self.order() as u64 - 1;
+        self.exp[(self.log[a as usize] as u64 * (n % group) % group) as usize]
+    }
+
+    /// `α^n`, GAP's `Z(2^m)^n`.
+    pub fn exp(&self, n: u64) -> u16 {
+        self.exp[(n % (self.order() as u64 - 1)) as usize]
+    }
+
+    /// The logarithm to the base `α`, or `None` for zero.
+    pub fn log(&self, a: u16) -> Option<u16> {
+        (a != 0).then(|| self.log[a as usize])
+    }
+
+    /// The image of `a` from the subfield `sub`, or `None` unless its
+    /// degree divides this one.
+    ///
+    /// Compatibility of the Conway polynomials makes this the inclusion
+    /// `α_d = α_m^((2^m - 1) / (2^d - 1))`, the same in every system that
+    /// uses them.
+    pub fn embed(&self, sub: &ConwayField, a: u16) -> Option<u16> {
+        if !self.degree.is_multiple_of(sub.degree) {
+            return None;
+        }
+        let step = (self.order() as u64 - 1) / (sub.order() as u64 - 1);
+        Some(match sub.log(a) {
+            Some(k) => self.exp(k as u64 * step),
+            None => 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lfsr::is_primitive;
+    use crate::Galois;
+
+    #[test]
+    fn test_table() {
+        assert_eq!(polynomial(0), None);
+        assert_eq!(polynomial(17), None);
+        assert_eq!(polynomial(8), Some(0x1d));
+        let fields: Vec<ConwayField> = (1..=MAX_DEGREE)
+            .map(|m| ConwayField::new(m).unwrap())
+            .collect();
+        for field in &fields {
+            let m = field.degree();
+            assert!(is_primitive(field.poly(), m));
+            // the root of C_d is a power of α_m, so the inclusion is additive
+            for sub in fields.iter().filter(|s| m.is_multiple_of(s.degree())) {
+                let n = sub.order();
+                for a in (0..n as u16).step_by(n / 16 + 1) {
+                    for b in (0..n).map(|b| b as u16) {
+                        let (x, y) = (field.embed(sub, a), field.embed(sub, b));
+                        assert_eq!(field.embed(sub, a ^ b).unwrap(), x.unwrap() ^ y.unwrap());
+                    }
+                }
+            }
+        }
+        assert_eq!(fields[5].embed(&fields[3], 1), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let field = ConwayField::new(8).unwrap();
+        assert_eq!(field.order(), 256);
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                let product = Galois::new(a) * Galois::new(b);
+                assert_eq!(field.mul(a as u16, b as u16), u8::from(product) as u16);
+            }
+            assert_eq!(
+                field.exp(a as u64),
+                u8::from(Galois::new(2).exp(a as u32)) as u16
+            );
+        }
+
+        let field = ConwayField::new(13).unwrap();
+        for a in (1..field.order() as u16).step_by(97) {
+            assert_eq!(field.mul(a, field.inv(a)), 1);
+            assert_eq!(field.exp(field.log(a).unwrap() as u64), a);
+            assert_eq!(field.pow(a, 8191), 1);
+            assert_eq!(field.pow(a, 8192), a);
+            assert_eq!(field.div(field.mul(a, 1234), 1234), a);
+        }
+        assert_eq!(field.log(0), None);
+    }
+}
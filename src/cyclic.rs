@@ -0,0 +1,324 @@
+//! Cyclic and polynomial codes from a generator polynomial or root set.
+//!
+//! A codeword of a code with generator `g` is a multiple of `g`, sent as
+//! the message followed by the remainder of `msg(x) * x^(n-k)` divided by
+//! `g`, first byte as the highest-degree coefficient. The code is cyclic
+//! when `g` divides `x^n - 1`, which is the case for roots that are `n`-th
+//! roots of unity; otherwise it is a plain polynomial code, as for a CRC.
+//!
+//! Binary codes hold one bit per byte, as in [`bch`](crate::bch); symbol
+//! codes hold one GF(256) element per byte.
+//!
+//! Decoding looks the syndrome up in a table of every error pattern up to a
+//! chosen weight, which is only practical for short codes.
+
+use std::collections::HashMap;
+
+use crate::poly::Poly;
+use crate::rs::CodeError;
+use crate::Galois;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CyclicCode {
+    n: usize,
+    generator: Poly,
+    binary: bool,
+    // syndrome -> (position, error value) pairs
+    table: HashMap<Vec<u8>, Vec<(usize, u8)>>,
+}
+
+impl CyclicCode {
+    /// The code over GF(256) generated by `generator`, made monic.
+    pub fn new(n: usize, generator: Poly) -> Result<Self, CodeError> {
+        if generator.degree().is_none_or(|d| d == 0 || d >= n) {
+            return Err(CodeError::InvalidParameters);
+        }
+        let generator = generator.scale(generator.lead().inv());
+        Ok(CyclicCode {
+            n,
+            generator,
+            binary: false,
+            table: HashMap::new(),
+        })
+    }
+
+    /// The code over GF(256) whose generator is `Π (x - r)` over `roots`.
+    pub fn from_roots(n: usize, roots: &[Galois]) -> Result<Self, CodeError> {
+        CyclicCode::new(n, Poly::from_roots(roots))
+    }
+
+    /// The binary code generated by `generator`, whose coefficients must be
+    /// 0 or 1.
+    pub fn binary(n: usize, generator: Poly) -> Result<Self, CodeError> {
+        if generator.coeffs().iter().any(|c| u8::from(*c) > 1) {
+            return Err(CodeError::InvalidParameters);
+        }
+        Ok(CyclicCode {
+            binary: true,
+            ..CyclicCode::new(n, generator)?
+        })
+    }
+
+    /// The binary code whose generator has `roots` and all their conjugates
+    /// `r^2, r^4, ..` as roots, i.e. the product of their minimal
+    /// polynomials.
+    pub fn binary_from_roots(n: usize, roots: &[Galois]) -> Result<Self, CodeError> {
+        let mut closed: Vec<Galois> = Vec::new();
+        for r in roots {
+            let mut c = *r;
+            while !closed.contains(&c) {
+                closed.push(c);
+                c *= c;
+            }
+        }
+        CyclicCode::binary(n, Poly::from_roots(&closed))
+    }
+
+    /// Builds the syndrome table for every error pattern of up to
+    /// `max_weight` symbols, keeping the lightest pattern for each syndrome.
+    ///
+    /// A binary code has `C(n, w)` patterns of weight `w` and a symbol code
+    /// `255^w` times as many.
+    pub fn with_table(mut self, max_weight: usize) -> Self {
+        let values: Vec<u8> = if self.binary {
+            vec![1]
+        } else {
+            (1..=255).collect()
+        };
+        self.table.clear();
+        let mut pattern = Vec::new();
+        for weight in 1..=max_weight {
+            self.fill_table(weight, 0, &values, &mut pattern);
+        }
+        self
+    }
+
+    // Adds every pattern extending `pattern` to `weight` errors at positions
+    // from `start` on.
+    fn fill_table(
+        &mut self,
+        weight: usize,
+        start: usize,
+        values: &[u8],
+        pattern: &mut Vec<(usize, u8)>,
+    ) {
+        if pattern.len() == weight {
+            let mut word = vec![0u8; self.n];
+            for (pos, v) in pattern.iter() {
+                word[*pos] = *v;
+            }
+            let syndrome = self.syndrome(&word);
+            self.table
+                .entry(syndrome)
+                .or_insert_with(|| pattern.clone());
+            return;
+        }
+        for pos in start..self.n {
+            for v in values {
+                pattern.push((pos, *v));
+                self.fill_table(weight, pos + 1, values, pattern);
+                pattern.pop();
+            }
+        }
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn k(&self) -> usize {
+        self.n - self.parity_len()
+    }
+
+    pub fn parity_len(&self) -> usize {
+        self.generator.degree().unwrap_or(0)
+    }
+
+    pub fn generator(&self) -> &Poly {
+        &self.generator
+    }
+
+    pub fn is_binary(&self) -> bool {
+        self.binary
+    }
+
+    /// Whether the generator divides `x^n - 1`, so that every cyclic shift
+    /// of a codeword is a codeword.
+    pub fn is_cyclic(&self) -> bool {
+        let xn1 = &Poly::monomial(Galois::identity(), self.n) - &Poly::one();
+        xn1.div_rem(&self.generator).1.is_zero()
+    }
+
+    /// Returns the codeword for `msg`: the message followed by its parity.
+    pub fn encode(&self, msg: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if msg.len() != self.k() {
+            return Err(CodeError::WrongLength);
+        }
+        let mut codeword = vec![0u8; self.n];
+        codeword[..self.k()].copy_from_slice(msg);
+        self.encode_parity(msg, &mut codeword[self.k()..])?;
+        Ok(codeword)
+    }
+
+    /// Computes the parity for `msg` with the shift register that divides
+    /// by the generator.
+    pub fn encode_parity(&self, msg: &[u8], parity: &mut [u8]) -> Result<(), CodeError> {
+        if msg.len() != self.k() || parity.len() != self.parity_len() {
+            return Err(CodeError::WrongLength);
+        }
+        parity.fill(0);
+        for m in msg {
+            self.shift_in(parity, *m);
+        }
+        Ok(())
+    }
+
+    fn shift_in(&self, regs: &mut [u8], symbol: u8) {
+        let g = self.generator.coeffs();
+        let p = regs.len();
+        let feedback = Galois::new(symbol) + Galois::new(regs[0]);
+        regs.copy_within(1.., 0);
+        regs[p - 1] = 0;
+        if feedback != Galois::zero() {
+            for (j, r) in regs.iter_mut().enumerate() {
+                *r = u8::from(Galois::new(*r) + feedback * g[p - 1 - j]);
+            }
+        }
+    }
+
+    /// The remainder of the received word divided by the generator, highest
+    /// degree first, which is zero exactly for codewords.
+    pub fn syndrome(&self, codeword: &[u8]) -> Vec<u8> {
+        let g = self.generator.coeffs();
+        let p = self.parity_len();
+        let mut regs = vec![0u8; p];
+        // r(x) <- r(x) x + c mod g, one symbol at a time
+        for c in codeword {
+            let top = Galois::new(regs[0]);
+            regs.copy_within(1.., 0);
+            regs[p - 1] = *c;
+            if top != Galois::zero() {
+                for (j, r) in regs.iter_mut().enumerate() {
+                    *r = u8::from(Galois::new(*r) + top * g[p - 1 - j]);
+                }
+            }
+        }
+        regs
+    }
+
+    /// Corrects `codeword` in place from the syndrome table, returning the
+    /// number of symbols fixed.
+    pub fn decode(&self, codeword: &mut [u8]) -> Result<usize, CodeError> {
+        if codeword.len() != self.n {
+            return Err(CodeError::WrongLength);
+        }
+        let syndrome = self.syndrome(codeword);
+        if syndrome.iter().all(|s| *s == 0) {
+            return Ok(0);
+        }
+        let pattern = self.table.get(&syndrome).ok_or(CodeError::TooManyErrors)?;
+        for (pos, v) in pattern {
+            codeword[*pos] ^= v;
+        }
+        Ok(pattern.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rs::RsCode;
+
+    fn bits(v: &[u8]) -> Poly {
+        Poly::new(v.iter().map(|c| Galois::new(*c)).collect())
+    }
+
+    #[test]
+    fn test_hamming() {
+        // Hamming(7, 4) with g = x^3 + x + 1
+        let code = CyclicCode::binary(7, bits(&[1, 1, 0, 1]))
+            .unwrap()
+            .with_table(1);
+        assert!(code.is_cyclic());
+        assert_eq!(code.k(), 4);
+        for m in 0..16u8 {
+            let msg: Vec<u8> = (0..4).map(|i| m >> i & 1).collect();
+            let original = code.encode(&msg).unwrap();
+            assert!(code.syndrome(&original).iter().all(|s| *s == 0));
+
+            // cyclic shifts of codewords are codewords
+            let mut shifted = original.clone();
+            shifted.rotate_left(1);
+            assert!(code.syndrome(&shifted).iter().all(|s| *s == 0));
+
+            for pos in 0..7 {
+                let mut received = original.clone();
+                received[pos] ^= 1;
+                assert_eq!(code.decode(&mut received), Ok(1));
+                assert_eq!(received, original);
+            }
+        }
+    }
+
+    #[test]
+    fn test_binary_from_roots() {
+        // β = α^17 has order 15; with β and β^3 this is the BCH(15, 7) code
+        let beta = Galois::new(2).exp(17);
+        let code = CyclicCode::binary_from_roots(15, &[beta, beta.exp(3)])
+            .unwrap()
+            .with_table(2);
+        assert!(code.is_cyclic());
+        assert_eq!(code.k(), 7);
+
+        let original = code.encode(&[1, 0, 1, 1, 0, 0, 1]).unwrap();
+        for a in 0..15 {
+            for b in a + 1..15 {
+                let mut received = original.clone();
+                received[a] ^= 1;
+                received[b] ^= 1;
+                assert_eq!(code.decode(&mut received), Ok(2));
+                assert_eq!(received, original);
+            }
+        }
+    }
+
+    #[test]
+    fn test_symbol_code() {
+        // the generator of RsCode::new(12, 10) gives the same parity
+        let roots = [Galois::identity(), Galois::new(2)];
+        let code = CyclicCode::from_roots(12, &roots).unwrap().with_table(1);
+        assert!(!code.is_cyclic());
+        let rs = RsCode::new(12, 10).unwrap();
+        let msg: Vec<u8> = (0..10u8).map(|i| i.wrapping_mul(29) + 3).collect();
+        let original = code.encode(&msg).unwrap();
+        assert_eq!(original, rs.encode(&msg).unwrap());
+
+        let mut received = original.clone();
+        received[4] ^= 0x9e;
+        assert_eq!(code.decode(&mut received), Ok(1));
+        assert_eq!(received, original);
+
+        let mut received = original.clone();
+        received[1] ^= 3;
+        received[8] ^= 5;
+        assert_ne!(code.decode(&mut received), Ok(0));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(
+            CyclicCode::binary(7, bits(&[1, 2, 1])),
+            Err(CodeError::InvalidParameters)
+        );
+        assert_eq!(
+            CyclicCode::new(3, bits(&[1, 0, 0, 1])),
+            Err(CodeError::InvalidParameters)
+        );
+        let code = CyclicCode::binary(7, bits(&[1, 1, 0, 1])).unwrap();
+        assert_eq!(code.encode(&[0; 5]), Err(CodeError::WrongLength));
+        // without a table only clean words decode
+        let mut received = code.encode(&[1, 0, 0, 1]).unwrap();
+        received[0] ^= 1;
+        assert_eq!(code.decode(&mut received), Err(CodeError::TooManyErrors));
+    }
+}
@@ -0,0 +1,341 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::rabin::{is_irreducible, mul_mod};
+
+/// The largest degree with exp and log tables, of `2^16` entries each.
+const TABLE_DEGREE: u8 = 16;
+
+/// GF(2^m) for `1 <= m <= 32`, as GF(2)[x] modulo a polynomial, both
+/// chosen at run time.
+///
+/// The fixed types, [`Galois`](crate::Galois), [`Gf16`](crate::Gf16),
+/// [`Gf65536`](crate::Gf65536) and [`Gf32`](crate::Gf32), are faster; this
+/// serves tools that must handle whatever field a file format or captured
+/// protocol uses. Up to degree 16 products are table lookups, as in
+/// [`GaloisField`](crate::GaloisField); above that they are carry-less
+/// products reduced bit by bit. Elements are `u32` bit vectors.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DynField {
+    degree: u8,
+    poly: u64,
+    tables: Option<Tables>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+struct Tables {
+    generator: u16,
+    // exp[i] = g^i, doubled so that the sum of two logs needs no reduction
+    exp: Vec<u16>,
+    log: Vec<u16>,
+}
+
+impl DynField {
+    /// Returns `None` unless `x^degree + poly`, `poly` given without its
+    /// `x^degree` term as in [`rabin`](crate::rabin), is irreducible and
+    /// `1 <= degree <= 32`.
+    pub fn new(poly: u64, degree: u8) -> Option<Self> {
+        if !(1..=32).contains(&degree) || poly >> degree != 0 || !is_irreducible(poly, degree) {
+            return None;
+        }
+        let mut field = DynField {
+            degree,
+            poly,
+            tables: None,
+        };
+        if degree <= TABLE_DEGREE {
+            field.tables = Some(field.build_tables());
+        }
+        Some(field)
+    }
+
+    pub fn degree(&self) -> u8 {
+        self.degree
+    }
+
+    /// The field polynomial, without its `x^degree` term.
+    pub fn poly(&self) -> u64 {
+        self.poly
+    }
+
+    /// The number of elements, `2^m`.
+    pub fn order(&self) -> u64 {
+        1 << self.degree
+    }
+
+    /// The generator of the multiplicative group the tables are built on,
+    /// or `None` above degree 16, where there are none.
+    pub fn generator(&self) -> Option<u32> {
+        self.tables.as_ref().map(|t| t.generator as u32)
+    }
+
+    /// The element with bit pattern `v`, with operators. Panics unless
+    /// `v < 2^m`.
+    pub fn elem(&self, v: u32) -> DynGf<'_> {
+        assert!((v as u64) < self.order(), "{v:#x} is not a field element");
+        DynGf {
+            field: self,
+            value: v,
+        }
+    }
+
+    pub fn add(&self, a: u32, b: u32) -> u32 {
+        a ^ b
+    }
+
+    pub fn sub(&self, a: u32, b: u32) -> u32 {
+        a ^ b
+    }
+
+    pub fn mul(&self, a: u32, b: u32) -> u32 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        match &self.tables {
+            Some(t) => t.exp[t.log[a as usize] as usize + t.log[b as usize] as usize] as u32,
+            None => self.mul_slow(a, b),
+        }
+    }
+
+    /// Panics if `b` is zero.
+    pub fn div(&self, a: u32, b: u32) -> u32 {
+        self.mul(a, self.inv(b))
+    }
+
+    /// Panics if `a` is zero.
+    pub fn inv(&self, a: u32) -> u32 {
+        assert!(a != 0, "divide by zero");
+        let group = self.order() - 1;
+        match &self.tables {
+            Some(t) => t.exp[(group - t.log[a as usize] as u64) as usize] as u32,
+            None => self.pow(a, group - 1),
+        }
+    }
+
+    pub fn pow(&self, a: u32, n: u64) -> u32 {
+        if n == 0 {
+            return 1;
+        }
+        if a == 0 {
+            return 0;
+        }
+        let group = self.order() - 1;
+        let n = n % group;
+        if let Some(t) = &self.tables {
+            return t.exp[(t.log[a as usize] as u64 * n % group) as usize] as u32;
+        }
+        let (mut base, mut result) = (a, 1);
+        for i in 0..64 - n.leading_zeros() {
+            if n >> i & 1 == 1 {
+                result = self.mul_slow(result, base);
+            }
+            base = self.mul_slow(base, base);
+        }
+        result
+    }
+
+    fn mul_slow(&self, a: u32, b: u32) -> u32 {
+        let p = 1u128 << self.degree | self.poly as u128;
+        mul_mod(a as u128, b as u128, p, self.degree) as u32
+    }
+
+    // Tables to the base of the smallest generator: x itself when the
+    // polynomial is primitive.
+    fn build_tables(&self) -> Tables {
+        let group = self.order() as usize - 1;
+        let cycle = |g: u32| {
+            let mut v = g;
+            (1..=group).find(|_| {
+                let done = v == 1;
+                v = self.mul_slow(v, g);
+                done
+            })
+        };
+        let generator = (1..self.order() as u32)
+            .find(|&g| cycle(g) == Some(group))
+            .expect("the multiplicative group of a field is cyclic");
+
+        let mut exp = vec![0u16; 2 * group];
+        let mut log = vec![0u16; group + 1];
+        let mut v = 1u32;
+        for i in 0..group {
+            exp[i] = v as u16;
+            exp[i + group] = v as u16;
+            log[v as usize] = i as u16;
+            v = self.mul_slow(v, generator);
+        }
+        Tables {
+            generator: generator as u16,
+            exp,
+            log,
+        }
+    }
+}
+
+impl fmt::Debug for DynField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DynField")
+            .field("degree", &self.degree)
+            .field("poly", &format_args!("{:#x}", self.poly))
+            .finish()
+    }
+}
+
+/// An element of a [`DynField`], from [`DynField::elem`].
+///
+/// Operators between elements of different fields panic.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DynGf<'a> {
+    field: &'a DynField,
+    value: u32,
+}
+
+impl DynGf<'_> {
+    pub fn value(self) -> u32 {
+        self.value
+    }
+
+    pub fn inv(self) -> Self {
+        self.field.elem(self.field.inv(self.value))
+    }
+
+    pub fn pow(self, n: u64) -> Self {
+        self.field.elem(self.field.pow(self.value, n))
+    }
+
+    fn same_field(self, rhs: Self) -> bool {
+        std::ptr::eq(self.field, rhs.field)
+            || (self.field.degree, self.field.poly) == (rhs.field.degree, rhs.field.poly)
+    }
+}
+
+impl fmt::Debug for DynGf<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let poly = 1u64 << self.field.degree | self.field.poly;
+        write!(f, "{:#x} mod {:#x}", self.value, poly)
+    }
+}
+
+impl<'a> Add for DynGf<'a> {
+    type Output = DynGf<'a>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        assert!(self.same_field(rhs), "elements of different fields");
+        self.field.elem(self.value ^ rhs.value)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<'a> Sub for DynGf<'a> {
+    type Output = DynGf<'a>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + rhs
+    }
+}
+
+impl<'a> Mul for DynGf<'a> {
+    type Output = DynGf<'a>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert!(self.same_field(rhs), "elements of different fields");
+        self.field.elem(self.field.mul(self.value, rhs.value))
+    }
+}
+
+impl<'a> Div for DynGf<'a> {
+    type Output = DynGf<'a>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(self.same_field(rhs), "elements of different fields");
+        self.field.elem(self.field.div(self.value, rhs.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Galois, Gf32, Gf65536};
+
+    #[test]
+    fn test_fixed_fields() {
+        let f = DynField::new(0x1d, 8).unwrap();
+        assert_eq!(f.generator(), Some(2));
+        for a in 0..=255u8 {
+            for b in (0..=255u8).step_by(3) {
+                assert_eq!(
+                    f.mul(a as u32, b as u32),
+                    u8::from(Galois::new(a) * Galois::new(b)) as u32
+                );
+            }
+        }
+
+        let f = DynField::new(0x100b, 16).unwrap();
+        for a in (0..=u16::MAX).step_by(257) {
+            for b in (1..=u16::MAX).step_by(4099) {
+                let product = Gf65536::new(a) * Gf65536::new(b);
+                assert_eq!(f.mul(a as u32, b as u32), u16::from(product) as u32);
+                assert_eq!(f.div(f.mul(a as u32, b as u32), b as u32), a as u32);
+            }
+        }
+
+        // no tables: carry-less products
+        let f = DynField::new(0xaf, 32).unwrap();
+        assert_eq!(f.generator(), None);
+        for a in (0..u32::MAX).step_by(0x0fed_cba9) {
+            for b in (1..u32::MAX).step_by(0x1234_5677) {
+                let product = Gf32::new(a) * Gf32::new(b);
+                assert_eq!(f.mul(a, b), u32::from(product));
+                let (x, y) = (f.elem(a), f.elem(b));
+                assert_eq!(x * y / y, x);
+            }
+            if a != 0 {
+                assert_eq!(f.inv(a), u32::from(Gf32::new(a).inv()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_other_fields() {
+        // AES's polynomial is not primitive: 3 generates
+        let aes = DynField::new(0x1b, 8).unwrap();
+        assert_eq!(aes.generator(), Some(3));
+        assert_eq!(aes.mul(0x57, 0x83), 0xc1);
+
+        for (poly, degree) in [
+            (0x1, 1),
+            (0x3, 2),
+            (0x5, 5),
+            (0x1b, 13),
+            (0x9, 20),
+            (0x9, 31),
+        ] {
+            let f = DynField::new(poly, degree).unwrap();
+            let group = f.order() - 1;
+            let step = (group / 300).max(1) as usize;
+            for a in (1..f.order() as u32).step_by(step) {
+                let x = f.elem(a);
+                assert_eq!((x * x.inv()).value(), 1);
+                assert_eq!(x.pow(group), f.elem(1));
+                assert_eq!(x.pow(group + 2), x * x);
+                assert_eq!(x + x, f.elem(0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_construction() {
+        assert!(DynField::new(0x1c, 8).is_none());
+        assert!(DynField::new(0x11d, 8).is_none());
+        assert!(DynField::new(0x1, 0).is_none());
+        assert!(DynField::new(0x8d, 33).is_none());
+        let f = DynField::new(0x3, 4).unwrap();
+        assert_eq!(format!("{:?}", f.elem(9)), "0x9 mod 0x13");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_range() {
+        DynField::new(0x3, 4).unwrap().elem(16);
+    }
+}
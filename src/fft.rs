@@ -0,0 +1,177 @@
+//! The additive FFT over GF(256) in the Lin-Chung-Han novel polynomial basis.
+//!
+//! With the subspaces `V_i = {0, 1, .., 2^i - 1}` (spanned by the bits
+//! `1, 2, .., 2^(i-1)`) and their vanishing polynomials `W_i(x) = Π (x - u)`
+//! over `u ∈ V_i`, the basis polynomials are `X_j = Π Ŵ_i^(j_i)` where
+//! `Ŵ_i = W_i / W_i(2^i)` and `j_i` is bit `i` of `j`. A polynomial of
+//! degree `< 2^r` written in this basis is evaluated on any coset `β + V_r`
+//! in `r 2^(r-1)` multiply-adds.
+//!
+//! Every function works on a slice of equally long byte vectors, each byte
+//! position being an independent transform of one symbol per vector, which
+//! is how erasure codes apply the transform to whole shards.
+
+use crate::slice::{add_slice, mul_add_slice};
+use crate::Galois;
+
+/// Evaluates the polynomial with novel-basis coefficients `data` at the
+/// points `shift + l` for `l` in `0..data.len()`, in place.
+///
+/// `data.len()` must be a power of two and `shift` a multiple of it.
+pub fn fft(data: &mut [Vec<u8>], shift: u8) {
+    let n = check_len(data, shift);
+    let mut half = n / 2;
+    while half > 0 {
+        let level = half.trailing_zeros() as usize;
+        for block in (0..n).step_by(2 * half) {
+            let skew = normalized_vanishing(level, Galois::new(shift ^ block as u8));
+            let (lo, hi) = data[block..block + 2 * half].split_at_mut(half);
+            for (a, b) in lo.iter_mut().zip(hi.iter_mut()) {
+                // D(x) = D0(x) + Ŵ(x) D1(x), D(x + 2^level) = D(x) + D1(x)
+                mul_add_slice(skew, b, a);
+                add_slice(a, b);
+            }
+        }
+        half /= 2;
+    }
+}
+
+/// The inverse of [`fft`]: interpolates the values `data` at `shift + l`
+/// into novel-basis coefficients, in place.
+pub fn ifft(data: &mut [Vec<u8>], shift: u8) {
+    let n = check_len(data, shift);
+    let mut half = 1;
+    while half < n {
+        let level = half.trailing_zeros() as usize;
+        for block in (0..n).step_by(2 * half) {
+            let skew = normalized_vanishing(level, Galois::new(shift ^ block as u8));
+            let (lo, hi) = data[block..block + 2 * half].split_at_mut(half);
+            for (a, b) in lo.iter_mut().zip(hi.iter_mut()) {
+                add_slice(a, b);
+                mul_add_slice(skew, b, a);
+            }
+        }
+        half *= 2;
+    }
+}
+
+/// Replaces novel-basis coefficients by those of the formal derivative.
+///
+/// Each `Ŵ_i` is linearized, so its derivative is a constant `c_i` and
+/// `X_j' = Σ c_i X_(j - 2^i)` over the bits `i` set in `j`.
+pub fn formal_derivative(data: &mut [Vec<u8>]) {
+    let n = data.len();
+    let constants: Vec<Galois> = (0..n.max(1).ilog2() as usize)
+        .map(derivative_constant)
+        .collect();
+    // coefficient j only depends on larger indices, so go upwards in place
+    for j in 0..n {
+        let mut acc = vec![0u8; data[j].len()];
+        for (i, c) in constants.iter().enumerate() {
+            let from = j | (1 << i);
+            if from != j && from < n {
+                mul_add_slice(*c, &data[from], &mut acc);
+            }
+        }
+        data[j] = acc;
+    }
+}
+
+// W_i(x) / W_i(2^i)
+pub(crate) fn normalized_vanishing(i: usize, x: Galois) -> Galois {
+    vanishing(i, x) / vanishing(i, Galois::new(1 << i))
+}
+
+// W_i(x) = Π (x - u) over u ∈ V_i
+fn vanishing(i: usize, x: Galois) -> Galois {
+    (0..1usize << i).fold(Galois::identity(), |acc, u| {
+        acc * (x + Galois::new(u as u8))
+    })
+}
+
+// Ŵ_i'(x): the coefficient of x in W_i, i.e. the product of its nonzero
+// roots, over the normalization W_i(2^i).
+fn derivative_constant(i: usize) -> Galois {
+    let roots = (1..1usize << i).fold(Galois::identity(), |acc, u| acc * Galois::new(u as u8));
+    roots / vanishing(i, Galois::new(1 << i))
+}
+
+fn check_len(data: &[Vec<u8>], shift: u8) -> usize {
+    let n = data.len();
+    assert!(
+        n.is_power_of_two() && n <= 256,
+        "length must be a power of two up to 256"
+    );
+    assert_eq!(
+        shift as usize % n,
+        0,
+        "shift must be a multiple of the length"
+    );
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poly::Poly;
+
+    // X_j(x) evaluated directly from its definition
+    fn basis(j: usize, x: Galois) -> Galois {
+        (0..8)
+            .filter(|i| j & (1 << i) != 0)
+            .fold(Galois::identity(), |acc, i| {
+                acc * normalized_vanishing(i, x)
+            })
+    }
+
+    fn column(values: &[u8]) -> Vec<Vec<u8>> {
+        values.iter().map(|v| vec![*v]).collect()
+    }
+
+    #[test]
+    fn test_fft_evaluates() {
+        let coeffs: Vec<u8> = (0..16).map(|i| (i * 37 + 5) as u8).collect();
+        for shift in [0u8, 16, 48, 240] {
+            let mut data = column(&coeffs);
+            fft(&mut data, shift);
+            for (l, v) in data.iter().enumerate() {
+                let x = Galois::new(shift ^ l as u8);
+                let expected = coeffs
+                    .iter()
+                    .enumerate()
+                    .fold(Galois::zero(), |acc, (j, c)| {
+                        acc + Galois::new(*c) * basis(j, x)
+                    });
+                assert_eq!(Galois::new(v[0]), expected);
+            }
+            ifft(&mut data, shift);
+            assert_eq!(data, column(&coeffs));
+        }
+    }
+
+    #[test]
+    fn test_formal_derivative() {
+        // expand D = Σ d_j X_j in the monomial basis and differentiate there
+        let w_hat: Vec<Poly> = (0..5)
+            .map(|i| {
+                let roots: Vec<Galois> = (0..1u8 << i).map(Galois::new).collect();
+                Poly::from_roots(&roots).scale(vanishing(i, Galois::new(1 << i)).inv())
+            })
+            .collect();
+        let coeffs: Vec<u8> = (0..32).map(|i| (i * 91 + 17) as u8).collect();
+        let monomial = coeffs.iter().enumerate().fold(Poly::zero(), |acc, (j, c)| {
+            let x_j = (0..5)
+                .filter(|i| j & (1 << i) != 0)
+                .fold(Poly::one(), |p, i| &p * &w_hat[i]);
+            &acc + &x_j.scale(Galois::new(*c))
+        });
+
+        let mut data = column(&coeffs);
+        formal_derivative(&mut data);
+        fft(&mut data, 0);
+        for (l, v) in data.iter().enumerate() {
+            let x = Galois::new(l as u8);
+            assert_eq!(Galois::new(v[0]), monomial.derivative().eval(x));
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+use crate::Galois;
+
+/// A finite field of characteristic 2 whose elements are stored in shards as
+/// `BYTES` little-endian bytes.
+pub trait Field:
+    Copy
+    + Debug
+    + Default
+    + Eq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+{
+    /// The number of elements.
+    const ORDER: usize;
+    /// The number of bytes per element.
+    const BYTES: usize;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn inv(self) -> Self;
+
+    /// The element with bit pattern `i`, for `i < ORDER`.
+    fn from_index(i: usize) -> Self;
+    fn to_index(self) -> usize;
+
+    /// Reads an element from the first `BYTES` bytes of `bytes`.
+    fn read(bytes: &[u8]) -> Self {
+        let i = bytes[..Self::BYTES]
+            .iter()
+            .rev()
+            .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        Self::from_index(i)
+    }
+
+    /// Writes the element to the first `BYTES` bytes of `bytes`.
+    fn write(self, bytes: &mut [u8]) {
+        let i = self.to_index();
+        for (n, b) in bytes[..Self::BYTES].iter_mut().enumerate() {
+            *b = (i >> (8 * n)) as u8;
+        }
+    }
+}
+
+impl Field for Galois {
+    const ORDER: usize = 256;
+    const BYTES: usize = 1;
+
+    fn zero() -> Self {
+        Galois::zero()
+    }
+
+    fn one() -> Self {
+        Galois::identity()
+    }
+
+    fn inv(self) -> Self {
+        Galois::inv(self)
+    }
+
+    fn from_index(i: usize) -> Self {
+        Galois::new(i as u8)
+    }
+
+    fn to_index(self) -> usize {
+        u8::from(self) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Gf, Gf16, Gf32, Gf65536};
+
+    fn check_field<F: Field>(samples: impl Iterator<Item = usize>) {
+        for i in samples {
+            let a = F::from_index(i);
+            assert_eq!(a.to_index(), i);
+            assert_eq!(a + a, F::zero());
+            if a != F::zero() {
+                assert_eq!(a * a.inv(), F::one());
+            }
+            let mut bytes = [0u8; 8];
+            a.write(&mut bytes);
+            assert_eq!(F::read(&bytes), a);
+        }
+    }
+
+    #[test]
+    fn test_fields() {
+        check_field::<Galois>(0..256);
+        check_field::<Gf<0x11b>>(0..256);
+        check_field::<Gf16>(0..16);
+        check_field::<Gf65536>((0..65536).step_by(97));
+        #[cfg(target_pointer_width = "64")]
+        check_field::<Gf32>((0..1 << 32).step_by(0x00ff_ffff));
+    }
+}
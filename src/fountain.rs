@@ -0,0 +1,362 @@
+//! A random linear fountain code: rateless erasure coding over GF(256).
+//!
+//! A [`FountainEncoder`] cuts data into `k` source symbols and emits an
+//! endless stream of random linear combinations of them. Each
+//! [`FountainSymbol`] carries only the seed its coefficients are derived
+//! from, and a [`FountainDecoder`] recovers the data from any `k` symbols
+//! whose coefficients are linearly independent, whichever ones are lost.
+//! Over GF(256) a random symbol is independent of the ones before with
+//! probability at least `1 - 1/256`, so `k` or a couple more almost always
+//! do.
+//!
+//! [`LtEncoder`] and [`LtDecoder`] are the sparse, LT-style alternative for
+//! large `k`: symbols are the XOR of a few source symbols, so encoding and
+//! peeling cost `O(log k)` per symbol instead of `O(k)`. [`FountainCodec`]
+//! uses a fixed number of symbols as an [`ErasureCodec`].
+
+use crate::rs::{ErasureCodec, Error};
+use crate::slice::{mul_add_slice, scale_slice};
+use crate::Galois;
+
+mod lt;
+pub use lt::{LtDecoder, LtEncoder};
+
+/// One encoded symbol: the combination with the coefficients derived from
+/// `seed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FountainSymbol {
+    pub seed: u32,
+    pub data: Vec<u8>,
+}
+
+impl FountainSymbol {
+    /// The symbol as sent: the seed as four big-endian bytes, then the data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(4 + self.data.len());
+        out.extend_from_slice(&self.seed.to_be_bytes());
+        out.extend_from_slice(&self.data);
+        out
+    }
+
+    /// Parses a symbol written by [`to_bytes`](FountainSymbol::to_bytes),
+    /// returning `None` if it is shorter than the header.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (seed, data) = bytes.split_first_chunk::<4>()?;
+        Some(FountainSymbol {
+            seed: u32::from_be_bytes(*seed),
+            data: data.to_vec(),
+        })
+    }
+
+    /// The `k` coefficients of the source symbols in this symbol.
+    pub fn coefficients(&self, k: usize) -> Vec<u8> {
+        coefficients(self.seed, k)
+    }
+}
+
+// splitmix64, which spreads consecutive seeds over unrelated values
+#[derive(Debug, Clone)]
+pub(crate) struct SplitMix(pub(crate) u64);
+
+impl SplitMix {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+fn coefficients(seed: u32, k: usize) -> Vec<u8> {
+    random_bytes(&mut SplitMix(u64::from(seed)), k)
+}
+
+pub(crate) fn random_bytes(rng: &mut SplitMix, len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len);
+    while out.len() < len {
+        let take = (len - out.len()).min(8);
+        out.extend_from_slice(&rng.next().to_le_bytes()[..take]);
+    }
+    out
+}
+
+/// Emits symbols for `k` source symbols, as an endless iterator.
+#[derive(Debug, Clone)]
+pub struct FountainEncoder {
+    symbol_len: usize,
+    source: Vec<Vec<u8>>,
+    next_seed: u32,
+}
+
+impl FountainEncoder {
+    /// Cuts `data` into source symbols of `symbol_len` bytes, zero-padding
+    /// the last one.
+    pub fn new(data: &[u8], symbol_len: usize) -> Self {
+        assert!(symbol_len > 0, "symbol length must be positive");
+        assert!(!data.is_empty(), "no data to encode");
+        let source = data
+            .chunks(symbol_len)
+            .map(|chunk| {
+                let mut symbol = chunk.to_vec();
+                symbol.resize(symbol_len, 0);
+                symbol
+            })
+            .collect();
+        FountainEncoder {
+            symbol_len,
+            source,
+            next_seed: 0,
+        }
+    }
+
+    /// The number of source symbols.
+    pub fn k(&self) -> usize {
+        self.source.len()
+    }
+
+    pub fn symbol_len(&self) -> usize {
+        self.symbol_len
+    }
+
+    /// The symbol for `seed`, independently of the iterator.
+    pub fn symbol(&self, seed: u32) -> FountainSymbol {
+        let mut data = vec![0u8; self.symbol_len];
+        for (c, s) in coefficients(seed, self.k()).into_iter().zip(&self.source) {
+            mul_add_slice(Galois(c), s, &mut data);
+        }
+        FountainSymbol { seed, data }
+    }
+}
+
+impl Iterator for FountainEncoder {
+    type Item = FountainSymbol;
+
+    fn next(&mut self) -> Option<FountainSymbol> {
+        let symbol = self.symbol(self.next_seed);
+        self.next_seed = self.next_seed.wrapping_add(1);
+        Some(symbol)
+    }
+}
+
+/// Collects symbols until the `k` source symbols can be solved for.
+///
+/// Every symbol is reduced against the ones kept so far as it arrives, so
+/// the work is spread over the reception and a symbol that adds nothing is
+/// dropped at once.
+#[derive(Debug, Clone)]
+pub struct FountainDecoder {
+    symbol_len: usize,
+    // rows[p] has its first nonzero coefficient, 1, at p
+    rows: Vec<Option<(Vec<u8>, Vec<u8>)>>,
+    rank: usize,
+}
+
+impl FountainDecoder {
+    pub fn new(k: usize, symbol_len: usize) -> Self {
+        assert!(k > 0, "need at least one source symbol");
+        FountainDecoder {
+            symbol_len,
+            rows: vec![None; k],
+            rank: 0,
+        }
+    }
+
+    /// The number of independent symbols received.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.rank == self.rows.len()
+    }
+
+    /// Adds a symbol, returning whether it was independent of the ones
+    /// before.
+    pub fn push(&mut self, symbol: &FountainSymbol) -> bool {
+        assert_eq!(symbol.data.len(), self.symbol_len, "symbol length mismatch");
+        if self.is_complete() {
+            return false;
+        }
+        self.push_row(symbol.coefficients(self.rows.len()), symbol.data.clone())
+    }
+
+    // Adds the equation `Σ coeffs[i] s_i = data`.
+    pub(crate) fn push_row(&mut self, mut coeffs: Vec<u8>, mut data: Vec<u8>) -> bool {
+        for pivot in 0..self.rows.len() {
+            let c = Galois(coeffs[pivot]);
+            if c == Galois::zero() {
+                continue;
+            }
+            match &self.rows[pivot] {
+                Some((row, row_data)) => {
+                    mul_add_slice(c, &row[pivot..], &mut coeffs[pivot..]);
+                    mul_add_slice(c, row_data, &mut data);
+                }
+                None => {
+                    let inv = c.inv();
+                    scale_slice(inv, &mut coeffs[pivot..]);
+                    scale_slice(inv, &mut data);
+                    self.rows[pivot] = Some((coeffs, data));
+                    self.rank += 1;
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    // The equations kept, as (coefficients, data) pairs.
+    pub(crate) fn rows(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.rows.iter().flatten().map(|(c, d)| (&c[..], &d[..]))
+    }
+
+    /// The `k * symbol_len` bytes of source data, padding included, once
+    /// [`is_complete`](FountainDecoder::is_complete).
+    pub fn data(&self) -> Option<Vec<u8>> {
+        if !self.is_complete() {
+            return None;
+        }
+        let k = self.rows.len();
+        let mut rows: Vec<(Vec<u8>, Vec<u8>)> = self.rows.iter().flatten().cloned().collect();
+        // back-substitute from the last pivot up
+        for p in (1..k).rev() {
+            let (before, after) = rows.split_at_mut(p);
+            let pivot = &after[0].1;
+            for (coeffs, data) in before {
+                mul_add_slice(Galois(coeffs[p]), pivot, data);
+            }
+        }
+        Some(rows.into_iter().flat_map(|(_, data)| data).collect())
+    }
+}
+
+/// A fixed-rate use of the fountain as an [`ErasureCodec`]: the `k` data
+/// shards are sent as they are, followed by the symbols for seeds `0..m`.
+///
+/// Unlike Reed-Solomon, `k` shards only almost always suffice: a choice of
+/// them is rank-deficient with probability about `1/256`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FountainCodec {
+    data_shards: usize,
+    parity_shards: usize,
+}
+
+impl FountainCodec {
+    pub fn new(data_shards: usize, parity_shards: usize) -> Self {
+        assert!(data_shards > 0, "need at least one data shard");
+        FountainCodec {
+            data_shards,
+            parity_shards,
+        }
+    }
+}
+
+impl ErasureCodec for FountainCodec {
+    fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        if data.len() != self.data_shards {
+            return Err(Error::WrongShardCount);
+        }
+        let len = data[0].len();
+        if data.iter().any(|d| d.len() != len) {
+            return Err(Error::ShardSizeMismatch);
+        }
+        Ok((0..self.parity_shards as u32)
+            .map(|seed| {
+                let mut out = vec![0u8; len];
+                for (c, d) in coefficients(seed, self.data_shards).into_iter().zip(data) {
+                    mul_add_slice(Galois(c), d, &mut out);
+                }
+                out
+            })
+            .collect())
+    }
+
+    fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        let k = self.data_shards;
+        if shards.len() != self.shards() {
+            return Err(Error::WrongShardCount);
+        }
+        let len = match shards.iter().flatten().next() {
+            Some(s) => s.len(),
+            None => return Err(Error::TooFewShards),
+        };
+        if shards.iter().flatten().any(|s| s.len() != len) {
+            return Err(Error::ShardSizeMismatch);
+        }
+        if shards.iter().all(Option::is_some) {
+            return Ok(());
+        }
+
+        let mut decoder = FountainDecoder::new(k, len);
+        for (i, shard) in shards.iter().enumerate() {
+            if let Some(s) = shard {
+                let coeffs = if i < k {
+                    (0..k).map(|c| (c == i) as u8).collect()
+                } else {
+                    coefficients((i - k) as u32, k)
+                };
+                decoder.push_row(coeffs, s.clone());
+            }
+            if decoder.is_complete() {
+                break;
+            }
+        }
+        let data = decoder.data().ok_or(Error::TooFewShards)?;
+        let data: Vec<&[u8]> = data.chunks(len).collect();
+        let parity = self.encode(&data)?;
+        for (slot, shard) in shards
+            .iter_mut()
+            .zip(data.into_iter().map(<[u8]>::to_vec).chain(parity))
+        {
+            if slot.is_none() {
+                *slot = Some(shard);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fountain_round_trip() {
+        let data: Vec<u8> = (0..1000).map(|i| (i * 17 + 3) as u8).collect();
+        let encoder = FountainEncoder::new(&data, 64);
+        assert_eq!(encoder.k(), 16);
+        let mut decoder = FountainDecoder::new(16, 64);
+
+        // lose two symbols in three; the wire format round-trips
+        let mut received = 0;
+        for symbol in encoder.clone().skip(5).step_by(3) {
+            let symbol = FountainSymbol::from_bytes(&symbol.to_bytes()).unwrap();
+            received += 1;
+            decoder.push(&symbol);
+            if decoder.is_complete() {
+                break;
+            }
+        }
+        assert!(received < 16 + 4, "{received} symbols");
+        let out = decoder.data().unwrap();
+        assert_eq!(out.len(), 1024);
+        assert_eq!(out[..1000], data[..]);
+
+        // a repeated symbol adds nothing
+        let mut decoder = FountainDecoder::new(16, 64);
+        assert!(decoder.push(&encoder.symbol(7)));
+        assert!(!decoder.push(&encoder.symbol(7)));
+        assert_eq!(decoder.rank(), 1);
+        assert_eq!(decoder.data(), None);
+        assert_eq!(FountainSymbol::from_bytes(&[1, 2]), None);
+    }
+}
@@ -0,0 +1,436 @@
+use super::{random_bytes, FountainDecoder, FountainSymbol, SplitMix};
+use crate::slice::{add_slice, mul_add_slice};
+use crate::Galois;
+
+// The robust soliton parameters, as commonly used for LT codes.
+const SOLITON_C: f64 = 0.1;
+const SOLITON_DELTA: f64 = 0.05;
+
+// `k` source symbols and `precode` dense GF(256) checks on them, which make
+// up the `k + precode` intermediate symbols that LT symbols combine.
+#[derive(Debug, Clone)]
+struct Shape {
+    k: usize,
+    precode: usize,
+    // cdf[d - 1] is the probability of a degree of at most `d`
+    cdf: Vec<f64>,
+}
+
+impl Shape {
+    fn new(k: usize, precode: usize) -> Self {
+        let l = k + precode;
+        let lf = l as f64;
+        let r = SOLITON_C * (lf / SOLITON_DELTA).ln() * lf.sqrt();
+        let spike = ((lf / r).floor() as usize).clamp(1, l);
+        let weights: Vec<f64> = (1..=l)
+            .map(|d| {
+                let df = d as f64;
+                let rho = if d == 1 {
+                    1.0 / lf
+                } else {
+                    1.0 / (df * (df - 1.0))
+                };
+                let tau = match d.cmp(&spike) {
+                    std::cmp::Ordering::Less => r / (df * lf),
+                    std::cmp::Ordering::Equal => r * (r / SOLITON_DELTA).ln() / lf,
+                    std::cmp::Ordering::Greater => 0.0,
+                };
+                rho + tau.max(0.0)
+            })
+            .collect();
+        let total: f64 = weights.iter().sum();
+        let mut acc = 0.0;
+        let cdf = weights
+            .iter()
+            .map(|w| {
+                acc += w / total;
+                acc
+            })
+            .collect();
+        Shape { k, precode, cdf }
+    }
+
+    fn len(&self) -> usize {
+        self.k + self.precode
+    }
+
+    // The distinct intermediate symbols XORed into the symbol for `seed`.
+    fn neighbours(&self, seed: u32) -> Vec<usize> {
+        let mut rng = SplitMix(u64::from(seed));
+        let u = (rng.next() >> 11) as f64 / (1u64 << 53) as f64;
+        let degree = self
+            .cdf
+            .iter()
+            .position(|c| u < *c)
+            .unwrap_or(self.len() - 1)
+            + 1;
+        let mut out = Vec::with_capacity(degree);
+        while out.len() < degree {
+            let i = (rng.next() % self.len() as u64) as usize;
+            if !out.contains(&i) {
+                out.push(i);
+            }
+        }
+        out
+    }
+
+    // The coefficients of the source symbols in precode symbol `j`, from a
+    // stream no symbol seed reaches.
+    fn precode_row(&self, j: usize) -> Vec<u8> {
+        random_bytes(&mut SplitMix((1 << 32) + j as u64), self.k)
+    }
+}
+
+/// Emits LT symbols for `k` source symbols, as an endless iterator.
+///
+/// Symbol degrees follow the robust soliton distribution. With a precode,
+/// as in Raptor codes, the symbols also cover a few dense checks on the
+/// source, which lets the decoder finish with fewer extra symbols.
+#[derive(Debug, Clone)]
+pub struct LtEncoder {
+    shape: Shape,
+    symbol_len: usize,
+    intermediate: Vec<Vec<u8>>,
+    next_seed: u32,
+}
+
+impl LtEncoder {
+    /// Cuts `data` into source symbols of `symbol_len` bytes, zero-padding
+    /// the last one.
+    pub fn new(data: &[u8], symbol_len: usize) -> Self {
+        assert!(symbol_len > 0, "symbol length must be positive");
+        assert!(!data.is_empty(), "no data to encode");
+        let intermediate: Vec<Vec<u8>> = data
+            .chunks(symbol_len)
+            .map(|chunk| {
+                let mut symbol = chunk.to_vec();
+                symbol.resize(symbol_len, 0);
+                symbol
+            })
+            .collect();
+        LtEncoder {
+            shape: Shape::new(intermediate.len(), 0),
+            symbol_len,
+            intermediate,
+            next_seed: 0,
+        }
+    }
+
+    /// Adds `precode` check symbols; the decoder must be given the same.
+    pub fn with_precode(mut self, precode: usize) -> Self {
+        let k = self.shape.k;
+        self.shape = Shape::new(k, precode);
+        self.intermediate.truncate(k);
+        for j in 0..precode {
+            let mut check = vec![0u8; self.symbol_len];
+            for (c, s) in self
+                .shape
+                .precode_row(j)
+                .into_iter()
+                .zip(&self.intermediate)
+            {
+                mul_add_slice(Galois(c), s, &mut check);
+            }
+            self.intermediate.push(check);
+        }
+        self
+    }
+
+    /// The number of source symbols.
+    pub fn k(&self) -> usize {
+        self.shape.k
+    }
+
+    pub fn symbol_len(&self) -> usize {
+        self.symbol_len
+    }
+
+    /// The symbol for `seed`, independently of the iterator.
+    pub fn symbol(&self, seed: u32) -> FountainSymbol {
+        let mut data = vec![0u8; self.symbol_len];
+        for i in self.shape.neighbours(seed) {
+            add_slice(&self.intermediate[i], &mut data);
+        }
+        FountainSymbol { seed, data }
+    }
+}
+
+impl Iterator for LtEncoder {
+    type Item = FountainSymbol;
+
+    fn next(&mut self) -> Option<FountainSymbol> {
+        let symbol = self.symbol(self.next_seed);
+        self.next_seed = self.next_seed.wrapping_add(1);
+        Some(symbol)
+    }
+}
+
+/// Decodes LT symbols by peeling, with Gaussian elimination once peeling
+/// stalls.
+///
+/// A symbol with a single unknown neighbour reveals it, which in turn may
+/// leave other symbols with one. When that chain runs dry but the symbols
+/// held and the precode checks are enough equations for the intermediate
+/// symbols still unknown, they are solved for directly: from then on each
+/// new symbol is reduced against the equations kept so far, as in
+/// [`FountainDecoder`].
+#[derive(Debug, Clone)]
+pub struct LtDecoder {
+    shape: Shape,
+    symbol_len: usize,
+    symbols: Vec<Option<Vec<u8>>>,
+    // received symbols with at least two unknown neighbours left
+    pending: Vec<(Vec<usize>, Vec<u8>)>,
+    // the pending symbols each intermediate symbol is a neighbour of
+    waiting: Vec<Vec<usize>>,
+    // the elimination, once peeling has stalled with enough equations
+    dense: Option<Dense>,
+}
+
+// Gaussian elimination over the intermediate symbols unknown when peeling
+// stalled, which stay the unknowns until it completes.
+#[derive(Debug, Clone)]
+struct Dense {
+    solver: FountainDecoder,
+    unknown: Vec<usize>,
+    // column[i] is the column of intermediate symbol i, if unknown
+    column: Vec<usize>,
+}
+
+impl Dense {
+    fn new(unknown: Vec<usize>, len: usize, symbol_len: usize) -> Self {
+        let mut column = vec![usize::MAX; len];
+        for (c, i) in unknown.iter().enumerate() {
+            column[*i] = c;
+        }
+        Dense {
+            solver: FountainDecoder::new(unknown.len(), symbol_len),
+            unknown,
+            column,
+        }
+    }
+
+    // Adds the equation that the unknown `neighbours` sum to `data`.
+    fn push(&mut self, neighbours: &[usize], data: Vec<u8>) {
+        let mut coeffs = vec![0u8; self.unknown.len()];
+        for n in neighbours {
+            coeffs[self.column[*n]] = 1;
+        }
+        self.solver.push_row(coeffs, data);
+    }
+}
+
+impl LtDecoder {
+    pub fn new(k: usize, symbol_len: usize) -> Self {
+        assert!(k > 0, "need at least one source symbol");
+        LtDecoder::with_shape(Shape::new(k, 0), symbol_len)
+    }
+
+    /// Expects `precode` check symbols, as given to
+    /// [`LtEncoder::with_precode`].
+    pub fn with_precode(self, precode: usize) -> Self {
+        LtDecoder::with_shape(Shape::new(self.shape.k, precode), self.symbol_len)
+    }
+
+    fn with_shape(shape: Shape, symbol_len: usize) -> Self {
+        LtDecoder {
+            symbol_len,
+            symbols: vec![None; shape.len()],
+            pending: Vec::new(),
+            waiting: vec![Vec::new(); shape.len()],
+            dense: None,
+            shape,
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.symbols[..self.shape.k].iter().all(Option::is_some)
+    }
+
+    /// Adds a symbol, returning whether the source symbols are now all
+    /// known.
+    pub fn push(&mut self, symbol: &FountainSymbol) -> bool {
+        assert_eq!(symbol.data.len(), self.symbol_len, "symbol length mismatch");
+        if self.is_complete() {
+            return true;
+        }
+        let mut data = symbol.data.clone();
+        let mut neighbours = self.shape.neighbours(symbol.seed);
+        neighbours.retain(|i| match &self.symbols[*i] {
+            Some(s) => {
+                add_slice(s, &mut data);
+                false
+            }
+            None => true,
+        });
+        if let Some(dense) = &mut self.dense {
+            if !neighbours.is_empty() {
+                dense.push(&neighbours, data);
+            }
+            return self.finish();
+        }
+        match neighbours.len() {
+            0 => {}
+            1 => self.peel(neighbours[0], data),
+            _ => {
+                for i in &neighbours {
+                    self.waiting[*i].push(self.pending.len());
+                }
+                self.pending.push((neighbours, data));
+            }
+        }
+        if !self.is_complete() {
+            self.eliminate();
+        }
+        self.is_complete()
+    }
+
+    /// The `k * symbol_len` bytes of source data, padding included, once
+    /// [`is_complete`](LtDecoder::is_complete).
+    pub fn data(&self) -> Option<Vec<u8>> {
+        self.symbols[..self.shape.k]
+            .iter()
+            .map(|s| s.as_deref())
+            .collect::<Option<Vec<&[u8]>>>()
+            .map(|s| s.concat())
+    }
+
+    // Records intermediate symbol `i` and everything that follows from it.
+    fn peel(&mut self, i: usize, data: Vec<u8>) {
+        let mut queue = vec![(i, data)];
+        while let Some((i, data)) = queue.pop() {
+            if self.symbols[i].is_some() {
+                continue;
+            }
+            for id in std::mem::take(&mut self.waiting[i]) {
+                let (neighbours, rest) = &mut self.pending[id];
+                if neighbours.is_empty() {
+                    continue;
+                }
+                add_slice(&data, rest);
+                neighbours.retain(|n| *n != i);
+                if let [n] = neighbours[..] {
+                    neighbours.clear();
+                    queue.push((n, std::mem::take(rest)));
+                }
+            }
+            self.symbols[i] = Some(data);
+        }
+    }
+
+    // Starts the elimination over the unknown intermediate symbols once
+    // there are enough equations for them, leaving everything as is
+    // otherwise.
+    fn eliminate(&mut self) {
+        let unknown: Vec<usize> = (0..self.shape.len())
+            .filter(|i| self.symbols[*i].is_none())
+            .collect();
+        let live = self.pending.iter().filter(|(n, _)| !n.is_empty()).count();
+        if live + self.shape.precode < unknown.len() {
+            return;
+        }
+
+        let mut dense = Dense::new(unknown, self.shape.len(), self.symbol_len);
+        for (neighbours, data) in std::mem::take(&mut self.pending) {
+            if !neighbours.is_empty() {
+                dense.push(&neighbours, data);
+            }
+        }
+        self.waiting.iter_mut().for_each(Vec::clear);
+        // Σ a_ji s_i + c_j = 0, with the known terms moved to the right
+        for j in 0..self.shape.precode {
+            let mut coeffs = vec![0u8; dense.unknown.len()];
+            let mut data = vec![0u8; self.symbol_len];
+            let row = self.shape.precode_row(j);
+            let terms = row
+                .into_iter()
+                .chain((0..self.shape.precode).map(|p| (p == j) as u8));
+            for (i, a) in terms.enumerate() {
+                match &self.symbols[i] {
+                    Some(s) => mul_add_slice(Galois(a), s, &mut data),
+                    None => coeffs[dense.column[i]] = a,
+                }
+            }
+            dense.solver.push_row(coeffs, data);
+            if dense.solver.is_complete() {
+                break;
+            }
+        }
+        self.dense = Some(dense);
+        self.finish();
+    }
+
+    // Records the unknown intermediate symbols once the elimination has
+    // enough independent equations, returning whether the decoding is
+    // complete.
+    fn finish(&mut self) -> bool {
+        let Some(dense) = &self.dense else {
+            return self.is_complete();
+        };
+        if let Some(solved) = dense.solver.data() {
+            for (i, s) in dense.unknown.iter().zip(solved.chunks(self.symbol_len)) {
+                self.symbols[*i] = Some(s.to_vec());
+            }
+            self.dense = None;
+        }
+        self.is_complete()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lt_round_trip() {
+        let data: Vec<u8> = (0..200 * 16).map(|i| (i * 29 + i / 7) as u8).collect();
+        for precode in [0, 10] {
+            let encoder = LtEncoder::new(&data, 16).with_precode(precode);
+            assert_eq!(encoder.k(), 200);
+            let mut decoder = LtDecoder::new(200, 16).with_precode(precode);
+            let mut received = 0;
+            // lose every fourth symbol
+            for (n, symbol) in encoder.clone().enumerate() {
+                if n % 4 == 3 {
+                    continue;
+                }
+                received += 1;
+                if decoder.push(&symbol) {
+                    break;
+                }
+                assert!(received < 400, "precode {precode}");
+            }
+            assert!(received < 220, "precode {precode}: {received} symbols");
+            assert_eq!(decoder.data().unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_dense_phase() {
+        // the elimination, once started, is kept and extended symbol by
+        // symbol rather than rebuilt
+        let data: Vec<u8> = (0..300 * 8).map(|i| (i * 13 + 7) as u8).collect();
+        let encoder = LtEncoder::new(&data, 8);
+        let mut decoder = LtDecoder::new(300, 8);
+        let mut ranks = Vec::new();
+        for symbol in encoder.step_by(2) {
+            if decoder.push(&symbol) {
+                break;
+            }
+            if let Some(dense) = &decoder.dense {
+                assert!(decoder.pending.is_empty());
+                ranks.push(dense.solver.rank());
+            }
+        }
+        assert!(!ranks.is_empty());
+        assert!(ranks.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(decoder.data().unwrap(), data);
+    }
+
+    #[test]
+    fn test_sparse_symbols() {
+        let shape = Shape::new(10_000, 0);
+        let degrees: usize = (0..1000).map(|s| shape.neighbours(s).len()).sum();
+        assert!(degrees < 1000 * 20, "mean degree {}", degrees / 1000);
+    }
+}
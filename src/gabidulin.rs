@@ -0,0 +1,328 @@
+//! Gabidulin codes, the rank-metric analogue of RS codes.
+//!
+//! Viewing each GF(256) symbol as a column of 8 bits, a word of `n` symbols
+//! is an `8 × n` binary matrix, and the rank distance between two words is
+//! the rank of their difference. A Gabidulin code of length `n <= 8` and
+//! dimension `k` evaluates the linearized polynomial
+//! `f(x) = Σ m_i x^(2^i)` of the message at `n` points `g_j` that are
+//! linearly independent over GF(2), so its generator matrix is the `k × n`
+//! Moore matrix of the points. It corrects every error of rank up to
+//! `(n - k) / 2`, however many symbols the error touches.
+//!
+//! Decoding is by interpolation: find `V` of q-degree at most `t` and `N`
+//! of q-degree below `k + t` with `V(y_j) = N(g_j)` for the received `y`,
+//! then `N = V ∘ f`.
+
+use crate::poly::LinearizedPoly;
+use crate::rs::CodeError;
+use crate::{Galois, GfMatrix};
+
+/// The rank over GF(2) of the bytes of `v`, i.e. the rank weight of `v`.
+pub fn rank_weight(v: &[u8]) -> usize {
+    // an XOR basis with distinct leading bits
+    let mut basis = [0u8; 8];
+    let mut rank = 0;
+    for x in v {
+        let mut x = *x;
+        while x != 0 {
+            let top = 7 - x.leading_zeros() as usize;
+            if basis[top] == 0 {
+                basis[top] = x;
+                rank += 1;
+                break;
+            }
+            x ^= basis[top];
+        }
+    }
+    rank
+}
+
+/// A Gabidulin code over GF(256) of length at most 8.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GabidulinCode {
+    k: usize,
+    points: Vec<Galois>,
+    generator: GfMatrix,
+}
+
+impl GabidulinCode {
+    /// Fails unless `0 < k < points.len()` and the points are linearly
+    /// independent over GF(2), which limits them to 8.
+    pub fn new(points: Vec<Galois>, k: usize) -> Result<Self, CodeError> {
+        let bytes: Vec<u8> = points.iter().map(|p| u8::from(*p)).collect();
+        if k == 0 || k >= points.len() || rank_weight(&bytes) != points.len() {
+            return Err(CodeError::InvalidParameters);
+        }
+        let generator = GfMatrix::moore(&points, k);
+        Ok(GabidulinCode {
+            k,
+            points,
+            generator,
+        })
+    }
+
+    /// The code on the points `1, 2, 4, .., 2^(n-1)`.
+    pub fn standard(n: usize, k: usize) -> Result<Self, CodeError> {
+        if n > 8 {
+            return Err(CodeError::InvalidParameters);
+        }
+        GabidulinCode::new((0..n).map(|i| Galois::new(1 << i)).collect(), k)
+    }
+
+    pub fn n(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn points(&self) -> &[Galois] {
+        &self.points
+    }
+
+    /// The `k × n` Moore matrix of the points.
+    pub fn generator(&self) -> &GfMatrix {
+        &self.generator
+    }
+
+    /// The rank of the errors the code can always correct.
+    pub fn capability(&self) -> usize {
+        (self.n() - self.k) / 2
+    }
+
+    /// Returns the codeword `(f(g_j))_j` for the message coefficients `msg`.
+    pub fn encode(&self, msg: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if msg.len() != self.k {
+            return Err(CodeError::WrongLength);
+        }
+        let f = LinearizedPoly::new(msg.iter().map(|m| Galois::new(*m)).collect());
+        Ok(self.points.iter().map(|g| u8::from(f.eval(*g))).collect())
+    }
+
+    /// Corrects `codeword` in place, returning the rank of the error.
+    pub fn decode(&self, codeword: &mut [u8]) -> Result<usize, CodeError> {
+        self.decode_with_erasures(codeword, &[])
+    }
+
+    /// Corrects `codeword` in place given the positions of erased symbols,
+    /// returning the rank of the error on the others.
+    ///
+    /// Dropping `s` symbols leaves a Gabidulin code of length `n - s`, so an
+    /// error of rank `r` is corrected whenever `2r + s <= n - k`. The erased
+    /// bytes may hold any value on input.
+    pub fn decode_with_erasures(
+        &self,
+        codeword: &mut [u8],
+        erasures: &[usize],
+    ) -> Result<usize, CodeError> {
+        if codeword.len() != self.n() || erasures.iter().any(|e| *e >= self.n()) {
+            return Err(CodeError::WrongLength);
+        }
+        let kept: Vec<usize> = (0..self.n()).filter(|j| !erasures.contains(j)).collect();
+        if kept.len() < self.k {
+            return Err(CodeError::TooManyErrors);
+        }
+        let t = (kept.len() - self.k) / 2;
+
+        // unknowns V_0..=V_t then N_0..N_(k+t), one equation per kept symbol
+        let unknowns = 2 * t + self.k + 1;
+        let rows: Vec<Vec<Galois>> = kept
+            .iter()
+            .map(|j| {
+                let (y, g) = (Galois::new(codeword[*j]), self.points[*j]);
+                let mut row = Vec::with_capacity(unknowns);
+                row.extend(successive_squares(y, t + 1));
+                row.extend(successive_squares(g, self.k + t));
+                row
+            })
+            .collect();
+        // with as many independent equations as unknowns only zero solves
+        // the system, and no polynomial pair explains the word
+        let solution = kernel_vector(rows, unknowns).ok_or(CodeError::TooManyErrors)?;
+        let v = LinearizedPoly::new(solution[..=t].to_vec());
+        let n = LinearizedPoly::new(solution[t + 1..].to_vec());
+        if v.is_zero() {
+            return Err(CodeError::TooManyErrors);
+        }
+
+        let (f, rem) = n.div_rem_left(&v);
+        if !rem.is_zero() || f.q_degree().is_some_and(|d| d >= self.k) {
+            return Err(CodeError::TooManyErrors);
+        }
+        let msg: Vec<u8> = (0..self.k).map(|i| u8::from(f.coeff(i))).collect();
+        let corrected = self.encode(&msg)?;
+
+        let error: Vec<u8> = kept.iter().map(|j| codeword[*j] ^ corrected[*j]).collect();
+        let rank = rank_weight(&error);
+        if rank > t {
+            return Err(CodeError::TooManyErrors);
+        }
+        codeword.copy_from_slice(&corrected);
+        Ok(rank)
+    }
+}
+
+// x, x^2, x^4, .. for `count` terms.
+fn successive_squares(x: Galois, count: usize) -> impl Iterator<Item = Galois> {
+    std::iter::successors(Some(x), |p| Some(*p * *p)).take(count)
+}
+
+// A nonzero solution of the homogeneous system `rows`, or `None` if it has
+// `cols` independent rows.
+fn kernel_vector(mut rows: Vec<Vec<Galois>>, cols: usize) -> Option<Vec<Galois>> {
+    let mut pivots = Vec::new();
+    let mut rank = 0;
+    for col in 0..cols {
+        let Some(p) = (rank..rows.len()).find(|r| rows[*r][col] != Galois::zero()) else {
+            continue;
+        };
+        rows.swap(rank, p);
+        let inv = rows[rank][col].inv();
+        rows[rank].iter_mut().for_each(|v| *v *= inv);
+        let pivot = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let c = row[col];
+            if r != rank && c != Galois::zero() {
+                row.iter_mut().zip(&pivot).for_each(|(a, b)| *a -= c * *b);
+            }
+        }
+        pivots.push(col);
+        rank += 1;
+    }
+
+    // set the first free unknown to one and solve for the pivots
+    let free = (0..cols).find(|c| !pivots.contains(c))?;
+    let mut x = vec![Galois::zero(); cols];
+    x[free] = Galois::identity();
+    for (r, col) in pivots.iter().enumerate() {
+        x[*col] = rows[r][free];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lfsr::LfsrRng;
+
+    fn message(k: usize, seed: usize) -> Vec<u8> {
+        (0..k).map(|i| (i * 97 + seed * 23 + 1) as u8).collect()
+    }
+
+    // An error of rank `rank` spread over every symbol: each byte is a
+    // combination of the first `rank` of a few fixed independent bytes.
+    fn error(n: usize, rank: usize, seed: usize) -> Vec<u8> {
+        let basis = [0x1d, 0x62, 0x85, 0x0b, 0xf0, 0x37, 0x4c, 0xa9];
+        let mut e: Vec<u8> = (0..n)
+            .map(|j| {
+                let mask = (j * 5 + seed * 3 + 1) as u8;
+                (0..rank)
+                    .filter(|b| mask >> b & 1 == 1)
+                    .fold(0, |acc, b| acc ^ basis[b])
+            })
+            .collect();
+        // make sure every basis vector appears
+        for (b, v) in basis.iter().take(rank).enumerate() {
+            e[b % n] ^= v;
+        }
+        e
+    }
+
+    #[test]
+    fn test_encode_is_moore() {
+        let code = GabidulinCode::standard(8, 3).unwrap();
+        let msg = message(3, 0);
+        let row = GfMatrix::from_vec(1, 3, msg.iter().map(|m| Galois::new(*m)).collect());
+        let expected: Vec<u8> = (&row * code.generator())
+            .into_vec()
+            .into_iter()
+            .map(u8::from)
+            .collect();
+        assert_eq!(code.encode(&msg).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_rank_errors() {
+        let points: Vec<Galois> = [3, 5, 9, 17, 33, 65, 129].map(Galois::new).to_vec();
+        for code in [
+            GabidulinCode::standard(8, 2).unwrap(),
+            GabidulinCode::standard(8, 4).unwrap(),
+            GabidulinCode::new(points, 3).unwrap(),
+        ] {
+            let n = code.n();
+            for seed in 0..6 {
+                let original = code.encode(&message(code.k(), seed)).unwrap();
+                for rank in 0..=code.capability() {
+                    let e = error(n, rank, seed);
+                    assert_eq!(rank_weight(&e), rank);
+                    let mut received: Vec<u8> =
+                        original.iter().zip(&e).map(|(c, e)| c ^ e).collect();
+                    assert_eq!(code.decode(&mut received), Ok(rank));
+                    assert_eq!(received, original);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_erasures() {
+        let code = GabidulinCode::standard(8, 2).unwrap();
+        let original = code.encode(&message(2, 4)).unwrap();
+        for erased in 0..=6 {
+            let rank = (6 - erased) / 2;
+            let erasures: Vec<usize> = (0..erased).map(|i| (i * 3) % 8).collect();
+            let mut received: Vec<u8> = original
+                .iter()
+                .zip(error(8, rank, erased))
+                .map(|(c, e)| c ^ e)
+                .collect();
+            for j in &erasures {
+                received[*j] = 0xff;
+            }
+            assert!(code.decode_with_erasures(&mut received, &erasures).is_ok());
+            assert_eq!(received, original, "s={erased} r={rank}");
+        }
+    }
+
+    #[test]
+    fn test_random_words() {
+        // with n - k odd the kept symbols give as many equations as
+        // unknowns; a word far from the code must fail, not panic
+        let mut rng = LfsrRng::seed_from_u64(7);
+        for (code, erasures) in [
+            (GabidulinCode::standard(7, 2).unwrap(), vec![]),
+            (GabidulinCode::standard(8, 4).unwrap(), vec![5]),
+            (GabidulinCode::standard(8, 3).unwrap(), vec![]),
+        ] {
+            for _ in 0..20 {
+                let mut word = vec![0u8; code.n()];
+                rng.fill_bytes(&mut word);
+                let before = word.clone();
+                assert_eq!(
+                    code.decode_with_erasures(&mut word, &erasures),
+                    Err(CodeError::TooManyErrors)
+                );
+                assert_eq!(word, before);
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        let dependent = [1, 2, 3].map(Galois::new).to_vec();
+        assert_eq!(
+            GabidulinCode::new(dependent, 1),
+            Err(CodeError::InvalidParameters)
+        );
+        assert_eq!(
+            GabidulinCode::standard(9, 2),
+            Err(CodeError::InvalidParameters)
+        );
+        assert_eq!(
+            GabidulinCode::standard(4, 4),
+            Err(CodeError::InvalidParameters)
+        );
+        assert_eq!(rank_weight(&[0x0f, 0xf0, 0xff, 0]), 2);
+    }
+}
@@ -0,0 +1,288 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::rabin::is_irreducible;
+use crate::Isomorphism;
+
+/// GF(256) as GF(2)[x] modulo a polynomial chosen at run time, with its own
+/// exp and log tables.
+///
+/// [`Galois`](crate::Galois) is fixed to `0x11d`; this context serves
+/// formats built on other polynomials, such as AES's `0x11b` or CCSDS's
+/// `0x187`, one table lookup per product like the crate's own field.
+/// [`GaloisField::isomorphism`] carries elements over to [`Galois`] when
+/// the rest of the crate's machinery is wanted.
+///
+/// [`Galois`]: crate::Galois
+#[derive(Clone, PartialEq, Eq)]
+pub struct GaloisField {
+    poly: u16,
+    generator: u8,
+    // exp[i] = g^i, doubled so that the sum of two logs needs no reduction
+    exp: [u8; 510],
+    log: [u8; 256],
+}
+
+impl GaloisField {
+    /// Returns `None` unless `poly` is an irreducible polynomial of degree 8,
+    /// written with its `x^8` term (e.g. `0x11b`).
+    pub fn new(poly: u16) -> Option<Self> {
+        if poly >> 8 != 1 || !is_irreducible(poly as u64 & 0xff, 8) {
+            return None;
+        }
+        // x itself generates the multiplicative group only when the
+        // polynomial is primitive; otherwise take the smallest element that
+        // does
+        let generator = (2..=255u8)
+            .find(|&g| {
+                let mut v = g;
+                (1..255).all(|_| {
+                    let done = v == 1;
+                    v = mul_mod(v, g, poly);
+                    !done
+                })
+            })
+            .expect("the multiplicative group of a field is cyclic");
+
+        let mut exp = [0u8; 510];
+        let mut log = [0u8; 256];
+        let mut v = 1u8;
+        for i in 0..255 {
+            exp[i] = v;
+            exp[i + 255] = v;
+            log[v as usize] = i as u8;
+            v = mul_mod(v, generator, poly);
+        }
+        Some(GaloisField {
+            poly,
+            generator,
+            exp,
+            log,
+        })
+    }
+
+    pub fn poly(&self) -> u16 {
+        self.poly
+    }
+
+    /// The generator of the multiplicative group the tables are built on:
+    /// `x` (2) for a primitive polynomial.
+    pub fn generator(&self) -> u8 {
+        self.generator
+    }
+
+    /// The element with bit pattern `v`, with operators.
+    pub fn elem(&self, v: u8) -> FieldElement<'_> {
+        FieldElement {
+            field: self,
+            value: v,
+        }
+    }
+
+    pub fn add(&self, a: u8, b: u8) -> u8 {
+        a ^ b
+    }
+
+    pub fn sub(&self, a: u8, b: u8) -> u8 {
+        a ^ b
+    }
+
+    pub fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+    }
+
+    /// Panics if `b` is zero, like [`Galois`](crate::Galois) division.
+    pub fn div(&self, a: u8, b: u8) -> u8 {
+        assert!(b != 0, "divide by zero");
+        if a == 0 {
+            return 0;
+        }
+        self.exp[self.log[a as usize] as usize + 255 - self.log[b as usize] as usize]
+    }
+
+    pub fn inv(&self, a: u8) -> u8 {
+        self.div(1, a)
+    }
+
+    pub fn pow(&self, a: u8, n: u32) -> u8 {
+        if n == 0 {
+            return 1;
+        }
+        if a == 0 {
+            return 0;
+        }
+        self.exp[(self.log[a as usize] as u64 * n as u64 % 255) as usize]
+    }
+
+    /// `g^n` for the [`generator`](GaloisField::generator) `g`.
+    pub fn exp(&self, n: u32) -> u8 {
+        self.exp[(n % 255) as usize]
+    }
+
+    /// The logarithm to the base of the generator, or `None` for zero.
+    pub fn log(&self, a: u8) -> Option<u8> {
+        (a != 0).then(|| self.log[a as usize])
+    }
+
+    /// The isomorphism from this field to the crate's [`Galois`](crate::Galois).
+    pub fn isomorphism(&self) -> Isomorphism {
+        Isomorphism::new(self.poly, crate::constants::PRIMITIVE_POLYNOMIAL as u16)
+            .expect("both polynomials are irreducible")
+    }
+}
+
+impl fmt::Debug for GaloisField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GaloisField")
+            .field("poly", &format_args!("{:#x}", self.poly))
+            .field("generator", &self.generator)
+            .finish()
+    }
+}
+
+/// An element of a [`GaloisField`], from [`GaloisField::elem`].
+///
+/// Operators between elements of different fields panic.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct FieldElement<'a> {
+    field: &'a GaloisField,
+    value: u8,
+}
+
+impl FieldElement<'_> {
+    pub fn value(self) -> u8 {
+        self.value
+    }
+
+    pub fn inv(self) -> Self {
+        self.field.elem(self.field.inv(self.value))
+    }
+
+    pub fn pow(self, n: u32) -> Self {
+        self.field.elem(self.field.pow(self.value, n))
+    }
+
+    fn same_field(self, rhs: Self) -> bool {
+        std::ptr::eq(self.field, rhs.field) || self.field.poly == rhs.field.poly
+    }
+}
+
+impl fmt::Debug for FieldElement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#04x} mod {:#x}", self.value, self.field.poly)
+    }
+}
+
+impl<'a> Add for FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        assert!(self.same_field(rhs), "elements of different fields");
+        self.field.elem(self.value ^ rhs.value)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<'a> Sub for FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + rhs
+    }
+}
+
+impl<'a> Mul for FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert!(self.same_field(rhs), "elements of different fields");
+        self.field.elem(self.field.mul(self.value, rhs.value))
+    }
+}
+
+impl<'a> Div for FieldElement<'a> {
+    type Output = FieldElement<'a>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        assert!(self.same_field(rhs), "elements of different fields");
+        self.field.elem(self.field.div(self.value, rhs.value))
+    }
+}
+
+// Multiplication in GF(2)[x]/(poly) by shift-and-add.
+pub(crate) const fn mul_mod(mut a: u8, mut b: u8, poly: u16) -> u8 {
+    let mut acc = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            acc ^= a;
+        }
+        b >>= 1;
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= poly as u8;
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{EXP_TABLE, LOG_TABLE};
+    use crate::Galois;
+
+    #[test]
+    fn test_crate_field() {
+        let f = GaloisField::new(0x11d).unwrap();
+        assert_eq!(f.generator(), 2);
+        for a in 0..=255u8 {
+            assert_eq!(f.log(a).unwrap_or(0), LOG_TABLE[a as usize]);
+            for b in (0..=255u8).step_by(3) {
+                assert_eq!(f.mul(a, b), u8::from(Galois::new(a) * Galois::new(b)));
+            }
+        }
+        for i in 0..255 {
+            assert_eq!(f.exp(i), u8::from(EXP_TABLE[i as usize]));
+        }
+    }
+
+    #[test]
+    fn test_other_fields() {
+        // the AES field, where x has order 51 and 3 generates
+        let aes = GaloisField::new(0x11b).unwrap();
+        assert_eq!(aes.generator(), 3);
+        assert_eq!(aes.mul(0x57, 0x83), 0xc1);
+        let (a, b) = (aes.elem(0x57), aes.elem(0x13));
+        assert_eq!((a * b).value(), 0xfe);
+        assert_eq!((a * b / b), a);
+        assert_eq!((a + b - b), a);
+        assert_eq!((a * a.inv()).value(), 1);
+        assert_eq!(a.pow(255).value(), 1);
+
+        for poly in [0x11b, 0x12d, 0x187, 0x1f5] {
+            let f = GaloisField::new(poly).unwrap();
+            let iso = f.isomorphism();
+            for a in 0..=255u8 {
+                assert_eq!(f.log(a).map(|l| f.exp(l as u32)), (a != 0).then_some(a));
+                for b in (1..=255u8).step_by(5) {
+                    let product = Galois::new(iso.map(a)) * Galois::new(iso.map(b));
+                    assert_eq!(iso.map(f.mul(a, b)), u8::from(product));
+                    assert_eq!(f.mul(f.div(a, b), b), a);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reducible() {
+        // x^8 + x^4 + x^3 + x^2 is divisible by x, and 0x1ff by x + 1
+        assert!(GaloisField::new(0x11c).is_none());
+        assert!(GaloisField::new(0x1ff).is_none());
+        assert!(GaloisField::new(0x1b).is_none());
+        assert!(GaloisField::new(0x21d).is_none());
+    }
+}
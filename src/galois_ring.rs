@@ -0,0 +1,323 @@
+//! The Galois ring GR(4, m), the Galois extension of Z/4 of degree `m`.
+//!
+//! GR(4, m) is Z4[x] modulo a basic primitive polynomial `h`: one whose
+//! reduction modulo 2 is a primitive polynomial `f` over GF(2). It is
+//! obtained from `f` by Graeffe's method, `h(x^2) = ±f(x) f(-x)` modulo 4.
+//! The ring has `4^m` elements; those with an odd reduction are units, and
+//! the rest are the multiples of 2, its only maximal ideal.
+//!
+//! The class `ξ` of `x` has order `2^m - 1`, and the Teichmüller set
+//! `T = {0, 1, ξ, .., ξ^(2^m - 2)}` lifts GF(2^m) multiplicatively. Every
+//! element is uniquely `a = t_0 + 2 t_1` with `t_0, t_1 ∈ T`, its 2-adic
+//! representation, through which the Z4-linear Kerdock and Preparata codes
+//! and the Gray map are defined.
+//!
+//! Elements are stored as two bit planes of their coefficients, and a ring
+//! is built from `f` written as in [`lfsr`](crate::lfsr), without its top
+//! term.
+
+use crate::lfsr::is_primitive;
+
+/// A polynomial of degree below `m` over Z4, coefficient `i` being
+/// `bit i of lo + 2 * bit i of hi`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct RingElement {
+    lo: u64,
+    hi: u64,
+}
+
+impl RingElement {
+    /// The element with the given coefficients, lowest degree first, each
+    /// taken modulo 4. Panics on more than 64.
+    pub fn from_coeffs(coeffs: &[u8]) -> Self {
+        assert!(coeffs.len() <= 64, "too many coefficients");
+        coeffs
+            .iter()
+            .enumerate()
+            .fold(RingElement::default(), |e, (i, &c)| RingElement {
+                lo: e.lo | (c as u64 & 1) << i,
+                hi: e.hi | (c as u64 >> 1 & 1) << i,
+            })
+    }
+
+    /// The element whose coefficients are the bits of `v`, each 0 or 1.
+    pub fn from_bits(v: u64) -> Self {
+        RingElement { lo: v, hi: 0 }
+    }
+
+    pub fn zero() -> Self {
+        RingElement::default()
+    }
+
+    pub fn one() -> Self {
+        RingElement::from_bits(1)
+    }
+
+    /// The coefficient of `x^i`, in `0..4`.
+    pub fn coeff(self, i: usize) -> u8 {
+        (self.lo >> i & 1 | (self.hi >> i & 1) << 1) as u8
+    }
+
+    /// The reduction modulo 2, an element of GF(2^m) as a bit polynomial.
+    pub fn mod2(self) -> u64 {
+        self.lo
+    }
+
+    /// Whether the element is a unit, i.e. not a multiple of 2.
+    pub fn is_unit(self) -> bool {
+        self.lo != 0
+    }
+
+    /// Twice the element, which depends only on its reduction modulo 2.
+    pub fn double(self) -> Self {
+        RingElement { lo: 0, hi: self.lo }
+    }
+}
+
+/// GR(4, m) for a basic primitive polynomial lifted from GF(2).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GaloisRing {
+    poly: u64,
+    degree: u8,
+    // the low coefficients h_0 .. h_(m-1) of the monic modulus
+    modulus: RingElement,
+}
+
+impl GaloisRing {
+    /// GR(4, degree) from the primitive polynomial `x^degree + poly` over
+    /// GF(2), or `None` if it is not primitive or `degree` is not in
+    /// `1..=32`.
+    pub fn new(poly: u64, degree: u8) -> Option<Self> {
+        if !(1..=32).contains(&degree) || !is_primitive(poly, degree) {
+            return None;
+        }
+        let m = degree as usize;
+        // f(x) f(-x) over Z4, with f(-x) negating the odd coefficients
+        let f: Vec<u8> = (0..=m)
+            .map(|i| if i == m { 1 } else { (poly >> i & 1) as u8 })
+            .collect();
+        let mut product = vec![0u8; 2 * m + 1];
+        for (i, &a) in f.iter().enumerate() {
+            for (j, &b) in f.iter().enumerate() {
+                let b = if j % 2 == 1 { (4 - b) % 4 } else { b };
+                product[i + j] = (product[i + j] + a * b) % 4;
+            }
+        }
+        // only even powers remain; the leading one is (-1)^m
+        let sign = if m % 2 == 1 { 3 } else { 1 };
+        let h: Vec<u8> = (0..m).map(|i| product[2 * i] * sign % 4).collect();
+        Some(GaloisRing {
+            poly,
+            degree,
+            modulus: RingElement::from_coeffs(&h),
+        })
+    }
+
+    /// The primitive polynomial over GF(2), without its top term.
+    pub fn poly(&self) -> u64 {
+        self.poly
+    }
+
+    pub fn degree(&self) -> u8 {
+        self.degree
+    }
+
+    /// The coefficients `h_0 .. h_m` of the basic primitive polynomial.
+    pub fn modulus(&self) -> Vec<u8> {
+        let m = self.degree as usize;
+        (0..=m)
+            .map(|i| if i == m { 1 } else { self.modulus.coeff(i) })
+            .collect()
+    }
+
+    /// `ξ`, the class of `x`, of order `2^m - 1`.
+    pub fn xi(&self) -> RingElement {
+        if self.degree == 1 {
+            // x = -h_0 when the modulus is linear
+            return self.neg(self.modulus);
+        }
+        RingElement::from_bits(2)
+    }
+
+    pub fn add(&self, a: RingElement, b: RingElement) -> RingElement {
+        // coefficientwise addition of two-bit numbers
+        let carry = a.lo & b.lo;
+        RingElement {
+            lo: a.lo ^ b.lo,
+            hi: a.hi ^ b.hi ^ carry,
+        }
+    }
+
+    pub fn neg(&self, a: RingElement) -> RingElement {
+        // -c = (c ^ 3) + 1 coefficientwise: lo stays, hi flips where lo is set
+        RingElement {
+            lo: a.lo,
+            hi: a.hi ^ a.lo,
+        }
+    }
+
+    pub fn sub(&self, a: RingElement, b: RingElement) -> RingElement {
+        self.add(a, self.neg(b))
+    }
+
+    pub fn mul(&self, a: RingElement, b: RingElement) -> RingElement {
+        let m = self.degree as usize;
+        let mut product = vec![0u8; 2 * m];
+        for i in (0..m).filter(|&i| a.coeff(i) != 0) {
+            for j in 0..m {
+                product[i + j] = (product[i + j] + a.coeff(i) * b.coeff(j)) % 4;
+            }
+        }
+        // x^m = -(h_0 + .. + h_(m-1) x^(m-1))
+        for k in (m..2 * m - 1).rev() {
+            let c = product[k];
+            if c == 0 {
+                continue;
+            }
+            for i in 0..m {
+                let h = self.modulus.coeff(i);
+                product[k - m + i] = (product[k - m + i] + 4 - c * h % 4) % 4;
+            }
+        }
+        RingElement::from_coeffs(&product[..m])
+    }
+
+    pub fn pow(&self, a: RingElement, n: u64) -> RingElement {
+        let (mut base, mut result) = (a, RingElement::one());
+        let mut n = n;
+        while n != 0 {
+            if n & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            n >>= 1;
+        }
+        result
+    }
+
+    /// The inverse of a unit, as `a^(2^(2m) - 2^m - 1)` since the unit
+    /// group has order `2^m (2^m - 1)`, or `None` for a multiple of 2.
+    pub fn inv(&self, a: RingElement) -> Option<RingElement> {
+        let m = self.degree as u32;
+        let order = (1u64 << m) * ((1u64 << m) - 1);
+        a.is_unit().then(|| self.pow(a, order - 1))
+    }
+
+    /// The Teichmüller lift of `v`, an element of GF(2^m) as a bit
+    /// polynomial: the unique `t ∈ T` with `t ≡ v` modulo 2, computed as
+    /// `a^(2^m)` for any lift `a`.
+    pub fn teichmuller(&self, v: u64) -> RingElement {
+        let mut t = RingElement::from_bits(v);
+        for _ in 0..self.degree {
+            t = self.mul(t, t);
+        }
+        t
+    }
+
+    /// The 2-adic representation: `(v_0, v_1)` in GF(2^m) with
+    /// `a = T(v_0) + 2 T(v_1)`.
+    pub fn two_adic(&self, a: RingElement) -> (u64, u64) {
+        let rest = self.sub(a, self.teichmuller(a.lo));
+        // rest is a multiple of 2, so its coefficients are 0 or 2
+        (a.lo, rest.hi)
+    }
+
+    /// The Frobenius automorphism `T(v_0) + 2 T(v_1) -> T(v_0)^2 + 2 T(v_1)^2`,
+    /// which generates the Galois group of GR(4, m) over Z4.
+    pub fn frobenius(&self, a: RingElement) -> RingElement {
+        let (v0, v1) = self.two_adic(a);
+        let t0 = self.teichmuller(v0);
+        let t1 = self.teichmuller(v1);
+        self.add(self.mul(t0, t0), self.mul(t1, t1).double())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Galois;
+
+    #[test]
+    fn test_lift() {
+        // the basic primitive polynomials lifted from x^2 + x + 1 and
+        // x^3 + x + 1
+        assert_eq!(GaloisRing::new(0b11, 2).unwrap().modulus(), [1, 1, 1]);
+        assert_eq!(GaloisRing::new(0b011, 3).unwrap().modulus(), [3, 1, 2, 1]);
+        // and x + 1 to x - 1, with ξ = 1
+        assert_eq!(GaloisRing::new(0b1, 1).unwrap().modulus(), [3, 1]);
+        assert_eq!(GaloisRing::new(0b1, 1).unwrap().xi(), RingElement::one());
+        // x^4 + x^3 + x^2 + x + 1 is irreducible but not primitive
+        assert!(GaloisRing::new(0b1111, 4).is_none());
+
+        for (poly, degree) in [(0b1, 1), (0b11, 2), (0b011, 3), (0x1d, 8), (0x100b, 16)] {
+            let ring = GaloisRing::new(poly, degree).unwrap();
+            let h = ring.modulus();
+            let f = (0..degree as usize).fold(0u64, |acc, i| acc | ((h[i] & 1) as u64) << i);
+            assert_eq!(f, poly);
+
+            // ξ has order 2^m - 1
+            let xi = ring.xi();
+            let order = (1u64 << degree) - 1;
+            assert_eq!(ring.pow(xi, order), RingElement::one());
+            if degree == 8 {
+                for q in [3, 5, 17] {
+                    assert_ne!(ring.pow(xi, order / q), RingElement::one());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gr_4_8() {
+        // GR(4, 8) over the crate's field polynomial
+        let ring = GaloisRing::new(0x1d, 8).unwrap();
+        let two = RingElement::from_coeffs(&[2]);
+        assert_eq!(ring.mul(two, two), RingElement::zero());
+        assert_eq!(ring.inv(two), None);
+
+        let samples: Vec<RingElement> = (0..40u64)
+            .map(|i| {
+                RingElement::from_coeffs(&(0..8).map(|j| (i * 7 + j * j) as u8).collect::<Vec<_>>())
+            })
+            .collect();
+        for &a in &samples {
+            assert_eq!(ring.add(a, ring.neg(a)), RingElement::zero());
+            if let Some(inv) = ring.inv(a) {
+                assert_eq!(ring.mul(a, inv), RingElement::one());
+            }
+            let (v0, v1) = ring.two_adic(a);
+            let back = ring.add(ring.teichmuller(v0), ring.teichmuller(v1).double());
+            assert_eq!(back, a);
+            for &b in &samples[..10] {
+                // reduction modulo 2 is the field product
+                let product = Galois::new(a.mod2() as u8) * Galois::new(b.mod2() as u8);
+                assert_eq!(ring.mul(a, b).mod2(), u8::from(product) as u64);
+                assert_eq!(
+                    ring.mul(a, ring.add(b, two)),
+                    ring.add(ring.mul(a, b), ring.mul(a, two))
+                );
+                // the Frobenius map is a ring automorphism
+                assert_eq!(
+                    ring.frobenius(ring.mul(a, b)),
+                    ring.mul(ring.frobenius(a), ring.frobenius(b))
+                );
+                assert_eq!(
+                    ring.frobenius(ring.add(a, b)),
+                    ring.add(ring.frobenius(a), ring.frobenius(b))
+                );
+            }
+        }
+
+        // the Teichmüller set is closed under multiplication, and is the
+        // powers of ξ
+        for v in 1..=255u64 {
+            let t = ring.teichmuller(v);
+            assert_eq!(t.mod2(), v);
+            assert_eq!(ring.pow(t, 255), RingElement::one());
+            let w = v * 37 % 255 + 1;
+            let product = u8::from(Galois::new(v as u8) * Galois::new(w as u8)) as u64;
+            assert_eq!(ring.mul(t, ring.teichmuller(w)), ring.teichmuller(product));
+        }
+        assert_eq!(ring.teichmuller(2), ring.xi());
+    }
+}
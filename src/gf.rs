@@ -0,0 +1,244 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+use crate::field::Field;
+use crate::galois_field::mul_mod;
+
+/// An element of GF(256) modulo the polynomial `POLY`, written with its
+/// `x^8` term, whose exp and log tables are built at compile time for each
+/// polynomial used.
+///
+/// The type-level counterpart of [`GaloisField`](crate::GaloisField):
+/// elements of different fields are different types, so they cannot be
+/// mixed by mistake, and there is no context to pass around. A polynomial
+/// that is not irreducible of degree 8 fails to compile on first use.
+///
+/// ```
+/// use gf256_lite::Gf;
+///
+/// type Aes = Gf<0x11b>;
+/// type Ccsds = Gf<0x187>;
+///
+/// assert_eq!(Aes::new(0x57) * Aes::new(0x83), Aes::new(0xc1));
+/// assert_eq!(Ccsds::new(0x57) * Ccsds::new(0x83), Ccsds::new(0xe7));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+#[repr(transparent)]
+pub struct Gf<const POLY: u16>(u8);
+
+struct Tables {
+    // twice the period, so a sum of two logs needs no reduction
+    exp: [u8; 510],
+    log: [u8; 256],
+}
+
+impl Tables {
+    const fn new(poly: u16) -> Tables {
+        assert!(poly >> 8 == 1, "the polynomial must have degree 8");
+        // an element of order 255 makes every nonzero element a unit, so
+        // finding one also shows that the polynomial is irreducible
+        let mut generator = 2u8;
+        loop {
+            let mut v = generator;
+            let mut order = 1;
+            while v != 1 && order < 255 {
+                v = mul_mod(v, generator, poly);
+                order += 1;
+            }
+            if v == 1 && order == 255 {
+                break;
+            }
+            assert!(generator < 255, "the polynomial must be irreducible");
+            generator += 1;
+        }
+
+        let mut exp = [0u8; 510];
+        let mut log = [0u8; 256];
+        let mut v = 1u8;
+        let mut i = 0;
+        while i < 255 {
+            exp[i] = v;
+            exp[i + 255] = v;
+            log[v as usize] = i as u8;
+            v = mul_mod(v, generator, poly);
+            i += 1;
+        }
+        Tables { exp, log }
+    }
+}
+
+impl<const POLY: u16> Gf<POLY> {
+    const TABLES: &'static Tables = &Tables::new(POLY);
+
+    pub const fn new(v: u8) -> Self {
+        Gf(v)
+    }
+
+    pub const fn zero() -> Self {
+        Gf(0)
+    }
+
+    pub const fn identity() -> Self {
+        Gf(1)
+    }
+
+    pub fn inv(self) -> Self {
+        Gf::identity() / self
+    }
+
+    pub fn exp(self, n: u32) -> Self {
+        if n == 0 {
+            return Gf::identity();
+        }
+        if self.0 == 0 {
+            return self;
+        }
+        let t = Self::TABLES;
+        Gf(t.exp[(t.log[self.0 as usize] as u64 * n as u64 % 255) as usize])
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<const POLY: u16> Add for Gf<POLY> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Gf(self.0 ^ rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<const POLY: u16> Sub for Gf<POLY> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Gf(self.0 ^ rhs.0)
+    }
+}
+
+impl<const POLY: u16> Mul for Gf<POLY> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if self.0 == 0 || rhs.0 == 0 {
+            return Gf(0);
+        }
+        let t = Self::TABLES;
+        Gf(t.exp[t.log[self.0 as usize] as usize + t.log[rhs.0 as usize] as usize])
+    }
+}
+
+impl<const POLY: u16> Div for Gf<POLY> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        assert_ne!(rhs.0, 0, "divide by zero");
+        if self.0 == 0 {
+            return self;
+        }
+        let t = Self::TABLES;
+        Gf(t.exp[t.log[self.0 as usize] as usize + 255 - t.log[rhs.0 as usize] as usize])
+    }
+}
+
+impl<const POLY: u16> AddAssign for Gf<POLY> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const POLY: u16> SubAssign for Gf<POLY> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const POLY: u16> MulAssign for Gf<POLY> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const POLY: u16> DivAssign for Gf<POLY> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const POLY: u16> From<u8> for Gf<POLY> {
+    fn from(v: u8) -> Self {
+        Gf(v)
+    }
+}
+
+impl<const POLY: u16> From<Gf<POLY>> for u8 {
+    fn from(v: Gf<POLY>) -> u8 {
+        v.0
+    }
+}
+
+impl<const POLY: u16> Field for Gf<POLY> {
+    const ORDER: usize = 256;
+    const BYTES: usize = 1;
+
+    fn zero() -> Self {
+        Gf::zero()
+    }
+
+    fn one() -> Self {
+        Gf::identity()
+    }
+
+    fn inv(self) -> Self {
+        Gf::inv(self)
+    }
+
+    fn from_index(i: usize) -> Self {
+        Gf(i as u8)
+    }
+
+    fn to_index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Galois, GaloisField};
+
+    #[test]
+    fn test_crate_field() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                let product = Gf::<0x11d>::new(a) * Gf::new(b);
+                assert_eq!(u8::from(product), u8::from(Galois::new(a) * Galois::new(b)));
+            }
+            assert_eq!(
+                u8::from(Gf::<0x11d>::new(a).exp(7)),
+                u8::from(Galois::new(a).exp(7))
+            );
+        }
+    }
+
+    fn check<const POLY: u16>() {
+        let field = GaloisField::new(POLY).unwrap();
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                let (x, y) = (Gf::<POLY>::new(a), Gf::<POLY>::new(b));
+                assert_eq!(u8::from(x * y), field.mul(a, b));
+                if b != 0 {
+                    assert_eq!(x / y * y, x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_other_fields() {
+        // 0x11b is not primitive, so its tables are built on 3
+        check::<0x11b>();
+        check::<0x12b>();
+        check::<0x187>();
+        assert_eq!(Gf::<0x11b>::new(2).exp(51), Gf::identity());
+    }
+}
@@ -0,0 +1,253 @@
+//! GF(2^128) and the GHASH and POLYVAL universal hashes of GCM and
+//! AES-GCM-SIV.
+//!
+//! [`Gf128`] is GF(2)[x] modulo `x^128 + x^7 + x^2 + x + 1`, bit `i` of its
+//! `u128` being the coefficient of `x^i`, multiplied by portable carry-less
+//! multiplication. GHASH reads a block with the first bit as `x^0`, the
+//! reverse of the usual bit order. POLYVAL works in the field of the
+//! reciprocal polynomial with little-endian blocks, and is computed here
+//! through its relation to GHASH in RFC 8452, appendix A.
+
+use std::ops::{Add, AddAssign, Mul, MulAssign};
+
+/// An element of GF(2^128) in the GHASH field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct Gf128(u128);
+
+impl Gf128 {
+    pub fn new(v: u128) -> Self {
+        Gf128(v)
+    }
+
+    pub fn value(self) -> u128 {
+        self.0
+    }
+
+    pub fn zero() -> Self {
+        Gf128(0)
+    }
+
+    pub fn identity() -> Self {
+        Gf128(1)
+    }
+
+    /// The element encoded by a GHASH block.
+    pub fn from_ghash_block(block: [u8; 16]) -> Self {
+        Gf128(u128::from_be_bytes(block).reverse_bits())
+    }
+
+    pub fn to_ghash_block(self) -> [u8; 16] {
+        self.0.reverse_bits().to_be_bytes()
+    }
+
+    /// The inverse as `self^(2^128 - 2)`, with zero mapped to zero.
+    pub fn inv(self) -> Self {
+        // 2^128 - 2 has bits 1 to 127 set
+        let mut square = self;
+        let mut inv = Gf128::identity();
+        for _ in 1..128 {
+            square = square * square;
+            inv *= square;
+        }
+        inv
+    }
+}
+
+// The 128 × 128 -> 256-bit carry-less product, as (high, low).
+fn clmul(a: u128, b: u128) -> (u128, u128) {
+    let (mut hi, mut lo) = (0u128, 0u128);
+    for i in 0..128 {
+        // all ones where bit i of b is set, so there is no branch on data
+        let mask = 0u128.wrapping_sub(b >> i & 1);
+        lo ^= (a << i) & mask;
+        if i > 0 {
+            hi ^= (a >> (128 - i)) & mask;
+        }
+    }
+    (hi, lo)
+}
+
+// Reduces hi x^128 + lo using x^128 = x^7 + x^2 + x + 1, folding the bits
+// that the shifts push past x^127 once more.
+fn reduce(hi: u128, lo: u128) -> u128 {
+    let fold = |t: u128| t ^ t << 1 ^ t << 2 ^ t << 7;
+    let overflow = hi >> 127 ^ hi >> 126 ^ hi >> 121;
+    lo ^ fold(hi) ^ fold(overflow)
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Add for Gf128 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Gf128(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Gf128 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let (hi, lo) = clmul(self.0, rhs.0);
+        Gf128(reduce(hi, lo))
+    }
+}
+
+impl AddAssign for Gf128 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl MulAssign for Gf128 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+/// An incremental GHASH: `Σ X_i H^(n - i + 1)` over the blocks `X_i`.
+#[derive(Debug, Clone)]
+pub struct Ghash {
+    h: Gf128,
+    acc: Gf128,
+}
+
+impl Ghash {
+    pub fn new(key: [u8; 16]) -> Self {
+        Ghash {
+            h: Gf128::from_ghash_block(key),
+            acc: Gf128::zero(),
+        }
+    }
+
+    /// Absorbs `data`, zero-padding a final partial block.
+    pub fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.update_block(block);
+        }
+    }
+
+    pub fn update_block(&mut self, block: [u8; 16]) {
+        self.acc = (self.acc + Gf128::from_ghash_block(block)) * self.h;
+    }
+
+    pub fn finalize(self) -> [u8; 16] {
+        self.acc.to_ghash_block()
+    }
+}
+
+/// GHASH as used by GCM: the padded associated data, the padded ciphertext,
+/// then their lengths in bits as big-endian `u64`s.
+pub fn ghash(key: [u8; 16], aad: &[u8], ct: &[u8]) -> [u8; 16] {
+    let mut g = Ghash::new(key);
+    g.update_padded(aad);
+    g.update_padded(ct);
+    let mut lengths = [0u8; 16];
+    lengths[..8].copy_from_slice(&(aad.len() as u64 * 8).to_be_bytes());
+    lengths[8..].copy_from_slice(&(ct.len() as u64 * 8).to_be_bytes());
+    g.update_block(lengths);
+    g.finalize()
+}
+
+/// An incremental POLYVAL of AES-GCM-SIV, which is
+/// `ByteReverse(GHASH(mulX(ByteReverse(H)), ByteReverse(X_i)..))`.
+#[derive(Debug, Clone)]
+pub struct Polyval {
+    inner: Ghash,
+}
+
+impl Polyval {
+    pub fn new(key: [u8; 16]) -> Self {
+        let mut h = key;
+        h.reverse();
+        let h = Gf128::from_ghash_block(h) * Gf128(2);
+        Polyval {
+            inner: Ghash {
+                h,
+                acc: Gf128::zero(),
+            },
+        }
+    }
+
+    /// Absorbs `data`, zero-padding a final partial block.
+    pub fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut block = [0u8; 16];
+            block[..chunk.len()].copy_from_slice(chunk);
+            self.update_block(block);
+        }
+    }
+
+    pub fn update_block(&mut self, mut block: [u8; 16]) {
+        block.reverse();
+        self.inner.update_block(block);
+    }
+
+    pub fn finalize(self) -> [u8; 16] {
+        let mut out = self.inner.finalize();
+        out.reverse();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn block(s: &str) -> [u8; 16] {
+        hex(s).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_field() {
+        let a = Gf128::new(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210);
+        let b = Gf128::new(0xdead_beef << 64 | 0x1234);
+        assert_eq!(a * b, b * a);
+        assert_eq!(a * Gf128::identity(), a);
+        assert_eq!(a * a.inv(), Gf128::identity());
+        assert_eq!((a + b) * a, a * a + b * a);
+        // x^127 * x = x^128 = x^7 + x^2 + x + 1
+        assert_eq!(Gf128::new(1 << 127) * Gf128::new(2), Gf128::new(0x87));
+    }
+
+    #[test]
+    fn test_ghash() {
+        // GCM test case 2: AES-128 with the zero key on one zero block
+        let h = block("66e94bd4ef8a2c3b884cfa59ca342b2e");
+        let ct = hex("0388dace60b6a392f328c2b971b2fe78");
+        assert_eq!(
+            ghash(h, &[], &ct),
+            block("f38cbb1ad69223dcc3457ae5b6b0f885")
+        );
+
+        // partial blocks of both inputs, from an AES-GCM tag XOR E(K, J0)
+        let h = block("c6a13b37878f5b826f4f8162a1c8d879");
+        let aad: Vec<u8> = (0..20).collect();
+        let ct = hex(concat!(
+            "1a6540b0c72192cba301115dd291498540ff067a1e3a47fb",
+            "5cc0072a7839252a5a1bbcd1771b2d80"
+        ));
+        assert_eq!(
+            ghash(h, &aad, &ct),
+            block("fb11478aa3a37af46138106c26b239c3")
+        );
+    }
+
+    #[test]
+    fn test_polyval() {
+        // RFC 8452, appendix A
+        let mut p = Polyval::new(block("25629347589242761d31f826ba4b757b"));
+        p.update_block(block("4f4f95668c83dfb6401762bb2d01a262"));
+        p.update_block(block("d1a24ddd2721d006bbe45f20d3c9f362"));
+        assert_eq!(p.finalize(), block("f7a3b47b846119fae5b7866cf5e5b77e"));
+    }
+}
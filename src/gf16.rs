@@ -0,0 +1,282 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+use std::sync::OnceLock;
+
+use crate::field::Field;
+use crate::tower::Tower;
+use crate::Galois;
+
+/// `x^4 + x + 1`, with `x = 2` primitive, as in the subfield of
+/// [`tower`](crate::tower).
+const POLYNOMIAL: u8 = 0x13;
+const ORDER: usize = 16;
+
+/// An element of GF(16), held in the low nibble of a byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+#[repr(transparent)]
+pub struct Gf16(u8);
+
+// exp[i] = x^i, twice the period, so a sum of two logs needs no reduction
+const EXP: [u8; 2 * (ORDER - 1)] = {
+    let mut exp = [0u8; 2 * (ORDER - 1)];
+    let mut v = 1u8;
+    let mut i = 0;
+    while i < ORDER - 1 {
+        exp[i] = v;
+        exp[i + ORDER - 1] = v;
+        v <<= 1;
+        if v >= ORDER as u8 {
+            v ^= POLYNOMIAL;
+        }
+        i += 1;
+    }
+    exp
+};
+
+const LOG: [u8; ORDER] = {
+    let mut log = [0u8; ORDER];
+    let mut i = 0;
+    while i < ORDER - 1 {
+        log[EXP[i] as usize] = i as u8;
+        i += 1;
+    }
+    log
+};
+
+impl Gf16 {
+    /// Panics unless `v < 16`.
+    pub fn new(v: u8) -> Self {
+        assert!(v < ORDER as u8, "{v} is not a GF(16) element");
+        Gf16(v)
+    }
+
+    pub fn zero() -> Self {
+        Gf16(0)
+    }
+
+    pub fn identity() -> Self {
+        Gf16(1)
+    }
+
+    pub fn inv(self) -> Self {
+        Gf16::identity() / self
+    }
+
+    pub fn exp(self, n: u32) -> Self {
+        if n == 0 {
+            return Gf16::identity();
+        }
+        if self.0 == 0 {
+            return self;
+        }
+        let log = LOG[self.0 as usize] as u32 * (n % 15) % 15;
+        Gf16(EXP[log as usize])
+    }
+
+    /// The two elements packed in a byte, `hi` in the high nibble.
+    pub fn split_byte(b: u8) -> (Gf16, Gf16) {
+        (Gf16(b >> 4), Gf16(b & 0xf))
+    }
+
+    pub fn join_byte(hi: Gf16, lo: Gf16) -> u8 {
+        hi.0 << 4 | lo.0
+    }
+
+    /// The image in the subfield of [`Galois`] of order 16, the elements
+    /// with `a^16 = a`.
+    ///
+    /// The embedding is the one of [`tower`](crate::tower), where GF(16) is
+    /// the constants `Tower::from_parts(0, v)`, so the two compose.
+    pub fn embed(self) -> Galois {
+        Galois::new(subfield().embed[self.0 as usize])
+    }
+
+    /// The inverse of [`Gf16::embed`], or `None` if `g` is not in the
+    /// subfield.
+    pub fn extract(g: Galois) -> Option<Gf16> {
+        let v = subfield().extract[u8::from(g) as usize];
+        (v != NOT_IN_SUBFIELD).then_some(Gf16(v))
+    }
+
+    /// Whether `g` lies in the subfield of order 16.
+    pub fn contains(g: Galois) -> bool {
+        Gf16::extract(g).is_some()
+    }
+}
+
+const NOT_IN_SUBFIELD: u8 = 0xff;
+
+struct Subfield {
+    embed: [u8; ORDER],
+    extract: [u8; 256],
+}
+
+fn subfield() -> &'static Subfield {
+    static SUBFIELD: OnceLock<Subfield> = OnceLock::new();
+    SUBFIELD.get_or_init(|| {
+        let embed: [u8; ORDER] =
+            std::array::from_fn(|v| u8::from(Tower::from_parts(0, v as u8).to_galois()));
+        let mut extract = [NOT_IN_SUBFIELD; 256];
+        for (v, &g) in embed.iter().enumerate() {
+            extract[g as usize] = v as u8;
+        }
+        Subfield { embed, extract }
+    })
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Add for Gf16 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Gf16(self.0 ^ rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Sub for Gf16 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Gf16(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Gf16 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if self.0 == 0 || rhs.0 == 0 {
+            return Gf16(0);
+        }
+        Gf16(EXP[LOG[self.0 as usize] as usize + LOG[rhs.0 as usize] as usize])
+    }
+}
+
+impl Div for Gf16 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        assert_ne!(rhs.0, 0, "divide by zero");
+        if self.0 == 0 {
+            return self;
+        }
+        Gf16(EXP[LOG[self.0 as usize] as usize + (ORDER - 1) - LOG[rhs.0 as usize] as usize])
+    }
+}
+
+impl AddAssign for Gf16 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Gf16 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Gf16 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Gf16 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl From<Gf16> for Galois {
+    fn from(v: Gf16) -> Galois {
+        v.embed()
+    }
+}
+
+impl From<Gf16> for u8 {
+    fn from(v: Gf16) -> u8 {
+        v.0
+    }
+}
+
+impl Field for Gf16 {
+    const ORDER: usize = ORDER;
+    const BYTES: usize = 1;
+
+    fn zero() -> Self {
+        Gf16::zero()
+    }
+
+    fn one() -> Self {
+        Gf16::identity()
+    }
+
+    fn inv(self) -> Self {
+        Gf16::inv(self)
+    }
+
+    fn from_index(i: usize) -> Self {
+        Gf16::new(i as u8)
+    }
+
+    fn to_index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tower::{gf16_inv, gf16_mul};
+
+    #[test]
+    fn test_arithmetic() {
+        for a in 0..16u8 {
+            let x = Gf16::new(a);
+            for b in 0..16u8 {
+                let y = Gf16::new(b);
+                assert_eq!(u8::from(x * y), gf16_mul(a, b));
+                if b != 0 {
+                    assert_eq!(x / y * y, x);
+                }
+            }
+            if a != 0 {
+                assert_eq!(u8::from(x.inv()), gf16_inv(a));
+                assert_eq!(x.exp(15), Gf16::identity());
+            }
+            assert_eq!(x.exp(3), x * x * x);
+        }
+        assert_eq!(Gf16::split_byte(0xa7), (Gf16::new(0xa), Gf16::new(7)));
+        assert_eq!(Gf16::join_byte(Gf16::new(0xa), Gf16::new(7)), 0xa7);
+    }
+
+    #[test]
+    fn test_subfield() {
+        for a in 0..16u8 {
+            let x = Gf16::new(a);
+            assert_eq!(Gf16::extract(x.embed()), Some(x));
+            for b in 0..16u8 {
+                let y = Gf16::new(b);
+                assert_eq!((x * y).embed(), x.embed() * y.embed());
+                assert_eq!((x + y).embed(), x.embed() + y.embed());
+            }
+        }
+        let members = (0..=255u8)
+            .map(Galois::new)
+            .filter(|&g| Gf16::contains(g))
+            .inspect(|&g| assert_eq!(g.exp(16), g))
+            .count();
+        assert_eq!(members, 16);
+        assert_eq!(Gf16::extract(Galois::new(2)), None);
+        // the constants of the tower
+        let t = Tower::from_parts(0, 0xb);
+        assert_eq!(Galois::from(Gf16::new(0xb)), t.to_galois());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_range() {
+        Gf16::new(16);
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+use crate::field::Field;
+
+/// `x^32 + x^7 + x^5 + x^3 + x^2 + x + 1`, with `x = 2` primitive. Its low
+/// taps let a 64-bit product be reduced in two folds.
+const POLYNOMIAL: u64 = 0x1_0000_00af;
+
+/// An element of GF(2^32), bit `i` being the coefficient of `x^i`.
+///
+/// Tables are out of the question at this size, so products are computed
+/// by carry-less multiplication and reduction. Since sums of unreduced
+/// products reduce to the sum of the products, [`Gf32::dot`] reduces only
+/// once for a whole inner product.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+#[repr(transparent)]
+pub struct Gf32(u32);
+
+// The 32 × 32 -> 64-bit carry-less product.
+fn clmul(a: u32, b: u32) -> u64 {
+    let a = a as u64;
+    let mut product = 0u64;
+    for i in 0..32 {
+        // all ones where bit i of b is set, so there is no branch on data
+        product ^= (a << i) & 0u64.wrapping_sub(b as u64 >> i & 1);
+    }
+    product
+}
+
+// Reduces a product of degree at most 62 using x^32 = x^7 + .. + 1. The
+// first fold leaves at most degree 37, the second less than 32.
+fn reduce(p: u64) -> u32 {
+    let fold = |p: u64| (p & 0xffff_ffff) ^ clmul((p >> 32) as u32, POLYNOMIAL as u32);
+    fold(fold(p)) as u32
+}
+
+impl Gf32 {
+    pub fn new(v: u32) -> Self {
+        Gf32(v)
+    }
+
+    pub fn zero() -> Self {
+        Gf32(0)
+    }
+
+    pub fn identity() -> Self {
+        Gf32(1)
+    }
+
+    /// The inverse as `self^(2^32 - 2)`, panicking on zero.
+    pub fn inv(self) -> Self {
+        assert_ne!(self.0, 0, "divide by zero");
+        self.exp(u32::MAX - 1)
+    }
+
+    pub fn exp(self, n: u32) -> Self {
+        let (mut base, mut result) = (self, Gf32::identity());
+        let mut n = n;
+        while n != 0 {
+            if n & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            n >>= 1;
+        }
+        result
+    }
+
+    /// `Σ a_i b_i`, with one reduction for the whole sum.
+    pub fn dot(a: &[Gf32], b: &[Gf32]) -> Gf32 {
+        assert_eq!(a.len(), b.len(), "length mismatch");
+        let acc = a
+            .iter()
+            .zip(b)
+            .fold(0u64, |acc, (x, y)| acc ^ clmul(x.0, y.0));
+        Gf32(reduce(acc))
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Add for Gf32 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Gf32(self.0 ^ rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Sub for Gf32 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Gf32(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Gf32 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Gf32(reduce(clmul(self.0, rhs.0)))
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div for Gf32 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl AddAssign for Gf32 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Gf32 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Gf32 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Gf32 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl fmt::Display for Gf32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Gf32({})", self.0)
+    }
+}
+
+impl From<u32> for Gf32 {
+    fn from(v: u32) -> Self {
+        Gf32(v)
+    }
+}
+
+impl From<Gf32> for u32 {
+    fn from(v: Gf32) -> u32 {
+        v.0
+    }
+}
+
+// ORDER does not fit a 32-bit usize
+#[cfg(target_pointer_width = "64")]
+impl Field for Gf32 {
+    const ORDER: usize = 1 << 32;
+    const BYTES: usize = 4;
+
+    fn zero() -> Self {
+        Gf32::zero()
+    }
+
+    fn one() -> Self {
+        Gf32::identity()
+    }
+
+    fn inv(self) -> Self {
+        Gf32::inv(self)
+    }
+
+    fn from_index(i: usize) -> Self {
+        Gf32(i as u32)
+    }
+
+    fn to_index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // shift-and-add multiplication modulo POLYNOMIAL
+    fn slow_mul(a: u32, b: u32) -> u32 {
+        let (mut a, mut b, mut acc) = (a as u64, b, 0u64);
+        while b != 0 {
+            if b & 1 == 1 {
+                acc ^= a;
+            }
+            b >>= 1;
+            a <<= 1;
+            if a >> 32 == 1 {
+                a ^= POLYNOMIAL;
+            }
+        }
+        acc as u32
+    }
+
+    #[test]
+    fn test_mul() {
+        let samples = [
+            0,
+            1,
+            2,
+            0x80,
+            0x8000_0000,
+            0xffff_ffff,
+            0xdead_beef,
+            0x1234_5678,
+        ];
+        for a in samples {
+            for b in samples {
+                let p = Gf32(a) * Gf32(b);
+                assert_eq!(p.0, slow_mul(a, b));
+                if b != 0 {
+                    assert_eq!(p / Gf32(b), Gf32(a));
+                }
+            }
+        }
+        // x is primitive: 2^32 - 1 = 3 · 5 · 17 · 257 · 65537
+        let x = Gf32(2);
+        assert_eq!(x.exp(u32::MAX), Gf32::identity());
+        for q in [3, 5, 17, 257, 65537] {
+            assert_ne!(x.exp(u32::MAX / q), Gf32::identity());
+        }
+    }
+
+    #[test]
+    fn test_dot() {
+        let a: Vec<Gf32> = (1..50u32)
+            .map(|i| Gf32(i.wrapping_mul(0x9e37_79b9)))
+            .collect();
+        let b: Vec<Gf32> = (1..50u32)
+            .map(|i| Gf32(i.wrapping_mul(0x85eb_ca6b)))
+            .collect();
+        let expected = a
+            .iter()
+            .zip(&b)
+            .fold(Gf32::zero(), |acc, (&x, &y)| acc + x * y);
+        assert_eq!(Gf32::dot(&a, &b), expected);
+        assert_eq!(Gf32::dot(&[], &[]), Gf32::zero());
+    }
+}
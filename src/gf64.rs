@@ -0,0 +1,192 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+
+/// An element of GF(2^64), GF(2)[x] modulo `x^64 + x^4 + x^3 + x + 1`, bit
+/// `i` being the coefficient of `x^i`.
+///
+/// The field of [`polyhash`](crate::polyhash) and of the 64-bit LFSR behind
+/// [`LfsrRng`](crate::lfsr::LfsrRng), with `x = 2` primitive. Products are
+/// computed by carry-less multiplication, and [`Gf64::dot`] reduces only
+/// once for a whole inner product. It does not implement
+/// [`Field`](crate::Field), whose `ORDER` is a `usize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+#[repr(transparent)]
+pub struct Gf64(u64);
+
+// The 64 × 64 -> 128-bit carry-less product.
+fn clmul(a: u64, b: u64) -> u128 {
+    let a = a as u128;
+    let mut product = 0u128;
+    for i in 0..64 {
+        // all ones where bit i of b is set, so there is no branch on data
+        product ^= (a << i) & 0u128.wrapping_sub((b >> i & 1) as u128);
+    }
+    product
+}
+
+// Reduces with x^64 = x^4 + x^3 + x + 1, folding the bits that the shifts
+// push past x^63 once more.
+fn reduce(p: u128) -> u64 {
+    let (hi, lo) = ((p >> 64) as u64, p as u64);
+    let fold = |t: u64| t ^ t << 1 ^ t << 3 ^ t << 4;
+    let overflow = hi >> 63 ^ hi >> 61 ^ hi >> 60;
+    lo ^ fold(hi) ^ fold(overflow)
+}
+
+impl Gf64 {
+    pub fn new(v: u64) -> Self {
+        Gf64(v)
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn zero() -> Self {
+        Gf64(0)
+    }
+
+    pub fn identity() -> Self {
+        Gf64(1)
+    }
+
+    /// The inverse as `self^(2^64 - 2)`, panicking on zero.
+    pub fn inv(self) -> Self {
+        assert_ne!(self.0, 0, "divide by zero");
+        self.exp(u64::MAX - 1)
+    }
+
+    pub fn exp(self, n: u64) -> Self {
+        let (mut base, mut result) = (self, Gf64::identity());
+        let mut n = n;
+        while n != 0 {
+            if n & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            n >>= 1;
+        }
+        result
+    }
+
+    /// `Σ a_i b_i`, with one reduction for the whole sum.
+    pub fn dot(a: &[Gf64], b: &[Gf64]) -> Gf64 {
+        assert_eq!(a.len(), b.len(), "length mismatch");
+        let acc = a
+            .iter()
+            .zip(b)
+            .fold(0u128, |acc, (x, y)| acc ^ clmul(x.0, y.0));
+        Gf64(reduce(acc))
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Add for Gf64 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Gf64(self.0 ^ rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Sub for Gf64 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Gf64(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Gf64 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Gf64(reduce(clmul(self.0, rhs.0)))
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div for Gf64 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl AddAssign for Gf64 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Gf64 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Gf64 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Gf64 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl fmt::Display for Gf64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Gf64({})", self.0)
+    }
+}
+
+impl From<u64> for Gf64 {
+    fn from(v: u64) -> Self {
+        Gf64(v)
+    }
+}
+
+impl From<Gf64> for u64 {
+    fn from(v: Gf64) -> u64 {
+        v.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rabin::mul_mod;
+
+    #[test]
+    fn test_field() {
+        // x^63 * x = x^4 + x^3 + x + 1
+        assert_eq!(Gf64(1 << 63) * Gf64(2), Gf64(0x1b));
+        let (a, b, c) = (
+            Gf64(0x0123_4567_89ab_cdef),
+            Gf64(0xfedc_ba98_7654_3210),
+            Gf64(0xdead_beef),
+        );
+        assert_eq!(a * b, b * a);
+        assert_eq!((a * b) * c, a * (b * c));
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!(
+            (a * b).value() as u128,
+            mul_mod(a.value() as u128, b.value() as u128, 1 << 64 | 0x1b, 64)
+        );
+        assert_eq!(a * b / b, a);
+        assert_eq!(a * a.inv(), Gf64::identity());
+        assert_eq!(Gf64::dot(&[a, b], &[b, c]), a * b + b * c);
+
+        // x is primitive: 2^64 - 1 = 3 · 5 · 17 · 257 · 641 · 65537 · 6700417
+        let x = Gf64(2);
+        assert_eq!(x.exp(u64::MAX), Gf64::identity());
+        for q in [3, 5, 17, 257, 641, 65537, 6_700_417] {
+            assert_ne!(x.exp(u64::MAX / q), Gf64::identity());
+        }
+    }
+}
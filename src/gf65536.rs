@@ -0,0 +1,219 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+use std::sync::OnceLock;
+
+use crate::field::Field;
+
+/// `x^16 + x^12 + x^3 + x + 1`, the PAR2 field polynomial, with `x = 2`
+/// primitive.
+const POLYNOMIAL: u32 = 0x1100b;
+const ORDER: usize = 1 << 16;
+
+/// An element of GF(2^16).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+#[repr(transparent)]
+pub struct Gf65536(u16);
+
+struct Tables {
+    log: Vec<u16>,
+    // twice the period, so a sum of two logs needs no reduction
+    exp: Vec<u16>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut log = vec![0u16; ORDER];
+        let mut exp = vec![0u16; 2 * (ORDER - 1)];
+        let mut v = 1u32;
+        for i in 0..ORDER - 1 {
+            exp[i] = v as u16;
+            exp[i + ORDER - 1] = v as u16;
+            log[v as usize] = i as u16;
+            v <<= 1;
+            if v >= ORDER as u32 {
+                v ^= POLYNOMIAL;
+            }
+        }
+        debug_assert_eq!(v, 1, "2 must be primitive");
+        Tables { log, exp }
+    })
+}
+
+impl Gf65536 {
+    pub fn new(v: u16) -> Self {
+        Gf65536(v)
+    }
+
+    pub fn zero() -> Self {
+        Gf65536(0)
+    }
+
+    pub fn identity() -> Self {
+        Gf65536(1)
+    }
+
+    pub fn inv(self) -> Self {
+        Gf65536::identity() / self
+    }
+
+    pub fn exp(self, n: u32) -> Self {
+        if n == 0 {
+            return Gf65536::identity();
+        }
+        if self.0 == 0 {
+            return self;
+        }
+        let t = tables();
+        let log = t.log[self.0 as usize] as u64 * n as u64 % (ORDER as u64 - 1);
+        Gf65536(t.exp[log as usize])
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Add for Gf65536 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Gf65536(self.0 ^ rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Sub for Gf65536 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Gf65536(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Gf65536 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        if self.0 == 0 || rhs.0 == 0 {
+            return Gf65536(0);
+        }
+        let t = tables();
+        Gf65536(t.exp[t.log[self.0 as usize] as usize + t.log[rhs.0 as usize] as usize])
+    }
+}
+
+impl Div for Gf65536 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        assert_ne!(rhs.0, 0, "divide by zero");
+        if self.0 == 0 {
+            return self;
+        }
+        let t = tables();
+        let log = t.log[self.0 as usize] as usize + (ORDER - 1) - t.log[rhs.0 as usize] as usize;
+        Gf65536(t.exp[log])
+    }
+}
+
+impl AddAssign for Gf65536 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Gf65536 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Gf65536 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Gf65536 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl fmt::Display for Gf65536 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Gf65536({})", self.0)
+    }
+}
+
+impl From<u16> for Gf65536 {
+    fn from(v: u16) -> Self {
+        Gf65536(v)
+    }
+}
+
+impl From<Gf65536> for u16 {
+    fn from(v: Gf65536) -> u16 {
+        v.0
+    }
+}
+
+impl Field for Gf65536 {
+    const ORDER: usize = ORDER;
+    const BYTES: usize = 2;
+
+    fn zero() -> Self {
+        Gf65536::zero()
+    }
+
+    fn one() -> Self {
+        Gf65536::identity()
+    }
+
+    fn inv(self) -> Self {
+        Gf65536::inv(self)
+    }
+
+    fn from_index(i: usize) -> Self {
+        Gf65536(i as u16)
+    }
+
+    fn to_index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // shift-and-add multiplication modulo POLYNOMIAL
+    fn slow_mul(a: u16, b: u16) -> u16 {
+        let (mut a, mut b, mut acc) = (a as u32, b, 0u32);
+        while b != 0 {
+            if b & 1 == 1 {
+                acc ^= a;
+            }
+            b >>= 1;
+            a <<= 1;
+            if a >= ORDER as u32 {
+                a ^= POLYNOMIAL;
+            }
+        }
+        acc as u16
+    }
+
+    #[test]
+    fn test_mul() {
+        for a in (0..=u16::MAX).step_by(251) {
+            for b in (0..=u16::MAX).step_by(509) {
+                let p = Gf65536(a) * Gf65536(b);
+                assert_eq!(p.0, slow_mul(a, b));
+                if b != 0 {
+                    assert_eq!(p / Gf65536(b), Gf65536(a));
+                }
+            }
+        }
+        assert_eq!(Gf65536(2).exp(65535), Gf65536::identity());
+        assert_ne!(Gf65536(2).exp(65535 / 3), Gf65536::identity());
+        assert_eq!(Gf65536(0x1234).to_string(), "Gf65536(4660)");
+    }
+}
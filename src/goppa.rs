@@ -0,0 +1,365 @@
+//! Binary Goppa codes with Patterson decoding.
+//!
+//! A Goppa code is defined by a Goppa polynomial `g` of degree `t` over
+//! GF(256) and a support of `n` distinct field elements `α_j`, none of them
+//! a root of `g`. A binary word `c` is a codeword when
+//! `Σ c_j / (x - α_j) ≡ 0 mod g`. When `g` is irreducible the code has
+//! dimension at least `n - 8t` and Patterson's algorithm corrects up to `t`
+//! bit errors, which is the setting of the McEliece cryptosystem.
+//!
+//! As with [`bch`](crate::bch), words hold one bit per byte, each 0 or 1.
+//! Bit `j` of a word belongs to support element `α_j`.
+
+use crate::fountain::SplitMix;
+use crate::poly::Poly;
+use crate::rs::CodeError;
+use crate::{BitMatrix, Galois, GfMatrix};
+
+/// A binary Goppa code with an irreducible Goppa polynomial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoppaCode {
+    goppa: Poly,
+    support: Vec<Galois>,
+    // the binary parity-check matrix in reduced row echelon form, one row
+    // per pivot
+    reduced: Vec<Vec<u8>>,
+    pivots: Vec<usize>,
+    information_set: Vec<usize>,
+}
+
+impl GoppaCode {
+    /// Fails unless `goppa` is irreducible of degree at least 2 and
+    /// `support` holds distinct elements, leaving a code of positive
+    /// dimension.
+    pub fn new(goppa: Poly, support: Vec<Galois>) -> Result<Self, CodeError> {
+        if goppa.degree().is_none_or(|t| t < 2) || !goppa.is_irreducible() {
+            return Err(CodeError::InvalidParameters);
+        }
+        let mut sorted = support.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.len() != support.len() {
+            return Err(CodeError::InvalidParameters);
+        }
+
+        let mut code = GoppaCode {
+            goppa,
+            support,
+            reduced: Vec::new(),
+            pivots: Vec::new(),
+            information_set: Vec::new(),
+        };
+        code.reduce();
+        if code.information_set.is_empty() {
+            return Err(CodeError::InvalidParameters);
+        }
+        Ok(code)
+    }
+
+    /// The code whose support is the whole field, of length 256.
+    pub fn full(goppa: Poly) -> Result<Self, CodeError> {
+        GoppaCode::new(goppa, (0..=255).map(Galois::new).collect())
+    }
+
+    pub fn n(&self) -> usize {
+        self.support.len()
+    }
+
+    pub fn k(&self) -> usize {
+        self.information_set.len()
+    }
+
+    /// The degree of the Goppa polynomial, which is also the number of bit
+    /// errors the code can always correct.
+    pub fn capability(&self) -> usize {
+        self.goppa.degree().unwrap_or(0)
+    }
+
+    pub fn goppa(&self) -> &Poly {
+        &self.goppa
+    }
+
+    pub fn support(&self) -> &[Galois] {
+        &self.support
+    }
+
+    /// The positions that [`GoppaCode::encode`] copies the message bits to,
+    /// in order.
+    pub fn information_set(&self) -> &[usize] {
+        &self.information_set
+    }
+
+    /// The `t × n` parity-check matrix over GF(256), with
+    /// `H[i][j] = α_j^i / g(α_j)`.
+    pub fn parity_check(&self) -> GfMatrix {
+        let t = self.capability();
+        GfMatrix::from_fn(t, self.n(), |i, j| {
+            let a = self.support[j];
+            a.exp(i as u32) / self.goppa.eval(a)
+        })
+    }
+
+    /// The `8t × n` binary parity-check matrix: row `8i + b` holds bit `b`
+    /// of row `i` of [`GoppaCode::parity_check`].
+    pub fn binary_parity_check(&self) -> BitMatrix {
+        let h = self.parity_check();
+        let mut bm = BitMatrix::zero(h.rows() * 8, h.cols());
+        for i in 0..h.rows() {
+            for j in 0..h.cols() {
+                let v = u8::from(h[(i, j)]);
+                for b in 0..8 {
+                    bm.set(8 * i + b, j, v >> b & 1 == 1);
+                }
+            }
+        }
+        bm
+    }
+
+    // Gaussian elimination of the binary parity-check matrix over GF(2).
+    fn reduce(&mut self) {
+        let h = self.binary_parity_check();
+        let mut rows: Vec<Vec<u8>> = (0..h.rows())
+            .map(|r| (0..h.cols()).map(|c| h[(r, c)] as u8).collect())
+            .collect();
+
+        let mut rank = 0;
+        for col in 0..self.n() {
+            let Some(p) = (rank..rows.len()).find(|r| rows[*r][col] == 1) else {
+                self.information_set.push(col);
+                continue;
+            };
+            rows.swap(rank, p);
+            let pivot = rows[rank].clone();
+            for (r, row) in rows.iter_mut().enumerate() {
+                if r != rank && row[col] == 1 {
+                    row.iter_mut().zip(&pivot).for_each(|(a, b)| *a ^= b);
+                }
+            }
+            self.pivots.push(col);
+            rank += 1;
+        }
+        rows.truncate(rank);
+        self.reduced = rows;
+    }
+
+    /// Returns the codeword carrying the `k` bits of `msg` on the
+    /// [information set](GoppaCode::information_set).
+    pub fn encode(&self, msg: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if msg.len() != self.k() {
+            return Err(CodeError::WrongLength);
+        }
+        let mut codeword = vec![0u8; self.n()];
+        for (pos, m) in self.information_set.iter().zip(msg) {
+            codeword[*pos] = m & 1;
+        }
+        // every reduced row has a single pivot, which it sets to the parity
+        // of the row's other ones
+        for (row, pivot) in self.reduced.iter().zip(&self.pivots) {
+            codeword[*pivot] = self
+                .information_set
+                .iter()
+                .fold(0, |acc, i| acc ^ (row[*i] & codeword[*i]));
+        }
+        Ok(codeword)
+    }
+
+    /// The syndrome polynomial `Σ c_j / (x - α_j) mod g`.
+    pub fn syndrome(&self, codeword: &[u8]) -> Poly {
+        let mut s = Poly::zero();
+        for (c, a) in codeword.iter().zip(&self.support) {
+            if c & 1 == 1 {
+                s = &s + &self.inverse_linear(*a);
+            }
+        }
+        s
+    }
+
+    // (x - a)^-1 mod g: dividing gives g(x) = (x - a) q(x) + g(a), so the
+    // inverse is q(x) / g(a) in characteristic 2.
+    fn inverse_linear(&self, a: Galois) -> Poly {
+        let (q, r) = self.goppa.div_rem(&Poly::new(vec![a, Galois::identity()]));
+        q.scale(r.coeff(0).inv())
+    }
+
+    /// Corrects `codeword` in place with Patterson's algorithm, returning
+    /// the number of bits flipped.
+    pub fn decode(&self, codeword: &mut [u8]) -> Result<usize, CodeError> {
+        if codeword.len() != self.n() {
+            return Err(CodeError::WrongLength);
+        }
+        let s = self.syndrome(codeword);
+        if s.is_zero() {
+            return Ok(0);
+        }
+
+        // σ = a² + x b² with b R ≡ a mod g, where R = sqrt(S^-1 + x)
+        let t = self.capability();
+        let x = Poly::monomial(Galois::identity(), 1);
+        let inv = self.inverse_mod(&s).ok_or(CodeError::TooManyErrors)?;
+        let locator = match &inv + &x {
+            r if r.is_zero() => x,
+            r => {
+                let r = self.sqrt_mod(&r);
+                let (a, b) = self.key_equation(&r, t);
+                &(&a * &a) + &(&b * &b).shift(1)
+            }
+        };
+
+        let degree = locator.degree().unwrap_or(0);
+        if degree > t {
+            return Err(CodeError::TooManyErrors);
+        }
+        let positions: Vec<usize> = (0..self.n())
+            .filter(|j| locator.eval(self.support[*j]) == Galois::zero())
+            .collect();
+        if positions.len() != degree {
+            return Err(CodeError::TooManyErrors);
+        }
+        for pos in &positions {
+            codeword[*pos] ^= 1;
+        }
+        if !self.syndrome(codeword).is_zero() {
+            return Err(CodeError::TooManyErrors);
+        }
+        Ok(positions.len())
+    }
+
+    fn mul_mod(&self, a: &Poly, b: &Poly) -> Poly {
+        (a * b).div_rem(&self.goppa).1
+    }
+
+    // The inverse of `a` in GF(256)[x] / g by the extended Euclidean
+    // algorithm, or `None` if `a` is zero mod g.
+    fn inverse_mod(&self, a: &Poly) -> Option<Poly> {
+        let (mut r_prev, mut r) = (self.goppa.clone(), a.div_rem(&self.goppa).1);
+        let (mut t_prev, mut t) = (Poly::zero(), Poly::one());
+        while r.degree().is_some_and(|d| d > 0) {
+            let (q, rem) = r_prev.div_rem(&r);
+            let t_next = &t_prev - &(&q * &t);
+            r_prev = std::mem::replace(&mut r, rem);
+            t_prev = std::mem::replace(&mut t, t_next);
+        }
+        (!r.is_zero()).then(|| t.scale(r.coeff(0).inv()))
+    }
+
+    // In GF(256)[x] / g, a field of 2^(8t) elements, squaring is a bijection
+    // and sqrt(a) = a^(2^(8t - 1)).
+    fn sqrt_mod(&self, a: &Poly) -> Poly {
+        let mut r = a.clone();
+        for _ in 0..8 * self.capability() - 1 {
+            r = self.mul_mod(&r, &r);
+        }
+        r
+    }
+
+    // Runs the extended Euclidean algorithm on (g, r) until the remainder
+    // `a` has degree at most t/2, returning `(a, b)` with `a ≡ b r mod g`.
+    fn key_equation(&self, r: &Poly, t: usize) -> (Poly, Poly) {
+        let (mut r_prev, mut r) = (self.goppa.clone(), r.clone());
+        let (mut b_prev, mut b) = (Poly::zero(), Poly::one());
+        while r.degree().is_some_and(|d| d > t / 2) {
+            let (q, rem) = r_prev.div_rem(&r);
+            let b_next = &b_prev - &(&q * &b);
+            r_prev = std::mem::replace(&mut r, rem);
+            b_prev = std::mem::replace(&mut b, b_next);
+        }
+        (r, b)
+    }
+}
+
+/// A random monic irreducible polynomial of the given degree, drawn
+/// deterministically from `seed`.
+pub fn irreducible(degree: usize, seed: u64) -> Poly {
+    let mut rng = SplitMix(seed);
+    loop {
+        let mut coeffs: Vec<Galois> = (0..degree).map(|_| Galois::new(rng.next() as u8)).collect();
+        coeffs.push(Galois::identity());
+        let p = Poly::new(coeffs);
+        if p.is_irreducible() {
+            return p;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(k: usize, seed: usize) -> Vec<u8> {
+        (0..k)
+            .map(|i| ((i * 29 + seed * 7) >> 2) as u8 & 1)
+            .collect()
+    }
+
+    #[test]
+    fn test_parity_check() {
+        let code = GoppaCode::full(irreducible(6, 1)).unwrap();
+        assert_eq!(code.n(), 256);
+        assert!(code.k() >= 256 - 8 * 6);
+
+        let h = code.parity_check();
+        let bh = code.binary_parity_check();
+        assert_eq!((h.rows(), h.cols()), (6, 256));
+        assert_eq!((bh.rows(), bh.cols()), (48, 256));
+
+        for seed in 0..4 {
+            let c = code.encode(&message(code.k(), seed)).unwrap();
+            assert!(code.syndrome(&c).is_zero());
+            for r in 0..bh.rows() {
+                let parity = (0..bh.cols()).fold(0, |acc, j| acc ^ (bh[(r, j)] as u8 & c[j]));
+                assert_eq!(parity, 0);
+            }
+            for (i, pos) in code.information_set().iter().enumerate() {
+                assert_eq!(c[*pos], message(code.k(), seed)[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_patterson() {
+        let support: Vec<Galois> = (0..80).map(|i| Galois::new(i * 3 + 1)).collect();
+        for (code, seeds) in [
+            (GoppaCode::full(irreducible(10, 7)).unwrap(), 0..4),
+            (GoppaCode::new(irreducible(5, 3), support).unwrap(), 0..8),
+            (GoppaCode::full(irreducible(2, 9)).unwrap(), 0..8),
+        ] {
+            let (n, t) = (code.n(), code.capability());
+            for seed in seeds {
+                let original = code.encode(&message(code.k(), seed)).unwrap();
+                for errors in 0..=t {
+                    let mut received = original.clone();
+                    for e in 0..errors {
+                        received[(seed * 31 + e * (n / t)) % n] ^= 1;
+                    }
+                    assert_eq!(code.decode(&mut received), Ok(errors), "t={t}");
+                    assert_eq!(received, original);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        // x^2 + x + 1 splits over GF(256)
+        let reducible = Poly::new(vec![Galois::identity(); 3]);
+        assert_eq!(
+            GoppaCode::full(reducible),
+            Err(CodeError::InvalidParameters)
+        );
+        let duplicate = vec![Galois::new(1), Galois::new(2), Galois::new(1)];
+        assert_eq!(
+            GoppaCode::new(irreducible(2, 0), duplicate),
+            Err(CodeError::InvalidParameters)
+        );
+        // the minimum distance 2t + 1 exceeds the length, so only zero is left
+        let short: Vec<Galois> = (0..10).map(Galois::new).collect();
+        assert_eq!(
+            GoppaCode::new(irreducible(5, 0), short),
+            Err(CodeError::InvalidParameters)
+        );
+
+        let code = GoppaCode::full(irreducible(4, 0)).unwrap();
+        assert_eq!(code.decode(&mut [0u8; 10]), Err(CodeError::WrongLength));
+        assert_eq!(code.encode(&[0u8; 3]), Err(CodeError::WrongLength));
+    }
+}
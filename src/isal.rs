@@ -0,0 +1,209 @@
+//! Functions shaped like Intel ISA-L's erasure-code API.
+//!
+//! Matrices are flat row-major byte slices and the field is ISA-L's own
+//! (`0x11d`), so matrices and `gftbls` built here are interchangeable with
+//! ones built by ISA-L. Each coefficient expands to 32 bytes of `gftbls`:
+//! its products with the 16 low nibbles, then with the 16 high nibbles.
+
+use crate::{Galois, GfMatrix, MatrixError};
+
+/// Bytes of `gftbls` per matrix coefficient.
+pub const GFTBL_LEN: usize = 32;
+
+/// Fills the `m × k` matrix `a` with the identity on top and rows
+/// `(g^0, g^1, .., g^(k-1))` for `g = 1, 2, 4, ..` below.
+///
+/// Like ISA-L's, this matrix is not guaranteed to be MDS for every `k, m`.
+pub fn gf_gen_rs_matrix(a: &mut [u8], m: usize, k: usize) {
+    assert!(a.len() >= m * k, "matrix too small");
+    a[..m * k].fill(0);
+    for i in 0..k {
+        a[k * i + i] = 1;
+    }
+    let mut gen = Galois::identity();
+    for i in k..m {
+        let mut p = Galois::identity();
+        for j in 0..k {
+            a[k * i + j] = u8::from(p);
+            p *= gen;
+        }
+        gen *= Galois::new(2);
+    }
+}
+
+/// Fills the `m × k` matrix `a` with the identity on top and the Cauchy
+/// rows `1 / (i + j)` below.
+pub fn gf_gen_cauchy1_matrix(a: &mut [u8], m: usize, k: usize) {
+    assert!(a.len() >= m * k, "matrix too small");
+    assert!(m <= 256, "at most 256 rows");
+    a[..m * k].fill(0);
+    for i in 0..k {
+        a[k * i + i] = 1;
+    }
+    for i in k..m {
+        for j in 0..k {
+            a[k * i + j] = u8::from(Galois::new((i ^ j) as u8).inv());
+        }
+    }
+}
+
+/// Inverts the `n × n` matrix `input` into `out`.
+pub fn gf_invert_matrix(input: &[u8], out: &mut [u8], n: usize) -> Result<(), MatrixError> {
+    assert!(
+        input.len() >= n * n && out.len() >= n * n,
+        "matrix too small"
+    );
+    let m = GfMatrix::from_fn(n, n, |r, c| Galois::new(input[r * n + c]));
+    let inv = m.invert()?;
+    for (o, v) in out.iter_mut().zip(inv.as_slice()) {
+        *o = u8::from(*v);
+    }
+    Ok(())
+}
+
+/// Expands `c` into its 32-byte nibble tables.
+pub fn gf_vect_mul_init(c: u8, tbl: &mut [u8]) {
+    let c = Galois::new(c);
+    for x in 0..16u8 {
+        tbl[x as usize] = u8::from(c * Galois::new(x));
+        tbl[16 + x as usize] = u8::from(c * Galois::new(x << 4));
+    }
+}
+
+/// Expands the `rows × k` coefficients `a` into `g_tbls`, which must hold
+/// `rows * k * 32` bytes.
+pub fn ec_init_tables(k: usize, rows: usize, a: &[u8], g_tbls: &mut [u8]) {
+    assert!(a.len() >= rows * k, "matrix too small");
+    assert!(g_tbls.len() >= rows * k * GFTBL_LEN, "tables too small");
+    for (c, tbl) in a[..rows * k].iter().zip(g_tbls.chunks_mut(GFTBL_LEN)) {
+        gf_vect_mul_init(*c, tbl);
+    }
+}
+
+/// `coding[i] = Σ_j a[i][j] * data[j]` over the first `len` bytes, with the
+/// coefficients given as tables from [`ec_init_tables`].
+pub fn ec_encode_data(
+    len: usize,
+    k: usize,
+    rows: usize,
+    g_tbls: &[u8],
+    data: &[&[u8]],
+    coding: &mut [&mut [u8]],
+) {
+    assert!(data.len() >= k && coding.len() >= rows, "too few buffers");
+    for (i, out) in coding[..rows].iter_mut().enumerate() {
+        out[..len].fill(0);
+        for (j, input) in data[..k].iter().enumerate() {
+            let tbl = &g_tbls[(i * k + j) * GFTBL_LEN..][..GFTBL_LEN];
+            mul_add_tbl(tbl, &input[..len], &mut out[..len]);
+        }
+    }
+}
+
+/// Adds the contribution of source `vec_i` alone to `coding`, so a stripe
+/// can be encoded one source buffer at a time.
+pub fn ec_encode_data_update(
+    len: usize,
+    k: usize,
+    rows: usize,
+    vec_i: usize,
+    g_tbls: &[u8],
+    data: &[u8],
+    coding: &mut [&mut [u8]],
+) {
+    assert!(vec_i < k, "source index out of range");
+    assert!(coding.len() >= rows, "too few buffers");
+    for (i, out) in coding[..rows].iter_mut().enumerate() {
+        let tbl = &g_tbls[(i * k + vec_i) * GFTBL_LEN..][..GFTBL_LEN];
+        mul_add_tbl(tbl, &data[..len], &mut out[..len]);
+    }
+}
+
+fn mul_add_tbl(tbl: &[u8], input: &[u8], out: &mut [u8]) {
+    let (lo, hi) = tbl.split_at(16);
+    for (o, v) in out.iter_mut().zip(input) {
+        *o ^= lo[(*v & 0x0f) as usize] ^ hi[(*v >> 4) as usize];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_match_matrix() {
+        let (k, m, len) = (6, 10, 50);
+        let mut a = vec![0u8; m * k];
+        gf_gen_cauchy1_matrix(&mut a, m, k);
+        let mut g_tbls = vec![0u8; (m - k) * k * GFTBL_LEN];
+        ec_init_tables(k, m - k, &a[k * k..], &mut g_tbls);
+
+        let data: Vec<Vec<u8>> = (0..k)
+            .map(|j| (0..len).map(|b| (b * 11 + j * 29) as u8).collect())
+            .collect();
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let mut coding = vec![vec![0u8; len]; m - k];
+        let mut outs: Vec<&mut [u8]> = coding.iter_mut().map(|c| &mut c[..]).collect();
+        ec_encode_data(len, k, m - k, &g_tbls, &refs, &mut outs);
+
+        for (i, row) in coding.iter().enumerate() {
+            for (b, v) in row.iter().enumerate() {
+                let expected = (0..k).fold(Galois::zero(), |acc, j| {
+                    acc + Galois::new(a[(k + i) * k + j]) * Galois::new(data[j][b])
+                });
+                assert_eq!(*v, u8::from(expected));
+            }
+        }
+
+        let mut updated = vec![vec![0u8; len]; m - k];
+        let mut outs: Vec<&mut [u8]> = updated.iter_mut().map(|c| &mut c[..]).collect();
+        for (j, d) in data.iter().enumerate() {
+            ec_encode_data_update(len, k, m - k, j, &g_tbls, d, &mut outs);
+        }
+        assert_eq!(updated, coding);
+    }
+
+    #[test]
+    fn test_recover() {
+        // the recovery flow of ISA-L's erasure_code example
+        let (k, m, len) = (4, 7, 16);
+        let mut a = vec![0u8; m * k];
+        gf_gen_rs_matrix(&mut a, m, k);
+        assert_eq!(&a[k * k..k * k + k], &[1, 1, 1, 1]);
+        assert_eq!(&a[(k + 1) * k..(k + 2) * k], &[1, 2, 4, 8]);
+
+        let mut g_tbls = vec![0u8; m * k * GFTBL_LEN];
+        ec_init_tables(k, m - k, &a[k * k..], &mut g_tbls);
+        let mut frags: Vec<Vec<u8>> = (0..k).map(|j| vec![j as u8 * 40 + 3; len]).collect();
+        frags.resize(m, vec![0u8; len]);
+        let (src, parity) = frags.split_at_mut(k);
+        let refs: Vec<&[u8]> = src.iter().map(|d| &d[..]).collect();
+        let mut outs: Vec<&mut [u8]> = parity.iter_mut().map(|c| &mut c[..]).collect();
+        ec_encode_data(len, k, m - k, &g_tbls, &refs, &mut outs);
+
+        // lose fragments 0 and 2, decode from 1, 3, 4, 5
+        let survivors = [1, 3, 4, 5];
+        let mut b = vec![0u8; k * k];
+        for (r, s) in survivors.iter().enumerate() {
+            b[r * k..(r + 1) * k].copy_from_slice(&a[s * k..(s + 1) * k]);
+        }
+        let mut d = vec![0u8; k * k];
+        gf_invert_matrix(&b, &mut d, k).unwrap();
+        let mut c = Vec::new();
+        for lost in [0, 2] {
+            c.extend_from_slice(&d[lost * k..(lost + 1) * k]);
+        }
+        ec_init_tables(k, 2, &c, &mut g_tbls);
+        let refs: Vec<&[u8]> = survivors.iter().map(|s| &frags[*s][..]).collect();
+        let mut recovered = vec![vec![0u8; len]; 2];
+        let mut outs: Vec<&mut [u8]> = recovered.iter_mut().map(|c| &mut c[..]).collect();
+        ec_encode_data(len, k, 2, &g_tbls, &refs, &mut outs);
+        assert_eq!(recovered[0], frags[0]);
+        assert_eq!(recovered[1], frags[2]);
+
+        assert_eq!(
+            gf_invert_matrix(&[1, 1, 1, 1], &mut d, 2),
+            Err(MatrixError::Singular)
+        );
+    }
+}
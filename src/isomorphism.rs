@@ -0,0 +1,383 @@
+use std::io::{self, Write};
+
+use crate::{Galois, GfMatrix};
+
+/// Maps between GF(2)[x]/(poly) and the crate's own GF(256) representation.
+///
+/// The map sends `x` to a root `β` of `poly` in the crate's field, so the
+/// element with bits `b_i` maps to `Σ b_i β^i`. This is a field isomorphism
+/// exactly when `poly` is irreducible of degree 8.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FieldMap {
+    to_std: [u8; 256],
+    from_std: [u8; 256],
+}
+
+impl FieldMap {
+    /// Returns `None` unless `poly` is an irreducible polynomial of degree 8,
+    /// written with its `x^8` term (e.g. `0x11b`).
+    pub(crate) fn new(poly: u16) -> Option<Self> {
+        if poly >> 8 != 1 {
+            return None;
+        }
+
+        let beta = (2..=255u8).map(Galois::new).find(|b| {
+            let value = (0..=8)
+                .filter(|i| poly & (1 << i) != 0)
+                .fold(Galois::zero(), |acc, i| acc + b.exp(i));
+            value == Galois::zero()
+        })?;
+        // a root outside every proper subfield (all of which lie in GF(16))
+        // has a degree-8 minimal polynomial, which must then be `poly`
+        if beta.exp(16) == beta {
+            return None;
+        }
+
+        let mut to_std = [0u8; 256];
+        let mut from_std = [0u8; 256];
+        for v in 0..=255u8 {
+            let image = (0..8)
+                .filter(|i| v & (1 << i) != 0)
+                .fold(Galois::zero(), |acc, i| acc + beta.exp(i));
+            to_std[v as usize] = u8::from(image);
+            from_std[u8::from(image) as usize] = v;
+        }
+        Some(FieldMap { to_std, from_std })
+    }
+
+    /// Builds a map from its forward table, which must be a permutation.
+    pub(crate) fn from_forward(to_std: [u8; 256]) -> Self {
+        let mut from_std = [0u8; 256];
+        for (v, image) in to_std.iter().enumerate() {
+            from_std[*image as usize] = v as u8;
+        }
+        debug_assert!((0..=255u8).all(|v| to_std[from_std[v as usize] as usize] == v));
+        FieldMap { to_std, from_std }
+    }
+
+    /// The map that applies `self` first and then `next`.
+    pub(crate) fn then(&self, next: &FieldMap) -> FieldMap {
+        let mut to_std = [0u8; 256];
+        for (v, image) in to_std.iter_mut().enumerate() {
+            *image = next.forward(self.forward(v as u8));
+        }
+        FieldMap::from_forward(to_std)
+    }
+
+    pub(crate) fn forward(&self, v: u8) -> u8 {
+        self.to_std[v as usize]
+    }
+
+    pub(crate) fn inverse(&self, v: u8) -> u8 {
+        self.from_std[v as usize]
+    }
+}
+
+/// The isomorphism between two representations of GF(256) as GF(2)[x]
+/// modulo different irreducible polynomials, e.g. this crate's `0x11d` and
+/// AES's `0x11b`.
+///
+/// Two such fields are isomorphic in eight ways, one for each root of
+/// `from` in the `to` field; this picks one deterministically. Elements are
+/// mapped by table lookup, and since the map is linear over GF(2) it also
+/// carries bitwise linear layers across by conjugation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Isomorphism {
+    from: u16,
+    to: u16,
+    map: FieldMap,
+}
+
+impl Isomorphism {
+    /// Returns `None` unless both polynomials are irreducible of degree 8,
+    /// written with their `x^8` term.
+    pub fn new(from: u16, to: u16) -> Option<Self> {
+        let to_map = FieldMap::new(to)?;
+        let from_map = FieldMap::new(from)?;
+        let map = FieldMap::from_forward(std::array::from_fn(|v| {
+            to_map.inverse(from_map.forward(v as u8))
+        }));
+        Some(Isomorphism { from, to, map })
+    }
+
+    pub fn from_poly(&self) -> u16 {
+        self.from
+    }
+
+    pub fn to_poly(&self) -> u16 {
+        self.to
+    }
+
+    /// The isomorphism in the other direction.
+    pub fn inverse(&self) -> Isomorphism {
+        Isomorphism {
+            from: self.to,
+            to: self.from,
+            map: FieldMap::from_forward(std::array::from_fn(|v| self.map.inverse(v as u8))),
+        }
+    }
+
+    /// Maps an element of the `from` field to the `to` field.
+    pub fn map(&self, v: u8) -> u8 {
+        self.map.forward(v)
+    }
+
+    /// Maps an element of the `to` field back to the `from` field.
+    pub fn unmap(&self, v: u8) -> u8 {
+        self.map.inverse(v)
+    }
+
+    pub fn map_slice(&self, data: &mut [u8]) {
+        for v in data {
+            *v = self.map.forward(*v);
+        }
+    }
+
+    pub fn unmap_slice(&self, data: &mut [u8]) {
+        for v in data {
+            *v = self.map.inverse(*v);
+        }
+    }
+
+    /// The map as an 8×8 matrix over GF(2), column `j` being the image of
+    /// bit `j`.
+    pub fn bit_matrix(&self) -> [u8; 8] {
+        std::array::from_fn(|j| self.map(1 << j))
+    }
+
+    /// Carries a GF(2)-linear map on bytes, given as columns like
+    /// [`bit_matrix`](Isomorphism::bit_matrix), from the `from`
+    /// representation to the `to` one: `T L T^-1` for `T` this map.
+    pub fn conjugate_bits(&self, columns: [u8; 8]) -> [u8; 8] {
+        let apply = |v: u8| {
+            (0..8)
+                .filter(|j| v >> j & 1 == 1)
+                .fold(0, |acc, j| acc ^ columns[j])
+        };
+        std::array::from_fn(|j| self.map(apply(self.unmap(1 << j))))
+    }
+
+    /// Carries any byte function given as a table, such as an S-box, across
+    /// the same way: the result on `map(v)` is `map(table[v])`.
+    pub fn conjugate_table(&self, table: &[u8; 256]) -> [u8; 256] {
+        std::array::from_fn(|v| self.map(table[self.unmap(v as u8) as usize]))
+    }
+
+    /// Maps every entry of a matrix whose entries are bytes of the `from`
+    /// representation, giving the same linear map over the `to` field.
+    pub fn map_matrix(&self, m: &GfMatrix) -> GfMatrix {
+        GfMatrix::from_fn(m.rows(), m.cols(), |r, c| {
+            Galois::new(self.map(u8::from(m[(r, c)])))
+        })
+    }
+
+    /// The table of [`map`](Isomorphism::map).
+    pub fn table(&self) -> [u8; 256] {
+        self.map.to_std
+    }
+
+    /// The table of [`unmap`](Isomorphism::unmap).
+    pub fn inverse_table(&self) -> [u8; 256] {
+        self.map.from_std
+    }
+
+    /// Writes both tables, for firmware or other languages that should not
+    /// build them at run time: as source, named `map_11b_to_11d` and
+    /// `map_11d_to_11b` (upper case in Rust) for `0x11b` and `0x11d`, or
+    /// as the 512 raw bytes of [`table`](Isomorphism::table) then
+    /// [`inverse_table`](Isomorphism::inverse_table).
+    pub fn export<W: Write>(&self, format: TableFormat, mut out: W) -> io::Result<()> {
+        if format == TableFormat::C {
+            writeln!(out, "#include <stdint.h>")?;
+            writeln!(out)?;
+        }
+        let tables = [
+            (self.from, self.to, self.table()),
+            (self.to, self.from, self.inverse_table()),
+        ];
+        for (i, (from, to, table)) in tables.iter().enumerate() {
+            if i > 0 && format != TableFormat::Raw {
+                writeln!(out)?;
+            }
+            if format != TableFormat::Raw {
+                writeln!(out, "// GF(2)[x]/({from:#x}) to GF(2)[x]/({to:#x})")?;
+            }
+            let name = format!("map_{from:x}_to_{to:x}");
+            format.write_table(&name, table, &mut out)?;
+        }
+        Ok(())
+    }
+}
+
+/// How [`Isomorphism::export`] and [`TableFormat::write_table`] write a
+/// 256-entry table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    /// A `pub const` array, its name in upper case.
+    Rust,
+    /// A `static const uint8_t` array.
+    C,
+    /// The 256 bytes themselves, without a name.
+    Raw,
+}
+
+impl TableFormat {
+    /// Writes any byte table, such as those of a
+    /// [`DualBasis`](crate::rs::DualBasis), 16 entries a line.
+    pub fn write_table<W: Write>(
+        self,
+        name: &str,
+        table: &[u8; 256],
+        mut out: W,
+    ) -> io::Result<()> {
+        let (open, close) = match self {
+            TableFormat::Raw => return out.write_all(table),
+            TableFormat::Rust => (
+                format!("pub const {}: [u8; 256] = [", name.to_uppercase()),
+                "];",
+            ),
+            TableFormat::C => (format!("static const uint8_t {name}[256] = {{"), "};"),
+        };
+        writeln!(out, "{open}")?;
+        for row in table.chunks(16) {
+            let entries: Vec<String> = row.iter().map(|v| format!("0x{v:02x},")).collect();
+            writeln!(out, "    {}", entries.join(" "))?;
+        }
+        writeln!(out, "{close}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Multiplication in GF(2)[x]/(poly) by shift-and-add.
+    fn mul_mod(mut a: u8, mut b: u8, poly: u16) -> u8 {
+        let mut acc = 0u8;
+        while b != 0 {
+            if b & 1 == 1 {
+                acc ^= a;
+            }
+            b >>= 1;
+            let carry = a & 0x80 != 0;
+            a <<= 1;
+            if carry {
+                a ^= (poly & 0xff) as u8;
+            }
+        }
+        acc
+    }
+
+    #[test]
+    fn test_homomorphism() {
+        for poly in [0x11bu16, 0x187, 0x12d, 0x11d] {
+            let map = FieldMap::new(poly).unwrap();
+            for a in (0..=255u8).step_by(7) {
+                assert_eq!(map.inverse(map.forward(a)), a);
+                for b in (0..=255u8).step_by(11) {
+                    let lhs = Galois::new(map.forward(mul_mod(a, b, poly)));
+                    let rhs = Galois::new(map.forward(a)) * Galois::new(map.forward(b));
+                    assert_eq!(lhs, rhs);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_isomorphism() {
+        let iso = Isomorphism::new(0x11b, 0x187).unwrap();
+        let back = iso.inverse();
+        assert_eq!((back.from_poly(), back.to_poly()), (0x187, 0x11b));
+        for a in 0..=255u8 {
+            assert_eq!(back.map(iso.map(a)), a);
+            for b in (0..=255u8).step_by(13) {
+                assert_eq!(
+                    iso.map(mul_mod(a, b, 0x11b)),
+                    mul_mod(iso.map(a), iso.map(b), 0x187)
+                );
+            }
+        }
+        let mut data = [1, 2, 3, 0x80];
+        iso.map_slice(&mut data);
+        assert_eq!(data[0], 1);
+        iso.unmap_slice(&mut data);
+        assert_eq!(data, [1, 2, 3, 0x80]);
+        assert!(Isomorphism::new(0x11b, 0x101).is_none());
+    }
+
+    #[test]
+    fn test_conjugation() {
+        let iso = Isomorphism::new(0x11d, 0x11b).unwrap();
+        let t = iso.bit_matrix();
+        assert_eq!(t[0], 1);
+
+        // multiplication by 3 as a bit matrix, carried across, is
+        // multiplication by the image of 3
+        let times3: [u8; 8] =
+            std::array::from_fn(|j| u8::from(Galois::new(3) * Galois::new(1 << j)));
+        let carried = iso.conjugate_bits(times3);
+        let table: [u8; 256] =
+            std::array::from_fn(|v| u8::from(Galois::new(3) * Galois::new(v as u8)));
+        let carried_table = iso.conjugate_table(&table);
+        for v in 0..=255u8 {
+            let expected = mul_mod(iso.map(3), v, 0x11b);
+            let by_bits = (0..8)
+                .filter(|j| v >> j & 1 == 1)
+                .fold(0, |acc, j| acc ^ carried[j]);
+            assert_eq!(by_bits, expected);
+            assert_eq!(carried_table[v as usize], expected);
+        }
+
+        // a matrix over the crate's field, mapped, acts the same in AES's
+        let m = GfMatrix::from_fn(2, 3, |r, c| Galois::new((7 * r + 5 * c + 1) as u8));
+        let v = [0x12, 0x9a, 0xf0];
+        let mapped = iso.map_matrix(&m);
+        for r in 0..2 {
+            let direct = (0..3).fold(Galois::zero(), |acc, c| acc + m[(r, c)] * Galois::new(v[c]));
+            let via = (0..3).fold(0, |acc, c| {
+                acc ^ mul_mod(u8::from(mapped[(r, c)]), iso.map(v[c]), 0x11b)
+            });
+            assert_eq!(iso.map(u8::from(direct)), via);
+        }
+    }
+
+    #[test]
+    fn test_export() {
+        let iso = Isomorphism::new(0x11b, 0x11d).unwrap();
+        let mut raw = Vec::new();
+        iso.export(TableFormat::Raw, &mut raw).unwrap();
+        assert_eq!(raw[..256], iso.table());
+        assert_eq!(raw[256..], iso.inverse_table());
+
+        // the entries read back from source, in order
+        let parse = |source: &str| -> Vec<u8> {
+            source
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter_map(|t| t.strip_prefix("0x"))
+                .filter(|t| t.len() == 2)
+                .map(|t| u8::from_str_radix(t, 16).unwrap())
+                .collect()
+        };
+        let source = |format| {
+            let mut source = Vec::new();
+            iso.export(format, &mut source).unwrap();
+            String::from_utf8(source).unwrap()
+        };
+        let (rust, c) = (source(TableFormat::Rust), source(TableFormat::C));
+        assert_eq!(parse(&rust), raw);
+        assert_eq!(parse(&c), raw);
+        assert!(rust.contains("pub const MAP_11B_TO_11D: [u8; 256] = [\n    0x00, 0x01,"));
+        assert!(rust.contains("pub const MAP_11D_TO_11B: [u8; 256] = ["));
+        assert!(c.contains("static const uint8_t map_11b_to_11d[256] = {\n"));
+        assert!(c.ends_with("};\n"));
+    }
+
+    #[test]
+    fn test_reducible() {
+        // x^8 + 1 = (x + 1)^8, and (x^4 + x + 1)(x^4 + x^3 + 1) whose roots
+        // all lie in GF(16)
+        assert!(FieldMap::new(0x101).is_none());
+        assert!(FieldMap::new(0x1bb).is_none());
+        assert!(FieldMap::new(0x7b).is_none());
+    }
+}
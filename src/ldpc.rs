@@ -0,0 +1,242 @@
+//! Non-binary LDPC codes over GF(256).
+//!
+//! A code is given by a sparse `m × n` parity-check matrix `H = [A | T]`:
+//! the first `k = n - m` symbols of a codeword are the message, and `T` is
+//! lower triangular with a nonzero diagonal so that the parity follows by
+//! forward substitution, one check at a time. [`LdpcCode::random`] builds
+//! the usual staircase form, with `T` bidiagonal.
+//!
+//! Decoding is for the erasure channel: the peeling decoder repeatedly
+//! solves a check with a single missing symbol. It runs in time linear in
+//! the number of nonzeros of `H`, but unlike an MDS code it can stall on a
+//! stopping set before `m` erasures.
+
+use std::collections::VecDeque;
+
+use crate::fountain::SplitMix;
+use crate::rs::CodeError;
+use crate::{Galois, SparseMatrix};
+
+/// An LDPC code over GF(256) with a triangular parity part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LdpcCode {
+    h: SparseMatrix,
+    // the checks each symbol takes part in
+    columns: Vec<Vec<usize>>,
+}
+
+impl LdpcCode {
+    /// Fails unless `h` has fewer rows than columns and its last `m`
+    /// columns are lower triangular with a nonzero diagonal.
+    pub fn new(h: SparseMatrix) -> Result<Self, CodeError> {
+        let (m, n) = (h.rows(), h.cols());
+        if m == 0 || m >= n {
+            return Err(CodeError::InvalidParameters);
+        }
+        let k = n - m;
+        for i in 0..m {
+            let row = h.row(i);
+            if row.last().map(|e| e.0) != Some(k + i) {
+                return Err(CodeError::InvalidParameters);
+            }
+        }
+
+        let columns = h.column_rows();
+        Ok(LdpcCode { h, columns })
+    }
+
+    /// A code with `column_weight` nonzeros in each message column, placed
+    /// on random checks with random values drawn from `seed`, and a
+    /// staircase parity part.
+    pub fn random(n: usize, k: usize, column_weight: usize, seed: u64) -> Result<Self, CodeError> {
+        if k == 0 || k >= n || column_weight == 0 || column_weight > n - k {
+            return Err(CodeError::InvalidParameters);
+        }
+        let m = n - k;
+        let mut rng = SplitMix(seed);
+        let mut nonzero = || Galois::new((rng.next() % 255) as u8 + 1);
+
+        let mut h = SparseMatrix::zero(m, n);
+        for i in 0..m {
+            h.set(i, k + i, nonzero());
+            if i > 0 {
+                h.set(i, k + i - 1, nonzero());
+            }
+        }
+        let mut rng = SplitMix(seed.rotate_left(32));
+        for c in 0..k {
+            let mut placed = 0;
+            while placed < column_weight {
+                let r = (rng.next() % m as u64) as usize;
+                if h.get(r, c) == Galois::zero() {
+                    h.set(r, c, Galois::new((rng.next() % 255) as u8 + 1));
+                    placed += 1;
+                }
+            }
+        }
+        LdpcCode::new(h)
+    }
+
+    pub fn n(&self) -> usize {
+        self.h.cols()
+    }
+
+    pub fn k(&self) -> usize {
+        self.h.cols() - self.h.rows()
+    }
+
+    pub fn parity_len(&self) -> usize {
+        self.h.rows()
+    }
+
+    pub fn parity_check(&self) -> &SparseMatrix {
+        &self.h
+    }
+
+    /// Returns the codeword for `msg`: the message followed by its parity.
+    pub fn encode(&self, msg: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if msg.len() != self.k() {
+            return Err(CodeError::WrongLength);
+        }
+        let k = self.k();
+        let mut codeword = vec![0u8; self.n()];
+        codeword[..k].copy_from_slice(msg);
+
+        // check `i` is the first to involve parity symbol `k + i`
+        for i in 0..self.parity_len() {
+            let (diag, rest) = self.h.row(i).split_last().unwrap();
+            let acc = rest.iter().fold(Galois::zero(), |acc, (c, v)| {
+                acc + *v * Galois::new(codeword[*c])
+            });
+            codeword[k + i] = u8::from(acc / diag.1);
+        }
+        Ok(codeword)
+    }
+
+    /// `H c`, which is zero exactly for codewords.
+    pub fn syndrome(&self, codeword: &[u8]) -> Vec<Galois> {
+        let c: Vec<Galois> = codeword.iter().map(|v| Galois::new(*v)).collect();
+        self.h.mul_vec(&c)
+    }
+
+    /// Fills in the erased symbols of `codeword` by peeling.
+    ///
+    /// The erased bytes may hold any value on input. Fails with
+    /// [`CodeError::TooManyErrors`] if the decoder stalls with symbols left.
+    pub fn decode_erasures(
+        &self,
+        codeword: &mut [u8],
+        erasures: &[usize],
+    ) -> Result<(), CodeError> {
+        if codeword.len() != self.n() || erasures.iter().any(|e| *e >= self.n()) {
+            return Err(CodeError::WrongLength);
+        }
+        let mut erased = vec![false; self.n()];
+        for e in erasures {
+            erased[*e] = true;
+        }
+
+        // the number of erased symbols in each check
+        let mut missing: Vec<usize> = (0..self.parity_len())
+            .map(|i| self.h.row(i).iter().filter(|(c, _)| erased[*c]).count())
+            .collect();
+        let mut ready: VecDeque<usize> = (0..missing.len()).filter(|i| missing[*i] == 1).collect();
+        let mut left = erased.iter().filter(|e| **e).count();
+
+        while let Some(i) = ready.pop_front() {
+            if missing[i] != 1 {
+                continue;
+            }
+            let row = self.h.row(i);
+            let mut acc = Galois::zero();
+            let mut target = None;
+            for (c, v) in row {
+                if erased[*c] {
+                    target = Some((*c, *v));
+                } else {
+                    acc += *v * Galois::new(codeword[*c]);
+                }
+            }
+            let (c, v) = target.unwrap();
+            codeword[c] = u8::from(acc / v);
+            erased[c] = false;
+            left -= 1;
+
+            for j in &self.columns[c] {
+                missing[*j] -= 1;
+                if missing[*j] == 1 {
+                    ready.push_back(*j);
+                }
+            }
+        }
+
+        if left > 0 {
+            return Err(CodeError::TooManyErrors);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(k: usize, seed: usize) -> Vec<u8> {
+        (0..k).map(|i| (i * 53 + seed * 17 + 5) as u8).collect()
+    }
+
+    #[test]
+    fn test_encode() {
+        let code = LdpcCode::random(120, 80, 3, 1).unwrap();
+        assert_eq!(code.parity_check().nnz(), 80 * 3 + 40 + 39);
+        for seed in 0..4 {
+            let codeword = code.encode(&message(80, seed)).unwrap();
+            assert_eq!(codeword[..80], message(80, seed)[..]);
+            assert!(code
+                .syndrome(&codeword)
+                .iter()
+                .all(|s| *s == Galois::zero()));
+        }
+        assert_eq!(code.encode(&[0; 79]), Err(CodeError::WrongLength));
+    }
+
+    #[test]
+    fn test_peeling() {
+        let code = LdpcCode::random(400, 200, 3, 7).unwrap();
+        let original = code.encode(&message(200, 2)).unwrap();
+
+        // scattered erasures well below the parity length peel cleanly
+        let erased: Vec<usize> = (0..60).map(|i| (i * 47 + 3) % 400).collect();
+        let mut received = original.clone();
+        for e in &erased {
+            received[*e] = 0;
+        }
+        assert_eq!(code.decode_erasures(&mut received, &erased), Ok(()));
+        assert_eq!(received, original);
+
+        // more erasures than checks can never be solved
+        let erased: Vec<usize> = (0..201).map(|i| i * 2 % 400).collect();
+        assert_eq!(
+            code.decode_erasures(&mut received.clone(), &erased),
+            Err(CodeError::TooManyErrors)
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        // the parity part must be lower triangular
+        let mut h = SparseMatrix::zero(2, 4);
+        h.set(0, 2, Galois::new(1));
+        h.set(0, 3, Galois::new(1));
+        h.set(1, 3, Galois::new(1));
+        assert_eq!(LdpcCode::new(h), Err(CodeError::InvalidParameters));
+        assert_eq!(
+            LdpcCode::random(10, 10, 1, 0),
+            Err(CodeError::InvalidParameters)
+        );
+        assert_eq!(
+            LdpcCode::random(10, 8, 3, 0),
+            Err(CodeError::InvalidParameters)
+        );
+    }
+}
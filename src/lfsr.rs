@@ -0,0 +1,305 @@
+//! Linear feedback shift registers over GF(2).
+//!
+//! A register of `n` bits is described by its characteristic polynomial
+//! `p(x) = x^n + c_(n-1) x^(n-1) + .. + c_0`, given as in [`crc`](crate::crc)
+//! and [`rabin`](crate::rabin) by its degree and the `u64` of the `c_i`
+//! without the `x^n` term. Its output satisfies
+//! `s_(t+n) = c_(n-1) s_(t+n-1) + .. + c_0 s_t`.
+//!
+//! - In the Fibonacci topology bit `i` of the state is `s_(t+i)`: the
+//!   register outputs bit 0 and shifts in the parity of the tapped bits.
+//! - In the Galois topology the state is a polynomial `r(x)` modulo `p`,
+//!   multiplied by `x` at each step, and the output is its coefficient of
+//!   `x^(n-1)`.
+//!
+//! Either way every nonzero state runs through all `2^n - 1` of them, an
+//! m-sequence, exactly when `p` is primitive, which [`is_primitive`]
+//! checks and [`primitive_polynomials`] enumerates. Both steps are linear
+//! over GF(2), so [`Lfsr::jump`] advances by any number of steps with a
+//! power of the `n × n` transition matrix.
+//!
+//! [`Gold`] builds the Gold code families of CDMA and GPS on pairs of
+//! registers, and [`berlekamp_massey`] finds the shortest register that
+//! generates a given sequence. [`LfsrRng`] is a seedable generator on a
+//! 64-bit register.
+
+mod gold;
+mod massey;
+mod rng;
+mod search;
+
+pub use gold::{cross_correlation, Gold};
+pub use massey::{berlekamp_massey, berlekamp_massey_bits, linear_complexity};
+pub use rng::LfsrRng;
+pub use search::{irreducible_polynomials, primitive_polynomials};
+
+use crate::rabin::{is_irreducible, mul_mod};
+use crate::util::prime_factors;
+
+/// How the feedback is wired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    Fibonacci,
+    Galois,
+}
+
+/// A linear feedback shift register of up to 64 bits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lfsr {
+    poly: u64,
+    degree: u8,
+    topology: Topology,
+    state: u64,
+}
+
+impl Lfsr {
+    /// A register for `x^degree + poly`, panicking if the degree is not in
+    /// `1..=64` or `poly` or `state` have bits at or above it.
+    ///
+    /// The zero state stays zero.
+    pub fn new(poly: u64, degree: u8, topology: Topology, state: u64) -> Self {
+        assert!((1..=64).contains(&degree), "bad LFSR degree");
+        assert!(poly & !mask(degree) == 0, "bad LFSR polynomial");
+        assert!(state & !mask(degree) == 0, "bad LFSR state");
+        Lfsr {
+            poly,
+            degree,
+            topology,
+            state,
+        }
+    }
+
+    pub fn fibonacci(poly: u64, degree: u8, state: u64) -> Self {
+        Lfsr::new(poly, degree, Topology::Fibonacci, state)
+    }
+
+    pub fn galois(poly: u64, degree: u8, state: u64) -> Self {
+        Lfsr::new(poly, degree, Topology::Galois, state)
+    }
+
+    /// The polynomial without its `x^degree` term.
+    pub fn poly(&self) -> u64 {
+        self.poly
+    }
+
+    pub fn degree(&self) -> u8 {
+        self.degree
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn set_state(&mut self, state: u64) {
+        assert!(state & !mask(self.degree) == 0, "bad LFSR state");
+        self.state = state;
+    }
+
+    /// Whether the register produces an m-sequence from every nonzero
+    /// state.
+    pub fn is_maximal(&self) -> bool {
+        is_primitive(self.poly, self.degree)
+    }
+
+    pub fn next_bit(&mut self) -> bool {
+        let (state, bit) = self.step(self.state);
+        self.state = state;
+        bit
+    }
+
+    /// Fills `out` with the next bits, one per byte, each 0 or 1.
+    pub fn fill_bits(&mut self, out: &mut [u8]) {
+        for b in out {
+            *b = self.next_bit() as u8;
+        }
+    }
+
+    /// Fills `out` with the next bits, eight per byte, the first in the
+    /// most significant bit.
+    pub fn fill_bytes(&mut self, out: &mut [u8]) {
+        for b in out {
+            *b = (0..8).fold(0, |acc, _| acc << 1 | self.next_bit() as u8);
+        }
+    }
+
+    /// The one-step transition as an `n × n` matrix over GF(2), column `j`
+    /// being the successor of the state with only bit `j` set.
+    pub fn transition(&self) -> Vec<u64> {
+        (0..self.degree).map(|j| self.step(1 << j).0).collect()
+    }
+
+    /// Advances by `steps` steps in `O(n^2 log steps)`, without producing
+    /// the output.
+    pub fn jump(&mut self, mut steps: u64) {
+        let mut power = self.transition();
+        while steps > 0 {
+            if steps & 1 == 1 {
+                self.state = apply(&power, self.state);
+            }
+            power = power.iter().map(|&c| apply(&power, c)).collect();
+            steps >>= 1;
+        }
+    }
+
+    fn step(&self, state: u64) -> (u64, bool) {
+        let n = self.degree;
+        match self.topology {
+            Topology::Fibonacci => {
+                let feedback = (state & self.poly).count_ones() as u64 & 1;
+                (state >> 1 | feedback << (n - 1), state & 1 == 1)
+            }
+            Topology::Galois => {
+                let top = state >> (n - 1) & 1;
+                let shifted = state << 1 & mask(n);
+                (shifted ^ (self.poly & 0u64.wrapping_sub(top)), top == 1)
+            }
+        }
+    }
+}
+
+/// Whether `x^degree + poly` is primitive over GF(2): irreducible, with `x`
+/// of order `2^degree - 1`, which is checked against `(2^degree - 1) / q`
+/// for each prime factor `q`.
+pub fn is_primitive(poly: u64, degree: u8) -> bool {
+    if poly & 1 == 0 || !is_irreducible(poly, degree) {
+        return false;
+    }
+    let p = 1u128 << degree | poly as u128;
+    let order = mask(degree);
+    prime_factors(order)
+        .into_iter()
+        .all(|q| pow_x(order / q, p, degree) != 1)
+}
+
+fn mask(degree: u8) -> u64 {
+    if degree == 64 {
+        !0
+    } else {
+        (1 << degree) - 1
+    }
+}
+
+fn apply(columns: &[u64], v: u64) -> u64 {
+    (0..columns.len())
+        .filter(|j| v >> j & 1 == 1)
+        .fold(0, |acc, j| acc ^ columns[j])
+}
+
+// x^e modulo p
+fn pow_x(e: u64, p: u128, degree: u8) -> u128 {
+    let (mut result, mut base) = (1u128, 2 % p);
+    for i in 0..64 - e.leading_zeros() {
+        if e >> i & 1 == 1 {
+            result = mul_mod(result, base, p, degree);
+        }
+        base = mul_mod(base, base, p, degree);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period(lfsr: &Lfsr) -> usize {
+        let mut l = lfsr.clone();
+        let start = l.state();
+        (1..)
+            .find(|_| {
+                l.next_bit();
+                l.state() == start
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_primitive() {
+        // primitive polynomials from the usual tap tables
+        assert!(is_primitive(0b0_0101, 5));
+        assert!(is_primitive(0x1d, 8));
+        assert!(is_primitive(0x0040_0007, 32));
+        assert!(is_primitive(0xb000_0000_0000_0001, 64));
+        // x^8 + x^4 + x^3 + x + 1 is irreducible but x has order 51
+        assert!(!is_primitive(0x1b, 8));
+        assert!(!is_primitive(0x1c, 8));
+    }
+
+    #[test]
+    fn test_m_sequences() {
+        // primitive exactly when every topology has the full period
+        for degree in 1..=10u8 {
+            let mut count = 0;
+            for poly in 0..1u64 << degree {
+                let primitive = is_primitive(poly, degree);
+                count += primitive as usize;
+                for topology in [Topology::Fibonacci, Topology::Galois] {
+                    let lfsr = Lfsr::new(poly, degree, topology, 1);
+                    let full = poly & 1 == 1 && period(&lfsr) == (1 << degree) - 1;
+                    assert_eq!(full, primitive, "{poly:#x} {degree} {topology:?}");
+                }
+            }
+            // φ(2^n - 1) / n
+            let expected = [1, 1, 2, 2, 6, 6, 18, 16, 48, 60][degree as usize - 1];
+            assert_eq!(count, expected);
+        }
+    }
+
+    #[test]
+    fn test_recurrence() {
+        // s_(t+5) = s_(t+2) + s_t for x^5 + x^2 + 1, in both topologies
+        for mut lfsr in [
+            Lfsr::fibonacci(0b101, 5, 0b1_0011),
+            Lfsr::galois(0b101, 5, 7),
+        ] {
+            let mut bits = [0u8; 100];
+            lfsr.fill_bits(&mut bits);
+            for t in 0..95 {
+                assert_eq!(bits[t + 5], bits[t + 2] ^ bits[t]);
+            }
+        }
+        // the Fibonacci register outputs its state first
+        let mut lfsr = Lfsr::fibonacci(0b101, 5, 0b1_0011);
+        let mut bits = [0u8; 5];
+        lfsr.fill_bits(&mut bits);
+        assert_eq!(bits, [1, 1, 0, 0, 1]);
+
+        let mut a = Lfsr::galois(0x1d, 8, 0x5a);
+        let mut b = a.clone();
+        let mut bytes = [0u8; 4];
+        a.fill_bytes(&mut bytes);
+        let mut bits = [0u8; 32];
+        b.fill_bits(&mut bits);
+        for (i, byte) in bytes.iter().enumerate() {
+            let packed = bits[8 * i..8 * i + 8]
+                .iter()
+                .fold(0, |acc, &b| acc << 1 | b);
+            assert_eq!(*byte, packed);
+        }
+    }
+
+    #[test]
+    fn test_jump() {
+        for (poly, degree) in [(0b101, 5), (0x0040_0007, 32), (0xb000_0000_0000_0001, 64)] {
+            for topology in [Topology::Fibonacci, Topology::Galois] {
+                let start = Lfsr::new(poly, degree, topology, 0x1234 & mask(degree));
+                for steps in [0, 1, 7, 100, 1000] {
+                    let mut stepped = start.clone();
+                    for _ in 0..steps {
+                        stepped.next_bit();
+                    }
+                    let mut jumped = start.clone();
+                    jumped.jump(steps);
+                    assert_eq!(jumped, stepped);
+                }
+                // an m-sequence returns after 2^n - 1 steps
+                let mut full = start.clone();
+                full.jump(mask(degree));
+                assert_eq!(full, start);
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use super::{is_primitive, mask, Lfsr};
+use crate::rabin::mul_mod;
+
+/// Gold sequences from a preferred pair of m-sequences.
+///
+/// Each code is `a_t + b_(t - delay)` for a delay below the period
+/// `N = 2^n - 1`, and together with `a` and `b` themselves they form a
+/// family of `N + 2` sequences of period `N`. For a preferred pair any two
+/// of them have a periodic cross-correlation in `{-1, -t, t - 2}`, with
+/// `t = 2^((n + 1) / 2) + 1` for odd `n` and `2^((n + 2) / 2) + 1` for even
+/// `n`, which is what makes them usable as CDMA spreading codes; the GPS
+/// C/A codes are one such family.
+#[derive(Debug, Clone)]
+pub struct Gold {
+    a: Lfsr,
+    b: Lfsr,
+}
+
+impl Gold {
+    /// The family of two registers of the same degree at their starting
+    /// states, panicking if their degrees differ.
+    pub fn new(a: Lfsr, b: Lfsr) -> Self {
+        assert_eq!(a.degree(), b.degree(), "LFSR degrees differ");
+        Gold { a, b }
+    }
+
+    /// The preferred pair of the primitive `x^degree + poly` and the
+    /// minimal polynomial of `α^(2^k + 1)` for a root `α`, with `k = 1` for
+    /// odd degrees and `k = 2` for degrees `2 mod 4`, both as Fibonacci
+    /// registers from the state 1. `None` if `poly` is not primitive or the
+    /// degree is a multiple of 4, for which no preferred pair exists.
+    pub fn preferred(poly: u64, degree: u8) -> Option<Self> {
+        let k = match degree % 4 {
+            1 | 3 => 1,
+            2 => 2,
+            _ => return None,
+        };
+        if !is_primitive(poly, degree) {
+            return None;
+        }
+        let other = minimal_poly(poly, degree, (1 << k) + 1);
+        Some(Gold::new(
+            Lfsr::fibonacci(poly, degree, 1),
+            Lfsr::fibonacci(other, degree, 1),
+        ))
+    }
+
+    pub fn a(&self) -> &Lfsr {
+        &self.a
+    }
+
+    pub fn b(&self) -> &Lfsr {
+        &self.b
+    }
+
+    /// The period `2^n - 1`.
+    pub fn period(&self) -> u64 {
+        mask(self.a.degree())
+    }
+
+    /// One period of the code with `b` delayed by `delay` chips, one bit per
+    /// byte.
+    pub fn code(&self, delay: u64) -> Vec<u8> {
+        let n = self.period();
+        let mut a = self.a.clone();
+        let mut b = self.b.clone();
+        b.jump(n - delay % n);
+        (0..n)
+            .map(|_| (a.next_bit() ^ b.next_bit()) as u8)
+            .collect()
+    }
+}
+
+/// The periodic cross-correlation of two sequences of bits, one per byte,
+/// of the same length: at each shift `τ`, the sum over `t` of `1` where
+/// `a_t = b_(t+τ)` and `-1` where they differ.
+pub fn cross_correlation(a: &[u8], b: &[u8]) -> Vec<i64> {
+    assert_eq!(a.len(), b.len(), "sequence lengths differ");
+    let n = a.len();
+    (0..n)
+        .map(|shift| {
+            let differ = (0..n).filter(|&t| a[t] != b[(t + shift) % n]).count();
+            n as i64 - 2 * differ as i64
+        })
+        .collect()
+}
+
+// The minimal polynomial of α^q, without its leading term, as the product
+// of `x + β` over the conjugates `β` of `α^q` in GF(2)[x] / p.
+fn minimal_poly(poly: u64, degree: u8, q: u64) -> u64 {
+    let p = 1u128 << degree | poly as u128;
+    let mut beta = 1u128;
+    for _ in 0..q {
+        beta = mul_mod(beta, 2 % p, p, degree);
+    }
+    let mut coeffs = vec![1u128];
+    let mut conjugate = beta;
+    loop {
+        // multiply by x + conjugate
+        let mut next = vec![0u128; coeffs.len() + 1];
+        for (i, &c) in coeffs.iter().enumerate() {
+            next[i + 1] ^= c;
+            next[i] ^= mul_mod(c, conjugate, p, degree);
+        }
+        coeffs = next;
+        conjugate = mul_mod(conjugate, conjugate, p, degree);
+        if conjugate == beta {
+            break;
+        }
+    }
+    assert_eq!(coeffs.len() - 1, degree as usize, "α^q is in a subfield");
+    coeffs[..degree as usize]
+        .iter()
+        .enumerate()
+        .fold(0, |acc, (i, &c)| acc | (c as u64) << i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gps() {
+        // the C/A code registers G1 = 1 + x^3 + x^10 and
+        // G2 = 1 + x^2 + x^3 + x^6 + x^8 + x^9 + x^10, as recurrences, from
+        // the all-ones state
+        let g1 = Lfsr::fibonacci(0x081, 10, 0x3ff);
+        let g2 = Lfsr::fibonacci(0x197, 10, 0x3ff);
+        let gold = Gold::new(g1, g2);
+        // the first ten chips of PRNs 1, 2 and 3 in octal are 1440, 1620
+        // and 1710, for G2 delays of 5, 6 and 7 chips
+        for (delay, octal) in [(5, 0o1440), (6, 0o1620), (7, 0o1710)] {
+            let code = gold.code(delay);
+            assert_eq!(code.len(), 1023);
+            let first = code[..10].iter().fold(0, |acc, &b| acc << 1 | b as u32);
+            assert_eq!(first, octal);
+        }
+    }
+
+    #[test]
+    fn test_preferred_pairs() {
+        assert!(Gold::preferred(0x1d, 8).is_none());
+        for (poly, degree, t) in [(0b0_0101, 5, 9), (0b100_0001, 7, 17), (0x009, 10, 65)] {
+            let gold = Gold::preferred(poly, degree).unwrap();
+            assert!(gold.b().is_maximal());
+            let allowed = [-1, -t, t - 2];
+            let codes: Vec<Vec<u8>> = (0..4).map(|d| gold.code(d * 11)).collect();
+            for i in 0..codes.len() {
+                for j in i + 1..codes.len() {
+                    let c = cross_correlation(&codes[i], &codes[j]);
+                    assert!(c.iter().all(|v| allowed.contains(v)), "{poly:#x} {i} {j}");
+                }
+            }
+            // an m-sequence has a two-valued autocorrelation
+            let mut a = gold.a().clone();
+            let mut bits = vec![0u8; gold.period() as usize];
+            a.fill_bits(&mut bits);
+            let auto = cross_correlation(&bits, &bits);
+            assert_eq!(auto[0], gold.period() as i64);
+            assert!(auto[1..].iter().all(|&v| v == -1));
+        }
+    }
+
+    #[test]
+    fn test_minimal_poly() {
+        // x^5 + x^2 + 1 with q = 1 and its reciprocal x^5 + x^3 + 1 with
+        // q = -1 = 30
+        assert_eq!(minimal_poly(0b0_0101, 5, 1), 0b0_0101);
+        assert_eq!(minimal_poly(0b0_0101, 5, 30), 0b0_1001);
+    }
+}
@@ -0,0 +1,136 @@
+use super::Lfsr;
+use crate::poly::Poly;
+use crate::rs::berlekamp_massey_with;
+use crate::Galois;
+
+/// The connection polynomial `C(x) = 1 + c_1 x + .. + c_L x^L` of the
+/// shortest LFSR generating `s`, by Berlekamp-Massey: every
+/// `s_n = c_1 s_(n-1) + .. + c_L s_(n-L)` for `n >= L`.
+///
+/// It is unique when `s` is at least twice as long as the register. For
+/// the syndromes of a Reed-Solomon or BCH codeword it is the error
+/// locator.
+pub fn berlekamp_massey(s: &[Galois]) -> Poly {
+    berlekamp_massey_with(s, &Poly::one(), 0).0
+}
+
+/// The length `L` of the shortest LFSR generating `s`, which may exceed
+/// the degree of its connection polynomial: `1, 0, 0` needs one cell with
+/// no feedback, `C(x) = 1`.
+pub fn linear_complexity(s: &[Galois]) -> usize {
+    berlekamp_massey_with(s, &Poly::one(), 0).1
+}
+
+/// Berlekamp-Massey over GF(2) for bits, one per byte, each 0 or 1.
+///
+/// Returns the connection coefficients `c_0 = 1, c_1, .., c_L`, so that the
+/// linear complexity is one less than their number.
+pub fn berlekamp_massey_bits(bits: &[u8]) -> Vec<u8> {
+    let mut c = vec![0u8; bits.len() + 1];
+    let mut b = c.clone();
+    c[0] = 1;
+    b[0] = 1;
+    let mut l = 0;
+    let mut shift = 1;
+    for n in 0..bits.len() {
+        let d = (1..=l).fold(bits[n] & 1, |d, i| d ^ (c[i] & bits[n - i]));
+        if d == 0 {
+            shift += 1;
+            continue;
+        }
+        let t = c.clone();
+        for i in 0..=bits.len() - shift {
+            c[i + shift] ^= b[i];
+        }
+        if 2 * l <= n {
+            l = n + 1 - l;
+            b = t;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+    c.truncate(l + 1);
+    c
+}
+
+impl Lfsr {
+    /// The shortest Fibonacci register that outputs `bits`, one per byte,
+    /// from its state, or `None` if the bits are all zero or need more than
+    /// 64 cells.
+    pub fn from_sequence(bits: &[u8]) -> Option<Lfsr> {
+        let c = berlekamp_massey_bits(bits);
+        let l = c.len() - 1;
+        if l == 0 || l > 64 {
+            return None;
+        }
+        // the characteristic polynomial x^L C(1/x)
+        let poly = (0..l).fold(0u64, |acc, j| acc | (c[l - j] as u64) << j);
+        let state = (0..l).fold(0u64, |acc, i| {
+            acc | (*bits.get(i).unwrap_or(&0) as u64 & 1) << i
+        });
+        Some(Lfsr::fibonacci(poly, l as u8, state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_galois() {
+        // s_n = 3 s_(n-1) + 7 s_(n-2)
+        let (a, b) = (Galois::new(3), Galois::new(7));
+        let mut s = vec![Galois::new(1), Galois::new(0x55)];
+        for n in 2..10 {
+            s.push(a * s[n - 1] + b * s[n - 2]);
+        }
+        assert_eq!(
+            berlekamp_massey(&s),
+            Poly::new(vec![Galois::identity(), a, b])
+        );
+        assert_eq!(linear_complexity(&s), 2);
+
+        let impulse = [Galois::identity(), Galois::zero(), Galois::zero()];
+        assert_eq!(berlekamp_massey(&impulse), Poly::one());
+        assert_eq!(linear_complexity(&impulse), 1);
+        let late = [Galois::zero(), Galois::zero(), Galois::identity()];
+        assert_eq!(linear_complexity(&late), 3);
+        assert_eq!(linear_complexity(&[]), 0);
+    }
+
+    #[test]
+    fn test_bits() {
+        // an m-sequence of degree n has complexity n, found from 2n bits
+        let mut lfsr = Lfsr::fibonacci(0x0040_0007, 32, 0xdead_beef);
+        let mut bits = vec![0u8; 64];
+        lfsr.clone().fill_bits(&mut bits);
+        assert_eq!(berlekamp_massey_bits(&bits).len(), 33);
+        assert_eq!(Lfsr::from_sequence(&bits), Some(lfsr.clone()));
+
+        // and keeps generating it
+        let mut found = Lfsr::from_sequence(&bits).unwrap();
+        let mut more = vec![0u8; 500];
+        let mut expected = vec![0u8; 500];
+        found.fill_bits(&mut more);
+        lfsr.fill_bits(&mut expected);
+        assert_eq!(more, expected);
+
+        // binary sequences give the same result over GF(2) and GF(256)
+        let bits: Vec<u8> = (0..40u32)
+            .map(|i| ((i * i * 7 + i / 3) >> 2 & 1) as u8)
+            .collect();
+        let as_galois: Vec<Galois> = bits.iter().map(|&b| Galois::new(b)).collect();
+        let c = berlekamp_massey_bits(&bits);
+        assert_eq!(c.len() - 1, linear_complexity(&as_galois));
+        let expected = berlekamp_massey(&as_galois);
+        for (i, &ci) in c.iter().enumerate() {
+            assert_eq!(Galois::new(ci), expected.coeff(i));
+        }
+
+        assert_eq!(Lfsr::from_sequence(&[0; 10]), None);
+        // a one after n zeros needs n + 1 cells
+        assert_eq!(berlekamp_massey_bits(&[0, 0, 0, 1]), [1, 0, 0, 0, 1]);
+        assert_eq!(berlekamp_massey_bits(&[1, 0, 0]), [1, 0]);
+    }
+}
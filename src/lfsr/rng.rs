@@ -0,0 +1,185 @@
+use super::Lfsr;
+use crate::fountain::SplitMix;
+use crate::rabin::Rabin;
+
+// x^64 + x^4 + x^3 + x + 1, primitive, with taps low enough that the top
+// byte of the state is exactly what the next eight steps shift out
+const POLY: u64 = 0x1b;
+
+// multiplication of the state by x^8, one table lookup per output byte
+static STEP: Rabin = Rabin::new(POLY, 64, 1);
+
+/// A seedable generator on a maximal-length 64-bit Galois LFSR, for fast
+/// reproducible test data.
+///
+/// Its output is the register's bit stream, eight bits per step, so it has
+/// period `2^64 - 1` bits and is perfectly balanced, but it is linear:
+/// [`berlekamp_massey_bits`](super::berlekamp_massey_bits) recovers it
+/// from 128 bits. Do not use it where unpredictability matters.
+///
+/// The methods mirror `rand_core`'s `RngCore` and `SeedableRng`, which it
+/// implements with the `rand_core` feature, and [`LfsrRng::fill_bytes`]
+/// fits the random-byte callbacks of [`shamir`](crate::shamir) and its
+/// neighbours.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LfsrRng {
+    state: u64,
+}
+
+impl LfsrRng {
+    /// Starts from the register state in `seed`, little-endian. The zero
+    /// state, which would stay zero, is replaced by 1.
+    pub fn from_seed(seed: [u8; 8]) -> Self {
+        LfsrRng {
+            state: u64::from_le_bytes(seed).max(1),
+        }
+    }
+
+    /// Starts from a state derived from `seed` by SplitMix64, so that
+    /// nearby seeds give unrelated streams.
+    pub fn seed_from_u64(seed: u64) -> Self {
+        LfsrRng::from_seed(SplitMix(seed).next().to_le_bytes())
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut b = [0u8; 4];
+        self.fill_bytes(&mut b);
+        u32::from_be_bytes(b)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut b = [0u8; 8];
+        self.fill_bytes(&mut b);
+        u64::from_be_bytes(b)
+    }
+
+    /// Fills `dest` with the next output bits, the first in the most
+    /// significant bit of each byte.
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for b in dest {
+            *b = (self.state >> 56) as u8;
+            self.state = STEP.push(self.state, 0);
+        }
+    }
+
+    /// Skips the next `bytes` bytes of output in logarithmic time, e.g. to
+    /// split one seed into disjoint streams.
+    pub fn jump(&mut self, bytes: u64) {
+        let mut lfsr = Lfsr::galois(POLY, 64, self.state);
+        lfsr.jump(bytes.wrapping_mul(8));
+        // the overflow of 8 * bytes, each 2^64 steps being one step more
+        // than a period
+        lfsr.jump(bytes >> 61);
+        self.state = lfsr.state();
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::RngCore for LfsrRng {
+    fn next_u32(&mut self) -> u32 {
+        LfsrRng::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        LfsrRng::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        LfsrRng::fill_bytes(self, dest)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::SeedableRng for LfsrRng {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        LfsrRng::from_seed(seed)
+    }
+
+    fn seed_from_u64(seed: u64) -> Self {
+        LfsrRng::seed_from_u64(seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register() {
+        // the output is the bit stream of the Galois register
+        assert!(super::super::is_primitive(POLY, 64));
+        let mut rng = LfsrRng::seed_from_u64(7);
+        let mut lfsr = Lfsr::galois(POLY, 64, rng.state);
+        let mut expected = [0u8; 100];
+        lfsr.fill_bytes(&mut expected);
+        let mut out = [0u8; 100];
+        rng.fill_bytes(&mut out);
+        assert_eq!(out, expected);
+        assert_eq!(rng.state, lfsr.state());
+    }
+
+    #[test]
+    fn test_seeding() {
+        let draw = |mut r: LfsrRng| (r.next_u64(), r.next_u32());
+        assert_eq!(
+            draw(LfsrRng::seed_from_u64(1)),
+            draw(LfsrRng::seed_from_u64(1))
+        );
+        assert_ne!(
+            draw(LfsrRng::seed_from_u64(1)),
+            draw(LfsrRng::seed_from_u64(2))
+        );
+        assert_eq!(
+            LfsrRng::from_seed([0; 8]),
+            LfsrRng::from_seed([1, 0, 0, 0, 0, 0, 0, 0])
+        );
+
+        // a rough balance check over 64 KiB
+        let mut rng = LfsrRng::seed_from_u64(3);
+        let mut data = vec![0u8; 1 << 16];
+        rng.fill_bytes(&mut data);
+        let ones: u32 = data.iter().map(|b| b.count_ones()).sum();
+        assert!((ones as i64 - (1 << 18)).abs() < 2000);
+    }
+
+    #[test]
+    fn test_jump() {
+        let mut stepped = LfsrRng::seed_from_u64(9);
+        let mut jumped = stepped.clone();
+        let mut skip = vec![0u8; 1000];
+        stepped.fill_bytes(&mut skip);
+        jumped.jump(1000);
+        assert_eq!(jumped, stepped);
+
+        // 2^64 - 1 bytes is a whole number of periods
+        let start = LfsrRng::seed_from_u64(10);
+        let mut full = start.clone();
+        full.jump(u64::MAX);
+        assert_eq!(full, start);
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn test_rand_core() {
+        use rand_core::{RngCore, SeedableRng};
+
+        fn draw<R: RngCore + SeedableRng>(seed: u64) -> (u32, u64, [u8; 5]) {
+            let mut rng = R::seed_from_u64(seed);
+            let mut b = [0u8; 5];
+            let (x, y) = (rng.next_u32(), rng.next_u64());
+            rng.fill_bytes(&mut b);
+            (x, y, b)
+        }
+        let mut rng = LfsrRng::seed_from_u64(4);
+        let mut b = [0u8; 5];
+        let (x, y) = (rng.next_u32(), rng.next_u64());
+        rng.fill_bytes(&mut b);
+        assert_eq!(draw::<LfsrRng>(4), (x, y, b));
+        assert_eq!(
+            <LfsrRng as SeedableRng>::from_seed([5; 8]),
+            LfsrRng::from_seed([5; 8])
+        );
+    }
+}
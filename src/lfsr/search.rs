@@ -0,0 +1,86 @@
+use super::is_primitive;
+use crate::rabin::is_irreducible;
+
+/// The irreducible polynomials `x^degree + poly` over GF(2), as the `poly`
+/// without the `x^degree` term, in increasing order.
+///
+/// With a `weight`, only those with that many nonzero terms, `x^degree`
+/// included: 3 for trinomials, 5 for pentanomials. These are enumerated
+/// directly, so the search stays quick for large degrees, where the full
+/// list is out of reach.
+pub fn irreducible_polynomials(degree: u8, weight: Option<u32>) -> impl Iterator<Item = u64> {
+    candidates(degree, weight).filter(move |&p| is_irreducible(p, degree))
+}
+
+/// The primitive polynomials, as [`irreducible_polynomials`]: those usable
+/// as a field polynomial with `x` as generator, or as the feedback of a
+/// maximal-length [`Lfsr`](super::Lfsr) or scrambler.
+pub fn primitive_polynomials(degree: u8, weight: Option<u32>) -> impl Iterator<Item = u64> {
+    candidates(degree, weight).filter(move |&p| is_primitive(p, degree))
+}
+
+// The polynomials of the given weight, skipping those divisible by x.
+fn candidates(degree: u8, weight: Option<u32>) -> impl Iterator<Item = u64> {
+    assert!((1..=64).contains(&degree), "bad degree");
+    let end = 1u128 << degree;
+    let first = match weight {
+        None => Some(0),
+        Some(w) if w == 0 || w - 1 > degree as u32 => None,
+        Some(w) => Some((1u128 << (w - 1)) - 1),
+    };
+    std::iter::successors(first, move |&v| {
+        let next = match weight {
+            None => Some(v + 1),
+            Some(_) => next_with_same_weight(v),
+        }?;
+        (next < end).then_some(next)
+    })
+    .map(|v| v as u64)
+    .filter(move |&p| p & 1 == 1 || degree == 1)
+}
+
+// The next integer with as many ones as v, by Gosper's hack.
+fn next_with_same_weight(v: u128) -> Option<u128> {
+    if v == 0 {
+        return None;
+    }
+    let lowest = v & v.wrapping_neg();
+    let ripple = v + lowest;
+    Some((((ripple ^ v) >> 2) / lowest) | ripple)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts() {
+        // (1/n) sum over d | n of mu(d) 2^(n/d), and phi(2^n - 1) / n
+        assert_eq!(irreducible_polynomials(1, None).collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(primitive_polynomials(1, None).collect::<Vec<_>>(), [1]);
+        assert_eq!(irreducible_polynomials(8, None).count(), 30);
+        assert_eq!(primitive_polynomials(8, None).count(), 16);
+        assert_eq!(primitive_polynomials(6, None).count(), 6);
+        assert_eq!(irreducible_polynomials(6, None).count(), 9);
+        assert_eq!(primitive_polynomials(12, None).count(), 144);
+        assert_eq!(primitive_polynomials(8, None).next(), Some(0x1d));
+        assert!(primitive_polynomials(8, None).all(|p| is_irreducible(p, 8)));
+    }
+
+    #[test]
+    fn test_weight() {
+        // x^8 has no irreducible trinomial, and neither has x^64
+        assert_eq!(irreducible_polynomials(8, Some(3)).count(), 0);
+        assert_eq!(irreducible_polynomials(64, Some(3)).next(), None);
+        assert_eq!(irreducible_polynomials(64, Some(5)).next(), Some(0x1b));
+        let trinomials: Vec<u64> = primitive_polynomials(7, Some(3)).collect();
+        assert_eq!(trinomials, [0x03, 0x09, 0x11, 0x41]);
+        for p in primitive_polynomials(10, Some(5)) {
+            assert_eq!(p.count_ones(), 4);
+            assert!(is_primitive(p, 10));
+        }
+        assert_eq!(irreducible_polynomials(4, Some(0)).count(), 0);
+        assert_eq!(irreducible_polynomials(4, Some(6)).count(), 0);
+        assert_eq!(irreducible_polynomials(1, Some(1)).collect::<Vec<_>>(), [0]);
+    }
+}
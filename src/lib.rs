@@ -4,7 +4,77 @@ use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 mod constants;
 use constants::*;
 
+mod gf;
+pub use gf::Gf;
+mod galois_field;
+pub use galois_field::{FieldElement, GaloisField};
+mod dyn_gf;
+pub use dyn_gf::{DynField, DynGf};
+mod isomorphism;
+pub use isomorphism::{Isomorphism, TableFormat};
+mod matrix;
+pub use matrix::{BlockSize, GfMatrix, GfMatrixViewMut, MatrixError};
+
+mod bitmatrix;
+pub use bitmatrix::{BitMatrix, Schedule, ScheduleOp};
+
+mod sparse;
+pub use sparse::SparseMatrix;
+
+mod util;
+
+pub mod aes;
+pub mod analysis;
+pub mod aont;
+pub mod bch;
+pub mod composite;
+pub mod conway;
+pub mod crc;
+pub mod cyclic;
+pub mod fft;
+mod field;
+pub use field::Field;
+pub mod fountain;
+pub mod gabidulin;
+pub mod galois_ring;
+pub mod gf128;
+mod gf16;
+pub use gf16::Gf16;
+mod gf32;
+pub use gf32::Gf32;
+mod gf64;
+pub use gf64::Gf64;
+mod gf65536;
+pub mod goppa;
+pub use gf65536::Gf65536;
+pub mod isal;
+pub mod ldpc;
+pub mod lfsr;
+pub mod masked;
+pub mod normal;
+pub mod poly;
+pub mod polyhash;
+pub mod prime;
+pub mod rabin;
+pub mod raid6;
+pub mod rlnc;
+pub mod rs;
+pub mod scrambler;
+pub mod shamir;
+pub mod slice;
+pub mod tower;
+pub mod zech;
+
+#[cfg(feature = "nalgebra")]
+mod nalgebra_interop;
+
+#[cfg(feature = "ndarray")]
+mod ndarray_interop;
+#[cfg(feature = "ndarray")]
+pub use ndarray_interop::GaloisArrayExt;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[repr(transparent)]
 pub struct Galois(u8);
 
 impl Galois {
@@ -41,8 +111,36 @@ impl Galois {
 
         EXP_TABLE[log_res as usize]
     }
+
+    /// Multiplies without branches or table lookups, so that the time taken
+    /// does not depend on the operands. Slower than `*`, for secret data.
+    pub fn mul_ct(self, rhs: Self) -> Self {
+        let (mut a, b) = (self.0, rhs.0);
+        let mut product = 0u8;
+        for i in 0..8 {
+            // all ones where the bit of b is set
+            product ^= a & 0u8.wrapping_sub(b >> i & 1);
+            let carry = 0u8.wrapping_sub(a >> 7);
+            a = a << 1 ^ (PRIMITIVE_POLYNOMIAL as u8 & carry);
+        }
+        Galois(product)
+    }
+
+    /// The inverse as `self^254` by [`mul_ct`](Galois::mul_ct), mapping zero
+    /// to zero rather than panicking.
+    pub fn inv_ct(self) -> Self {
+        // the product of self^2, self^4, .., self^128
+        let mut square = self;
+        let mut inv = Galois::identity();
+        for _ in 1..8 {
+            square = square.mul_ct(square);
+            inv = inv.mul_ct(square);
+        }
+        inv
+    }
 }
 
+#[allow(clippy::suspicious_arithmetic_impl)]
 impl Add for Galois {
     type Output = Self;
 
@@ -51,6 +149,7 @@ impl Add for Galois {
     }
 }
 
+#[allow(clippy::suspicious_arithmetic_impl)]
 impl Sub for Galois {
     type Output = Self;
 
@@ -101,12 +200,14 @@ impl Div for Galois {
     }
 }
 
+#[allow(clippy::suspicious_op_assign_impl)]
 impl AddAssign for Galois {
     fn add_assign(&mut self, rhs: Self) {
         *self = Galois(self.0 ^ rhs.0);
     }
 }
 
+#[allow(clippy::suspicious_op_assign_impl)]
 impl SubAssign for Galois {
     fn sub_assign(&mut self, rhs: Self) {
         *self = Galois(self.0 ^ rhs.0);
@@ -226,6 +327,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_constant_time() {
+        for a in 0..=255u8 {
+            for b in 0..=255u8 {
+                assert_eq!(Galois(a).mul_ct(Galois(b)), Galois(a) * Galois(b));
+            }
+            let expected = if a == 0 { Galois(0) } else { Galois(a).inv() };
+            assert_eq!(Galois(a).inv_ct(), expected);
+        }
+    }
+
     #[test]
     fn test_logs_eq() {
         assert_eq!(LOG_TABLE, gen_log_table());
@@ -0,0 +1,199 @@
+//! First-order boolean masking of GF(256) elements, for prototyping
+//! side-channel countermeasures.
+//!
+//! A [`MaskedGalois`] holds two random shares whose XOR is the element, so
+//! that no single intermediate value depends on it. Linear operations act
+//! on each share; products use the Ishai-Sahai-Wagner scheme with one fresh
+//! random byte each, and inversion goes through the [`tower`](crate::tower)
+//! field, where it takes a handful of masked GF(16) products.
+//!
+//! As in [`shamir`](crate::shamir), randomness comes from a caller-supplied
+//! function that fills a buffer.
+
+use std::ops::Add;
+
+use crate::tower::{gf16_mul, Tower, LAMBDA};
+use crate::Galois;
+
+/// A GF(256) element split into two shares, `value = shares[0] ^ shares[1]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaskedGalois {
+    shares: [u8; 2],
+}
+
+impl MaskedGalois {
+    /// Masks `value` with a random byte.
+    pub fn mask<R>(value: Galois, mut rng: R) -> Self
+    where
+        R: FnMut(&mut [u8]),
+    {
+        let m = random_byte(&mut rng);
+        MaskedGalois {
+            shares: [u8::from(value) ^ m, m],
+        }
+    }
+
+    pub fn from_shares(shares: [u8; 2]) -> Self {
+        MaskedGalois { shares }
+    }
+
+    pub fn shares(self) -> [u8; 2] {
+        self.shares
+    }
+
+    /// Recombines the shares, exposing the value.
+    pub fn unmask(self) -> Galois {
+        Galois::new(self.shares[0] ^ self.shares[1])
+    }
+
+    /// The same value under a fresh mask.
+    pub fn refresh<R>(self, mut rng: R) -> Self
+    where
+        R: FnMut(&mut [u8]),
+    {
+        let m = random_byte(&mut rng);
+        MaskedGalois {
+            shares: [self.shares[0] ^ m, self.shares[1] ^ m],
+        }
+    }
+
+    /// The product with a public constant, share by share.
+    pub fn scale(self, c: Galois) -> Self {
+        MaskedGalois {
+            shares: self.shares.map(|s| u8::from(c.mul_ct(Galois::new(s)))),
+        }
+    }
+
+    /// The square, share by share, as squaring is linear over GF(2).
+    pub fn square(self) -> Self {
+        MaskedGalois {
+            shares: self
+                .shares
+                .map(|s| u8::from(Galois::new(s).mul_ct(Galois::new(s)))),
+        }
+    }
+
+    /// The masked product, with one random byte.
+    ///
+    /// The two operands must be independently masked; refresh one of them
+    /// first if both derive from the same sharing.
+    pub fn mul<R>(self, rhs: Self, mut rng: R) -> Self
+    where
+        R: FnMut(&mut [u8]),
+    {
+        let r = random_byte(&mut rng);
+        let m = |a: u8, b: u8| u8::from(Galois::new(a).mul_ct(Galois::new(b)));
+        MaskedGalois {
+            shares: isw(self.shares, rhs.shares, r, m),
+        }
+    }
+
+    /// The masked inverse, with zero mapped to zero, computed in the tower
+    /// field with five masked GF(16) products and two refreshes.
+    pub fn inv<R>(self, mut rng: R) -> Self
+    where
+        R: FnMut(&mut [u8]),
+    {
+        let t = self.shares.map(|s| Tower::from_galois(Galois::new(s)));
+        let h = t.map(Tower::hi);
+        let l = t.map(Tower::lo);
+        let square = |x: [u8; 2]| x.map(|v| gf16_mul(v, v));
+        let mut mul = |a: [u8; 2], b: [u8; 2]| {
+            let r = random_byte(&mut rng) & 0xf;
+            isw(a, b, r, gf16_mul)
+        };
+
+        // the norm h^2 λ + h l + l^2, then its inverse as norm^(2 + 4 + 8)
+        let hl = mul(h, l);
+        let norm = xor(xor(square(h).map(|v| gf16_mul(v, LAMBDA)), hl), square(l));
+        let n2 = square(norm);
+        let n4 = square(n2);
+        let n8 = square(n4);
+        let n4 = refresh16(n4, &mut mul);
+        let n6 = mul(n2, n4);
+        let n8 = refresh16(n8, &mut mul);
+        let norm_inv = mul(n6, n8);
+
+        let hi = mul(h, norm_inv);
+        let lo = mul(xor(h, l), norm_inv);
+        MaskedGalois {
+            shares: [0, 1].map(|i| u8::from(Tower::from_parts(hi[i], lo[i]).to_galois())),
+        }
+    }
+}
+
+impl Add for MaskedGalois {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        MaskedGalois {
+            shares: xor(self.shares, rhs.shares),
+        }
+    }
+}
+
+// The ISW product of two sharings with the random value `r`, grouping the
+// cross terms so that no partial sum is unmasked.
+fn isw(a: [u8; 2], b: [u8; 2], r: u8, mul: impl Fn(u8, u8) -> u8) -> [u8; 2] {
+    [
+        mul(a[0], b[0]) ^ r,
+        mul(a[1], b[1]) ^ (r ^ mul(a[0], b[1]) ^ mul(a[1], b[0])),
+    ]
+}
+
+// A fresh GF(16) sharing of `x`, drawing randomness through a masked
+// product by one, whose ISW step re-randomizes the shares.
+fn refresh16(x: [u8; 2], mul: &mut impl FnMut([u8; 2], [u8; 2]) -> [u8; 2]) -> [u8; 2] {
+    mul(x, [1, 0])
+}
+
+fn xor(a: [u8; 2], b: [u8; 2]) -> [u8; 2] {
+    [a[0] ^ b[0], a[1] ^ b[1]]
+}
+
+fn random_byte<R: FnMut(&mut [u8])>(rng: &mut R) -> u8 {
+    let mut b = [0u8];
+    rng(&mut b);
+    b[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::tests::test_rng;
+
+    #[test]
+    fn test_arithmetic() {
+        let mut rng = test_rng(1);
+        for a in 0..=255u8 {
+            let b = a.wrapping_mul(31).wrapping_add(7);
+            let (ga, gb) = (Galois::new(a), Galois::new(b));
+            let ma = MaskedGalois::mask(ga, &mut rng);
+            let mb = MaskedGalois::mask(gb, &mut rng);
+            assert_eq!(ma.unmask(), ga);
+            assert_eq!((ma + mb).unmask(), ga + gb);
+            assert_eq!(ma.mul(mb, &mut rng).unmask(), ga * gb);
+            assert_eq!(ma.square().unmask(), ga * ga);
+            assert_eq!(ma.scale(gb).unmask(), ga * gb);
+            assert_eq!(ma.refresh(&mut rng).unmask(), ga);
+            let expected = if a == 0 { ga } else { ga.inv() };
+            assert_eq!(ma.inv(&mut rng).unmask(), expected);
+        }
+    }
+
+    #[test]
+    fn test_masks_vary() {
+        // with a fixed value, the shares follow the randomness
+        let mut rng = test_rng(2);
+        let value = Galois::new(0x42);
+        let shares: Vec<[u8; 2]> = (0..64)
+            .map(|_| MaskedGalois::mask(value, &mut rng).inv(&mut rng).shares())
+            .collect();
+        let distinct: std::collections::HashSet<u8> = shares.iter().map(|s| s[0]).collect();
+        assert!(distinct.len() > 32);
+        assert_eq!(
+            MaskedGalois::from_shares([0x12, 0x34]).unmask(),
+            Galois::new(0x26)
+        );
+    }
+}
@@ -0,0 +1,580 @@
+use std::fmt;
+use std::ops::{Index, IndexMut, Mul};
+
+use crate::slice::{as_bytes, as_bytes_mut, mul_add_slice, scale_slice};
+use crate::Galois;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixError {
+    NotSquare,
+    Singular,
+    DimensionMismatch,
+    ScratchTooSmall,
+}
+
+impl fmt::Display for MatrixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MatrixError::NotSquare => write!(f, "matrix is not square"),
+            MatrixError::Singular => write!(f, "matrix is singular"),
+            MatrixError::DimensionMismatch => write!(f, "dimension mismatch"),
+            MatrixError::ScratchTooSmall => write!(f, "scratch buffer too small"),
+        }
+    }
+}
+
+impl std::error::Error for MatrixError {}
+
+/// A dense, row-major matrix over GF(256).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GfMatrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<Galois>,
+}
+
+impl GfMatrix {
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        GfMatrix {
+            rows,
+            cols,
+            data: vec![Galois::zero(); rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = GfMatrix::zero(n, n);
+        for i in 0..n {
+            m[(i, i)] = Galois::identity();
+        }
+        m
+    }
+
+    /// Builds a matrix from row-major data.
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<Galois>) -> Self {
+        assert_eq!(data.len(), rows * cols, "data length mismatch");
+        GfMatrix { rows, cols, data }
+    }
+
+    pub fn from_fn<F>(rows: usize, cols: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> Galois,
+    {
+        let mut data = Vec::with_capacity(rows * cols);
+        for r in 0..rows {
+            for c in 0..cols {
+                data.push(f(r, c));
+            }
+        }
+        GfMatrix { rows, cols, data }
+    }
+
+    /// Builds the `rows × cols` Vandermonde matrix with entry `(r, c) = r^c`.
+    pub fn vandermonde(rows: usize, cols: usize) -> Self {
+        assert!(rows <= 256, "at most 256 distinct evaluation points");
+        GfMatrix::from_fn(rows, cols, |r, c| Galois::new(r as u8).exp(c as u32))
+    }
+
+    /// Builds the Cauchy matrix with entry `(r, c) = 1 / (xs[r] + ys[c])`.
+    ///
+    /// All of `xs` and `ys` together must be distinct, in which case every
+    /// square submatrix is invertible.
+    pub fn cauchy(xs: &[Galois], ys: &[Galois]) -> Self {
+        GfMatrix::from_fn(xs.len(), ys.len(), |r, c| {
+            assert_ne!(xs[r], ys[c], "cauchy points must be distinct");
+            (xs[r] + ys[c]).inv()
+        })
+    }
+
+    /// Builds the `rows × elements.len()` Moore matrix, whose row `i` is
+    /// `elements` raised to the `2^i`-th power (the `i`-th Frobenius image).
+    pub fn moore(elements: &[Galois], rows: usize) -> Self {
+        let mut m = GfMatrix::zero(rows, elements.len());
+        if rows == 0 {
+            return m;
+        }
+        m.row_mut(0).copy_from_slice(elements);
+        for r in 1..rows {
+            for c in 0..elements.len() {
+                let v = m[(r - 1, c)];
+                m[(r, c)] = v * v;
+            }
+        }
+        m
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn as_slice(&self) -> &[Galois] {
+        &self.data
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [Galois] {
+        &mut self.data
+    }
+
+    pub fn into_vec(self) -> Vec<Galois> {
+        self.data
+    }
+
+    pub fn row(&self, r: usize) -> &[Galois] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, r: usize) -> &mut [Galois] {
+        &mut self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    /// Returns the rows `rows` of `self`, in the order given.
+    pub fn select_rows(&self, rows: &[usize]) -> GfMatrix {
+        let mut data = Vec::with_capacity(rows.len() * self.cols);
+        for r in rows {
+            data.extend_from_slice(self.row(*r));
+        }
+        GfMatrix::from_vec(rows.len(), self.cols, data)
+    }
+
+    /// Returns the `rows × cols` block starting at `(row0, col0)`.
+    pub fn submatrix(&self, row0: usize, col0: usize, rows: usize, cols: usize) -> GfMatrix {
+        assert!(
+            row0 + rows <= self.rows && col0 + cols <= self.cols,
+            "index out of bounds"
+        );
+        GfMatrix::from_fn(rows, cols, |r, c| self[(row0 + r, col0 + c)])
+    }
+
+    /// Raises a square matrix to the `n`-th power by square-and-multiply.
+    pub fn pow(&self, mut n: u64) -> GfMatrix {
+        assert!(self.is_square(), "matrix is not square");
+
+        let mut base = self.clone();
+        let mut acc = GfMatrix::identity(self.rows);
+        while n > 0 {
+            if n & 1 == 1 {
+                acc = &acc * &base;
+            }
+            n >>= 1;
+            if n > 0 {
+                base = &base * &base;
+            }
+        }
+        acc
+    }
+
+    pub fn transpose(&self) -> GfMatrix {
+        GfMatrix::from_fn(self.cols, self.rows, |r, c| self[(c, r)])
+    }
+}
+
+/// Tile sizes for [`GfMatrix::mul_blocked`].
+///
+/// `rows` and `inner` bound the tile of `self`, `cols` bounds the width of the
+/// strip of `rhs` and of the output that is kept hot while the tile is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSize {
+    pub rows: usize,
+    pub inner: usize,
+    pub cols: usize,
+}
+
+impl Default for BlockSize {
+    fn default() -> Self {
+        BlockSize {
+            rows: 16,
+            inner: 64,
+            cols: 16 * 1024,
+        }
+    }
+}
+
+impl GfMatrix {
+    /// Cache-blocked `self * rhs`.
+    pub fn mul_blocked(&self, rhs: &GfMatrix, block: BlockSize) -> GfMatrix {
+        assert_eq!(self.cols, rhs.rows, "dimension mismatch");
+        assert!(block.rows > 0 && block.inner > 0 && block.cols > 0);
+
+        let mut out = GfMatrix::zero(self.rows, rhs.cols);
+        if rhs.cols == 0 {
+            return out;
+        }
+        for (rb, chunk) in out.data.chunks_mut(block.rows * rhs.cols).enumerate() {
+            self.mul_row_block(rhs, block, rb * block.rows, chunk);
+        }
+        out
+    }
+
+    /// Cache-blocked `self * rhs`, with row blocks of the output computed in
+    /// parallel on the rayon thread pool.
+    #[cfg(feature = "rayon")]
+    pub fn par_mul_blocked(&self, rhs: &GfMatrix, block: BlockSize) -> GfMatrix {
+        use rayon::prelude::*;
+
+        assert_eq!(self.cols, rhs.rows, "dimension mismatch");
+        assert!(block.rows > 0 && block.inner > 0 && block.cols > 0);
+
+        let mut out = GfMatrix::zero(self.rows, rhs.cols);
+        if rhs.cols == 0 {
+            return out;
+        }
+        out.data
+            .par_chunks_mut(block.rows * rhs.cols)
+            .enumerate()
+            .for_each(|(rb, chunk)| self.mul_row_block(rhs, block, rb * block.rows, chunk));
+        out
+    }
+
+    // Computes output rows `row0..` into `out`, which holds whole rows of the result.
+    fn mul_row_block(&self, rhs: &GfMatrix, block: BlockSize, row0: usize, out: &mut [Galois]) {
+        let n = rhs.cols;
+        let rows = out.len() / n;
+
+        for c0 in (0..n).step_by(block.cols) {
+            let c1 = (c0 + block.cols).min(n);
+            for i0 in (0..self.cols).step_by(block.inner) {
+                let i1 = (i0 + block.inner).min(self.cols);
+                for r in 0..rows {
+                    let dst = &mut out[r * n + c0..r * n + c1];
+                    for i in i0..i1 {
+                        let a = self[(row0 + r, i)];
+                        let src = &rhs.row(i)[c0..c1];
+                        mul_add_slice(a, as_bytes(src), as_bytes_mut(dst));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl GfMatrix {
+    pub fn view_mut(&mut self) -> GfMatrixViewMut<'_> {
+        GfMatrixViewMut {
+            rows: self.rows,
+            cols: self.cols,
+            data: &mut self.data,
+        }
+    }
+
+    /// Number of scratch elements [`GfMatrix::invert_into`] needs for an
+    /// `n × n` matrix: exactly `n * n`.
+    pub const fn invert_scratch_len(n: usize) -> usize {
+        n * n
+    }
+
+    pub fn invert(&self) -> Result<GfMatrix, MatrixError> {
+        let mut scratch = vec![Galois::zero(); GfMatrix::invert_scratch_len(self.rows)];
+        let mut out = GfMatrix::zero(self.rows, self.cols);
+        self.invert_into(&mut scratch, &mut out.view_mut())?;
+        Ok(out)
+    }
+
+    /// Inverts `self` into `out` by Gauss-Jordan elimination without
+    /// allocating.
+    ///
+    /// `scratch` must hold at least [`GfMatrix::invert_scratch_len`]`(n)`
+    /// elements; its contents on return are unspecified. `out` must be
+    /// `n × n`.
+    pub fn invert_into(
+        &self,
+        scratch: &mut [Galois],
+        out: &mut GfMatrixViewMut,
+    ) -> Result<(), MatrixError> {
+        if !self.is_square() {
+            return Err(MatrixError::NotSquare);
+        }
+        let n = self.rows;
+        if out.rows != n || out.cols != n {
+            return Err(MatrixError::DimensionMismatch);
+        }
+        if scratch.len() < GfMatrix::invert_scratch_len(n) {
+            return Err(MatrixError::ScratchTooSmall);
+        }
+
+        let work = &mut scratch[..n * n];
+        work.copy_from_slice(&self.data);
+        invert_in_place(work, out.data, n)
+    }
+}
+
+// Inverts the n × n matrix in `work` into `out`, destroying `work`.
+pub(crate) fn invert_in_place(
+    work: &mut [Galois],
+    out: &mut [Galois],
+    n: usize,
+) -> Result<(), MatrixError> {
+    out.fill(Galois::zero());
+    for i in 0..n {
+        out[i * n + i] = Galois::identity();
+    }
+
+    for col in 0..n {
+        let pivot = (col..n)
+            .find(|r| work[r * n + col] != Galois::zero())
+            .ok_or(MatrixError::Singular)?;
+        if pivot != col {
+            swap_rows(work, n, pivot, col);
+            swap_rows(out, n, pivot, col);
+        }
+
+        let inv = work[col * n + col].inv();
+        scale_slice(inv, as_bytes_mut(&mut work[col * n..(col + 1) * n]));
+        scale_slice(inv, as_bytes_mut(&mut out[col * n..(col + 1) * n]));
+
+        for r in (0..n).filter(|r| *r != col) {
+            let factor = work[r * n + col];
+            if factor == Galois::zero() {
+                continue;
+            }
+            let (src, dst) = two_rows(work, n, col, r);
+            mul_add_slice(factor, as_bytes(src), as_bytes_mut(dst));
+            let (src, dst) = two_rows(out, n, col, r);
+            mul_add_slice(factor, as_bytes(src), as_bytes_mut(dst));
+        }
+    }
+    Ok(())
+}
+
+fn swap_rows(data: &mut [Galois], cols: usize, a: usize, b: usize) {
+    let (x, y) = two_rows(data, cols, a, b);
+    x.swap_with_slice(y);
+}
+
+// Returns rows `a` and `b` (which must differ) as disjoint slices, in that order.
+fn two_rows(
+    data: &mut [Galois],
+    cols: usize,
+    a: usize,
+    b: usize,
+) -> (&mut [Galois], &mut [Galois]) {
+    assert_ne!(a, b);
+    let (lo, hi) = (a.min(b), a.max(b));
+    let (head, tail) = data.split_at_mut(hi * cols);
+    let lo_row = &mut head[lo * cols..(lo + 1) * cols];
+    let hi_row = &mut tail[..cols];
+    if a < b {
+        (lo_row, hi_row)
+    } else {
+        (hi_row, lo_row)
+    }
+}
+
+/// A mutable, row-major view over caller-owned storage.
+#[derive(Debug)]
+pub struct GfMatrixViewMut<'a> {
+    rows: usize,
+    cols: usize,
+    data: &'a mut [Galois],
+}
+
+impl<'a> GfMatrixViewMut<'a> {
+    pub fn new(rows: usize, cols: usize, data: &'a mut [Galois]) -> Self {
+        assert_eq!(data.len(), rows * cols, "data length mismatch");
+        GfMatrixViewMut { rows, cols, data }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn as_slice(&self) -> &[Galois] {
+        self.data
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [Galois] {
+        self.data
+    }
+
+    pub fn row(&self, r: usize) -> &[Galois] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, r: usize) -> &mut [Galois] {
+        &mut self.data[r * self.cols..(r + 1) * self.cols]
+    }
+}
+
+impl Index<(usize, usize)> for GfMatrixViewMut<'_> {
+    type Output = Galois;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(r < self.rows && c < self.cols, "index out of bounds");
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl IndexMut<(usize, usize)> for GfMatrixViewMut<'_> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut Self::Output {
+        assert!(r < self.rows && c < self.cols, "index out of bounds");
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+impl Index<(usize, usize)> for GfMatrix {
+    type Output = Galois;
+
+    fn index(&self, (r, c): (usize, usize)) -> &Self::Output {
+        assert!(r < self.rows && c < self.cols, "index out of bounds");
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl IndexMut<(usize, usize)> for GfMatrix {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut Self::Output {
+        assert!(r < self.rows && c < self.cols, "index out of bounds");
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+impl Mul for &GfMatrix {
+    type Output = GfMatrix;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.cols, rhs.rows, "dimension mismatch");
+
+        let mut out = GfMatrix::zero(self.rows, rhs.cols);
+        for r in 0..self.rows {
+            for i in 0..self.cols {
+                let a = self[(r, i)];
+                if a == Galois::zero() {
+                    continue;
+                }
+                for c in 0..rhs.cols {
+                    out[(r, c)] += a * rhs[(i, c)];
+                }
+            }
+        }
+        out
+    }
+}
+
+impl Mul for GfMatrix {
+    type Output = GfMatrix;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl fmt::Display for GfMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for r in 0..self.rows {
+            let row: Vec<String> = self
+                .row(r)
+                .iter()
+                .map(|v| u8::from(*v).to_string())
+                .collect();
+            writeln!(f, "[{}]", row.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_mul() {
+        let m = GfMatrix::from_fn(3, 4, |r, c| Galois::new((r * 4 + c) as u8 + 7));
+        assert_eq!(&GfMatrix::identity(3) * &m, m);
+        assert_eq!(&m * &GfMatrix::identity(4), m);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let m = GfMatrix::from_fn(2, 3, |r, c| Galois::new((r * 3 + c) as u8));
+        let t = m.transpose();
+        assert_eq!(t.rows(), 3);
+        assert_eq!(t[(2, 1)], m[(1, 2)]);
+        assert_eq!(t.transpose(), m);
+    }
+
+    #[test]
+    fn test_invert() {
+        let m = GfMatrix::from_fn(5, 5, |r, c| Galois::new(r as u8 + 1).exp(c as u32));
+        let inv = m.invert().unwrap();
+        assert_eq!(&m * &inv, GfMatrix::identity(5));
+        assert_eq!(&inv * &m, GfMatrix::identity(5));
+
+        let singular = GfMatrix::from_fn(3, 3, |r, c| Galois::new((r * c) as u8));
+        assert_eq!(singular.invert(), Err(MatrixError::Singular));
+        assert_eq!(GfMatrix::zero(2, 3).invert(), Err(MatrixError::NotSquare));
+    }
+
+    #[test]
+    fn test_invert_into() {
+        let m = GfMatrix::from_fn(4, 4, |r, c| Galois::new(r as u8 + 7).exp(c as u32));
+        let mut scratch = [Galois::zero(); GfMatrix::invert_scratch_len(4)];
+        let mut storage = [Galois::zero(); 16];
+        let mut out = GfMatrixViewMut::new(4, 4, &mut storage);
+
+        assert_eq!(
+            m.invert_into(&mut scratch[..15], &mut out),
+            Err(MatrixError::ScratchTooSmall)
+        );
+        m.invert_into(&mut scratch, &mut out).unwrap();
+        let inv = GfMatrix::from_vec(4, 4, storage.to_vec());
+        assert_eq!(&m * &inv, GfMatrix::identity(4));
+    }
+
+    #[test]
+    fn test_pow() {
+        let m = GfMatrix::from_fn(4, 4, |r, c| Galois::new(((r * 4 + c) * 17 + 3) as u8));
+        assert_eq!(m.pow(0), GfMatrix::identity(4));
+        assert_eq!(m.pow(1), m);
+
+        let mut expected = GfMatrix::identity(4);
+        for n in 0..20 {
+            assert_eq!(m.pow(n), expected);
+            expected = &expected * &m;
+        }
+        assert_eq!(m.pow(1000), &m.pow(999) * &m);
+    }
+
+    #[test]
+    fn test_moore() {
+        let elements: Vec<Galois> = [1u8, 2, 4, 8, 0x1d]
+            .iter()
+            .map(|v| Galois::new(*v))
+            .collect();
+        let m = GfMatrix::moore(&elements, 10);
+        for (c, e) in elements.iter().enumerate() {
+            for r in 0..8 {
+                assert_eq!(m[(r, c)], e.exp(1 << r));
+            }
+            // the Frobenius map has order 8 on GF(256)
+            assert_eq!(m[(8, c)], *e);
+            assert_eq!(m[(9, c)], m[(1, c)]);
+        }
+    }
+
+    #[test]
+    fn test_mul_blocked() {
+        let a = GfMatrix::from_fn(37, 23, |r, c| Galois::new((r * 23 + c) as u8 ^ 0x35));
+        let b = GfMatrix::from_fn(23, 301, |r, c| Galois::new((r * 7 + c * 13) as u8));
+        let expected = &a * &b;
+
+        let block = BlockSize {
+            rows: 5,
+            inner: 6,
+            cols: 64,
+        };
+        assert_eq!(a.mul_blocked(&b, block), expected);
+        assert_eq!(a.mul_blocked(&b, BlockSize::default()), expected);
+        #[cfg(feature = "rayon")]
+        assert_eq!(a.par_mul_blocked(&b, block), expected);
+    }
+}
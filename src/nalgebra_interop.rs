@@ -0,0 +1,67 @@
+use nalgebra::DMatrix;
+use num_traits::{One, Zero};
+
+use crate::{Galois, GfMatrix};
+
+impl Zero for Galois {
+    fn zero() -> Self {
+        Galois::zero()
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Galois::zero()
+    }
+}
+
+impl One for Galois {
+    fn one() -> Self {
+        Galois::identity()
+    }
+}
+
+impl From<GfMatrix> for DMatrix<Galois> {
+    fn from(m: GfMatrix) -> Self {
+        DMatrix::from_row_slice(m.rows(), m.cols(), m.as_slice())
+    }
+}
+
+impl From<&GfMatrix> for DMatrix<Galois> {
+    fn from(m: &GfMatrix) -> Self {
+        DMatrix::from_row_slice(m.rows(), m.cols(), m.as_slice())
+    }
+}
+
+impl From<DMatrix<Galois>> for GfMatrix {
+    fn from(m: DMatrix<Galois>) -> Self {
+        GfMatrix::from_fn(m.nrows(), m.ncols(), |r, c| m[(r, c)])
+    }
+}
+
+impl From<&DMatrix<Galois>> for GfMatrix {
+    fn from(m: &DMatrix<Galois>) -> Self {
+        GfMatrix::from_fn(m.nrows(), m.ncols(), |r, c| m[(r, c)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let m = GfMatrix::from_fn(3, 5, |r, c| Galois::new((r * 31 + c * 7) as u8));
+        let d: DMatrix<Galois> = (&m).into();
+        assert_eq!(d.nrows(), 3);
+        assert_eq!(d[(2, 4)], m[(2, 4)]);
+        assert_eq!(GfMatrix::from(d), m);
+    }
+
+    #[test]
+    fn test_mul_matches() {
+        let a = GfMatrix::from_fn(4, 3, |r, c| Galois::new((r * 3 + c) as u8 + 100));
+        let b = GfMatrix::from_fn(3, 2, |r, c| Galois::new((r * 2 + c) as u8 + 3));
+        let da: DMatrix<Galois> = (&a).into();
+        let db: DMatrix<Galois> = (&b).into();
+        assert_eq!(GfMatrix::from(da * db), &a * &b);
+    }
+}
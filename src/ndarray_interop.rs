@@ -0,0 +1,140 @@
+use ndarray::{Array2, ArrayRef, Axis, Ix2, Zip};
+
+use crate::slice::{add_slice, as_bytes, as_bytes_mut, mul_add_slice, scale_slice};
+use crate::{Galois, GfMatrix};
+
+impl From<GfMatrix> for Array2<Galois> {
+    fn from(m: GfMatrix) -> Self {
+        let shape = (m.rows(), m.cols());
+        Array2::from_shape_vec(shape, m.into_vec()).expect("shape matches data length")
+    }
+}
+
+impl From<&GfMatrix> for Array2<Galois> {
+    fn from(m: &GfMatrix) -> Self {
+        m.clone().into()
+    }
+}
+
+impl From<&ArrayRef<Galois, Ix2>> for GfMatrix {
+    fn from(a: &ArrayRef<Galois, Ix2>) -> Self {
+        let (rows, cols) = a.dim();
+        GfMatrix::from_fn(rows, cols, |r, c| a[(r, c)])
+    }
+}
+
+impl From<Array2<Galois>> for GfMatrix {
+    fn from(a: Array2<Galois>) -> Self {
+        GfMatrix::from(&*a)
+    }
+}
+
+/// Element-wise GF(256) operations on 2-D arrays.
+///
+/// Lanes that are contiguous in memory on both sides are processed with the
+/// slice kernels from [`crate::slice`]; anything else falls back to
+/// element-wise iteration.
+pub trait GaloisArrayExt {
+    /// `self += rhs`
+    fn gf_add_assign(&mut self, rhs: &ArrayRef<Galois, Ix2>);
+
+    /// `self += c * rhs`
+    fn gf_mul_add(&mut self, c: Galois, rhs: &ArrayRef<Galois, Ix2>);
+
+    /// `self *= c`
+    fn gf_scale(&mut self, c: Galois);
+}
+
+impl GaloisArrayExt for ArrayRef<Galois, Ix2> {
+    fn gf_add_assign(&mut self, rhs: &ArrayRef<Galois, Ix2>) {
+        zip_lanes(self, rhs, add_slice, |o, i| *o += i);
+    }
+
+    fn gf_mul_add(&mut self, c: Galois, rhs: &ArrayRef<Galois, Ix2>) {
+        zip_lanes(self, rhs, |i, o| mul_add_slice(c, i, o), |o, i| *o += c * i);
+    }
+
+    fn gf_scale(&mut self, c: Galois) {
+        let axis = contiguous_axis(self);
+        for mut lane in self.lanes_mut(axis) {
+            match lane.as_slice_mut() {
+                Some(s) => scale_slice(c, as_bytes_mut(s)),
+                None => lane.map_inplace(|v| *v *= c),
+            }
+        }
+    }
+}
+
+fn contiguous_axis(a: &ArrayRef<Galois, Ix2>) -> Axis {
+    if a.ncols() > 1 && a.stride_of(Axis(0)) == 1 && a.stride_of(Axis(1)) != 1 {
+        Axis(0)
+    } else {
+        Axis(1)
+    }
+}
+
+fn zip_lanes<K, E>(dst: &mut ArrayRef<Galois, Ix2>, src: &ArrayRef<Galois, Ix2>, kernel: K, elem: E)
+where
+    K: Fn(&[u8], &mut [u8]),
+    E: Fn(&mut Galois, Galois),
+{
+    assert_eq!(dst.dim(), src.dim(), "shape mismatch");
+
+    let axis = contiguous_axis(dst);
+    for (mut d, s) in dst.lanes_mut(axis).into_iter().zip(src.lanes(axis)) {
+        match (d.as_slice_mut(), s.as_slice()) {
+            (Some(d), Some(s)) => kernel(as_bytes(s), as_bytes_mut(d)),
+            _ => Zip::from(&mut d).and(&s).for_each(|o, i| elem(o, *i)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::ShapeBuilder;
+
+    fn sample(rows: usize, cols: usize, seed: u8) -> GfMatrix {
+        GfMatrix::from_fn(rows, cols, |r, c| Galois::new((r * cols + c) as u8 ^ seed))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let m = sample(3, 4, 0x5a);
+        let a: Array2<Galois> = (&m).into();
+        assert_eq!(a[(2, 3)], m[(2, 3)]);
+        assert_eq!(GfMatrix::from(a), m);
+    }
+
+    #[test]
+    fn test_mul_add_matches_scalar() {
+        let a = sample(5, 7, 1);
+        let b = sample(5, 7, 200);
+        let c = Galois::new(0x8e);
+
+        let mut x: Array2<Galois> = (&a).into();
+        let y: Array2<Galois> = (&b).into();
+        x.gf_mul_add(c, &y);
+
+        let expected = GfMatrix::from_fn(5, 7, |r, col| a[(r, col)] + c * b[(r, col)]);
+        assert_eq!(GfMatrix::from(x), expected);
+    }
+
+    #[test]
+    fn test_non_standard_layout() {
+        let a = sample(4, 6, 9);
+        let b = sample(4, 6, 17);
+
+        let mut x = Array2::from_shape_fn((4, 6).f(), |(r, c)| a[(r, c)]);
+        let y: Array2<Galois> = (&b).into();
+        x.gf_add_assign(&y);
+        x.gf_scale(Galois::new(3));
+
+        let expected = GfMatrix::from_fn(4, 6, |r, c| (a[(r, c)] + b[(r, c)]) * Galois::new(3));
+        assert_eq!(GfMatrix::from(&*x), expected);
+
+        let mut t: Array2<Galois> = (&a).into();
+        t.view_mut().reversed_axes().gf_scale(Galois::new(2));
+        assert_eq!(t[(1, 2)], a[(1, 2)] * Galois::new(2));
+    }
+}
@@ -0,0 +1,222 @@
+//! GF(256) in a normal basis.
+//!
+//! A normal basis is `β, β^2, β^4, .., β^128` for an element `β` whose
+//! conjugates are linearly independent. Squaring then rotates the
+//! coordinates, so powers and inverses cost only rotations and a few
+//! products, which is why hardware multipliers often use one. Products are
+//! computed by the Massey-Omura method: coordinate `k` of `ab` is the
+//! bilinear form of one fixed matrix applied to `a` and `b` rotated by `k`.
+//!
+//! GF(256) has no optimal normal basis, so `β` is the first element, in
+//! byte order, whose multiplication matrix has the fewest ones, 21 against
+//! the optimal 15. As in [`tower`](crate::tower), the basis change to and
+//! from [`Galois`] is given as 8×8 bit matrices.
+
+use std::ops::{Add, Mul};
+use std::sync::OnceLock;
+
+use crate::Galois;
+
+/// An element of GF(256) in the normal basis, bit `i` being the
+/// coordinate of `β^(2^i)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+#[repr(transparent)]
+pub struct Normal(u8);
+
+impl Normal {
+    pub fn new(v: u8) -> Self {
+        Normal(v)
+    }
+
+    pub fn zero() -> Self {
+        Normal(0)
+    }
+
+    /// One, the sum of all the conjugates of `β`.
+    pub fn identity() -> Self {
+        Normal(0xff)
+    }
+
+    /// The square, a rotation.
+    pub fn square(self) -> Self {
+        Normal(self.0.rotate_left(1))
+    }
+
+    /// `self^(2^k)`, a rotation by `k`.
+    pub fn pow2(self, k: u32) -> Self {
+        Normal(self.0.rotate_left(k % 8))
+    }
+
+    /// The inverse as `self^254 = self^2 self^4 .. self^128`, with zero
+    /// mapped to zero.
+    pub fn inv(self) -> Self {
+        (1..8).fold(Normal::identity(), |acc, k| acc * self.pow2(k))
+    }
+
+    pub fn from_galois(g: Galois) -> Self {
+        Normal(apply(&basis().to_normal, u8::from(g)))
+    }
+
+    pub fn to_galois(self) -> Galois {
+        Galois::new(apply(&basis().from_normal, self.0))
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Add for Normal {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Normal(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Normal {
+    type Output = Self;
+
+    // c_k = a' M b' with a', b' rotated so that coordinate k comes first
+    fn mul(self, rhs: Self) -> Self::Output {
+        let m = &basis().multiplication;
+        let mut product = 0u8;
+        for k in 0..8 {
+            let (a, b) = (self.0.rotate_right(k), rhs.0.rotate_right(k));
+            let bit = (0..8)
+                .filter(|i| a >> i & 1 == 1)
+                .fold(0, |acc, i| acc ^ (m[i] & b).count_ones() as u8 & 1);
+            product |= bit << k;
+        }
+        Normal(product)
+    }
+}
+
+impl From<u8> for Normal {
+    fn from(v: u8) -> Self {
+        Normal(v)
+    }
+}
+
+impl From<Normal> for u8 {
+    fn from(n: Normal) -> Self {
+        n.0
+    }
+}
+
+struct Basis {
+    beta: Galois,
+    to_normal: [u8; 8],
+    from_normal: [u8; 8],
+    // row i: bit j is coordinate 0 of β^(2^i) β^(2^j)
+    multiplication: [u8; 8],
+}
+
+fn basis() -> &'static Basis {
+    static BASIS: OnceLock<Basis> = OnceLock::new();
+    BASIS.get_or_init(|| {
+        (1..=255u8)
+            .filter_map(|b| normal_basis(Galois::new(b)))
+            .min_by_key(|basis| complexity(&basis.multiplication))
+            .expect("every finite field has a normal basis")
+    })
+}
+
+// The basis of the conjugates of `beta`, if they are independent.
+fn normal_basis(beta: Galois) -> Option<Basis> {
+    let from_normal: [u8; 8] = std::array::from_fn(|i| u8::from(beta.exp(1 << i)));
+    let mut table = [None; 256];
+    for v in 0..=255u8 {
+        table[apply(&from_normal, v) as usize] = Some(v);
+    }
+    if table.contains(&None) {
+        return None;
+    }
+    let to_normal = std::array::from_fn(|j| table[1 << j].unwrap());
+
+    let multiplication = std::array::from_fn(|i| {
+        (0..8).fold(0u8, |row, j| {
+            let product = beta.exp(1 << i) * beta.exp(1 << j);
+            row | (apply(&to_normal, u8::from(product)) & 1) << j
+        })
+    });
+    Some(Basis {
+        beta,
+        to_normal,
+        from_normal,
+        multiplication,
+    })
+}
+
+/// The normal element `β`.
+pub fn beta() -> Galois {
+    basis().beta
+}
+
+/// The basis change from [`Galois`] to [`Normal`] as an 8×8 matrix over
+/// GF(2), column `j` being the image of bit `j`.
+pub fn to_normal_matrix() -> [u8; 8] {
+    basis().to_normal
+}
+
+/// The inverse of [`to_normal_matrix`]: column `j` is `β^(2^j)`.
+pub fn from_normal_matrix() -> [u8; 8] {
+    basis().from_normal
+}
+
+/// The Massey-Omura matrix, row `i` bit `j` being coordinate 0 of
+/// `β^(2^i) β^(2^j)`.
+pub fn multiplication_matrix() -> [u8; 8] {
+    basis().multiplication
+}
+
+// The number of ones in a multiplication matrix, that of XOR gates in a
+// hardware multiplier growing with it.
+fn complexity(multiplication: &[u8; 8]) -> u32 {
+    multiplication.iter().map(|r| r.count_ones()).sum()
+}
+
+fn apply(columns: &[u8; 8], v: u8) -> u8 {
+    (0..8)
+        .filter(|j| v >> j & 1 == 1)
+        .fold(0, |acc, j| acc ^ columns[j])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basis() {
+        // the conjugates of β are independent: the change of basis is onto
+        let mut seen = [false; 256];
+        for v in 0..=255u8 {
+            let g = Normal(v).to_galois();
+            assert!(!seen[u8::from(g) as usize]);
+            seen[u8::from(g) as usize] = true;
+            assert_eq!(Normal::from_galois(g), Normal(v));
+        }
+        assert_eq!(Normal::identity().to_galois(), Galois::identity());
+        assert_eq!(Normal(1).to_galois(), beta());
+        // no normal basis has a sparser matrix, and none is optimal
+        let least = (1..=255u8)
+            .filter_map(|b| normal_basis(Galois::new(b)))
+            .map(|basis| complexity(&basis.multiplication))
+            .min();
+        assert_eq!(least, Some(21));
+        assert_eq!(complexity(&multiplication_matrix()), 21);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        for a in 0..=255u8 {
+            let (na, ga) = (Normal::from_galois(Galois::new(a)), Galois::new(a));
+            assert_eq!(na.square().to_galois(), ga * ga);
+            assert_eq!(na.pow2(3).to_galois(), ga.exp(8));
+            let expected = if a == 0 { Galois::zero() } else { ga.inv() };
+            assert_eq!(na.inv().to_galois(), expected);
+            for b in 0..=255u8 {
+                let nb = Normal::from_galois(Galois::new(b));
+                assert_eq!((na * nb).to_galois(), ga * Galois::new(b));
+                assert_eq!((na + nb).to_galois(), ga + Galois::new(b));
+            }
+        }
+    }
+}
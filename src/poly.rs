@@ -0,0 +1,361 @@
+//! Polynomials over GF(256).
+
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+use crate::Galois;
+
+mod extension;
+pub use extension::{ExtensionElement, ExtensionField};
+mod linearized;
+pub use linearized::{frobenius, LinearizedPoly};
+
+/// A polynomial over GF(256), stored lowest degree first with no trailing
+/// zero coefficients.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Poly {
+    coeffs: Vec<Galois>,
+}
+
+impl Poly {
+    /// Builds a polynomial from coefficients, lowest degree first.
+    pub fn new(mut coeffs: Vec<Galois>) -> Self {
+        while coeffs.last() == Some(&Galois::zero()) {
+            coeffs.pop();
+        }
+        Poly { coeffs }
+    }
+
+    pub fn zero() -> Self {
+        Poly { coeffs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        Poly::monomial(Galois::identity(), 0)
+    }
+
+    /// `c * x^degree`
+    pub fn monomial(c: Galois, degree: usize) -> Self {
+        let mut coeffs = vec![Galois::zero(); degree + 1];
+        coeffs[degree] = c;
+        Poly::new(coeffs)
+    }
+
+    /// `Π (x - r)` over `roots`.
+    pub fn from_roots(roots: &[Galois]) -> Self {
+        let mut p = Poly::one();
+        for r in roots {
+            p = &p * &Poly::new(vec![*r, Galois::identity()]);
+        }
+        p
+    }
+
+    /// The unique polynomial of degree `< points.len()` through
+    /// `(points[i], values[i])`. The points must be distinct.
+    pub fn interpolate(points: &[Galois], values: &[Galois]) -> Self {
+        assert_eq!(points.len(), values.len(), "point/value count mismatch");
+
+        let all = Poly::from_roots(points);
+        let mut acc = vec![Galois::zero(); points.len()];
+        for (a, y) in points.iter().zip(values) {
+            // all / (x - a) by synthetic division, and its value at `a`
+            let mut basis = vec![Galois::zero(); points.len()];
+            let mut carry = Galois::zero();
+            for i in (0..points.len()).rev() {
+                carry = all.coeff(i + 1) + carry * *a;
+                basis[i] = carry;
+            }
+            let denom = basis
+                .iter()
+                .rev()
+                .fold(Galois::zero(), |acc, c| acc * *a + *c);
+            let scale = *y / denom;
+            for (o, b) in acc.iter_mut().zip(basis) {
+                *o += scale * b;
+            }
+        }
+        Poly::new(acc)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// The degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    pub fn coeffs(&self) -> &[Galois] {
+        &self.coeffs
+    }
+
+    /// The coefficient of `x^i`, which is zero past the degree.
+    pub fn coeff(&self, i: usize) -> Galois {
+        self.coeffs.get(i).copied().unwrap_or_default()
+    }
+
+    pub fn lead(&self) -> Galois {
+        self.coeffs.last().copied().unwrap_or_default()
+    }
+
+    pub fn eval(&self, x: Galois) -> Galois {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(Galois::zero(), |acc, c| acc * x + *c)
+    }
+
+    /// The formal derivative. In characteristic 2 only odd terms survive.
+    pub fn derivative(&self) -> Poly {
+        let coeffs = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, c)| if i % 2 == 1 { *c } else { Galois::zero() })
+            .collect();
+        Poly::new(coeffs)
+    }
+
+    pub fn scale(&self, c: Galois) -> Poly {
+        Poly::new(self.coeffs.iter().map(|v| *v * c).collect())
+    }
+
+    /// `self * x^n`
+    pub fn shift(&self, n: usize) -> Poly {
+        if self.is_zero() {
+            return Poly::zero();
+        }
+        let mut coeffs = vec![Galois::zero(); n];
+        coeffs.extend_from_slice(&self.coeffs);
+        Poly { coeffs }
+    }
+
+    /// `self mod x^n`
+    pub fn truncate(&self, n: usize) -> Poly {
+        Poly::new(self.coeffs.iter().take(n).copied().collect())
+    }
+
+    /// Euclidean division, returning `(quotient, remainder)`.
+    pub fn div_rem(&self, divisor: &Poly) -> (Poly, Poly) {
+        let dd = divisor.degree().expect("divide by zero polynomial");
+        let Some(nd) = self.degree().filter(|nd| *nd >= dd) else {
+            return (Poly::zero(), self.clone());
+        };
+
+        let inv_lead = divisor.lead().inv();
+        let mut rem = self.coeffs.clone();
+        let mut quot = vec![Galois::zero(); nd - dd + 1];
+        for i in (0..quot.len()).rev() {
+            let q = rem[i + dd] * inv_lead;
+            quot[i] = q;
+            if q == Galois::zero() {
+                continue;
+            }
+            for (j, d) in divisor.coeffs.iter().enumerate() {
+                rem[i + j] -= q * *d;
+            }
+        }
+        (Poly::new(quot), Poly::new(rem))
+    }
+
+    /// The monic greatest common divisor, or zero if both are zero.
+    pub fn gcd(&self, other: &Poly) -> Poly {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while !b.is_zero() {
+            let (_, r) = a.div_rem(&b);
+            a = std::mem::replace(&mut b, r);
+        }
+        if a.is_zero() {
+            return a;
+        }
+        a.scale(a.lead().inv())
+    }
+
+    /// Whether the polynomial is irreducible over GF(256), by Rabin's test:
+    /// a polynomial `f` of degree `d` is irreducible iff `f` divides
+    /// `x^(256^d) - x` and is coprime to `x^(256^(d/p)) - x` for every prime
+    /// `p` dividing `d`.
+    pub fn is_irreducible(&self) -> bool {
+        let Some(d) = self.degree().filter(|d| *d > 0) else {
+            return false;
+        };
+        if d == 1 {
+            return true;
+        }
+
+        let x = Poly::monomial(Galois::identity(), 1);
+        // frobenius[i] = x^(256^i) mod self
+        let mut frobenius = vec![x.clone()];
+        for i in 0..d {
+            let mut h = frobenius[i].clone();
+            for _ in 0..8 {
+                h = (&h * &h).div_rem(self).1;
+            }
+            frobenius.push(h);
+        }
+
+        frobenius[d] == x.div_rem(self).1
+            && (2..=d)
+                .filter(|p| d % p == 0 && (2..*p).all(|q| p % q != 0))
+                .all(|p| (&frobenius[d / p] - &x).gcd(self) == Poly::one())
+    }
+}
+
+impl Add for &Poly {
+    type Output = Poly;
+
+    fn add(self, rhs: Self) -> Poly {
+        let n = self.coeffs.len().max(rhs.coeffs.len());
+        Poly::new((0..n).map(|i| self.coeff(i) + rhs.coeff(i)).collect())
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Sub for &Poly {
+    type Output = Poly;
+
+    fn sub(self, rhs: Self) -> Poly {
+        self + rhs
+    }
+}
+
+impl Mul for &Poly {
+    type Output = Poly;
+
+    fn mul(self, rhs: Self) -> Poly {
+        if self.is_zero() || rhs.is_zero() {
+            return Poly::zero();
+        }
+        let mut coeffs = vec![Galois::zero(); self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in rhs.coeffs.iter().enumerate() {
+                coeffs[i + j] += *a * *b;
+            }
+        }
+        Poly::new(coeffs)
+    }
+}
+
+impl Add for Poly {
+    type Output = Poly;
+
+    fn add(self, rhs: Self) -> Poly {
+        &self + &rhs
+    }
+}
+
+impl Sub for Poly {
+    type Output = Poly;
+
+    fn sub(self, rhs: Self) -> Poly {
+        &self - &rhs
+    }
+}
+
+impl Mul for Poly {
+    type Output = Poly;
+
+    fn mul(self, rhs: Self) -> Poly {
+        &self * &rhs
+    }
+}
+
+impl fmt::Display for Poly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let mut first = true;
+        for (i, c) in self.coeffs.iter().enumerate().rev() {
+            if *c == Galois::zero() {
+                continue;
+            }
+            if !first {
+                write!(f, " + ")?;
+            }
+            first = false;
+            match i {
+                0 => write!(f, "{}", u8::from(*c))?,
+                1 => write!(f, "{}x", u8::from(*c))?,
+                _ => write!(f, "{}x^{}", u8::from(*c), i)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poly(v: &[u8]) -> Poly {
+        Poly::new(v.iter().map(|c| Galois::new(*c)).collect())
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let a = poly(&[7, 3, 0, 200, 41, 9]);
+        let b = poly(&[5, 0, 17]);
+        let (q, r) = a.div_rem(&b);
+        assert!(r.degree() < b.degree());
+        assert_eq!(&(&q * &b) + &r, a);
+
+        let (q, r) = b.div_rem(&a);
+        assert!(q.is_zero());
+        assert_eq!(r, b);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let p = poly(&[9, 0, 77, 3, 150]);
+        let points: Vec<Galois> = (1..=7).map(|x| Galois::new(x * 31)).collect();
+        let values: Vec<Galois> = points.iter().map(|x| p.eval(*x)).collect();
+        assert_eq!(Poly::interpolate(&points, &values), p);
+        assert_eq!(Poly::interpolate(&points[..5], &values[..5]), p);
+    }
+
+    #[test]
+    fn test_roots_and_derivative() {
+        let roots = [Galois::new(3), Galois::new(99), Galois::new(250)];
+        let p = Poly::from_roots(&roots);
+        assert_eq!(p.degree(), Some(3));
+        for r in roots {
+            assert_eq!(p.eval(r), Galois::zero());
+        }
+        assert_ne!(p.eval(Galois::new(4)), Galois::zero());
+
+        // d/dx (x^3 + a x^2 + b x + c) = x^2 + b
+        let d = p.derivative();
+        assert_eq!(d, poly(&[u8::from(p.coeff(1)), 0, 1]));
+    }
+
+    #[test]
+    fn test_gcd_and_irreducible() {
+        let a = Poly::from_roots(&[Galois::new(3), Galois::new(8)]);
+        let b = Poly::from_roots(&[Galois::new(8), Galois::new(41)]);
+        assert_eq!(a.gcd(&b), Poly::from_roots(&[Galois::new(8)]));
+        assert_eq!(a.scale(Galois::new(7)).gcd(&a), a);
+
+        assert!(!a.is_irreducible());
+        assert!(poly(&[5, 1]).is_irreducible());
+        // x^2 + x + 1 splits, as GF(4) is a subfield of GF(256)
+        assert!(!poly(&[1, 1, 1]).is_irreducible());
+        // x^2 + x + α is irreducible iff the absolute trace of α is 1
+        let trace = |a: Galois| (0..8).fold(Galois::zero(), |acc, i| acc + a.exp(1 << i));
+        for v in 1..=255u8 {
+            let expected = trace(Galois::new(v)) == Galois::identity();
+            assert_eq!(poly(&[v, 1, 1]).is_irreducible(), expected, "{v}");
+        }
+        // a product of two irreducible cubics has no roots but is reducible
+        let cubic = (2..=255u8)
+            .map(|v| poly(&[v, 0, 0, 1]))
+            .filter(Poly::is_irreducible)
+            .take(2)
+            .collect::<Vec<_>>();
+        assert_eq!(cubic.len(), 2);
+        assert!(!(&cubic[0] * &cubic[1]).is_irreducible());
+    }
+}
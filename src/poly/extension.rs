@@ -0,0 +1,231 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+use super::Poly;
+use crate::Galois;
+
+/// The extension GF(256^n) = GF(256)[x] / (f), for an irreducible `f` of
+/// degree `n` chosen at run time.
+///
+/// Elements are polynomials of degree below `n`, so arithmetic is that of
+/// [`Poly`] followed by reduction modulo `f`, and inversion is the extended
+/// Euclidean algorithm. It is meant for exploring fields the crate has no
+/// dedicated type for, not for speed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionField {
+    modulus: Poly,
+}
+
+impl ExtensionField {
+    /// Returns `None` unless `modulus` is irreducible over GF(256) by
+    /// [`Poly::is_irreducible`]. It is made monic.
+    pub fn new(modulus: Poly) -> Option<Self> {
+        if !modulus.is_irreducible() {
+            return None;
+        }
+        let modulus = modulus.scale(modulus.lead().inv());
+        Some(ExtensionField { modulus })
+    }
+
+    pub fn modulus(&self) -> &Poly {
+        &self.modulus
+    }
+
+    /// The degree `n` of the extension.
+    pub fn degree(&self) -> usize {
+        self.modulus.degree().unwrap()
+    }
+
+    /// The number of elements, `256^n`, if it fits.
+    pub fn order(&self) -> Option<u128> {
+        256u128.checked_pow(self.degree() as u32)
+    }
+
+    /// The element `p mod f`.
+    pub fn elem(&self, p: Poly) -> ExtensionElement<'_> {
+        let (_, value) = p.div_rem(&self.modulus);
+        ExtensionElement { field: self, value }
+    }
+
+    pub fn zero(&self) -> ExtensionElement<'_> {
+        self.elem(Poly::zero())
+    }
+
+    pub fn one(&self) -> ExtensionElement<'_> {
+        self.elem(Poly::one())
+    }
+
+    /// The class of `x`, a root of the modulus.
+    pub fn x(&self) -> ExtensionElement<'_> {
+        self.elem(Poly::monomial(Galois::identity(), 1))
+    }
+
+    /// The element of the base field `c`.
+    pub fn constant(&self, c: Galois) -> ExtensionElement<'_> {
+        self.elem(Poly::new(vec![c]))
+    }
+}
+
+/// An element of an [`ExtensionField`], from [`ExtensionField::elem`].
+///
+/// Operators between elements of different fields panic.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ExtensionElement<'a> {
+    field: &'a ExtensionField,
+    value: Poly,
+}
+
+impl<'a> ExtensionElement<'a> {
+    /// The representative of degree below `n`.
+    pub fn poly(&self) -> &Poly {
+        &self.value
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    /// The inverse by the extended Euclidean algorithm, panicking on zero.
+    pub fn inv(&self) -> ExtensionElement<'a> {
+        assert!(!self.is_zero(), "divide by zero");
+        // invariant: s_i * value = r_i modulo f
+        let (mut r0, mut r1) = (self.field.modulus.clone(), self.value.clone());
+        let (mut s0, mut s1) = (Poly::zero(), Poly::one());
+        while !r1.is_zero() {
+            let (q, r) = r0.div_rem(&r1);
+            let s = &s0 - &(&q * &s1);
+            r0 = std::mem::replace(&mut r1, r);
+            s0 = std::mem::replace(&mut s1, s);
+        }
+        // r0 is the gcd, a nonzero constant since f is irreducible
+        self.field.elem(s0.scale(r0.lead().inv()))
+    }
+
+    pub fn pow(&self, n: u128) -> ExtensionElement<'a> {
+        let (mut base, mut result) = (self.clone(), self.field.one());
+        let mut n = n;
+        while n != 0 {
+            if n & 1 == 1 {
+                result = result * base.clone();
+            }
+            base = base.clone() * base;
+            n >>= 1;
+        }
+        result
+    }
+
+    fn check_field(&self, rhs: &Self) {
+        assert!(
+            std::ptr::eq(self.field, rhs.field) || self.field == rhs.field,
+            "elements of different fields"
+        );
+    }
+}
+
+impl fmt::Debug for ExtensionElement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} mod ({})", self.value, self.field.modulus)
+    }
+}
+
+impl<'a> Add for ExtensionElement<'a> {
+    type Output = ExtensionElement<'a>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.check_field(&rhs);
+        ExtensionElement {
+            field: self.field,
+            value: &self.value + &rhs.value,
+        }
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<'a> Sub for ExtensionElement<'a> {
+    type Output = ExtensionElement<'a>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + rhs
+    }
+}
+
+impl<'a> Mul for ExtensionElement<'a> {
+    type Output = ExtensionElement<'a>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.check_field(&rhs);
+        self.field.elem(&self.value * &rhs.value)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<'a> Div for ExtensionElement<'a> {
+    type Output = ExtensionElement<'a>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.check_field(&rhs);
+        self * rhs.inv()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poly(coeffs: &[u8]) -> Poly {
+        Poly::new(coeffs.iter().map(|&c| Galois::new(c)).collect())
+    }
+
+    #[test]
+    fn test_quadratic() {
+        // x^2 + x + c is irreducible for the c outside {y^2 + y}
+        let reducible: Vec<u8> = (0..=255u8)
+            .map(|y| u8::from(Galois::new(y) * Galois::new(y) + Galois::new(y)))
+            .collect();
+        let c = (0..=255u8).find(|c| !reducible.contains(c)).unwrap();
+        let field = ExtensionField::new(poly(&[c, 1, 1])).unwrap();
+        assert_eq!(field.degree(), 2);
+        assert_eq!(field.order(), Some(65536));
+
+        let x = field.x();
+        // x is a root of the modulus
+        assert!((x.clone() * x.clone() + x.clone() + field.constant(Galois::new(c))).is_zero());
+        let samples: Vec<_> = (1..20u8)
+            .map(|i| field.elem(poly(&[i.wrapping_mul(37), i])))
+            .collect();
+        for a in &samples {
+            assert_eq!(a.clone() * a.inv(), field.one());
+            assert_eq!(a.pow(65535), field.one());
+            for b in &samples {
+                assert_eq!(a.clone() * b.clone() / b.clone(), a.clone());
+                assert_eq!(
+                    (a.clone() + b.clone()) * x.clone(),
+                    a.clone() * x.clone() + b.clone() * x.clone()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_construction() {
+        // (x + 1)(x + 2) = x^2 + 3x + 2
+        assert!(ExtensionField::new(poly(&[2, 3, 1])).is_none());
+        assert!(ExtensionField::new(poly(&[5])).is_none());
+        assert!(ExtensionField::new(Poly::zero()).is_none());
+
+        // made monic, and reduces its input
+        let field = ExtensionField::new(poly(&[2, 3])).unwrap();
+        assert_eq!(field.modulus().lead(), Galois::identity());
+        assert_eq!(field.order(), Some(256));
+        assert_eq!(
+            field.x().poly(),
+            field.constant(Galois::new(2) / Galois::new(3)).poly()
+        );
+
+        let field = ExtensionField::new(crate::goppa::irreducible(3, 1)).unwrap();
+        let a = field.elem(poly(&[1, 2, 3, 4, 5]));
+        assert!(a.poly().degree().unwrap() < 3);
+        assert_eq!(a.pow(255 * 65793), field.one());
+        assert_eq!(a.clone() * a.inv(), field.one());
+    }
+}
@@ -0,0 +1,164 @@
+use std::ops::Add;
+
+use crate::Galois;
+
+/// `a^(2^i)`, the `i`-th power of the Frobenius map. As it has order 8 on
+/// GF(256), negative `i` give its inverse powers.
+pub fn frobenius(a: Galois, i: isize) -> Galois {
+    (0..i.rem_euclid(8)).fold(a, |acc, _| acc * acc)
+}
+
+/// A linearized polynomial `L(x) = Σ a_i x^(2^i)` over GF(256), stored by
+/// q-degree lowest first with no trailing zero coefficients.
+///
+/// Linearized polynomials are GF(2)-linear maps and form a ring under
+/// addition and composition, which is not commutative: `a x ∘ x^2 = a x^2`
+/// but `x^2 ∘ a x = a^2 x^2`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LinearizedPoly {
+    coeffs: Vec<Galois>,
+}
+
+impl LinearizedPoly {
+    /// Builds a polynomial from its coefficients, the `i`-th being that of
+    /// `x^(2^i)`.
+    pub fn new(mut coeffs: Vec<Galois>) -> Self {
+        while coeffs.last() == Some(&Galois::zero()) {
+            coeffs.pop();
+        }
+        LinearizedPoly { coeffs }
+    }
+
+    pub fn zero() -> Self {
+        LinearizedPoly { coeffs: Vec::new() }
+    }
+
+    /// The identity map `x`.
+    pub fn x() -> Self {
+        LinearizedPoly::monomial(Galois::identity(), 0)
+    }
+
+    /// `c * x^(2^q_degree)`
+    pub fn monomial(c: Galois, q_degree: usize) -> Self {
+        let mut coeffs = vec![Galois::zero(); q_degree + 1];
+        coeffs[q_degree] = c;
+        LinearizedPoly::new(coeffs)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// The q-degree, or `None` for the zero polynomial.
+    pub fn q_degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    pub fn coeffs(&self) -> &[Galois] {
+        &self.coeffs
+    }
+
+    /// The coefficient of `x^(2^i)`, which is zero past the q-degree.
+    pub fn coeff(&self, i: usize) -> Galois {
+        self.coeffs.get(i).copied().unwrap_or_default()
+    }
+
+    pub fn eval(&self, x: Galois) -> Galois {
+        let mut power = x;
+        let mut acc = Galois::zero();
+        for c in &self.coeffs {
+            acc += *c * power;
+            power *= power;
+        }
+        acc
+    }
+
+    /// `self ∘ rhs`, the map `x ↦ self(rhs(x))`.
+    pub fn compose(&self, rhs: &LinearizedPoly) -> LinearizedPoly {
+        if self.is_zero() || rhs.is_zero() {
+            return LinearizedPoly::zero();
+        }
+        let mut coeffs = vec![Galois::zero(); self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            for (j, b) in rhs.coeffs.iter().enumerate() {
+                coeffs[i + j] += *a * frobenius(*b, i as isize);
+            }
+        }
+        LinearizedPoly::new(coeffs)
+    }
+
+    /// Division on the left, returning `(quotient, remainder)` with
+    /// `self = divisor ∘ quotient + remainder` and the remainder of smaller
+    /// q-degree than `divisor`.
+    pub fn div_rem_left(&self, divisor: &LinearizedPoly) -> (LinearizedPoly, LinearizedPoly) {
+        let dd = divisor.q_degree().expect("divide by zero polynomial");
+        let Some(nd) = self.q_degree().filter(|nd| *nd >= dd) else {
+            return (LinearizedPoly::zero(), self.clone());
+        };
+
+        let lead = divisor.coeffs[dd];
+        let mut rem = self.coeffs.clone();
+        let mut quot = vec![Galois::zero(); nd - dd + 1];
+        for s in (0..quot.len()).rev() {
+            // divisor ∘ c x^(2^s) leads with lead * c^(2^dd)
+            let c = frobenius(rem[s + dd] / lead, -(dd as isize));
+            quot[s] = c;
+            if c == Galois::zero() {
+                continue;
+            }
+            for (i, d) in divisor.coeffs.iter().enumerate() {
+                rem[s + i] -= *d * frobenius(c, i as isize);
+            }
+        }
+        (LinearizedPoly::new(quot), LinearizedPoly::new(rem))
+    }
+}
+
+impl Add for &LinearizedPoly {
+    type Output = LinearizedPoly;
+
+    fn add(self, rhs: Self) -> LinearizedPoly {
+        let n = self.coeffs.len().max(rhs.coeffs.len());
+        LinearizedPoly::new((0..n).map(|i| self.coeff(i) + rhs.coeff(i)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lin(v: &[u8]) -> LinearizedPoly {
+        LinearizedPoly::new(v.iter().map(|c| Galois::new(*c)).collect())
+    }
+
+    #[test]
+    fn test_compose_and_eval() {
+        let a = lin(&[3, 0, 77, 5]);
+        let b = lin(&[9, 200, 1]);
+        for x in 0..=255 {
+            let x = Galois::new(x);
+            assert_eq!(a.compose(&b).eval(x), a.eval(b.eval(x)));
+            // linear over GF(2)
+            let y = Galois::new(0x5a);
+            assert_eq!(a.eval(x + y), a.eval(x) + a.eval(y));
+        }
+        assert_ne!(a.compose(&b), b.compose(&a));
+        assert_eq!(a.compose(&LinearizedPoly::x()), a);
+        assert_eq!(
+            frobenius(frobenius(Galois::new(77), 3), -3),
+            Galois::new(77)
+        );
+    }
+
+    #[test]
+    fn test_div_rem_left() {
+        let a = lin(&[7, 3, 0, 200, 41, 9]);
+        let b = lin(&[5, 0, 17]);
+        let (q, r) = a.div_rem_left(&b);
+        assert!(r.q_degree() < b.q_degree());
+        assert_eq!(&b.compose(&q) + &r, a);
+
+        let (q, r) = b.compose(&a).div_rem_left(&b);
+        assert_eq!((q, r), (a, LinearizedPoly::zero()));
+    }
+}
@@ -0,0 +1,452 @@
+//! Prime fields GF(p) and the number-theoretic transform.
+//!
+//! Everything else in the crate works in characteristic 2, where addition
+//! is XOR. [`PrimeField`] is the integers modulo a prime `P < 2^32`, with
+//! Barrett reduction, for comparing that against the codes built on prime
+//! fields such as GF(257) and GF(65537): their multiplicative groups have
+//! order a power of two, so [`ntt`] evaluates a polynomial at the `n`-th
+//! roots of unity in `n log n` operations, which is not possible over
+//! GF(2^m), whose group order is odd. It does not implement
+//! [`Field`](crate::Field), whose users rely on characteristic 2.
+//!
+//! [`convolution`] multiplies polynomials through the transform, the
+//! counterpart of multiplying through the additive [`fft`](crate::fft) of
+//! GF(256). [`Gf65537`] and [`NttPrime`] are fields with long transforms.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::util::prime_factors;
+
+/// An element of GF(P), for a prime `P < 2^32`, stored reduced.
+///
+/// A `P` that is not such a prime fails to compile on first use.
+///
+/// ```
+/// use gf256_lite::prime::PrimeField;
+///
+/// type F = PrimeField<257>;
+/// assert_eq!(F::new(200) + F::new(100), F::new(43));
+/// assert_eq!(F::new(3).inv() * F::new(3), F::one());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+pub struct PrimeField<const P: u64>(u64);
+
+/// The Fermat prime field GF(2^8 + 1), with transforms up to length 256.
+pub type Gf257 = PrimeField<257>;
+
+/// The Fermat prime field GF(2^16 + 1), with transforms up to length
+/// 65536.
+pub type Gf65537 = PrimeField<65_537>;
+
+/// GF(3 × 2^30 + 1), the prime below 2^32 with the longest transforms,
+/// up to length 2^30.
+pub type NttPrime = PrimeField<3_221_225_473>;
+
+const fn is_prime(p: u64) -> bool {
+    if p < 2 {
+        return false;
+    }
+    let mut d = 2;
+    while d * d <= p {
+        if p.is_multiple_of(d) {
+            return false;
+        }
+        d += 1;
+    }
+    true
+}
+
+impl<const P: u64> PrimeField<P> {
+    // floor(2^64 / P), evaluated where P is checked
+    const BARRETT: u64 = {
+        assert!(P < 1 << 32, "the modulus must be below 2^32");
+        assert!(is_prime(P), "the modulus must be prime");
+        ((1u128 << 64) / P as u128) as u64
+    };
+
+    /// `v` modulo `P`.
+    pub fn new(v: u64) -> Self {
+        PrimeField(Self::reduce(v))
+    }
+
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn zero() -> Self {
+        PrimeField(0)
+    }
+
+    pub fn one() -> Self {
+        PrimeField::new(1)
+    }
+
+    // x mod P for x < 2^64: the Barrett quotient is at most one short
+    fn reduce(x: u64) -> u64 {
+        let q = ((x as u128 * Self::BARRETT as u128) >> 64) as u64;
+        let r = x - q * P;
+        if r >= P {
+            r - P
+        } else {
+            r
+        }
+    }
+
+    pub fn pow(self, n: u64) -> Self {
+        let (mut base, mut result) = (self, PrimeField::one());
+        let mut n = n;
+        while n != 0 {
+            if n & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            n >>= 1;
+        }
+        result
+    }
+
+    /// The inverse as `self^(P - 2)`, panicking on zero.
+    pub fn inv(self) -> Self {
+        assert_ne!(self.0, 0, "divide by zero");
+        self.pow(P - 2)
+    }
+
+    /// The smallest generator of the multiplicative group.
+    pub fn primitive_root() -> Self {
+        let factors = prime_factors(P - 1);
+        (1..P)
+            .map(PrimeField::new)
+            .find(|g| factors.iter().all(|&q| g.pow((P - 1) / q) != Self::one()))
+            .expect("the multiplicative group of a field is cyclic")
+    }
+
+    /// A primitive `n`-th root of unity, or `None` unless `n` divides
+    /// `P - 1`.
+    pub fn root_of_unity(n: u64) -> Option<Self> {
+        if n == 0 || !(P - 1).is_multiple_of(n) {
+            return None;
+        }
+        Some(Self::primitive_root().pow((P - 1) / n))
+    }
+}
+
+/// Evaluates the polynomial with coefficients `values` at the powers
+/// `ω^0, .., ω^(n-1)` of the root of unity `ω` of order `n`, in place.
+///
+/// `n = values.len()` must be a power of two dividing `P - 1`; empty and
+/// single-value slices are left as they are.
+///
+/// # Panics
+///
+/// If `n > 1` is not a power of two, or does not divide `P - 1`.
+pub fn ntt<const P: u64>(values: &mut [PrimeField<P>]) {
+    if values.len() <= 1 {
+        return;
+    }
+    let n = values.len() as u64;
+    transform(values, root(n));
+}
+
+/// The inverse of [`ntt`]: interpolates the values at `ω^0, .., ω^(n-1)`
+/// into coefficients, in place.
+///
+/// # Panics
+///
+/// As [`ntt`], if `n > 1` is not a power of two dividing `P - 1`.
+pub fn intt<const P: u64>(values: &mut [PrimeField<P>]) {
+    if values.len() <= 1 {
+        return;
+    }
+    let n = values.len() as u64;
+    transform(values, root(n).inv());
+    let scale = PrimeField::new(n).inv();
+    values.iter_mut().for_each(|v| *v *= scale);
+}
+
+/// The cyclic convolution `c_k = Σ a_i b_j` over `i + j = k mod n`, by
+/// pointwise products of transforms.
+///
+/// Both must have the same length `n`, a power of two dividing `P - 1`, or
+/// be empty, which gives an empty result.
+pub fn cyclic_convolution<const P: u64>(
+    a: &[PrimeField<P>],
+    b: &[PrimeField<P>],
+) -> Vec<PrimeField<P>> {
+    assert_eq!(a.len(), b.len(), "lengths differ");
+    if a.is_empty() {
+        return Vec::new();
+    }
+    let (mut fa, mut fb) = (a.to_vec(), b.to_vec());
+    ntt(&mut fa);
+    ntt(&mut fb);
+    fa.iter_mut().zip(&fb).for_each(|(x, &y)| *x *= y);
+    intt(&mut fa);
+    fa
+}
+
+/// The coefficients of the product of the polynomials `a` and `b`, of
+/// length `a.len() + b.len() - 1`, by a cyclic convolution long enough not
+/// to wrap around.
+///
+/// Panics if that length, rounded up to a power of two, does not divide
+/// `P - 1`.
+pub fn convolution<const P: u64>(a: &[PrimeField<P>], b: &[PrimeField<P>]) -> Vec<PrimeField<P>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    let pad = |v: &[PrimeField<P>]| {
+        let mut padded = v.to_vec();
+        padded.resize(n, PrimeField::zero());
+        padded
+    };
+    let mut c = cyclic_convolution(&pad(a), &pad(b));
+    c.truncate(len);
+    c
+}
+
+fn root<const P: u64>(n: u64) -> PrimeField<P> {
+    assert!(n.is_power_of_two(), "length must be a power of two");
+    PrimeField::root_of_unity(n).expect("length must divide P - 1")
+}
+
+// Iterative radix-2 Cooley-Tukey on bit-reversed input.
+fn transform<const P: u64>(values: &mut [PrimeField<P>], omega: PrimeField<P>) {
+    let n = values.len();
+    if n == 1 {
+        return;
+    }
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+    let mut half = 1;
+    while half < n {
+        let step = omega.pow((n / (2 * half)) as u64);
+        for block in (0..n).step_by(2 * half) {
+            let mut w = PrimeField::one();
+            for i in block..block + half {
+                let t = values[i + half] * w;
+                values[i + half] = values[i] - t;
+                values[i] += t;
+                w *= step;
+            }
+        }
+        half *= 2;
+    }
+}
+
+impl<const P: u64> Add for PrimeField<P> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let sum = self.0 + rhs.0;
+        PrimeField(if sum >= P { sum - P } else { sum })
+    }
+}
+
+impl<const P: u64> Sub for PrimeField<P> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl<const P: u64> Neg for PrimeField<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        PrimeField(if self.0 == 0 { 0 } else { P - self.0 })
+    }
+}
+
+impl<const P: u64> Mul for PrimeField<P> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        PrimeField(Self::reduce(self.0 * rhs.0))
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl<const P: u64> Div for PrimeField<P> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inv()
+    }
+}
+
+impl<const P: u64> AddAssign for PrimeField<P> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const P: u64> SubAssign for PrimeField<P> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const P: u64> MulAssign for PrimeField<P> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const P: u64> DivAssign for PrimeField<P> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<const P: u64> fmt::Display for PrimeField<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} mod {}", self.0, P)
+    }
+}
+
+impl<const P: u64> From<u64> for PrimeField<P> {
+    fn from(v: u64) -> Self {
+        PrimeField::new(v)
+    }
+}
+
+impl<const P: u64> From<PrimeField<P>> for u64 {
+    fn from(v: PrimeField<P>) -> u64 {
+        v.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type F257 = PrimeField<257>;
+    type F65537 = PrimeField<65537>;
+
+    #[test]
+    fn test_arithmetic() {
+        for a in 0..257u64 {
+            for b in 0..257u64 {
+                let (x, y) = (F257::new(a), F257::new(b));
+                assert_eq!((x + y).value(), (a + b) % 257);
+                assert_eq!((x - y).value(), (a + 257 - b) % 257);
+                assert_eq!((x * y).value(), a * b % 257);
+                if b != 0 {
+                    assert_eq!(x / y * y, x);
+                }
+            }
+        }
+        // the largest 32-bit prime
+        type Big = PrimeField<4_294_967_291>;
+        let (a, b) = (4_294_967_290u64, 4_000_000_007u64);
+        let product = (a as u128 * b as u128 % 4_294_967_291) as u64;
+        assert_eq!((Big::new(a) * Big::new(b)).value(), product);
+        assert_eq!(Big::new(u64::MAX).value(), u64::MAX % 4_294_967_291);
+        assert_eq!(F257::new(5).to_string(), "5 mod 257");
+    }
+
+    #[test]
+    fn test_roots() {
+        assert_eq!(F257::primitive_root(), F257::new(3));
+        assert_eq!(F65537::primitive_root(), F65537::new(3));
+        let w = F65537::root_of_unity(1 << 16).unwrap();
+        assert_eq!(w.pow(1 << 16), F65537::one());
+        assert_ne!(w.pow(1 << 15), F65537::one());
+        assert_eq!(F257::root_of_unity(3), None);
+        assert_eq!(PrimeField::<7>::root_of_unity(3).unwrap().pow(3).value(), 1);
+    }
+
+    #[test]
+    fn test_ntt() {
+        // cyclic convolution by pointwise products
+        let a: Vec<F65537> = (0..16).map(|i| F65537::new(i * i + 1)).collect();
+        let b: Vec<F65537> = (0..16).map(|i| F65537::new(3 * i + 7)).collect();
+        let mut expected = vec![F65537::zero(); 16];
+        for i in 0..16 {
+            for j in 0..16 {
+                expected[(i + j) % 16] += a[i] * b[j];
+            }
+        }
+        let (mut fa, mut fb) = (a.clone(), b.clone());
+        ntt(&mut fa);
+        ntt(&mut fb);
+        // the first value is the sum of the coefficients
+        assert_eq!(fa[0], a.iter().fold(F65537::zero(), |s, &v| s + v));
+        let mut c: Vec<F65537> = fa.iter().zip(&fb).map(|(&x, &y)| x * y).collect();
+        intt(&mut c);
+        assert_eq!(c, expected);
+
+        // GF(257) takes every power of two up to 256
+        let data: Vec<F257> = (0..256).map(|i| F257::new(i * 7 + 3)).collect();
+        let mut v = data.clone();
+        ntt(&mut v);
+        let w = F257::root_of_unity(256).unwrap();
+        let at = |x: F257| data.iter().rev().fold(F257::zero(), |acc, &c| acc * x + c);
+        assert_eq!(v[5], at(w.pow(5)));
+        intt(&mut v);
+        assert_eq!(v, data);
+        let mut single = [F257::new(9)];
+        ntt(&mut single);
+        assert_eq!(single, [F257::new(9)]);
+        intt(&mut single);
+        assert_eq!(single, [F257::new(9)]);
+        let mut empty: [F257; 0] = [];
+        ntt(&mut empty);
+        intt(&mut empty);
+    }
+
+    #[test]
+    fn test_convolution() {
+        fn schoolbook<const P: u64>(
+            a: &[PrimeField<P>],
+            b: &[PrimeField<P>],
+        ) -> Vec<PrimeField<P>> {
+            let mut c = vec![PrimeField::zero(); a.len() + b.len() - 1];
+            for (i, &x) in a.iter().enumerate() {
+                for (j, &y) in b.iter().enumerate() {
+                    c[i + j] += x * y;
+                }
+            }
+            c
+        }
+
+        let a: Vec<NttPrime> = (0..100).map(|i| NttPrime::new(i * i * 977 + 13)).collect();
+        let b: Vec<NttPrime> = (0..37).map(|i| NttPrime::new(u64::MAX - i)).collect();
+        assert_eq!(convolution(&a, &b), schoolbook(&a, &b));
+        assert_eq!(convolution(&a, &[]), []);
+        assert_eq!(convolution::<257>(&[], &[]), []);
+        assert_eq!(cyclic_convolution::<257>(&[], &[]), []);
+        assert_eq!(
+            NttPrime::root_of_unity(1 << 30)
+                .unwrap()
+                .pow(1 << 29)
+                .value(),
+            3 << 30
+        );
+
+        let a: Vec<Gf65537> = (0..300).map(|i| Gf65537::new(i * 31)).collect();
+        assert_eq!(convolution(&a, &[Gf65537::one()]), a);
+        assert_eq!(convolution(&a, &a), schoolbook(&a, &a));
+        assert_eq!(convolution::<257>(&[], &[Gf257::one()]), []);
+
+        // x^n = 1 folds the product over
+        let a: Vec<Gf257> = (0..8).map(|i| Gf257::new(i + 1)).collect();
+        let b: Vec<Gf257> = (0..8).map(|i| Gf257::new(2 * i)).collect();
+        let linear = schoolbook(&a, &b);
+        let mut folded = linear[..8].to_vec();
+        folded[..7]
+            .iter_mut()
+            .zip(&linear[8..])
+            .for_each(|(x, &y)| *x += y);
+        assert_eq!(cyclic_convolution(&a, &b), folded);
+    }
+}
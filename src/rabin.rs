@@ -0,0 +1,301 @@
+//! Rabin fingerprints and the rolling hash of content-defined chunking.
+//!
+//! The fingerprint of a message is its polynomial over GF(2), first byte
+//! and most significant bit as the highest-degree coefficient, reduced
+//! modulo a polynomial `p` of degree `k`: an element of GF(2^k) when `p`
+//! is irreducible, which [`is_irreducible`] checks. Rabin's scheme draws
+//! `p` at random, which bounds the collision probability of two distinct
+//! `n`-bit messages by about `n / 2^k`.
+//!
+//! Leading zero bytes leave the fingerprint unchanged, so a window of `w`
+//! bytes slides in O(1): push the new byte with one table lookup, and
+//! cancel the byte leaving the window, worth `b x^(8w)`, with another.
+//! Like a [`Crc`](crate::crc::Crc), a [`Rabin`] builds its tables in a
+//! `const fn`.
+
+/// The fingerprinting polynomial with its push and pop tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rabin {
+    poly: u64,
+    degree: u8,
+    window: usize,
+    // push[t]: t x^k mod p, for the top byte t shifted out of the register
+    push: [u64; 256],
+    // pop[b]: b x^(8 window) mod p, for the byte b leaving the window
+    pop: [u64; 256],
+}
+
+impl Rabin {
+    /// Builds the tables for `x^degree + poly` and a window of `window`
+    /// bytes, panicking if the degree is not in `8..=64`, `poly` has bits at
+    /// or above it, or the window is empty.
+    pub const fn new(poly: u64, degree: u8, window: usize) -> Rabin {
+        assert!(degree >= 8 && degree <= 64, "bad Rabin degree");
+        assert!(poly & !mask(degree) == 0, "bad Rabin polynomial");
+        assert!(window > 0, "empty Rabin window");
+
+        // x^(k + j) mod p for the bits j of a byte
+        let mut high = [0u64; 8];
+        high[0] = poly;
+        let mut j = 1;
+        while j < 8 {
+            high[j] = mul_x(high[j - 1], poly, degree);
+            j += 1;
+        }
+        let mut push = [0u64; 256];
+        let mut t = 1;
+        while t < 256 {
+            let low = t & (t - 1);
+            // the lowest set bit of t, added to the entry without it
+            push[t] = push[low] ^ high[(t ^ low).trailing_zeros() as usize];
+            t += 1;
+        }
+
+        let mut shifted = [0u64; 8];
+        let mut j = 0;
+        while j < 8 {
+            let mut r = 1u64 << j;
+            let mut i = 0;
+            while i < window {
+                r = push_byte(&push, r, 0, degree);
+                i += 1;
+            }
+            shifted[j] = r;
+            j += 1;
+        }
+        let mut pop = [0u64; 256];
+        let mut b = 1;
+        while b < 256 {
+            let low = b & (b - 1);
+            pop[b] = pop[low] ^ shifted[(b ^ low).trailing_zeros() as usize];
+            b += 1;
+        }
+
+        Rabin {
+            poly,
+            degree,
+            window,
+            push,
+            pop,
+        }
+    }
+
+    /// The polynomial without its `x^degree` term.
+    pub fn poly(&self) -> u64 {
+        self.poly
+    }
+
+    pub fn degree(&self) -> u8 {
+        self.degree
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// The fingerprint of a whole message, ignoring the window.
+    pub fn fingerprint(&self, data: &[u8]) -> u64 {
+        data.iter()
+            .fold(0, |fp, &b| push_byte(&self.push, fp, b, self.degree))
+    }
+
+    /// The fingerprint after appending `b`.
+    pub fn push(&self, fp: u64, b: u8) -> u64 {
+        push_byte(&self.push, fp, b, self.degree)
+    }
+
+    /// The fingerprint after removing `b`, pushed `window` bytes ago, from
+    /// the front.
+    pub fn pop(&self, fp: u64, b: u8) -> u64 {
+        fp ^ self.pop[b as usize]
+    }
+
+    /// Starts a rolling hash over an empty window.
+    pub fn rolling(&self) -> RollingHash<'_> {
+        RollingHash {
+            rabin: self,
+            buf: vec![0; self.window],
+            pos: 0,
+            fp: 0,
+        }
+    }
+}
+
+/// A sliding-window fingerprint, from [`Rabin::rolling`].
+#[derive(Debug, Clone)]
+pub struct RollingHash<'a> {
+    rabin: &'a Rabin,
+    // the window as a ring, oldest byte at pos; zeros before it fills
+    buf: Vec<u8>,
+    pos: usize,
+    fp: u64,
+}
+
+impl RollingHash<'_> {
+    /// Slides the window over `b` and returns the fingerprint of its
+    /// `window` last bytes, or of all of them if there are fewer.
+    pub fn roll(&mut self, b: u8) -> u64 {
+        let out = std::mem::replace(&mut self.buf[self.pos], b);
+        self.pos = (self.pos + 1) % self.buf.len();
+        self.fp = self.rabin.pop(self.rabin.push(self.fp, b), out);
+        self.fp
+    }
+
+    pub fn fingerprint(&self) -> u64 {
+        self.fp
+    }
+
+    /// Empties the window.
+    pub fn reset(&mut self) {
+        self.buf.fill(0);
+        self.pos = 0;
+        self.fp = 0;
+    }
+}
+
+/// Whether `x^degree + poly` is irreducible over GF(2), by Ben-Or's test:
+/// it has no factor of degree `d` for each `d <= degree / 2`, that is
+/// `gcd(p, x^(2^d) - x) = 1`.
+pub fn is_irreducible(poly: u64, degree: u8) -> bool {
+    assert!((1..=64).contains(&degree), "bad degree");
+    let p = 1u128 << degree | poly as u128;
+    let mut x_pow = 2u128; // x^(2^d) mod p
+    for _ in 0..degree / 2 {
+        x_pow = mul_mod(x_pow, x_pow, p, degree);
+        if gcd(p, x_pow ^ 2) != 1 {
+            return false;
+        }
+    }
+    true
+}
+
+const fn mask(degree: u8) -> u64 {
+    if degree == 64 {
+        !0
+    } else {
+        (1 << degree) - 1
+    }
+}
+
+const fn mul_x(v: u64, poly: u64, degree: u8) -> u64 {
+    let carry = v >> (degree - 1) & 1;
+    (v << 1 & mask(degree)) ^ (poly & 0u64.wrapping_sub(carry))
+}
+
+const fn push_byte(push: &[u64; 256], fp: u64, b: u8, degree: u8) -> u64 {
+    let top = (fp >> (degree - 8)) as usize;
+    ((fp << 8 & mask(degree)) | b as u64) ^ push[top]
+}
+
+// The product modulo `p`, of degree `degree`, of two polynomials below it.
+pub(crate) fn mul_mod(a: u128, b: u128, p: u128, degree: u8) -> u128 {
+    let mut product = 0;
+    let mut a = a;
+    for i in 0..degree {
+        if b >> i & 1 == 1 {
+            product ^= a;
+        }
+        a <<= 1;
+        if a >> degree & 1 == 1 {
+            a ^= p;
+        }
+    }
+    product
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let db = 127 - b.leading_zeros();
+        while a != 0 && 127 - a.leading_zeros() >= db {
+            a ^= b << (127 - a.leading_zeros() - db);
+        }
+        std::mem::swap(&mut a, &mut b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // x^64 + x^4 + x^3 + x + 1
+    const POLY_64: u64 = 0x1b;
+    // a random irreducible polynomial of degree 53
+    const POLY_53: u64 = 0x003d_a335_8b4d_c173 ^ 1 << 53;
+
+    #[test]
+    fn test_irreducible() {
+        assert!(is_irreducible(POLY_64, 64));
+        assert!(is_irreducible(POLY_53, 53));
+        // the field polynomials of this crate and of AES
+        assert!(is_irreducible(0x1d, 8));
+        assert!(is_irreducible(0x1b, 8));
+        // x^8 + x^4 + x^3 + x^2 is divisible by x
+        assert!(!is_irreducible(0x1c, 8));
+        // (x^2 + x + 1)^2 = x^4 + x^2 + 1
+        assert!(!is_irreducible(0b101, 4));
+        assert!(is_irreducible(0b11, 4));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        // the remainder of a message of degree below k is the message
+        let r = Rabin::new(POLY_53, 53, 16);
+        assert_eq!(r.fingerprint(&[0x12, 0x34, 0x56]), 0x12_3456);
+        assert_eq!(r.fingerprint(&[0, 0, 0x12, 0x34]), 0x1234);
+
+        // x^64 mod x^64 + 0x1b
+        let r = Rabin::new(POLY_64, 64, 16);
+        assert_eq!(r.fingerprint(&[1, 0, 0, 0, 0, 0, 0, 0, 0]), POLY_64);
+
+        // the CRC-8 polynomial with no init, reflection or xorout is the
+        // remainder of the message times x^8
+        let r = Rabin::new(0x07, 8, 4);
+        assert_eq!(
+            r.fingerprint(b"123456789\0"),
+            crate::crc::CRC_8.checksum(b"123456789")
+        );
+    }
+
+    #[test]
+    fn test_rolling() {
+        let data: Vec<u8> = (0..2000u32).map(|i| (i * 167 + i / 7) as u8).collect();
+        for (poly, degree) in [(POLY_64, 64), (POLY_53, 53), (0x1d, 8)] {
+            for window in [1, 16, 48] {
+                let r = Rabin::new(poly, degree, window);
+                let mut rolling = r.rolling();
+                for (i, &b) in data.iter().enumerate() {
+                    let start = (i + 1).saturating_sub(window);
+                    assert_eq!(rolling.roll(b), r.fingerprint(&data[start..=i]));
+                }
+                rolling.reset();
+                assert_eq!(rolling.roll(data[0]), r.fingerprint(&data[..1]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunking() {
+        // cut where the low bits of the fingerprint are zero; an insertion
+        // only moves the cuts near it
+        static RABIN: Rabin = Rabin::new(POLY_53, 53, 48);
+        let cuts = |data: &[u8]| -> Vec<usize> {
+            let mut rolling = RABIN.rolling();
+            (0..data.len())
+                .filter(|&i| rolling.roll(data[i]) & 0x3f == 0)
+                .map(|i| data.len() - i)
+                .collect()
+        };
+        let data: Vec<u8> = (0..20_000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect();
+        let mut edited = data.clone();
+        edited.splice(100..100, [1, 2, 3]);
+        let (before, after) = (cuts(&data), cuts(&edited));
+        assert!(before.len() > 100);
+        // distances from the end are unchanged past the edit
+        let tail = |c: &[usize]| c.iter().filter(|&&d| d < 19_000).count();
+        assert_eq!(tail(&before), tail(&after));
+        assert_eq!(before[before.len() - 10..], after[after.len() - 10..]);
+    }
+}
@@ -0,0 +1,251 @@
+//! RAID-6 syndromes and double-failure recovery in the Linux kernel's
+//! convention.
+//!
+//! A stripe is `n` data blocks followed by `P`, their XOR, and `Q`, the sum
+//! of `g^i D_i` with `g = 2` in the `0x11d` field, exactly as the kernel's
+//! `raid6_pq` computes them. As there, every function takes the whole stripe
+//! as `n + 2` equally sized blocks, data first, and panics on misuse.
+//!
+//! For triple parity, as in ZFS's raidz3, a third block `R` holds the sum of
+//! `4^i D_i`, and [`recover_pqr`] rebuilds any three lost blocks.
+
+use crate::slice::{add_slice, mul_add_slice, mul_slice, scale_slice};
+use crate::{Galois, GfMatrix};
+
+// The bases of the P, Q and R sums.
+const PQR: [u8; 3] = [1, 2, 4];
+
+fn g_pow(i: usize) -> Galois {
+    Galois::new(2).exp(i as u32)
+}
+
+// Checks the stripe shape, returning the number of data blocks.
+fn check_stripe(blocks: &[&mut [u8]], parity: usize) -> usize {
+    assert!(blocks.len() > parity, "need at least one data block");
+    let n = blocks.len() - parity;
+    assert!(n <= 255, "at most 255 data blocks");
+    let len = blocks[0].len();
+    assert!(
+        blocks.iter().all(|b| b.len() == len),
+        "block length mismatch"
+    );
+    n
+}
+
+// out = Σ c^i D_i over the data blocks, treating those in `skip` as zero.
+fn weighted_sum(data: &[&mut [u8]], c: Galois, skip: &[usize], out: &mut [u8]) {
+    out.fill(0);
+    // Horner's rule from the highest block down
+    for (i, block) in data.iter().enumerate().rev() {
+        scale_slice(c, out);
+        if !skip.contains(&i) {
+            add_slice(block, out);
+        }
+    }
+}
+
+/// Computes `P` and `Q` from the data blocks, like the kernel's
+/// `gen_syndrome`.
+pub fn gen_syndrome(blocks: &mut [&mut [u8]]) {
+    let n = check_stripe(blocks, 2);
+    let (data, parity) = blocks.split_at_mut(n);
+    let [p, q] = parity else { unreachable!() };
+    weighted_sum(data, Galois::identity(), &[], p);
+    weighted_sum(data, Galois::new(2), &[], q);
+}
+
+/// Rebuilds data blocks `faila` and `failb` from the others and `P` and
+/// `Q`, like the kernel's `raid6_2data_recov`.
+pub fn recover_2data(blocks: &mut [&mut [u8]], faila: usize, failb: usize) {
+    let n = check_stripe(blocks, 2);
+    assert!(
+        faila < n && failb < n && faila != failb,
+        "bad failed blocks"
+    );
+    let (a, b) = (faila.min(failb), faila.max(failb));
+    let len = blocks[0].len();
+
+    // dp = Da + Db and dq = g^a Da + g^b Db
+    let (data, parity) = blocks.split_at_mut(n);
+    let mut dp = vec![0u8; len];
+    let mut dq = vec![0u8; len];
+    weighted_sum(data, Galois::identity(), &[a, b], &mut dp);
+    weighted_sum(data, Galois::new(2), &[a, b], &mut dq);
+    add_slice(parity[0], &mut dp);
+    add_slice(parity[1], &mut dq);
+
+    // Db = dp / (g^(b-a) + 1) + dq / (g^a + g^b), Da = Db + dp
+    let pmul = (g_pow(b - a) + Galois::identity()).inv();
+    let qmul = (g_pow(a) + g_pow(b)).inv();
+    mul_slice(pmul, &dp, data[b]);
+    mul_add_slice(qmul, &dq, data[b]);
+    add_slice(data[b], &mut dp);
+    data[a].copy_from_slice(&dp);
+}
+
+/// Rebuilds data block `faila` and `P` from the others and `Q`, like the
+/// kernel's `raid6_datap_recov`.
+pub fn recover_datap(blocks: &mut [&mut [u8]], faila: usize) {
+    let n = check_stripe(blocks, 2);
+    assert!(faila < n, "bad failed block");
+    let len = blocks[0].len();
+
+    // Da = (Q + Σ_{i≠a} g^i D_i) / g^a
+    let (data, parity) = blocks.split_at_mut(n);
+    let mut dq = vec![0u8; len];
+    weighted_sum(data, Galois::new(2), &[faila], &mut dq);
+    add_slice(parity[1], &mut dq);
+    mul_slice(g_pow(faila).inv(), &dq, data[faila]);
+    weighted_sum(data, Galois::identity(), &[], parity[0]);
+}
+
+/// Rebuilds data block `faila` and `Q` from the others and `P`.
+pub fn recover_dataq(blocks: &mut [&mut [u8]], faila: usize) {
+    let n = check_stripe(blocks, 2);
+    assert!(faila < n, "bad failed block");
+
+    // Da = P + Σ_{i≠a} D_i
+    let (data, parity) = blocks.split_at_mut(n);
+    let mut dp = vec![0u8; parity[0].len()];
+    weighted_sum(data, Galois::identity(), &[faila], &mut dp);
+    add_slice(parity[0], &mut dp);
+    data[faila].copy_from_slice(&dp);
+    weighted_sum(data, Galois::new(2), &[], parity[1]);
+}
+
+/// Computes `P`, `Q` and `R` from the data blocks of an `n + 3` block
+/// stripe.
+pub fn gen_syndrome_pqr(blocks: &mut [&mut [u8]]) {
+    let n = check_stripe(blocks, 3);
+    let (data, parity) = blocks.split_at_mut(n);
+    for (c, out) in PQR.iter().zip(parity) {
+        weighted_sum(data, Galois::new(*c), &[], out);
+    }
+}
+
+/// Rebuilds the blocks at the (at most three) indices in `failed` of an
+/// `n + 3` block stripe from the others.
+///
+/// Lost data blocks are solved for with as many surviving parities; the
+/// system is a Vandermonde matrix in `g^a`, hence always solvable.
+pub fn recover_pqr(blocks: &mut [&mut [u8]], failed: &[usize]) {
+    let n = check_stripe(blocks, 3);
+    assert!(failed.len() <= 3, "at most three failures");
+    assert!(failed.iter().all(|f| *f < n + 3), "bad failed block");
+    let mut failed = failed.to_vec();
+    failed.sort_unstable();
+    failed.dedup();
+    let len = blocks[0].len();
+
+    let lost: Vec<usize> = failed.iter().copied().filter(|f| *f < n).collect();
+    let (data, parity) = blocks.split_at_mut(n);
+    if !lost.is_empty() {
+        let rows: Vec<usize> = (0..3).filter(|r| !failed.contains(&(n + r))).collect();
+        let rows = &rows[..lost.len()];
+
+        // s_r = parity_r + Σ_{good i} c_r^i D_i = Σ_{lost a} c_r^a D_a
+        let mut syndromes = vec![vec![0u8; len]; rows.len()];
+        for (r, s) in rows.iter().zip(&mut syndromes) {
+            weighted_sum(data, Galois::new(PQR[*r]), &lost, s);
+            add_slice(parity[*r], s);
+        }
+        let m = GfMatrix::from_fn(rows.len(), lost.len(), |r, c| {
+            Galois::new(PQR[rows[r]]).exp(lost[c] as u32)
+        });
+        let inv = m.invert().expect("vandermonde in distinct points");
+        for (c, a) in lost.iter().enumerate() {
+            data[*a].fill(0);
+            for (r, s) in syndromes.iter().enumerate() {
+                mul_add_slice(inv[(c, r)], s, data[*a]);
+            }
+        }
+    }
+
+    for f in failed.iter().filter(|f| **f >= n) {
+        weighted_sum(data, Galois::new(PQR[f - n]), &[], parity[f - n]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stripe(n: usize, len: usize) -> Vec<Vec<u8>> {
+        let mut blocks: Vec<Vec<u8>> = (0..n)
+            .map(|d| (0..len).map(|i| (i * 29 + d * 113 + 3) as u8).collect())
+            .collect();
+        blocks.extend([vec![0; len], vec![0; len]]);
+        let mut refs: Vec<&mut [u8]> = blocks.iter_mut().map(|b| &mut b[..]).collect();
+        gen_syndrome(&mut refs);
+        blocks
+    }
+
+    #[test]
+    fn test_syndrome() {
+        let blocks = stripe(4, 16);
+        for i in 0..16 {
+            let (mut p, mut q) = (Galois::zero(), Galois::zero());
+            for (d, block) in blocks[..4].iter().enumerate() {
+                p += Galois::new(block[i]);
+                q += g_pow(d) * Galois::new(block[i]);
+            }
+            assert_eq!(blocks[4][i], u8::from(p));
+            assert_eq!(blocks[5][i], u8::from(q));
+        }
+    }
+
+    #[test]
+    fn test_recover() {
+        let n = 6;
+        let good = stripe(n, 32);
+        for a in 0..n {
+            for b in (0..n).filter(|b| *b != a) {
+                let mut blocks = good.clone();
+                blocks[a].fill(0);
+                blocks[b].fill(0xff);
+                let mut refs: Vec<&mut [u8]> = blocks.iter_mut().map(|b| &mut b[..]).collect();
+                recover_2data(&mut refs, a, b);
+                assert_eq!(blocks, good, "{a} {b}");
+            }
+
+            let mut blocks = good.clone();
+            blocks[a].fill(0);
+            blocks[n].fill(0);
+            let mut refs: Vec<&mut [u8]> = blocks.iter_mut().map(|b| &mut b[..]).collect();
+            recover_datap(&mut refs, a);
+            assert_eq!(blocks, good);
+
+            let mut blocks = good.clone();
+            blocks[a].fill(0);
+            blocks[n + 1].fill(0);
+            let mut refs: Vec<&mut [u8]> = blocks.iter_mut().map(|b| &mut b[..]).collect();
+            recover_dataq(&mut refs, a);
+            assert_eq!(blocks, good);
+        }
+    }
+
+    #[test]
+    fn test_triple_parity() {
+        let n = 5;
+        let mut good: Vec<Vec<u8>> = stripe(n, 24)[..n].to_vec();
+        good.extend([vec![0; 24], vec![0; 24], vec![0; 24]]);
+        let mut refs: Vec<&mut [u8]> = good.iter_mut().map(|b| &mut b[..]).collect();
+        gen_syndrome_pqr(&mut refs);
+        // P and Q match the RAID-6 ones
+        assert_eq!(good[n..n + 2], stripe(n, 24)[n..]);
+
+        for a in 0..n + 3 {
+            for b in a + 1..n + 3 {
+                for c in b + 1..n + 3 {
+                    let mut blocks = good.clone();
+                    for f in [a, b, c] {
+                        blocks[f].fill(0x5a);
+                    }
+                    let mut refs: Vec<&mut [u8]> = blocks.iter_mut().map(|b| &mut b[..]).collect();
+                    recover_pqr(&mut refs, &[c, a, b]);
+                    assert_eq!(blocks, good, "{a} {b} {c}");
+                }
+            }
+        }
+    }
+}
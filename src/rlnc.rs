@@ -0,0 +1,313 @@
+//! Random linear network coding over GF(256).
+//!
+//! Data is cut into generations of `g` symbols, and every [`Packet`] is a
+//! random linear combination of the symbols of one generation that carries
+//! its `g` coefficients in the header. Unlike a fountain, any node along the
+//! way can mix the packets it holds into new ones with
+//! [`RlncDecoder::recode`] without decoding first, and the receiver decodes
+//! each generation as soon as it has `g` independent packets for it.
+//!
+//! For streams, [`WindowEncoder`] instead codes over a sliding window of the
+//! symbols the receiver has not yet acknowledged, and [`WindowDecoder`]
+//! releases each symbol as soon as it is decodable, which keeps the delay
+//! of a repair to a few packets.
+
+use std::fmt;
+
+use crate::fountain::{random_bytes, FountainDecoder, SplitMix};
+use crate::slice::mul_add_slice;
+use crate::Galois;
+
+mod window;
+pub use window::{WindowDecoder, WindowEncoder, WindowPacket};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RlncError {
+    /// The packet belongs to a generation the decoder does not know.
+    UnknownGeneration,
+    /// The packet's coefficients or data do not have the expected length.
+    WrongLength,
+    /// The encoder's window is full of unacknowledged symbols.
+    WindowFull,
+}
+
+impl fmt::Display for RlncError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RlncError::UnknownGeneration => write!(f, "unknown generation"),
+            RlncError::WrongLength => write!(f, "wrong packet length"),
+            RlncError::WindowFull => write!(f, "window full"),
+        }
+    }
+}
+
+impl std::error::Error for RlncError {}
+
+/// A coded packet: `data = Σ coefficients[i] s_i` over the symbols `s_i` of
+/// `generation`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub generation: u32,
+    pub coefficients: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+impl Packet {
+    /// The packet as sent: the generation as four big-endian bytes, the
+    /// coefficients, then the data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(4 + self.coefficients.len() + self.data.len());
+        out.extend_from_slice(&self.generation.to_be_bytes());
+        out.extend_from_slice(&self.coefficients);
+        out.extend_from_slice(&self.data);
+        out
+    }
+
+    /// Parses a packet written by [`to_bytes`](Packet::to_bytes) for
+    /// generations of `generation_size` symbols.
+    pub fn from_bytes(bytes: &[u8], generation_size: usize) -> Option<Self> {
+        let (generation, rest) = bytes.split_first_chunk::<4>()?;
+        if rest.len() < generation_size {
+            return None;
+        }
+        let (coefficients, data) = rest.split_at(generation_size);
+        Some(Packet {
+            generation: u32::from_be_bytes(*generation),
+            coefficients: coefficients.to_vec(),
+            data: data.to_vec(),
+        })
+    }
+}
+
+/// Emits random packets for the generations of a piece of data.
+#[derive(Debug, Clone)]
+pub struct RlncEncoder {
+    generation_size: usize,
+    symbol_len: usize,
+    generations: Vec<Vec<Vec<u8>>>,
+    rng: SplitMix,
+}
+
+impl RlncEncoder {
+    /// Cuts `data` into symbols of `symbol_len` bytes and those into
+    /// generations of `generation_size`, zero-padding the last generation.
+    pub fn new(data: &[u8], generation_size: usize, symbol_len: usize) -> Self {
+        assert!(generation_size > 0, "generation size must be positive");
+        assert!(symbol_len > 0, "symbol length must be positive");
+        assert!(!data.is_empty(), "no data to encode");
+        let generations = data
+            .chunks(generation_size * symbol_len)
+            .map(|chunk| {
+                let mut chunk = chunk.to_vec();
+                chunk.resize(generation_size * symbol_len, 0);
+                chunk.chunks(symbol_len).map(<[u8]>::to_vec).collect()
+            })
+            .collect();
+        RlncEncoder {
+            generation_size,
+            symbol_len,
+            generations,
+            rng: SplitMix(0),
+        }
+    }
+
+    /// Seeds the coefficient generator, so that separate sources do not send
+    /// the same combinations.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = SplitMix(seed);
+        self
+    }
+
+    pub fn generation_size(&self) -> usize {
+        self.generation_size
+    }
+
+    pub fn symbol_len(&self) -> usize {
+        self.symbol_len
+    }
+
+    pub fn generation_count(&self) -> usize {
+        self.generations.len()
+    }
+
+    /// A fresh random packet for `generation`.
+    pub fn packet(&mut self, generation: usize) -> Packet {
+        let coefficients = random_bytes(&mut self.rng, self.generation_size);
+        let mut data = vec![0u8; self.symbol_len];
+        for (c, s) in coefficients.iter().zip(&self.generations[generation]) {
+            mul_add_slice(Galois(*c), s, &mut data);
+        }
+        Packet {
+            generation: generation as u32,
+            coefficients,
+            data,
+        }
+    }
+}
+
+/// Collects packets for every generation, decoding each on the fly.
+///
+/// Each packet is eliminated against the ones kept for its generation as it
+/// arrives, so [`rank`](RlncDecoder::rank) always tells how many more are
+/// needed. The same buffer lets an intermediate node recode.
+#[derive(Debug, Clone)]
+pub struct RlncDecoder {
+    generation_size: usize,
+    symbol_len: usize,
+    generations: Vec<FountainDecoder>,
+    rng: SplitMix,
+}
+
+impl RlncDecoder {
+    pub fn new(generation_count: usize, generation_size: usize, symbol_len: usize) -> Self {
+        assert!(generation_size > 0, "generation size must be positive");
+        RlncDecoder {
+            generation_size,
+            symbol_len,
+            generations: vec![FountainDecoder::new(generation_size, symbol_len); generation_count],
+            rng: SplitMix(0),
+        }
+    }
+
+    /// Seeds the coefficients [`recode`](RlncDecoder::recode) mixes with.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = SplitMix(seed);
+        self
+    }
+
+    /// Adds a packet, returning whether it was innovative, i.e. raised the
+    /// rank of its generation.
+    pub fn push(&mut self, packet: &Packet) -> Result<bool, RlncError> {
+        let generation = self
+            .generations
+            .get_mut(packet.generation as usize)
+            .ok_or(RlncError::UnknownGeneration)?;
+        if packet.coefficients.len() != self.generation_size || packet.data.len() != self.symbol_len
+        {
+            return Err(RlncError::WrongLength);
+        }
+        if generation.is_complete() {
+            return Ok(false);
+        }
+        Ok(generation.push_row(packet.coefficients.clone(), packet.data.clone()))
+    }
+
+    /// The number of independent packets held for `generation`.
+    pub fn rank(&self, generation: usize) -> usize {
+        self.generations[generation].rank()
+    }
+
+    /// The number of generations that can be decoded.
+    pub fn completed(&self) -> usize {
+        self.generations.iter().filter(|g| g.is_complete()).count()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed() == self.generations.len()
+    }
+
+    /// A random combination of the packets held for `generation`, or `None`
+    /// if there are none.
+    ///
+    /// Unless what a receiver holds already spans everything this node has,
+    /// the packet is innovative to it with probability at least `1 - 1/256`.
+    pub fn recode(&mut self, generation: usize) -> Option<Packet> {
+        let held = &self.generations[generation];
+        if held.rank() == 0 {
+            return None;
+        }
+        let weights = random_bytes(&mut self.rng, held.rank());
+        let mut coefficients = vec![0u8; self.generation_size];
+        let mut data = vec![0u8; self.symbol_len];
+        for (w, (c, d)) in weights.into_iter().zip(held.rows()) {
+            mul_add_slice(Galois(w), c, &mut coefficients);
+            mul_add_slice(Galois(w), d, &mut data);
+        }
+        Some(Packet {
+            generation: generation as u32,
+            coefficients,
+            data,
+        })
+    }
+
+    /// The decoded symbols of `generation`, once its rank is full.
+    pub fn generation(&self, generation: usize) -> Option<Vec<u8>> {
+        self.generations[generation].data()
+    }
+
+    /// All the data, padding included, once every generation is decoded.
+    pub fn data(&self) -> Option<Vec<u8>> {
+        self.generations
+            .iter()
+            .map(FountainDecoder::data)
+            .collect::<Option<Vec<_>>>()
+            .map(|g| g.concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let data: Vec<u8> = (0..1000).map(|i| (i * 13 + 1) as u8).collect();
+        let mut encoder = RlncEncoder::new(&data, 8, 32).with_seed(1);
+        assert_eq!(encoder.generation_count(), 4);
+        let mut decoder = RlncDecoder::new(4, 8, 32);
+
+        for round in 0.. {
+            for g in 0..4 {
+                let packet = Packet::from_bytes(&encoder.packet(g).to_bytes(), 8).unwrap();
+                decoder.push(&packet).unwrap();
+            }
+            assert!(round < 12);
+            if decoder.is_complete() {
+                break;
+            }
+        }
+        assert_eq!(decoder.data().unwrap()[..1000], data[..]);
+
+        let bad = Packet {
+            generation: 4,
+            coefficients: vec![0; 8],
+            data: vec![0; 32],
+        };
+        assert_eq!(decoder.push(&bad), Err(RlncError::UnknownGeneration));
+        let bad = Packet {
+            generation: 0,
+            ..bad
+        };
+        assert_eq!(decoder.push(&bad), Ok(false));
+        let short = Packet {
+            data: vec![0; 31],
+            ..bad
+        };
+        assert_eq!(decoder.push(&short), Err(RlncError::WrongLength));
+    }
+
+    #[test]
+    fn test_relay_recodes() {
+        // source -> relay -> sink, with the relay holding only half of the
+        // generation from each of two sources
+        let data: Vec<u8> = (0..64).map(|i| i as u8 * 3).collect();
+        let mut a = RlncEncoder::new(&data, 8, 8).with_seed(2);
+        let mut b = RlncEncoder::new(&data, 8, 8).with_seed(3);
+        let mut relay = RlncDecoder::new(1, 8, 8).with_seed(4);
+        let mut sink = RlncDecoder::new(1, 8, 8);
+        assert_eq!(relay.recode(0), None);
+
+        for _ in 0..4 {
+            relay.push(&a.packet(0)).unwrap();
+            relay.push(&b.packet(0)).unwrap();
+        }
+        assert_eq!(relay.rank(0), 8);
+        let mut sent = 0;
+        while !sink.is_complete() {
+            sink.push(&relay.recode(0).unwrap()).unwrap();
+            sent += 1;
+        }
+        assert!(sent < 12, "{sent} packets");
+        assert_eq!(sink.generation(0).unwrap(), data);
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use super::RlncError;
+use crate::fountain::{random_bytes, SplitMix};
+use crate::slice::{mul_add_slice, scale_slice};
+use crate::Galois;
+
+/// A coded packet over the window of source symbols starting at sequence
+/// number `start`: `data = Σ coefficients[i] s_(start + i)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowPacket {
+    pub start: u64,
+    pub coefficients: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+impl WindowPacket {
+    /// The packet as sent: `start` as eight big-endian bytes, the number of
+    /// coefficients as two, the coefficients, then the data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(10 + self.coefficients.len() + self.data.len());
+        out.extend_from_slice(&self.start.to_be_bytes());
+        out.extend_from_slice(&(self.coefficients.len() as u16).to_be_bytes());
+        out.extend_from_slice(&self.coefficients);
+        out.extend_from_slice(&self.data);
+        out
+    }
+
+    /// Parses a packet written by [`to_bytes`](WindowPacket::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (start, rest) = bytes.split_first_chunk::<8>()?;
+        let (count, rest) = rest.split_first_chunk::<2>()?;
+        let count = u16::from_be_bytes(*count) as usize;
+        if rest.len() < count {
+            return None;
+        }
+        let (coefficients, data) = rest.split_at(count);
+        Some(WindowPacket {
+            start: u64::from_be_bytes(*start),
+            coefficients: coefficients.to_vec(),
+            data: data.to_vec(),
+        })
+    }
+}
+
+/// Codes a stream of source symbols over a window of the ones not yet
+/// acknowledged, so that a lost symbol is repaired by the next packets
+/// rather than after a whole generation.
+#[derive(Debug, Clone)]
+pub struct WindowEncoder {
+    window: usize,
+    symbol_len: usize,
+    // the sequence number of symbols[0]
+    start: u64,
+    symbols: VecDeque<Vec<u8>>,
+    rng: SplitMix,
+}
+
+impl WindowEncoder {
+    /// An encoder holding at most `window` unacknowledged symbols.
+    pub fn new(window: usize, symbol_len: usize) -> Self {
+        assert!(
+            (1..=u16::MAX as usize).contains(&window),
+            "window must be between 1 and 65535"
+        );
+        assert!(symbol_len > 0, "symbol length must be positive");
+        WindowEncoder {
+            window,
+            symbol_len,
+            start: 0,
+            symbols: VecDeque::new(),
+            rng: SplitMix(0),
+        }
+    }
+
+    /// Seeds the coefficient generator.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = SplitMix(seed);
+        self
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// The number of symbols sent but not yet acknowledged.
+    pub fn in_flight(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Appends a source symbol to the window, returning its sequence number,
+    /// or [`RlncError::WindowFull`] until an acknowledgement makes room.
+    pub fn push(&mut self, symbol: &[u8]) -> Result<u64, RlncError> {
+        assert_eq!(symbol.len(), self.symbol_len, "symbol length mismatch");
+        if self.symbols.len() == self.window {
+            return Err(RlncError::WindowFull);
+        }
+        self.symbols.push_back(symbol.to_vec());
+        Ok(self.start + self.symbols.len() as u64 - 1)
+    }
+
+    /// Drops the symbols before `next`, which the receiver reports as
+    /// decoded with [`WindowDecoder::next`].
+    pub fn ack(&mut self, next: u64) {
+        while self.start < next && self.symbols.pop_front().is_some() {
+            self.start += 1;
+        }
+    }
+
+    /// A random combination of the symbols in the window, or `None` if it is
+    /// empty.
+    pub fn packet(&mut self) -> Option<WindowPacket> {
+        if self.symbols.is_empty() {
+            return None;
+        }
+        let coefficients = random_bytes(&mut self.rng, self.symbols.len());
+        let mut data = vec![0u8; self.symbol_len];
+        for (c, s) in coefficients.iter().zip(&self.symbols) {
+            mul_add_slice(Galois(*c), s, &mut data);
+        }
+        Some(WindowPacket {
+            start: self.start,
+            coefficients,
+            data,
+        })
+    }
+}
+
+/// Decodes a [`WindowEncoder`]'s stream, releasing source symbols in order
+/// as soon as they can be solved for.
+///
+/// Decoded symbols are kept until packets stop covering them, to eliminate
+/// them from late packets whose window still includes them.
+#[derive(Debug, Clone)]
+pub struct WindowDecoder {
+    symbol_len: usize,
+    // decoded symbols from sequence number `history_start` up to `next`
+    history_start: u64,
+    history: VecDeque<Vec<u8>>,
+    // rows[p] has its first nonzero coefficient, 1, at sequence number p
+    rows: BTreeMap<u64, (Vec<u8>, Vec<u8>)>,
+    ready: VecDeque<Vec<u8>>,
+}
+
+impl WindowDecoder {
+    pub fn new(symbol_len: usize) -> Self {
+        WindowDecoder {
+            symbol_len,
+            history_start: 0,
+            history: VecDeque::new(),
+            rows: BTreeMap::new(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// The sequence number of the first symbol not yet decoded, which is
+    /// what to send back as feedback.
+    pub fn next(&self) -> u64 {
+        self.history_start + self.history.len() as u64
+    }
+
+    /// The number of independent packets held for symbols not yet decoded.
+    pub fn rank(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Adds a packet, returning whether it was innovative.
+    ///
+    /// A packet whose window starts before the decoded symbols still kept is
+    /// too late to be of use and is ignored.
+    pub fn push(&mut self, packet: &WindowPacket) -> Result<bool, RlncError> {
+        if packet.data.len() != self.symbol_len || packet.coefficients.len() > u16::MAX as usize {
+            return Err(RlncError::WrongLength);
+        }
+        if packet.start < self.history_start {
+            return Ok(false);
+        }
+        // nothing before this packet's window will be needed again
+        while self.history_start < packet.start && self.history.pop_front().is_some() {
+            self.history_start += 1;
+        }
+
+        let mut first = packet.start;
+        let mut coeffs = packet.coefficients.clone();
+        let mut data = packet.data.clone();
+        while first < self.next() && !coeffs.is_empty() {
+            let known = &self.history[(first - self.history_start) as usize];
+            mul_add_slice(Galois(coeffs[0]), known, &mut data);
+            coeffs.remove(0);
+            first += 1;
+        }
+
+        loop {
+            let lead = coeffs.iter().take_while(|c| **c == 0).count();
+            if lead == coeffs.len() {
+                return Ok(false);
+            }
+            coeffs.drain(..lead);
+            first += lead as u64;
+            let c = Galois(coeffs[0]);
+            match self.rows.get(&first) {
+                Some((row, row_data)) => {
+                    if row.len() > coeffs.len() {
+                        coeffs.resize(row.len(), 0);
+                    }
+                    mul_add_slice(c, row, &mut coeffs[..row.len()]);
+                    mul_add_slice(c, row_data, &mut data);
+                }
+                None => {
+                    let inv = c.inv();
+                    scale_slice(inv, &mut coeffs);
+                    scale_slice(inv, &mut data);
+                    self.rows.insert(first, (coeffs, data));
+                    self.release();
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    /// The next decoded source symbol, in sequence order.
+    pub fn pop(&mut self) -> Option<Vec<u8>> {
+        self.ready.pop_front()
+    }
+
+    // Solves every run of rows from `next` on that only involves itself.
+    fn release(&mut self) {
+        let next = self.next();
+        let (mut end, mut reach) = (next, next);
+        while let Some((row, _)) = self.rows.get(&end) {
+            reach = reach.max(end + row.len() as u64);
+            end += 1;
+            if end < reach {
+                continue;
+            }
+            // back-substitute from the last row of the run up
+            let mut solved: Vec<Vec<u8>> = Vec::new();
+            for p in (next..end).rev() {
+                let (row, mut data) = self.rows.remove(&p).unwrap();
+                for (i, c) in row.iter().enumerate().skip(1) {
+                    mul_add_slice(Galois(*c), &solved[solved.len() - i], &mut data);
+                }
+                solved.push(data);
+            }
+            for s in solved.into_iter().rev() {
+                self.ready.push_back(s.clone());
+                self.history.push_back(s);
+            }
+            return self.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lossy_stream() {
+        let source: Vec<Vec<u8>> = (0..200u32)
+            .map(|s| (0..12).map(|i| (s * 7 + i * 31) as u8).collect())
+            .collect();
+        let mut encoder = WindowEncoder::new(8, 12).with_seed(9);
+        let mut decoder = WindowDecoder::new(12);
+        let mut out = Vec::new();
+
+        // one packet per tick, every third one lost, with feedback from the
+        // receiver after each tick
+        let mut pending = source.iter();
+        let mut next = pending.next();
+        for tick in 0..1000 {
+            if let Some(symbol) = next {
+                if encoder.push(symbol).is_ok() {
+                    next = pending.next();
+                }
+            }
+            if let Some(packet) = encoder.packet() {
+                if tick % 3 != 2 {
+                    let packet = WindowPacket::from_bytes(&packet.to_bytes()).unwrap();
+                    decoder.push(&packet).unwrap();
+                }
+            }
+            while let Some(symbol) = decoder.pop() {
+                out.push(symbol);
+            }
+            encoder.ack(decoder.next());
+            if out.len() == source.len() {
+                break;
+            }
+        }
+        assert_eq!(out, source);
+        assert_eq!(encoder.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_window_limits() {
+        let mut encoder = WindowEncoder::new(2, 4);
+        assert_eq!(encoder.packet(), None);
+        assert_eq!(encoder.push(&[1; 4]), Ok(0));
+        assert_eq!(encoder.push(&[2; 4]), Ok(1));
+        assert_eq!(encoder.push(&[3; 4]), Err(RlncError::WindowFull));
+        encoder.ack(1);
+        assert_eq!(encoder.push(&[3; 4]), Ok(2));
+
+        let mut decoder = WindowDecoder::new(4);
+        let late = WindowPacket {
+            start: 0,
+            coefficients: vec![1],
+            data: vec![1; 4],
+        };
+        let fresh = WindowPacket {
+            start: 1,
+            coefficients: vec![1],
+            data: vec![2; 4],
+        };
+        assert_eq!(decoder.push(&fresh), Ok(true));
+        assert_eq!(decoder.next(), 0);
+        assert_eq!(decoder.push(&late), Ok(true));
+        assert_eq!(decoder.next(), 2);
+        assert_eq!(decoder.pop(), Some(vec![1; 4]));
+        assert_eq!(decoder.pop(), Some(vec![2; 4]));
+        let short = WindowPacket {
+            data: vec![0; 3],
+            ..late
+        };
+        assert_eq!(decoder.push(&short), Err(RlncError::WrongLength));
+    }
+}
@@ -0,0 +1,1223 @@
+//! Systematic Reed-Solomon erasure coding over GF(256).
+//!
+//! A `ReedSolomon` codec turns `k` equally sized data shards into `m` parity
+//! shards, such that the original data can be recovered from any `k` of the
+//! `k + m` shards; `ReedSolomon::encode_bytes` takes data of any length.
+//! [`FftReedSolomon`] is an FFT-based codec for large shard counts,
+//! [`GenericReedSolomon`] works over other fields such as GF(2^16), and
+//! [`FixedReedSolomon`] fixes the shard counts at compile time. The
+//! experimental [`ClayCode`] repairs a lost shard from a fraction of the
+//! others. [`ErasureCodec`] is the interface these codecs share. [`Ida`] is
+//! Rabin's non-systematic dispersal into fragments.
+//!
+//! [`RsWriter`] and [`RsReader`] code streams, with `AsyncRsWriter` and
+//! `AsyncRsReader` behind the `async` feature. [`write_shard`] and
+//! [`read_shard`] frame shards with a checksummed [`ShardHeader`] for storage
+//! or transport, and [`encode_file`] and [`decode_file`] protect whole files
+//! with a directory of shard files. [`ShardGeometry`] maps byte ranges of
+//! the data to the shards that hold them. With the `memmap2` feature,
+//! `ReedSolomon::encode_mapped` and `ReedSolomon::reconstruct_mapped` code
+//! shard files in place through memory maps.
+//!
+//! [`RsCode`] is the classical RS(n, k) error-correcting code, which also
+//! corrects symbols that are wrong at unknown positions, and
+//! [`SystematicEncoder`] encodes it one symbol at a time. Shortened codes are
+//! plain `RsCode`s with `n < 255`, and [`PuncturedCode`] drops parity symbols
+//! from an existing code. [`Interleaved`] spreads burst errors over several
+//! codewords, and [`ProductCode`] protects a 2-D block with one code on its
+//! rows and another on its columns. [`ConcatenatedCode`] sends every byte of
+//! an `RsCode` codeword through an [`InnerCode`] such as [`Hamming84`].
+//! [`RsCode::with_dual_basis`] puts symbols in a [`DualBasis`] on the wire,
+//! as CCSDS does.
+
+use std::fmt;
+
+mod archive;
+#[cfg(feature = "async")]
+mod async_stream;
+mod bytes;
+mod clay;
+mod code;
+mod codec;
+mod concatenated;
+mod container;
+mod dual;
+mod fixed;
+mod generic;
+mod geometry;
+mod ida;
+mod interleave;
+mod leopard;
+#[cfg(feature = "memmap2")]
+mod mapped;
+pub mod presets;
+mod product;
+mod punctured;
+mod stream;
+pub use archive::{
+    decode_file, decode_file_with_progress, encode_file, encode_file_with_progress, Manifest,
+};
+#[cfg(feature = "async")]
+pub use async_stream::{AsyncRsReader, AsyncRsWriter};
+pub use bytes::EncodedBytes;
+pub use clay::ClayCode;
+pub(crate) use code::berlekamp_massey_with;
+pub use code::{CodeError, DecoderAlgorithm, RsCode, RsParams, SystematicEncoder};
+pub use codec::ErasureCodec;
+pub use concatenated::{ConcatenatedCode, Hamming84, InnerCode, Repetition};
+pub use container::{read_shard, write_shard, ShardHeader, HEADER_LEN};
+pub use dual::DualBasis;
+pub use fixed::FixedReedSolomon;
+pub use generic::{GenericReedSolomon, ReedSolomon16};
+pub use geometry::{ShardGeometry, ShardRange};
+pub use ida::Ida;
+pub use interleave::Interleaved;
+pub use leopard::FftReedSolomon;
+pub use product::ProductCode;
+pub use punctured::PuncturedCode;
+pub use stream::{RsReader, RsWriter};
+
+use crate::matrix::invert_in_place;
+use crate::poly::Poly;
+use crate::slice::{from_bytes_mut, mul_add_slice, mul_slice, xor_slices};
+use crate::{Galois, GfMatrix};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// `k` is zero or `k + m` exceeds 256.
+    InvalidShardCount,
+    /// The number of shards passed does not match the codec.
+    WrongShardCount,
+    /// Shards are not all the same length.
+    ShardSizeMismatch,
+    /// Fewer than `k` shards are present.
+    TooFewShards,
+    /// A shard index is out of range.
+    InvalidShardIndex,
+    /// More shards are corrupt than the parity can locate.
+    TooManyCorrupt,
+    /// The scratch buffer is shorter than the codec needs.
+    ScratchTooSmall,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidShardCount => write!(f, "invalid number of data or parity shards"),
+            Error::WrongShardCount => write!(f, "wrong number of shards"),
+            Error::ShardSizeMismatch => write!(f, "shards are not all the same size"),
+            Error::TooFewShards => write!(f, "too few shards present to reconstruct"),
+            Error::InvalidShardIndex => write!(f, "shard index out of range"),
+            Error::TooManyCorrupt => write!(f, "too many corrupt shards to locate"),
+            Error::ScratchTooSmall => write!(f, "scratch buffer too small"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReedSolomon {
+    data_shards: usize,
+    parity_shards: usize,
+    // (k + m) × k, the top k rows are the identity
+    matrix: GfMatrix,
+    // the distinct evaluation points of the generalized RS code the matrix
+    // generates, one per shard
+    points: Vec<Galois>,
+}
+
+impl ReedSolomon {
+    /// Creates a codec whose encoding matrix is a `(k + m) × k` Vandermonde
+    /// matrix multiplied by the inverse of its top square, which makes the
+    /// code systematic while keeping every `k × k` submatrix invertible.
+    ///
+    /// The parity columns are then scaled so that the first parity row is
+    /// all ones: scaling a column of the parity block keeps every square
+    /// submatrix of it invertible, so the code stays MDS, and the first
+    /// parity shard is the XOR of the data, as in RAID-5.
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<Self, Error> {
+        check_shard_counts(data_shards, parity_shards)?;
+
+        let matrix = xor_first_parity(
+            systematic_vandermonde(data_shards, parity_shards),
+            data_shards,
+        );
+        Ok(ReedSolomon::from_matrix(
+            data_shards,
+            parity_shards,
+            matrix,
+            None,
+        ))
+    }
+
+    /// Creates a codec whose parity rows form the Cauchy matrix
+    /// `1 / (x_i + y_j)` with `x_i = k + i` and `y_j = j`, its columns
+    /// scaled so that the first parity row is all ones as in
+    /// [`ReedSolomon::new`].
+    ///
+    /// Every square submatrix of a Cauchy matrix is invertible, so together
+    /// with the identity on top any `k` rows are too, for all `k + m <= 256`.
+    pub fn new_cauchy(data_shards: usize, parity_shards: usize) -> Result<Self, Error> {
+        check_shard_counts(data_shards, parity_shards)?;
+
+        let xs: Vec<Galois> = (data_shards..data_shards + parity_shards)
+            .map(|x| Galois::new(x as u8))
+            .collect();
+        let ys: Vec<Galois> = (0..data_shards).map(|y| Galois::new(y as u8)).collect();
+
+        let mut data = GfMatrix::identity(data_shards).into_vec();
+        data.extend(GfMatrix::cauchy(&xs, &ys).into_vec());
+        let total = data_shards + parity_shards;
+        let matrix = GfMatrix::from_vec(total, data_shards, data);
+        Ok(ReedSolomon::from_matrix(
+            data_shards,
+            parity_shards,
+            xor_first_parity(matrix, data_shards),
+            None,
+        ))
+    }
+
+    /// Creates a codec that is shard-for-shard compatible with the Go
+    /// library `github.com/klauspost/reedsolomon` constructed with `New`.
+    ///
+    /// Its default matrix is the systematic Vandermonde construction of
+    /// [`ReedSolomon::new`] without the column scaling. Its other matrix
+    /// options, such as `WithCauchyMatrix`, are not supported:
+    /// [`ReedSolomon::new_cauchy`] scales its columns and so writes
+    /// different parity. Use [`split`](ReedSolomon::split) and
+    /// [`join`](ReedSolomon::join) for its `Split`/`Join` conventions.
+    pub fn klauspost(data_shards: usize, parity_shards: usize) -> Result<Self, Error> {
+        check_shard_counts(data_shards, parity_shards)?;
+
+        let matrix = systematic_vandermonde(data_shards, parity_shards);
+        Ok(ReedSolomon::from_matrix(
+            data_shards,
+            parity_shards,
+            matrix,
+            None,
+        ))
+    }
+
+    /// Creates a codec compatible with zfec (as used by Tahoe-LAFS) with
+    /// parameters `k` and `n = k + m`, where share `i` is shard `i`.
+    ///
+    /// zfec's Vandermonde rows are the points `0, α^0, α^1, .., α^(n-2)`
+    /// rather than `0, 1, 2, ..`, again made systematic by the inverse of
+    /// the top square.
+    pub fn zfec(data_shards: usize, parity_shards: usize) -> Result<Self, Error> {
+        check_shard_counts(data_shards, parity_shards)?;
+
+        let total = data_shards + parity_shards;
+        let alpha = Galois::new(2);
+        let vm = GfMatrix::from_fn(total, data_shards, |r, c| match r {
+            0 => Galois::new((c == 0) as u8),
+            _ => alpha.exp((r - 1) as u32).exp(c as u32),
+        });
+        let top = vm.submatrix(0, 0, data_shards, data_shards);
+        let top_inv = top.invert().expect("vandermonde top square is invertible");
+        let points = std::iter::once(Galois::zero())
+            .chain((0..total - 1).map(|i| alpha.exp(i as u32)))
+            .collect();
+        Ok(ReedSolomon::from_matrix(
+            data_shards,
+            parity_shards,
+            &vm * &top_inv,
+            Some(points),
+        ))
+    }
+
+    // `points` defaults to 0, 1, .., n - 1, those of the Vandermonde rows
+    // and, as data then parity, of the Cauchy matrix.
+    fn from_matrix(
+        data_shards: usize,
+        parity_shards: usize,
+        matrix: GfMatrix,
+        points: Option<Vec<Galois>>,
+    ) -> Self {
+        debug_assert_eq!(
+            matrix.submatrix(0, 0, data_shards, data_shards),
+            GfMatrix::identity(data_shards)
+        );
+        let total = data_shards + parity_shards;
+        let points = points.unwrap_or_else(|| (0..total).map(|i| Galois::new(i as u8)).collect());
+        ReedSolomon {
+            data_shards,
+            parity_shards,
+            matrix,
+            points,
+        }
+    }
+
+    pub fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    pub fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+
+    /// The `(k + m) × k` encoding matrix.
+    pub fn matrix(&self) -> &GfMatrix {
+        &self.matrix
+    }
+
+    /// Splits `data` into `k + m` shards of `ceil(len / k)` bytes: the data
+    /// zero-padded across the data shards, then zeroed parity shards ready
+    /// for encoding.
+    pub fn split(&self, data: &[u8]) -> Vec<Vec<u8>> {
+        let per_shard = data.len().div_ceil(self.data_shards);
+        let mut shards: Vec<Vec<u8>> = data
+            .chunks(per_shard.max(1))
+            .map(|chunk| {
+                let mut shard = chunk.to_vec();
+                shard.resize(per_shard, 0);
+                shard
+            })
+            .collect();
+        shards.resize(self.total_shards(), vec![0u8; per_shard]);
+        shards
+    }
+
+    /// Concatenates the data shards and keeps the first `len` bytes, undoing
+    /// [`split`](ReedSolomon::split).
+    pub fn join(&self, shards: &[Vec<u8>], len: usize) -> Result<Vec<u8>, Error> {
+        if shards.len() < self.data_shards {
+            return Err(Error::WrongShardCount);
+        }
+        let data = &shards[..self.data_shards];
+        if data.iter().map(Vec::len).sum::<usize>() < len {
+            return Err(Error::TooFewShards);
+        }
+        Ok(data.iter().flatten().copied().take(len).collect())
+    }
+
+    /// Computes the parity shards for `data`.
+    pub fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        let len = check_shards(data, self.data_shards)?;
+
+        let mut parity = vec![vec![0u8; len]; self.parity_shards];
+        let mut outputs: Vec<&mut [u8]> = parity.iter_mut().map(|p| &mut p[..]).collect();
+        self.encode_into(data, &mut outputs)?;
+        Ok(parity)
+    }
+
+    /// Computes the parity shards for `data` into `parity`, without
+    /// allocating.
+    pub fn encode_into(&self, data: &[&[u8]], parity: &mut [&mut [u8]]) -> Result<(), Error> {
+        let len = check_shards(data, self.data_shards)?;
+        if parity.len() != self.parity_shards {
+            return Err(Error::WrongShardCount);
+        }
+        if parity.iter().any(|p| p.len() != len) {
+            return Err(Error::ShardSizeMismatch);
+        }
+
+        for (r, out) in parity.iter_mut().enumerate() {
+            code_row(self.matrix.row(self.data_shards + r), data, out);
+        }
+        Ok(())
+    }
+
+    /// The number of scratch bytes [`reconstruct_into`](ReedSolomon::reconstruct_into)
+    /// needs: `2 k^2 + k`.
+    pub fn reconstruct_scratch_len(&self) -> usize {
+        let k = self.data_shards;
+        2 * k * k + k
+    }
+
+    /// Overwrites the shards not marked `present` with their contents,
+    /// without allocating.
+    ///
+    /// `shards` holds buffers for all `k + m` shards, data first, and
+    /// `scratch` at least [`reconstruct_scratch_len`](ReedSolomon::reconstruct_scratch_len)
+    /// bytes, whose contents on return are unspecified.
+    pub fn reconstruct_into(
+        &self,
+        shards: &mut [&mut [u8]],
+        present: &[bool],
+        scratch: &mut [u8],
+    ) -> Result<(), Error> {
+        let k = self.data_shards;
+        if present.len() != self.total_shards() {
+            return Err(Error::WrongShardCount);
+        }
+        let len = check_shards(shards, self.total_shards())?;
+        if scratch.len() < self.reconstruct_scratch_len() {
+            return Err(Error::ScratchTooSmall);
+        }
+
+        let (used, scratch) = scratch.split_at_mut(k);
+        let mut count = 0;
+        for (i, _) in present.iter().enumerate().filter(|(_, p)| **p).take(k) {
+            used[count] = i as u8;
+            count += 1;
+        }
+        if count < k {
+            return Err(Error::TooFewShards);
+        }
+
+        if present[..k].iter().any(|p| !p) {
+            let (work, decode) = scratch[..2 * k * k].split_at_mut(k * k);
+            let (work, decode) = (from_bytes_mut(work), from_bytes_mut(decode));
+            for (r, u) in used.iter().enumerate() {
+                work[r * k..(r + 1) * k].copy_from_slice(self.matrix.row(*u as usize));
+            }
+            invert_in_place(work, decode, k)
+                .expect("any k rows of the encoding matrix are invertible");
+
+            for i in (0..k).filter(|i| !present[*i]) {
+                // the shard is taken out while the used ones are read
+                let out = std::mem::take(&mut shards[i]);
+                for (c, u) in used.iter().enumerate() {
+                    let input = &shards[*u as usize][..len];
+                    if c == 0 {
+                        mul_slice(decode[i * k + c], input, out);
+                    } else {
+                        mul_add_slice(decode[i * k + c], input, out);
+                    }
+                }
+                shards[i] = out;
+            }
+        }
+
+        let (data, parity) = shards.split_at_mut(k);
+        for (r, out) in parity.iter_mut().enumerate() {
+            if !present[k + r] {
+                code_row(self.matrix.row(k + r), data, out);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the parity shards in `shards` (all `k + m`, data first)
+    /// match the data shards.
+    pub fn verify(&self, shards: &[&[u8]]) -> Result<bool, Error> {
+        Ok(self.verify_parity(shards)?.iter().all(|ok| *ok))
+    }
+
+    /// Like [`verify`](ReedSolomon::verify), but reports for each parity
+    /// shard whether it matches the data.
+    pub fn verify_parity(&self, shards: &[&[u8]]) -> Result<Vec<bool>, Error> {
+        check_shards(shards, self.total_shards())?;
+        let (data, parity) = shards.split_at(self.data_shards);
+        let expected = self.encode(data)?;
+        Ok(expected
+            .iter()
+            .zip(parity)
+            .map(|(e, p)| e[..] == p[..])
+            .collect())
+    }
+
+    /// Finds the shards that disagree with the rest, for up to `m / 2`
+    /// corrupt shards. Returns an empty list if `shards` is consistent.
+    ///
+    /// The matrix generates a generalized Reed-Solomon code, so every byte
+    /// column has syndromes like an [`RsCode`] codeword: Berlekamp-Massey
+    /// gives the column's error locator, whose roots among the evaluation
+    /// points are the corrupt shards. The work is linear in the shard
+    /// length, and corruption past `m / 2` shards is reported rather than
+    /// searched for.
+    pub fn locate_corrupt(&self, shards: &[&[u8]]) -> Result<Vec<usize>, Error> {
+        Ok(self.find_corrupt(shards)?.0)
+    }
+
+    /// Locates corrupt shards like [`locate_corrupt`] and overwrites them
+    /// with their recovered contents, returning their indices.
+    ///
+    /// [`locate_corrupt`]: ReedSolomon::locate_corrupt
+    pub fn repair(&self, shards: &mut [Vec<u8>]) -> Result<Vec<usize>, Error> {
+        let refs: Vec<&[u8]> = shards.iter().map(|s| &s[..]).collect();
+        let (corrupt, recovered) = self.find_corrupt(&refs)?;
+        for (i, r) in corrupt.iter().zip(recovered) {
+            shards[*i] = r;
+        }
+        Ok(corrupt)
+    }
+
+    fn find_corrupt(&self, shards: &[&[u8]]) -> Result<(Vec<usize>, Vec<Vec<u8>>), Error> {
+        let len = check_shards(shards, self.total_shards())?;
+        if self.verify(shards)? {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let t = self.parity_shards / 2;
+        let check = self.parity_check();
+        let mut syndromes = vec![vec![0u8; len]; self.parity_shards];
+        let mut outputs: Vec<&mut [u8]> = syndromes.iter_mut().map(|s| &mut s[..]).collect();
+        code_shards(&check, shards, &mut outputs);
+
+        let mut corrupt = vec![false; shards.len()];
+        for b in 0..len {
+            let s: Vec<Galois> = syndromes.iter().map(|row| Galois::new(row[b])).collect();
+            if s.iter().all(|x| *x == Galois::zero()) {
+                continue;
+            }
+            // S_j = Σ Y_e X_e^j, so the connection polynomial C is the
+            // reciprocal of Π (x - X_e), which keeps a root at a zero point
+            let (c, l) = berlekamp_massey_with(&s, &Poly::one(), 0);
+            if l > t || c.degree().is_some_and(|d| d > l) {
+                return Err(Error::TooManyCorrupt);
+            }
+            let sigma = Poly::new((0..=l).map(|i| c.coeff(l - i)).collect());
+            let roots: Vec<usize> = (0..shards.len())
+                .filter(|i| sigma.eval(self.points[*i]) == Galois::zero())
+                .collect();
+            if roots.len() != l {
+                return Err(Error::TooManyCorrupt);
+            }
+            roots.into_iter().for_each(|i| corrupt[i] = true);
+        }
+
+        let suspects: Vec<usize> = (0..shards.len()).filter(|i| corrupt[*i]).collect();
+        if suspects.len() > t {
+            return Err(Error::TooManyCorrupt);
+        }
+        match self.recover_if_consistent(shards, &suspects) {
+            Some(recovered) => Ok((suspects, recovered)),
+            None => Err(Error::TooManyCorrupt),
+        }
+    }
+
+    // The m × n parity-check matrix `u_i X_i^j` of the code, for the
+    // evaluation points X_i.
+    //
+    // Column c of the matrix is the codeword `v_i f_c(X_i)` with f_c
+    // vanishing on the other data points, which pins down the multipliers
+    // v_i up to a common factor; those of the dual code are then
+    // `u_i = 1 / (v_i Π_(j != i) (X_i - X_j))`.
+    fn parity_check(&self) -> GfMatrix {
+        let (k, n) = (self.data_shards, self.total_shards());
+        let x = &self.points;
+        // L_c(y) = Π over the other data points (y - X_i)
+        let lagrange = |c: usize, y: Galois| {
+            (0..k)
+                .filter(|i| *i != c)
+                .fold(Galois::identity(), |acc, i| acc * (y - x[i]))
+        };
+        let mut v = vec![Galois::zero(); n];
+        for r in k..n {
+            v[r] = self.matrix[(r, 0)] / lagrange(0, x[r]);
+        }
+        for c in 0..k {
+            let scale = self.matrix[(k, c)] / (v[k] * lagrange(c, x[k]));
+            v[c] = (scale * lagrange(c, x[c])).inv();
+        }
+        let u: Vec<Galois> = (0..n)
+            .map(|i| {
+                let spread = (0..n)
+                    .filter(|j| *j != i)
+                    .fold(Galois::identity(), |acc, j| acc * (x[i] - x[j]));
+                (v[i] * spread).inv()
+            })
+            .collect();
+        GfMatrix::from_fn(self.parity_shards, n, |j, i| u[i] * x[i].exp(j as u32))
+    }
+
+    // Rebuilds `suspects` from the other shards, provided those agree with
+    // each other.
+    fn recover_if_consistent(&self, shards: &[&[u8]], suspects: &[usize]) -> Option<Vec<Vec<u8>>> {
+        let k = self.data_shards;
+        let others: Vec<usize> = (0..shards.len())
+            .filter(|i| suspects.binary_search(i).is_err())
+            .collect();
+        let (used, checks) = others.split_at(k);
+        let decode = self
+            .matrix
+            .select_rows(used)
+            .invert()
+            .expect("any k rows of the encoding matrix are invertible");
+
+        let targets: Vec<usize> = checks.iter().chain(suspects).copied().collect();
+        let rows = &self.matrix.select_rows(&targets) * &decode;
+        let len = shards[0].len();
+        let mut recovered = vec![vec![0u8; len]; targets.len()];
+        let inputs: Vec<&[u8]> = used.iter().map(|i| shards[*i]).collect();
+        let mut outputs: Vec<&mut [u8]> = recovered.iter_mut().map(|r| &mut r[..]).collect();
+        code_shards(&rows, &inputs, &mut outputs);
+
+        let consistent = checks
+            .iter()
+            .zip(&recovered)
+            .all(|(i, r)| shards[*i] == &r[..]);
+        consistent.then(|| recovered.split_off(checks.len()))
+    }
+
+    /// Updates `parity` in place after data shard `index` changed from `old`
+    /// to `new`, without reading the other data shards.
+    ///
+    /// The code is linear, so each parity shard changes by its coefficient
+    /// for the shard times `old + new`.
+    pub fn update_parity(
+        &self,
+        old: &[u8],
+        new: &[u8],
+        index: usize,
+        parity: &mut [&mut [u8]],
+    ) -> Result<(), Error> {
+        if index >= self.data_shards {
+            return Err(Error::InvalidShardIndex);
+        }
+        if parity.len() != self.parity_shards {
+            return Err(Error::WrongShardCount);
+        }
+        if new.len() != old.len() || parity.iter().any(|p| p.len() != old.len()) {
+            return Err(Error::ShardSizeMismatch);
+        }
+
+        for (j, p) in parity.iter_mut().enumerate() {
+            let c = self.matrix[(self.data_shards + j, index)];
+            mul_add_slice(c, old, p);
+            mul_add_slice(c, new, p);
+        }
+        Ok(())
+    }
+
+    /// Fills in every missing (`None`) shard from the present ones.
+    ///
+    /// `shards` holds all `k + m` shards in order, data first.
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        let all: Vec<usize> = (0..self.total_shards()).collect();
+        self.reconstruct_only(shards, &all)
+    }
+
+    /// Fills in only the missing shards among `wanted`, leaving the others
+    /// `None`.
+    ///
+    /// Each wanted shard is computed straight from `k` present shards, so
+    /// recovering a single lost shard costs one row of work whatever else is
+    /// missing.
+    pub fn reconstruct_only(
+        &self,
+        shards: &mut [Option<Vec<u8>>],
+        wanted: &[usize],
+    ) -> Result<(), Error> {
+        self.reconstruct_with(shards, wanted, code_shards)
+    }
+
+    /// Like [`encode`](ReedSolomon::encode), but splits the shards into at
+    /// most `threads` segments that are coded in parallel on the rayon thread
+    /// pool. `threads == 0` means one segment per pool thread.
+    #[cfg(feature = "rayon")]
+    pub fn par_encode(&self, data: &[&[u8]], threads: usize) -> Result<Vec<Vec<u8>>, Error> {
+        let len = check_shards(data, self.data_shards)?;
+
+        let rows = self
+            .matrix
+            .submatrix(self.data_shards, 0, self.parity_shards, self.data_shards);
+        let mut parity = vec![vec![0u8; len]; self.parity_shards];
+        let mut outputs: Vec<&mut [u8]> = parity.iter_mut().map(|p| &mut p[..]).collect();
+        par_code_shards(&rows, data, &mut outputs, threads);
+        Ok(parity)
+    }
+
+    /// Like [`reconstruct`](ReedSolomon::reconstruct), with the shards coded
+    /// in parallel as in [`par_encode`](ReedSolomon::par_encode).
+    #[cfg(feature = "rayon")]
+    pub fn par_reconstruct(
+        &self,
+        shards: &mut [Option<Vec<u8>>],
+        threads: usize,
+    ) -> Result<(), Error> {
+        let all: Vec<usize> = (0..self.total_shards()).collect();
+        self.reconstruct_with(shards, &all, |rows, inputs, outputs| {
+            par_code_shards(rows, inputs, outputs, threads)
+        })
+    }
+
+    fn reconstruct_with(
+        &self,
+        shards: &mut [Option<Vec<u8>>],
+        wanted: &[usize],
+        code: impl Fn(&GfMatrix, &[&[u8]], &mut [&mut [u8]]),
+    ) -> Result<(), Error> {
+        if shards.len() != self.total_shards() {
+            return Err(Error::WrongShardCount);
+        }
+        if wanted.iter().any(|w| *w >= shards.len()) {
+            return Err(Error::InvalidShardIndex);
+        }
+
+        let mut missing: Vec<usize> = wanted
+            .iter()
+            .copied()
+            .filter(|w| shards[*w].is_none())
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let present: Vec<usize> = (0..shards.len()).filter(|i| shards[*i].is_some()).collect();
+        if present.len() < self.data_shards {
+            return Err(Error::TooFewShards);
+        }
+        let len = shards[present[0]].as_ref().map_or(0, |s| s.len());
+        if present
+            .iter()
+            .any(|i| shards[*i].as_ref().map_or(0, |s| s.len()) != len)
+        {
+            return Err(Error::ShardSizeMismatch);
+        }
+
+        let used = &present[..self.data_shards];
+        let rows = self.recovery_rows(&missing, used);
+        let mut recovered = vec![vec![0u8; len]; missing.len()];
+        {
+            let inputs: Vec<&[u8]> = used
+                .iter()
+                .map(|i| shards[*i].as_deref().unwrap_or_default())
+                .collect();
+            let mut outputs: Vec<&mut [u8]> = recovered.iter_mut().map(|r| &mut r[..]).collect();
+            code(&rows, &inputs, &mut outputs);
+        }
+        for (i, r) in missing.iter().zip(recovered) {
+            shards[*i] = Some(r);
+        }
+        Ok(())
+    }
+
+    // The rows that compute shards `missing` from the `k` shards `used`:
+    // shard w = M[w] · data = M[w] · M[used]^-1 · shards[used].
+    fn recovery_rows(&self, missing: &[usize], used: &[usize]) -> GfMatrix {
+        let k = self.data_shards;
+        let rows = self.matrix.select_rows(missing);
+        if used[k - 1] == k - 1 {
+            return rows;
+        }
+        // a single lost data shard `i` next to a parity shard `p = Σ c_j d_j`
+        // is `(p + Σ_{j≠i} c_j d_j) / c_i`, one scaled pass with no inversion
+        if let [i] = *missing {
+            if i < k && used[k - 1] >= k && used[..k - 1].iter().all(|j| *j != i && *j < k) {
+                let parity = self.matrix.row(used[k - 1]);
+                let inv = parity[i].inv();
+                return GfMatrix::from_fn(1, k, |_, c| match used[c] {
+                    j if j < k => parity[j] * inv,
+                    _ => inv,
+                });
+            }
+        }
+        let decode = self
+            .matrix
+            .select_rows(used)
+            .invert()
+            .expect("any k rows of the encoding matrix are invertible");
+        &rows * &decode
+    }
+}
+
+// The (k + m) × k Vandermonde matrix times the inverse of its top square.
+fn systematic_vandermonde(data_shards: usize, parity_shards: usize) -> GfMatrix {
+    let vm = GfMatrix::vandermonde(data_shards + parity_shards, data_shards);
+    let top = vm.submatrix(0, 0, data_shards, data_shards);
+    let top_inv = top.invert().expect("vandermonde top square is invertible");
+    &vm * &top_inv
+}
+
+// Divides each column of the parity block by its entry in the first parity
+// row, which an MDS code never has zero, so that code_row takes the XOR path
+// for that row.
+fn xor_first_parity(mut matrix: GfMatrix, data_shards: usize) -> GfMatrix {
+    if matrix.rows() == data_shards {
+        return matrix;
+    }
+    for c in 0..data_shards {
+        let scale = matrix[(data_shards, c)].inv();
+        for r in data_shards..matrix.rows() {
+            matrix[(r, c)] *= scale;
+        }
+    }
+    matrix
+}
+
+fn check_shard_counts(data_shards: usize, parity_shards: usize) -> Result<(), Error> {
+    if data_shards == 0 || data_shards + parity_shards > 256 {
+        return Err(Error::InvalidShardCount);
+    }
+    Ok(())
+}
+
+// Checks the shard count and that all shards share a length, which is returned.
+fn check_shards<S: AsRef<[u8]>>(shards: &[S], count: usize) -> Result<usize, Error> {
+    if shards.len() != count {
+        return Err(Error::WrongShardCount);
+    }
+    let len = shards.first().map_or(0, |s| s.as_ref().len());
+    if shards.iter().any(|s| s.as_ref().len() != len) {
+        return Err(Error::ShardSizeMismatch);
+    }
+    Ok(len)
+}
+
+// out = Σ_c row[c] * inputs[c]
+fn code_row<S: AsRef<[u8]>>(row: &[Galois], inputs: &[S], out: &mut [u8]) {
+    // a row of ones, like RAID-5 parity, is a plain XOR
+    if row.iter().all(|c| *c == Galois::identity()) {
+        return xor_slices(inputs, out);
+    }
+    for (c, input) in inputs.iter().enumerate() {
+        if c == 0 {
+            mul_slice(row[c], input.as_ref(), out);
+        } else {
+            mul_add_slice(row[c], input.as_ref(), out);
+        }
+    }
+}
+
+// outputs[r] = Σ_c rows[r][c] * inputs[c]
+fn code_shards(rows: &GfMatrix, inputs: &[&[u8]], outputs: &mut [&mut [u8]]) {
+    debug_assert_eq!(rows.rows(), outputs.len());
+    debug_assert_eq!(rows.cols(), inputs.len());
+
+    for (r, out) in outputs.iter_mut().enumerate() {
+        code_row(rows.row(r), inputs, out);
+    }
+}
+
+// code_shards over at most `threads` segments of the shards at once
+#[cfg(feature = "rayon")]
+fn par_code_shards(rows: &GfMatrix, inputs: &[&[u8]], outputs: &mut [&mut [u8]], threads: usize) {
+    use rayon::prelude::*;
+
+    // segments shorter than this are not worth a task
+    const MIN_SEGMENT: usize = 4096;
+
+    let len = inputs.first().map_or(0, |i| i.len());
+    let threads = match threads {
+        0 => rayon::current_num_threads(),
+        t => t,
+    };
+    let segments = threads.min(len.div_ceil(MIN_SEGMENT)).max(1);
+    if segments == 1 {
+        return code_shards(rows, inputs, outputs);
+    }
+    // rounded up to whole cache lines
+    let seg = len.div_ceil(segments).next_multiple_of(64);
+
+    let mut parts: Vec<Vec<&mut [u8]>> = (0..len.div_ceil(seg)).map(|_| Vec::new()).collect();
+    for out in outputs.iter_mut() {
+        for (part, chunk) in parts.iter_mut().zip(out.chunks_mut(seg)) {
+            part.push(chunk);
+        }
+    }
+    parts.into_par_iter().enumerate().for_each(|(s, mut part)| {
+        let end = ((s + 1) * seg).min(len);
+        let ins: Vec<&[u8]> = inputs.iter().map(|i| &i[s * seg..end]).collect();
+        code_shards(rows, &ins, &mut part);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_shards(k: usize, len: usize) -> Vec<Vec<u8>> {
+        (0..k)
+            .map(|s| (0..len).map(|i| (i * 31 + s * 97 + 5) as u8).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_one_encode() {
+        // the vector from JavaReedSolomon's and klauspost/reedsolomon's test suites
+        let rs = ReedSolomon::klauspost(5, 5).unwrap();
+        let data: [&[u8]; 5] = [&[0, 1], &[4, 5], &[2, 3], &[6, 7], &[8, 9]];
+        let parity = rs.encode(&data).unwrap();
+        assert_eq!(
+            parity,
+            vec![
+                vec![12, 13],
+                vec![10, 11],
+                vec![14, 15],
+                vec![90, 91],
+                vec![94, 95]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_klauspost_split_join() {
+        let rs = ReedSolomon::klauspost(5, 5).unwrap();
+        let scaled = xor_first_parity(rs.matrix.clone(), 5);
+        assert_eq!(scaled, ReedSolomon::new(5, 5).unwrap().matrix);
+
+        // the Go library's testOneEncode input, split from one buffer
+        let mut shards = rs.split(&[0, 1, 4, 5, 2, 3, 6, 7, 8, 9]);
+        assert_eq!(shards.len(), 10);
+        let refs: Vec<&[u8]> = shards[..5].iter().map(|s| &s[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        assert_eq!(parity[3], [90, 91]);
+        shards.splice(5.., parity);
+        assert_eq!(
+            rs.join(&shards, 10).unwrap(),
+            [0, 1, 4, 5, 2, 3, 6, 7, 8, 9]
+        );
+
+        // 11 bytes over 4 shards: 3 bytes each, the last one padded
+        let rs = ReedSolomon::klauspost(4, 2).unwrap();
+        let data: Vec<u8> = (1..=11).collect();
+        let shards = rs.split(&data);
+        assert_eq!(shards[3], [10, 11, 0]);
+        assert_eq!(shards[5], [0, 0, 0]);
+        assert_eq!(rs.join(&shards, 11).unwrap(), data);
+        assert_eq!(rs.join(&shards, 13), Err(Error::TooFewShards));
+    }
+
+    #[test]
+    fn test_zfec() {
+        // the k = 5, n = 8 vector from the zfec-rs port's test suite
+        let rs = ReedSolomon::zfec(5, 3).unwrap();
+        let input = b"some_ssidthe_password";
+        let shards = rs.split(input);
+        let refs: Vec<&[u8]> = shards[..5].iter().map(|s| &s[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        assert_eq!(
+            parity,
+            [&b"]\xd8\x94\xea\x91"[..], b"\x1bGU\xff+", b"\x882[\xa6\xd3"]
+        );
+
+        let mut shards: Vec<Option<Vec<u8>>> = refs
+            .iter()
+            .map(|s| s.to_vec())
+            .chain(parity)
+            .map(Some)
+            .collect();
+        shards[0] = None;
+        shards[3] = None;
+        shards[4] = None;
+        rs.reconstruct(&mut shards).unwrap();
+        let shards: Vec<Vec<u8>> = shards.into_iter().map(Option::unwrap).collect();
+        assert_eq!(rs.join(&shards, input.len()).unwrap(), input);
+    }
+
+    #[test]
+    fn test_reconstruct() {
+        let rs = ReedSolomon::new(4, 3).unwrap();
+        let data = sample_shards(4, 100);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+
+        let full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+        for lost in [[0usize, 1, 2], [0, 4, 6], [3, 5, 6], [1, 2, 5]] {
+            let mut shards: Vec<Option<Vec<u8>>> = full.iter().cloned().map(Some).collect();
+            for i in lost {
+                shards[i] = None;
+            }
+            rs.reconstruct(&mut shards).unwrap();
+            let shards: Vec<Vec<u8>> = shards.into_iter().map(Option::unwrap).collect();
+            assert_eq!(shards, full);
+        }
+
+        let mut shards: Vec<Option<Vec<u8>>> = full.iter().cloned().map(Some).collect();
+        for s in shards.iter_mut().take(4) {
+            *s = None;
+        }
+        assert_eq!(rs.reconstruct(&mut shards), Err(Error::TooFewShards));
+    }
+
+    #[test]
+    fn test_update_parity() {
+        let rs = ReedSolomon::new_cauchy(5, 3).unwrap();
+        let mut data = sample_shards(5, 40);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let mut parity = rs.encode(&refs).unwrap();
+
+        let old = std::mem::replace(&mut data[3], vec![0x5a; 40]);
+        let mut outputs: Vec<&mut [u8]> = parity.iter_mut().map(|p| &mut p[..]).collect();
+        rs.update_parity(&old, &data[3], 3, &mut outputs).unwrap();
+
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        assert_eq!(parity, rs.encode(&refs).unwrap());
+
+        let mut outputs: Vec<&mut [u8]> = parity.iter_mut().map(|p| &mut p[..]).collect();
+        assert_eq!(
+            rs.update_parity(&old, &data[3], 5, &mut outputs),
+            Err(Error::InvalidShardIndex)
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let rs = ReedSolomon::new(3, 3).unwrap();
+        let data = sample_shards(3, 16);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        let mut full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+
+        let refs: Vec<&[u8]> = full.iter().map(|s| &s[..]).collect();
+        assert_eq!(rs.verify(&refs), Ok(true));
+
+        full[4][9] ^= 1;
+        let refs: Vec<&[u8]> = full.iter().map(|s| &s[..]).collect();
+        assert_eq!(rs.verify(&refs), Ok(false));
+        assert_eq!(rs.verify_parity(&refs), Ok(vec![true, false, true]));
+
+        // a corrupt data shard breaks every parity shard
+        full[4][9] ^= 1;
+        full[0][0] ^= 1;
+        let refs: Vec<&[u8]> = full.iter().map(|s| &s[..]).collect();
+        assert_eq!(rs.verify_parity(&refs), Ok(vec![false; 3]));
+        assert_eq!(rs.verify(&refs[..5]), Err(Error::WrongShardCount));
+    }
+
+    #[test]
+    fn test_repair() {
+        let rs = ReedSolomon::new(4, 4).unwrap();
+        let data = sample_shards(4, 30);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        let full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+
+        for bad in [vec![], vec![2], vec![6], vec![0, 7], vec![1, 3]] {
+            let mut shards = full.clone();
+            for i in &bad {
+                shards[*i][*i + 3] ^= 0x40;
+            }
+            let refs: Vec<&[u8]> = shards.iter().map(|s| &s[..]).collect();
+            assert_eq!(rs.locate_corrupt(&refs), Ok(bad.clone()));
+            assert_eq!(rs.repair(&mut shards), Ok(bad));
+            assert_eq!(shards, full);
+        }
+
+        let mut shards = full.clone();
+        for i in [0, 1, 2] {
+            shards[i][0] ^= 1;
+        }
+        assert_eq!(rs.repair(&mut shards), Err(Error::TooManyCorrupt));
+    }
+
+    #[test]
+    fn test_parity_check() {
+        for rs in [
+            ReedSolomon::new(5, 3).unwrap(),
+            ReedSolomon::new(200, 56).unwrap(),
+            ReedSolomon::new_cauchy(7, 4).unwrap(),
+            ReedSolomon::klauspost(6, 6).unwrap(),
+            ReedSolomon::zfec(9, 4).unwrap(),
+            ReedSolomon::zfec(250, 6).unwrap(),
+        ] {
+            let zero = GfMatrix::from_fn(rs.parity_shards, rs.data_shards, |_, _| Galois::zero());
+            assert_eq!(&rs.parity_check() * &rs.matrix, zero);
+        }
+    }
+
+    #[test]
+    fn test_repair_many() {
+        // past m / 2 corrupt shards the answer comes at once
+        let rs = ReedSolomon::new(20, 20).unwrap();
+        let data = sample_shards(20, 100);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        let full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+
+        let bad: Vec<usize> = (0..40).step_by(4).collect();
+        let mut shards = full.clone();
+        for (n, i) in bad.iter().enumerate() {
+            shards[*i][n * 7 % 100] ^= 0x21;
+            shards[*i][99 - n] ^= 0x80;
+        }
+        assert_eq!(rs.repair(&mut shards), Ok(bad));
+        assert_eq!(shards, full);
+
+        for i in (0..40).step_by(3) {
+            shards[i][i] ^= 0x11;
+        }
+        let refs: Vec<&[u8]> = shards.iter().map(|s| &s[..]).collect();
+        assert_eq!(rs.locate_corrupt(&refs), Err(Error::TooManyCorrupt));
+
+        // a corrupt shard at the evaluation point zero
+        let rs = ReedSolomon::zfec(4, 4).unwrap();
+        let data = sample_shards(4, 10);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        let mut shards: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+        shards[0][3] ^= 9;
+        shards[5][3] ^= 1;
+        assert_eq!(rs.repair(&mut shards), Ok(vec![0, 5]));
+    }
+
+    #[test]
+    fn test_reconstruct_only() {
+        let rs = ReedSolomon::new(4, 3).unwrap();
+        let data = sample_shards(4, 24);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        let full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+
+        let mut shards: Vec<Option<Vec<u8>>> = full.iter().cloned().map(Some).collect();
+        shards[1] = None;
+        shards[2] = None;
+        shards[6] = None;
+        rs.reconstruct_only(&mut shards, &[2, 6]).unwrap();
+        assert_eq!(shards[1], None);
+        assert_eq!(shards[2].as_ref(), Some(&full[2]));
+        assert_eq!(shards[6].as_ref(), Some(&full[6]));
+
+        assert_eq!(
+            rs.reconstruct_only(&mut shards, &[7]),
+            Err(Error::InvalidShardIndex)
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_matches_serial() {
+        let rs = ReedSolomon::new(6, 3).unwrap();
+        let data = sample_shards(6, 50_001);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        for threads in [0, 1, 3, 64] {
+            assert_eq!(rs.par_encode(&refs, threads).unwrap(), parity);
+        }
+
+        let full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+        let mut shards: Vec<Option<Vec<u8>>> = full.iter().cloned().map(Some).collect();
+        shards[0] = None;
+        shards[4] = None;
+        shards[7] = None;
+        rs.par_reconstruct(&mut shards, 4).unwrap();
+        let shards: Vec<Vec<u8>> = shards.into_iter().map(Option::unwrap).collect();
+        assert_eq!(shards, full);
+    }
+
+    #[test]
+    fn test_single_failure_rows() {
+        for rs in [
+            ReedSolomon::new(5, 3).unwrap(),
+            ReedSolomon::new_cauchy(5, 1).unwrap(),
+        ] {
+            for i in 0..5 {
+                for p in 5..rs.total_shards() {
+                    let used: Vec<usize> = (0..5).filter(|j| *j != i).chain([p]).collect();
+                    let decode = rs.matrix.select_rows(&used).invert().unwrap();
+                    let general = &rs.matrix.select_rows(&[i]) * &decode;
+                    assert_eq!(rs.recovery_rows(&[i], &used), general, "{i} {p}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_xor_parity() {
+        let data = sample_shards(5, 64);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let mut xor = vec![0u8; 64];
+        xor_slices(&refs, &mut xor);
+        for rs in [
+            ReedSolomon::new(5, 1).unwrap(),
+            ReedSolomon::new_cauchy(5, 1).unwrap(),
+            ReedSolomon::new(5, 3).unwrap(),
+            ReedSolomon::new_cauchy(5, 3).unwrap(),
+        ] {
+            let ones = rs.matrix.row(5);
+            assert!(ones.iter().all(|c| *c == Galois::identity()));
+
+            // the generic product, coefficient by coefficient
+            let parity = rs.encode(&refs).unwrap();
+            let mut generic = vec![0u8; 64];
+            for (c, input) in refs.iter().enumerate() {
+                mul_add_slice(ones[c], input, &mut generic);
+            }
+            assert_eq!(parity[0], generic);
+            assert_eq!(parity[0], xor);
+        }
+
+        // with one parity shard every recovery row is all ones too
+        let rs = ReedSolomon::new(5, 1).unwrap();
+        let parity = rs.encode(&refs).unwrap();
+        let full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+        for lost in 0..6 {
+            let used: Vec<usize> = (0..6).filter(|&j| j != lost).collect();
+            let rows = rs.recovery_rows(&[lost], &used);
+            assert!(rows.row(0).iter().all(|c| *c == Galois::identity()));
+
+            let mut shards: Vec<Option<Vec<u8>>> = full.iter().cloned().map(Some).collect();
+            shards[lost] = None;
+            rs.reconstruct(&mut shards).unwrap();
+            assert_eq!(shards[lost].as_deref(), Some(&full[lost][..]));
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_into() {
+        let rs = ReedSolomon::new(5, 3).unwrap();
+        let data = sample_shards(5, 40);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        let full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+
+        let mut scratch = vec![0u8; rs.reconstruct_scratch_len()];
+        for lost in [[0, 3, 6], [5, 6, 7], [1, 2, 4]] {
+            let mut bufs = full.clone();
+            for l in lost {
+                bufs[l].fill(0xee);
+            }
+            let present: Vec<bool> = (0..8).map(|i| !lost.contains(&i)).collect();
+            let mut shards: Vec<&mut [u8]> = bufs.iter_mut().map(|b| &mut b[..]).collect();
+            rs.reconstruct_into(&mut shards, &present, &mut scratch)
+                .unwrap();
+            assert_eq!(bufs, full);
+        }
+
+        let mut bufs = full.clone();
+        let mut shards: Vec<&mut [u8]> = bufs.iter_mut().map(|b| &mut b[..]).collect();
+        assert_eq!(
+            rs.reconstruct_into(&mut shards, &[true; 8], &mut scratch[..10]),
+            Err(Error::ScratchTooSmall)
+        );
+        let mut present = [false; 8];
+        present[..4].fill(true);
+        assert_eq!(
+            rs.reconstruct_into(&mut shards, &present, &mut scratch),
+            Err(Error::TooFewShards)
+        );
+    }
+
+    #[test]
+    fn test_cauchy_any_k_rows() {
+        let rs = ReedSolomon::new_cauchy(3, 4).unwrap();
+        let m = rs.matrix();
+        assert_eq!(m.submatrix(0, 0, 3, 3), GfMatrix::identity(3));
+        for a in 0..7 {
+            for b in a + 1..7 {
+                for c in b + 1..7 {
+                    assert!(m.select_rows(&[a, b, c]).invert().is_ok());
+                }
+            }
+        }
+
+        let data = sample_shards(3, 33);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        let mut shards: Vec<Option<Vec<u8>>> =
+            data.iter().chain(&parity).cloned().map(Some).collect();
+        shards[0] = None;
+        shards[2] = None;
+        shards[5] = None;
+        shards[6] = None;
+        rs.reconstruct(&mut shards).unwrap();
+        assert_eq!(shards[0].as_ref(), Some(&data[0]));
+        assert_eq!(shards[2].as_ref(), Some(&data[2]));
+        assert_eq!(shards[6].as_ref(), Some(&parity[3]));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(ReedSolomon::new(0, 2), Err(Error::InvalidShardCount));
+        assert_eq!(
+            ReedSolomon::new_cauchy(250, 7),
+            Err(Error::InvalidShardCount)
+        );
+        assert_eq!(ReedSolomon::new(200, 57), Err(Error::InvalidShardCount));
+        assert!(ReedSolomon::new(200, 56).is_ok());
+
+        let rs = ReedSolomon::new(2, 1).unwrap();
+        assert_eq!(rs.encode(&[&[1, 2], &[3]]), Err(Error::ShardSizeMismatch));
+        assert_eq!(rs.encode(&[&[1, 2]]), Err(Error::WrongShardCount));
+    }
+}
@@ -0,0 +1,354 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::stream::report;
+use super::{read_shard, write_shard, ReedSolomon, ShardHeader};
+
+const MANIFEST_HEADER: &str = "gf256-lite archive 1";
+
+/// What [`decode_file`] needs to know about an archive besides its shard
+/// files.
+///
+/// The file is cut into stripes of `k * block_size` bytes, the last one
+/// zero-padded. Shard file `i` holds block `i` of every stripe, each framed
+/// by [`write_shard`], and is named `<name>.<i>.shard` next to the
+/// `<name>.manifest` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub name: String,
+    pub length: u64,
+    pub data_shards: usize,
+    pub parity_shards: usize,
+    pub block_size: usize,
+}
+
+impl Manifest {
+    pub fn stripes(&self) -> u64 {
+        self.length
+            .div_ceil(self.data_shards as u64 * self.block_size as u64)
+    }
+
+    pub fn manifest_path(&self, dir: &Path) -> PathBuf {
+        dir.join(format!("{}.manifest", self.name))
+    }
+
+    pub fn shard_path(&self, dir: &Path, index: usize) -> PathBuf {
+        dir.join(format!("{}.{index}.shard", self.name))
+    }
+
+    /// Reads a manifest written by [`encode_file`]. A `name` that is not a
+    /// single plain file name, such as `../x` or `/x`, is
+    /// [`io::ErrorKind::InvalidData`].
+    pub fn read<R: Read>(mut r: R) -> io::Result<Self> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        let bad = || io::Error::new(io::ErrorKind::InvalidData, "bad archive manifest");
+
+        let mut lines = text.lines();
+        if lines.next() != Some(MANIFEST_HEADER) {
+            return Err(bad());
+        }
+        let mut field = |key: &str| {
+            lines
+                .next()
+                .and_then(|l| l.strip_prefix(key))
+                .and_then(|l| l.strip_prefix('='))
+                .ok_or_else(bad)
+        };
+        let name = field("name")?.to_string();
+        // the name is joined to the archive directory, so it must not be
+        // able to leave it
+        let path = Path::new(&name);
+        if path.file_name() != Some(path.as_os_str()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("archive manifest name {name:?} is not a plain file name"),
+            ));
+        }
+        let length = field("length")?.parse().map_err(|_| bad())?;
+        let data_shards = field("data")?.parse().map_err(|_| bad())?;
+        let parity_shards = field("parity")?.parse().map_err(|_| bad())?;
+        let block_size = field("block")?.parse().map_err(|_| bad())?;
+        if block_size == 0 || ReedSolomon::new(data_shards, parity_shards).is_err() {
+            return Err(bad());
+        }
+        Ok(Manifest {
+            name,
+            length,
+            data_shards,
+            parity_shards,
+            block_size,
+        })
+    }
+
+    pub fn write<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "{MANIFEST_HEADER}")?;
+        writeln!(w, "name={}", self.name)?;
+        writeln!(w, "length={}", self.length)?;
+        writeln!(w, "data={}", self.data_shards)?;
+        writeln!(w, "parity={}", self.parity_shards)?;
+        writeln!(w, "block={}", self.block_size)
+    }
+}
+
+/// Splits the file at `input` into `k + m` shard files and a manifest in
+/// `out_dir`, using the codec of [`ReedSolomon::new`].
+pub fn encode_file(
+    input: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+    data_shards: usize,
+    parity_shards: usize,
+    block_size: usize,
+) -> io::Result<Manifest> {
+    encode_file_with_progress(
+        input,
+        out_dir,
+        data_shards,
+        parity_shards,
+        block_size,
+        |_| true,
+    )
+}
+
+/// Like [`encode_file`], calling `progress` with the bytes encoded so far
+/// after every stripe. If it returns `false` the job stops with an
+/// [`io::ErrorKind::Other`] error and no manifest is written.
+pub fn encode_file_with_progress(
+    input: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+    data_shards: usize,
+    parity_shards: usize,
+    block_size: usize,
+    mut progress: impl FnMut(u64) -> bool,
+) -> io::Result<Manifest> {
+    let (input, out_dir) = (input.as_ref(), out_dir.as_ref());
+    assert!(block_size > 0, "block size must be positive");
+    let rs = ReedSolomon::new(data_shards, parity_shards)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let name = input
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "input has no file name"))?;
+
+    let mut file = BufReader::new(File::open(input)?);
+    let mut manifest = Manifest {
+        name: name.to_string(),
+        length: 0,
+        data_shards,
+        parity_shards,
+        block_size,
+    };
+    let mut shards = (0..rs.total_shards())
+        .map(|i| File::create(manifest.shard_path(out_dir, i)).map(BufWriter::new))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let mut stripe = vec![0u8; data_shards * block_size];
+    for id in 0.. {
+        let n = read_full(&mut file, &mut stripe)?;
+        if n == 0 {
+            break;
+        }
+        stripe[n..].fill(0);
+        manifest.length += n as u64;
+
+        let data: Vec<&[u8]> = stripe.chunks(block_size).collect();
+        let parity = rs
+            .encode(&data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let blocks = data.iter().copied().chain(parity.iter().map(|p| &p[..]));
+        for (index, (shard, block)) in shards.iter_mut().zip(blocks).enumerate() {
+            let header = ShardHeader {
+                data_shards,
+                parity_shards,
+                index,
+                stripe: id,
+            };
+            write_shard(shard, &header, block)?;
+        }
+        report(&mut progress, manifest.length)?;
+        if n < stripe.len() {
+            break;
+        }
+    }
+    for shard in &mut shards {
+        shard.flush()?;
+    }
+    manifest.write(File::create(manifest.manifest_path(out_dir))?)?;
+    Ok(manifest)
+}
+
+/// Rebuilds the file described by the manifest at `manifest` into `output`,
+/// from whichever shard files next to it are present and intact.
+///
+/// A shard file that is missing, fails its checksum or is out of step is
+/// ignored from then on; each stripe needs `k` good blocks.
+pub fn decode_file(manifest: impl AsRef<Path>, output: impl AsRef<Path>) -> io::Result<()> {
+    decode_file_with_progress(manifest, output, |_| true)
+}
+
+/// Like [`decode_file`], calling `progress` with the bytes written so far
+/// after every stripe and stopping with an [`io::ErrorKind::Other`] error
+/// if it returns `false`.
+pub fn decode_file_with_progress(
+    manifest: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+    mut progress: impl FnMut(u64) -> bool,
+) -> io::Result<()> {
+    let path = manifest.as_ref();
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let manifest = Manifest::read(File::open(path)?)?;
+    let (k, m) = (manifest.data_shards, manifest.parity_shards);
+    let rs = ReedSolomon::new(k, m).expect("manifest shard counts are checked");
+
+    let mut shards: Vec<Option<BufReader<File>>> = (0..k + m)
+        .map(|i| {
+            File::open(manifest.shard_path(dir, i))
+                .ok()
+                .map(BufReader::new)
+        })
+        .collect();
+    let mut out = BufWriter::new(File::create(output)?);
+    let mut remaining = manifest.length;
+    let data: Vec<usize> = (0..k).collect();
+
+    for id in 0..manifest.stripes() {
+        let mut blocks: Vec<Option<Vec<u8>>> = vec![None; k + m];
+        for (index, (reader, block)) in shards.iter_mut().zip(&mut blocks).enumerate() {
+            let Some(r) = reader else { continue };
+            let expected = ShardHeader {
+                data_shards: k,
+                parity_shards: m,
+                index,
+                stripe: id,
+            };
+            match read_shard(r) {
+                Ok((h, p)) if h == expected && p.len() == manifest.block_size => *block = Some(p),
+                _ => *reader = None,
+            }
+        }
+        rs.reconstruct_only(&mut blocks, &data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        for block in blocks.into_iter().take(k).flatten() {
+            let n = remaining.min(block.len() as u64) as usize;
+            out.write_all(&block[..n])?;
+            remaining -= n as u64;
+        }
+        report(&mut progress, manifest.length - remaining)?;
+    }
+    out.flush()
+}
+
+// Reads until `buf` is full or the reader is exhausted.
+fn read_full<R: Read>(r: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut n = 0;
+    while n < buf.len() {
+        match r.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(read) => n += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("gf256-lite-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let dir = scratch_dir("archive");
+        let input = dir.join("input.bin");
+        let contents: Vec<u8> = (0..10_000u32).map(|i| (i * 7 % 251) as u8).collect();
+        fs::write(&input, &contents).unwrap();
+
+        let shard_dir = dir.join("shards");
+        fs::create_dir(&shard_dir).unwrap();
+        let manifest = encode_file(&input, &shard_dir, 4, 2, 1000).unwrap();
+        assert_eq!(manifest.length, 10_000);
+        assert_eq!(manifest.stripes(), 3);
+
+        // lose one shard file and corrupt another
+        fs::remove_file(manifest.shard_path(&shard_dir, 1)).unwrap();
+        let corrupt = manifest.shard_path(&shard_dir, 4);
+        let mut bytes = fs::read(&corrupt).unwrap();
+        bytes[100] ^= 1;
+        fs::write(&corrupt, bytes).unwrap();
+
+        let output = dir.join("output.bin");
+        decode_file(manifest.manifest_path(&shard_dir), &output).unwrap();
+        assert_eq!(fs::read(&output).unwrap(), contents);
+
+        fs::remove_file(manifest.shard_path(&shard_dir, 0)).unwrap();
+        let err = decode_file(manifest.manifest_path(&shard_dir), &output).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cancel() {
+        let dir = scratch_dir("cancel");
+        let input = dir.join("big.bin");
+        fs::write(&input, vec![9u8; 5000]).unwrap();
+
+        let mut seen = Vec::new();
+        let err = encode_file_with_progress(&input, &dir, 2, 1, 1000, |n| {
+            seen.push(n);
+            n < 4000
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+        assert_eq!(seen, [2000, 4000]);
+        assert!(!dir.join("big.bin.manifest").exists());
+
+        let manifest = encode_file(&input, &dir, 2, 1, 1000).unwrap();
+        let mut seen = Vec::new();
+        decode_file_with_progress(manifest.manifest_path(&dir), dir.join("out"), |n| {
+            seen.push(n);
+            true
+        })
+        .unwrap();
+        assert_eq!(seen, [2000, 4000, 5000]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_manifest() {
+        let manifest = Manifest {
+            name: "a.txt".to_string(),
+            length: 0,
+            data_shards: 3,
+            parity_shards: 1,
+            block_size: 64,
+        };
+        let mut buf = Vec::new();
+        manifest.write(&mut buf).unwrap();
+        assert_eq!(Manifest::read(&buf[..]).unwrap(), manifest);
+        assert_eq!(manifest.stripes(), 0);
+        assert!(Manifest::read(&b"gf256-lite archive 1\nname=x\n"[..]).is_err());
+    }
+
+    #[test]
+    fn test_hostile_manifest() {
+        for name in ["../x", "/etc/x", "a/b", "..", ".", ""] {
+            let text = format!(
+                "gf256-lite archive 1\nname={name}\nlength=1\ndata=3\nparity=1\nblock=64\n"
+            );
+            let err = Manifest::read(text.as_bytes()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{name:?}");
+        }
+        let text = "gf256-lite archive 1\nname=x.bin\nlength=1\ndata=3\nparity=1\nblock=64\n";
+        assert_eq!(Manifest::read(text.as_bytes()).unwrap().name, "x.bin");
+    }
+}
@@ -0,0 +1,285 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_io::{AsyncRead, AsyncWrite};
+
+use super::ReedSolomon;
+
+/// The asynchronous counterpart of [`RsWriter`](super::RsWriter), writing
+/// the same shard layout to `AsyncWrite` shards.
+///
+/// Closing the writer pads and writes the final stripe; [`len`] then gives
+/// the stream length needed by [`AsyncRsReader`].
+///
+/// [`len`]: AsyncRsWriter::len
+#[derive(Debug)]
+pub struct AsyncRsWriter<W> {
+    rs: ReedSolomon,
+    shards: Vec<W>,
+    block_size: usize,
+    // k * block_size, the bytes in a full stripe
+    stripe_len: usize,
+    stripe: Vec<u8>,
+    // encoded blocks not yet fully written, with the bytes already written
+    pending: Vec<(Vec<u8>, usize)>,
+    written: u64,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncRsWriter<W> {
+    /// Panics unless there is one writer per shard and `block_size > 0`.
+    pub fn new(rs: ReedSolomon, shards: Vec<W>, block_size: usize) -> Self {
+        assert_eq!(shards.len(), rs.total_shards(), "one writer per shard");
+        assert!(block_size > 0, "block size must be positive");
+        let stripe_len = rs.data_shards() * block_size;
+        AsyncRsWriter {
+            rs,
+            shards,
+            block_size,
+            stripe_len,
+            stripe: Vec::with_capacity(stripe_len),
+            pending: Vec::new(),
+            written: 0,
+        }
+    }
+
+    /// The number of bytes accepted so far.
+    pub fn len(&self) -> u64 {
+        self.written
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.written == 0
+    }
+
+    pub fn into_inner(self) -> Vec<W> {
+        self.shards
+    }
+
+    fn encode_stripe(&mut self) -> io::Result<()> {
+        let data: Vec<&[u8]> = self.stripe.chunks(self.block_size).collect();
+        let parity = self
+            .rs
+            .encode(&data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        self.pending = data
+            .into_iter()
+            .map(<[u8]>::to_vec)
+            .chain(parity)
+            .map(|block| (block, 0))
+            .collect();
+        self.stripe.clear();
+        Ok(())
+    }
+
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut blocked = false;
+        for (shard, (block, done)) in self.shards.iter_mut().zip(&mut self.pending) {
+            while *done < block.len() {
+                match Pin::new(&mut *shard).poll_write(cx, &block[*done..]) {
+                    Poll::Ready(Ok(0)) => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                    Poll::Ready(Ok(n)) => *done += n,
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Pending => {
+                        blocked = true;
+                        break;
+                    }
+                }
+            }
+        }
+        if blocked {
+            return Poll::Pending;
+        }
+        self.pending.clear();
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_flush_shards(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        for shard in &mut self.shards {
+            std::task::ready!(Pin::new(&mut *shard).poll_flush(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for AsyncRsWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        std::task::ready!(this.poll_pending(cx))?;
+
+        let room = this.stripe_len - this.stripe.len();
+        let n = room.min(buf.len());
+        this.stripe.extend_from_slice(&buf[..n]);
+        this.written += n as u64;
+        if this.stripe.len() == this.stripe_len {
+            this.encode_stripe()?;
+        }
+        Poll::Ready(Ok(n))
+    }
+
+    /// Writes out any encoded blocks and flushes the shards. A partial stripe
+    /// stays buffered until it fills up or the writer is closed.
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        std::task::ready!(this.poll_pending(cx))?;
+        this.poll_flush_shards(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        std::task::ready!(this.poll_pending(cx))?;
+        if !this.stripe.is_empty() {
+            this.stripe.resize(this.stripe_len, 0);
+            this.encode_stripe()?;
+            std::task::ready!(this.poll_pending(cx))?;
+        }
+        std::task::ready!(this.poll_flush_shards(cx))?;
+        for shard in &mut this.shards {
+            std::task::ready!(Pin::new(&mut *shard).poll_close(cx))?;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// The asynchronous counterpart of [`RsReader`](super::RsReader).
+#[derive(Debug)]
+pub struct AsyncRsReader<R> {
+    rs: ReedSolomon,
+    shards: Vec<Option<R>>,
+    block_size: usize,
+    // the stripe being read, with the bytes read so far per shard
+    blocks: Vec<(Vec<u8>, usize)>,
+    stripe: Vec<u8>,
+    pos: usize,
+    remaining: u64,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRsReader<R> {
+    /// Panics unless there is one entry per shard and `block_size > 0`.
+    /// `len` is the stream length reported by [`AsyncRsWriter::len`].
+    pub fn new(rs: ReedSolomon, shards: Vec<Option<R>>, block_size: usize, len: u64) -> Self {
+        assert_eq!(shards.len(), rs.total_shards(), "one entry per shard");
+        assert!(block_size > 0, "block size must be positive");
+        let blocks = vec![(vec![0u8; block_size], 0); shards.len()];
+        AsyncRsReader {
+            rs,
+            shards,
+            block_size,
+            blocks,
+            stripe: Vec::new(),
+            pos: 0,
+            remaining: len,
+        }
+    }
+
+    fn poll_stripe(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut blocked = false;
+        for (slot, (block, done)) in self.shards.iter_mut().zip(&mut self.blocks) {
+            while let Some(shard) = slot.as_mut().filter(|_| *done < block.len()) {
+                match Pin::new(shard).poll_read(cx, &mut block[*done..]) {
+                    Poll::Ready(Ok(n)) if n > 0 => *done += n,
+                    // a shard that ends early or fails is treated as lost
+                    Poll::Ready(_) => *slot = None,
+                    Poll::Pending => {
+                        blocked = true;
+                        break;
+                    }
+                }
+            }
+        }
+        if blocked {
+            return Poll::Pending;
+        }
+
+        let mut blocks: Vec<Option<Vec<u8>>> = self
+            .shards
+            .iter()
+            .zip(&self.blocks)
+            .map(|(slot, (block, _))| slot.as_ref().map(|_| block.clone()))
+            .collect();
+        for (_, done) in &mut self.blocks {
+            *done = 0;
+        }
+        self.rs
+            .reconstruct(&mut blocks)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let stripe_len = (self.rs.data_shards() * self.block_size) as u64;
+        let keep = stripe_len.min(self.remaining) as usize;
+        self.stripe = blocks
+            .into_iter()
+            .take(self.rs.data_shards())
+            .flatten()
+            .flatten()
+            .take(keep)
+            .collect();
+        self.pos = 0;
+        self.remaining -= keep as u64;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncRsReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.pos == this.stripe.len() {
+            if this.remaining == 0 {
+                return Poll::Ready(Ok(0));
+            }
+            std::task::ready!(this.poll_stripe(cx))?;
+        }
+        let n = buf.len().min(this.stripe.len() - this.pos);
+        buf[..n].copy_from_slice(&this.stripe[this.pos..this.pos + n]);
+        this.pos += n;
+        Poll::Ready(Ok(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let rs = ReedSolomon::new(3, 2).unwrap();
+        let input: Vec<u8> = (0..700u32).map(|i| (i % 251) as u8).collect();
+
+        let (shards, len) = block_on(async {
+            let shards = vec![Cursor::new(Vec::new()); rs.total_shards()];
+            let mut writer = AsyncRsWriter::new(rs.clone(), shards, 32);
+            for chunk in input.chunks(50) {
+                writer.write_all(chunk).await.unwrap();
+            }
+            writer.close().await.unwrap();
+            let len = writer.len();
+            let shards: Vec<Vec<u8>> = writer
+                .into_inner()
+                .into_iter()
+                .map(Cursor::into_inner)
+                .collect();
+            (shards, len)
+        });
+        assert_eq!(len, 700);
+        assert!(shards.iter().all(|s| s.len() == 8 * 32));
+
+        let readers: Vec<Option<&[u8]>> = shards
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (i != 0 && i != 3).then_some(&s[..]))
+            .collect();
+        let mut output = Vec::new();
+        block_on(AsyncRsReader::new(rs, readers, 32, len).read_to_end(&mut output)).unwrap();
+        assert_eq!(output, input);
+    }
+}
@@ -0,0 +1,83 @@
+use super::{Error, ReedSolomon};
+
+/// Arbitrary-length data encoded by [`ReedSolomon::encode_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedBytes {
+    /// The length of the original data, which the padding is stripped to.
+    pub len: usize,
+    /// The `k + m` shards of each stripe, data first; a lost shard is `None`.
+    pub stripes: Vec<Vec<Option<Vec<u8>>>>,
+}
+
+impl ReedSolomon {
+    /// Cuts `data` into stripes of `k` shards of `shard_size` bytes and
+    /// encodes each one.
+    ///
+    /// The final stripe is short: its shards are only as long as needed to
+    /// hold the rest of the data, zero-padded to a multiple of `k`.
+    pub fn encode_bytes(&self, data: &[u8], shard_size: usize) -> EncodedBytes {
+        assert!(shard_size > 0, "shard size must be positive");
+        let stripes = data
+            .chunks(self.data_shards * shard_size)
+            .map(|stripe| {
+                let mut shards = self.split(stripe);
+                let refs: Vec<&[u8]> = shards[..self.data_shards].iter().map(|s| &s[..]).collect();
+                let parity = self.encode(&refs).expect("split shards match the codec");
+                shards.truncate(self.data_shards);
+                shards.extend(parity);
+                shards.into_iter().map(Some).collect()
+            })
+            .collect();
+        EncodedBytes {
+            len: data.len(),
+            stripes,
+        }
+    }
+
+    /// Reconstructs what is needed of each stripe and returns the original
+    /// data.
+    pub fn decode_to_bytes(&self, mut encoded: EncodedBytes) -> Result<Vec<u8>, Error> {
+        let wanted: Vec<usize> = (0..self.data_shards).collect();
+        let mut out = Vec::with_capacity(encoded.len);
+        for stripe in &mut encoded.stripes {
+            self.reconstruct_only(stripe, &wanted)?;
+            for shard in stripe.iter().take(self.data_shards).flatten() {
+                out.extend_from_slice(shard);
+            }
+        }
+        if out.len() < encoded.len {
+            return Err(Error::TooFewShards);
+        }
+        out.truncate(encoded.len);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let rs = ReedSolomon::new(3, 2).unwrap();
+        for len in [0usize, 1, 2, 29, 30, 31, 100] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 37 + 11) as u8).collect();
+            let mut encoded = rs.encode_bytes(&data, 10);
+            assert_eq!(encoded.stripes.len(), len.div_ceil(30));
+            if let Some(last) = encoded.stripes.last_mut() {
+                // the short stripe holds the remainder rounded up to 3 bytes
+                let rest = (len - 1) % 30 + 1;
+                assert_eq!(last[0].as_ref().unwrap().len(), rest.div_ceil(3));
+                last[0] = None;
+                last[4] = None;
+            }
+            assert_eq!(rs.decode_to_bytes(encoded).unwrap(), data, "len {len}");
+        }
+
+        let mut encoded = rs.encode_bytes(&[1; 50], 10);
+        for i in 0..3 {
+            encoded.stripes[1][i] = None;
+        }
+        assert_eq!(rs.decode_to_bytes(encoded), Err(Error::TooFewShards));
+    }
+}
@@ -0,0 +1,393 @@
+use super::{check_shard_counts, check_shards, Error, ReedSolomon};
+use crate::slice::{add_slice, mul_add_slice, mul_slice};
+use crate::Galois;
+
+/// An experimental Clay code: an MDS array code with `k` data and `m`
+/// parity shards that repairs one lost shard by reading only `1 / m` of
+/// every other shard, the least possible.
+///
+/// Every shard is cut into `α = m^t` sub-chunks, where `t = ⌈(k + m) / m⌉`,
+/// so shard lengths must be multiples of [`sub_chunks`](ClayCode::sub_chunks).
+/// Nodes are laid out on an `m × t` grid, padded with zero data nodes when
+/// `m` does not divide `k + m`. Sub-chunks are coupled in pairs across
+/// layers, each layer of the uncoupled sub-chunks being a codeword of the
+/// scalar code of [`ReedSolomon::new`], following Vajha et al., "Clay Codes:
+/// Moulding MDS Codes to Yield an MSR Code" (FAST 2018).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClayCode {
+    data_shards: usize,
+    parity_shards: usize,
+    // zero data nodes appended to the data to fill the grid
+    padding: usize,
+    // the grid has `q = m` rows and `t` columns
+    t: usize,
+    alpha: usize,
+    rs: ReedSolomon,
+}
+
+// The pair coupling coefficient; any γ with γ ≠ 0 and γ^2 ≠ 1 will do.
+const GAMMA: Galois = Galois(2);
+
+impl ClayCode {
+    /// Fails unless `k > 0`, `m > 0`, the padded `k + m` is at most 256 and
+    /// `α` at most 65536.
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<Self, Error> {
+        check_shard_counts(data_shards, parity_shards)?;
+        let q = parity_shards;
+        if q == 0 {
+            return Err(Error::InvalidShardCount);
+        }
+        let t = (data_shards + q).div_ceil(q);
+        let padding = q * t - data_shards - q;
+        let alpha = (0..t)
+            .try_fold(1usize, |a, _| a.checked_mul(q).filter(|a| *a <= 1 << 16))
+            .ok_or(Error::InvalidShardCount)?;
+        let rs = ReedSolomon::new(data_shards + padding, q)?;
+        Ok(ClayCode {
+            data_shards,
+            parity_shards,
+            padding,
+            t,
+            alpha,
+            rs,
+        })
+    }
+
+    pub fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    pub fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+
+    /// The number `α` of sub-chunks per shard.
+    pub fn sub_chunks(&self) -> usize {
+        self.alpha
+    }
+
+    /// Computes the parity shards for `data`.
+    pub fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        let len = check_shards(data, self.data_shards)?;
+        if !len.is_multiple_of(self.alpha) {
+            return Err(Error::ShardSizeMismatch);
+        }
+        let mut shards: Vec<Option<Vec<u8>>> = data.iter().map(|d| Some(d.to_vec())).collect();
+        shards.resize(self.total_shards(), None);
+        self.reconstruct(&mut shards)?;
+        Ok(shards
+            .drain(self.data_shards..)
+            .map(|s| s.expect("reconstructed"))
+            .collect())
+    }
+
+    /// Fills in every missing (`None`) shard from the present ones.
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        if shards.len() != self.total_shards() {
+            return Err(Error::WrongShardCount);
+        }
+        let present: Vec<usize> = (0..shards.len()).filter(|i| shards[*i].is_some()).collect();
+        if present.len() == shards.len() {
+            return Ok(());
+        }
+        if present.len() < self.data_shards {
+            return Err(Error::TooFewShards);
+        }
+        let len = shards[present[0]].as_ref().map_or(0, |s| s.len());
+        if !len.is_multiple_of(self.alpha)
+            || present
+                .iter()
+                .any(|i| shards[*i].as_ref().map_or(0, |s| s.len()) != len)
+        {
+            return Err(Error::ShardSizeMismatch);
+        }
+
+        let erased: Vec<usize> = (0..self.nodes())
+            .filter(|node| self.shard_of(*node).is_some_and(|s| shards[s].is_none()))
+            .collect();
+        let mut nodes: Vec<Vec<u8>> = (0..self.nodes())
+            .map(|node| {
+                self.shard_of(node)
+                    .and_then(|s| shards[s].take())
+                    .unwrap_or_else(|| vec![0; len])
+            })
+            .collect();
+        self.decode(&mut nodes, &erased, len / self.alpha);
+
+        for (node, content) in nodes.into_iter().enumerate() {
+            if let Some(s) = self.shard_of(node) {
+                shards[s] = Some(content);
+            }
+        }
+        Ok(())
+    }
+
+    /// The sub-chunks every helper sends to repair shard `lost`: the `α / m`
+    /// layers `z` whose digit for `lost`'s column is its row.
+    pub fn repair_sub_chunks(&self, lost: usize) -> Vec<usize> {
+        assert!(lost < self.total_shards(), "shard index out of range");
+        let (x0, y0) = self.coords(self.node_of(lost));
+        (0..self.alpha)
+            .filter(|z| self.digit(*z, y0) == x0)
+            .collect()
+    }
+
+    /// Rebuilds shard `lost` from the [`repair_sub_chunks`](ClayCode::repair_sub_chunks)
+    /// of every other shard, concatenated in that order; `helpers` has one
+    /// entry per shard and the entry for `lost` is ignored.
+    pub fn repair(&self, lost: usize, helpers: &[&[u8]]) -> Result<Vec<u8>, Error> {
+        if helpers.len() != self.total_shards() {
+            return Err(Error::WrongShardCount);
+        }
+        if lost >= helpers.len() {
+            return Err(Error::InvalidShardIndex);
+        }
+        let layers = self.repair_sub_chunks(lost);
+        let part = helpers[(lost + 1) % helpers.len()].len();
+        if !part.is_multiple_of(layers.len())
+            || (0..helpers.len()).any(|s| s != lost && helpers[s].len() != part)
+        {
+            return Err(Error::ShardSizeMismatch);
+        }
+        let sub = part / layers.len();
+
+        let i0 = self.node_of(lost);
+        let (_, y0) = self.coords(i0);
+        // position of each repair layer within a helper's sub-chunks
+        let mut slot = vec![usize::MAX; self.alpha];
+        for (p, z) in layers.iter().enumerate() {
+            slot[*z] = p;
+        }
+        let zeros = vec![0u8; sub];
+        let c = |node: usize, z: usize| -> &[u8] {
+            match self.shard_of(node) {
+                Some(s) => &helpers[s][slot[z] * sub..(slot[z] + 1) * sub],
+                None => &zeros,
+            }
+        };
+
+        let mut out = vec![0u8; self.alpha * sub];
+        let column: Vec<usize> = (0..self.parity_shards).map(|x| self.node(x, y0)).collect();
+        for z in &layers {
+            let z = *z;
+            // the uncoupled layer, with the lost node's column erased
+            let mut layer: Vec<Option<Vec<u8>>> = (0..self.nodes())
+                .map(|node| {
+                    if column.contains(&node) {
+                        return None;
+                    }
+                    let mut u = vec![0u8; sub];
+                    match self.companion(node, z) {
+                        None => u.copy_from_slice(c(node, z)),
+                        Some((pair, pz)) => uncouple(c(node, z), c(pair, pz), &mut u),
+                    }
+                    Some(u)
+                })
+                .collect();
+            self.rs
+                .reconstruct_only(&mut layer, &column)
+                .expect("the column fits the parity");
+
+            for node in column.iter().copied() {
+                let u = layer[node].as_deref().expect("reconstructed");
+                let (x, _) = self.coords(node);
+                if node == i0 {
+                    out[z * sub..(z + 1) * sub].copy_from_slice(u);
+                    continue;
+                }
+                // C(j, z) = U(j, z) + γ U(i0, z*) and C(i0, z*) = U(i0, z*) + γ U(j, z)
+                let zs = self.with_digit(z, y0, x);
+                let dst = &mut out[zs * sub..(zs + 1) * sub];
+                let mut diff = c(node, z).to_vec();
+                add_slice(u, &mut diff);
+                mul_slice(GAMMA.inv(), &diff, dst);
+                mul_add_slice(GAMMA, u, dst);
+            }
+        }
+        Ok(out)
+    }
+
+    // Decodes the erased nodes (at most m) of `nodes`, whose sub-chunks are
+    // `sub` bytes long, layer by layer in order of intersection score.
+    fn decode(&self, nodes: &mut [Vec<u8>], erased: &[usize], sub: usize) {
+        let score = |z: usize| {
+            erased
+                .iter()
+                .filter(|e| {
+                    let (x, y) = self.coords(**e);
+                    self.digit(z, y) == x
+                })
+                .count()
+        };
+        let mut order: Vec<usize> = (0..self.alpha).collect();
+        order.sort_by_key(|z| score(*z));
+
+        let chunk = |v: &[u8], z: usize| v[z * sub..(z + 1) * sub].to_vec();
+        let mut uncoupled = vec![vec![0u8; self.alpha * sub]; self.nodes()];
+        for z in order {
+            let mut layer: Vec<Option<Vec<u8>>> = (0..self.nodes())
+                .map(|node| {
+                    if erased.contains(&node) {
+                        return None;
+                    }
+                    let mut u = chunk(&nodes[node], z);
+                    match self.companion(node, z) {
+                        None => {}
+                        Some((pair, pz)) if !erased.contains(&pair) => {
+                            uncouple(&chunk(&nodes[node], z), &chunk(&nodes[pair], pz), &mut u)
+                        }
+                        // the pair's layer has a lower score and is done
+                        Some((pair, pz)) => {
+                            mul_add_slice(GAMMA, &chunk(&uncoupled[pair], pz), &mut u)
+                        }
+                    }
+                    Some(u)
+                })
+                .collect();
+            self.rs
+                .reconstruct_only(&mut layer, erased)
+                .expect("at most m erasures");
+            for (node, u) in layer.into_iter().enumerate() {
+                let u = u.expect("reconstructed");
+                uncoupled[node][z * sub..(z + 1) * sub].copy_from_slice(&u);
+            }
+        }
+
+        // C(i, z) = U(i, z) + γ U(pair, z')
+        for e in erased {
+            for z in 0..self.alpha {
+                let dst = &mut nodes[*e][z * sub..(z + 1) * sub];
+                dst.copy_from_slice(&uncoupled[*e][z * sub..(z + 1) * sub]);
+                if let Some((pair, pz)) = self.companion(*e, z) {
+                    mul_add_slice(GAMMA, &uncoupled[pair][pz * sub..(pz + 1) * sub], dst);
+                }
+            }
+        }
+    }
+
+    fn nodes(&self) -> usize {
+        self.parity_shards * self.t
+    }
+
+    // Data shards come first, then the padding nodes, then parity.
+    fn node_of(&self, shard: usize) -> usize {
+        if shard < self.data_shards {
+            shard
+        } else {
+            shard + self.padding
+        }
+    }
+
+    fn shard_of(&self, node: usize) -> Option<usize> {
+        let k = self.data_shards;
+        if node < k {
+            Some(node)
+        } else if node < k + self.padding {
+            None
+        } else {
+            Some(node - self.padding)
+        }
+    }
+
+    fn coords(&self, node: usize) -> (usize, usize) {
+        (node % self.parity_shards, node / self.parity_shards)
+    }
+
+    fn node(&self, x: usize, y: usize) -> usize {
+        y * self.parity_shards + x
+    }
+
+    fn digit(&self, z: usize, y: usize) -> usize {
+        z / self.parity_shards.pow(y as u32) % self.parity_shards
+    }
+
+    fn with_digit(&self, z: usize, y: usize, v: usize) -> usize {
+        let p = self.parity_shards.pow(y as u32);
+        z - self.digit(z, y) * p + v * p
+    }
+
+    // The sub-chunk coupled with sub-chunk `z` of `node`, unless it is
+    // uncoupled (its layer's digit for its column is its row).
+    fn companion(&self, node: usize, z: usize) -> Option<(usize, usize)> {
+        let (x, y) = self.coords(node);
+        let zy = self.digit(z, y);
+        (zy != x).then(|| (self.node(zy, y), self.with_digit(z, y, x)))
+    }
+}
+
+// u = (c + γ c_pair) / (1 + γ^2), inverting the pairwise coupling
+// C = U + γ U_pair, C_pair = U_pair + γ U.
+fn uncouple(c: &[u8], c_pair: &[u8], u: &mut [u8]) {
+    let scale = (Galois::identity() + GAMMA * GAMMA).inv();
+    mul_slice(scale, c, u);
+    mul_add_slice(scale * GAMMA, c_pair, u);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(code: &ClayCode, sub: usize) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let len = code.sub_chunks() * sub;
+        let data: Vec<Vec<u8>> = (0..code.data_shards())
+            .map(|s| (0..len).map(|i| (i * 59 + s * 23 + 1) as u8).collect())
+            .collect();
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = code.encode(&refs).unwrap();
+        let full = data.iter().chain(&parity).cloned().collect();
+        (data, full)
+    }
+
+    #[test]
+    fn test_mds() {
+        for (k, m) in [(4, 2), (5, 3), (2, 2)] {
+            let code = ClayCode::new(k, m).unwrap();
+            let (_, full) = sample(&code, 3);
+            let n = k + m;
+            for mask in 0u32..1 << n {
+                if mask.count_ones() as usize != m {
+                    continue;
+                }
+                let mut shards: Vec<Option<Vec<u8>>> = full
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| (mask & (1 << i) == 0).then(|| s.clone()))
+                    .collect();
+                code.reconstruct(&mut shards).unwrap();
+                assert!(shards.iter().zip(&full).all(|(s, f)| s.as_ref() == Some(f)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_repair_reads_one_mth() {
+        let code = ClayCode::new(5, 3).unwrap();
+        assert_eq!(code.sub_chunks(), 27);
+        let (_, full) = sample(&code, 4);
+        for lost in 0..8 {
+            let layers = code.repair_sub_chunks(lost);
+            assert_eq!(layers.len(), 9);
+            let helpers: Vec<Vec<u8>> = full
+                .iter()
+                .map(|s| {
+                    layers
+                        .iter()
+                        .flat_map(|z| s[z * 4..(z + 1) * 4].to_vec())
+                        .collect()
+                })
+                .collect();
+            let refs: Vec<&[u8]> = helpers.iter().map(|h| &h[..]).collect();
+            assert_eq!(
+                code.repair(lost, &refs).unwrap(),
+                full[lost],
+                "shard {lost}"
+            );
+        }
+        // α = 4^51 is far too many sub-chunks
+        assert!(ClayCode::new(200, 4).is_err());
+    }
+}
@@ -0,0 +1,850 @@
+use std::fmt;
+
+use crate::constants::PRIMITIVE_POLYNOMIAL;
+use crate::isomorphism::FieldMap;
+use crate::poly::Poly;
+use crate::Galois;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeError {
+    /// `k` is zero, `k >= n` or `n` exceeds 255.
+    InvalidParameters,
+    /// The message or codeword does not have the length the code expects.
+    WrongLength,
+    /// More errors than the code can correct.
+    TooManyErrors,
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodeError::InvalidParameters => write!(f, "invalid code parameters"),
+            CodeError::WrongLength => write!(f, "wrong message or codeword length"),
+            CodeError::TooManyErrors => write!(f, "too many errors to correct"),
+        }
+    }
+}
+
+impl std::error::Error for CodeError {}
+
+/// How [`RsCode::decode`] solves the key equation `S Λ ≡ Ω mod x^(n-k)`.
+///
+/// Both produce the same corrections for decodable words; the Euclidean
+/// variant is useful as a cross-check and has a simpler control flow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecoderAlgorithm {
+    #[default]
+    BerlekampMassey,
+    /// Sugiyama's extended Euclidean algorithm on `x^(n-k)` and `S(x)`.
+    Euclidean,
+    /// Gao's interpolation-based decoder, which recovers the message
+    /// polynomial of the equivalent evaluation code directly.
+    Gao,
+}
+
+/// The conventions that pin down an RS code bit-for-bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RsParams {
+    /// The field's reduction polynomial including the `x^8` term, e.g.
+    /// `0x11d`. It must be irreducible.
+    pub field_poly: u16,
+    /// The primitive element `γ` whose powers are the generator roots,
+    /// written in the field defined by `field_poly`.
+    pub generator: u8,
+    /// The first consecutive root: the generator polynomial has the roots
+    /// `γ^fcr .. γ^(fcr + n - k - 1)`.
+    pub fcr: u8,
+}
+
+impl Default for RsParams {
+    fn default() -> Self {
+        RsParams {
+            field_poly: 0x11d,
+            generator: 2,
+            fcr: 0,
+        }
+    }
+}
+
+/// A classical systematic RS(n, k) code that corrects up to `(n - k) / 2`
+/// symbol errors at unknown positions.
+///
+/// Codewords are the message followed by `n - k` parity bytes, read as a
+/// polynomial with the first byte as the highest-degree coefficient. By
+/// default the field is the crate's own (`0x11d`) and the generator
+/// polynomial has the roots `α^0 .. α^(n-k-1)` with `α = 2`; see
+/// [`RsCode::with_params`] for other conventions.
+///
+/// Codes in other fields are handled by mapping every symbol into the
+/// crate's field with an isomorphism, so [`RsCode::generator`] and
+/// [`RsCode::syndromes`] are expressed in the crate's representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsCode {
+    n: usize,
+    k: usize,
+    params: RsParams,
+    // `params.generator` in the crate's representation
+    gamma: Galois,
+    map: Option<FieldMap>,
+    generator: Poly,
+    algorithm: DecoderAlgorithm,
+}
+
+impl RsCode {
+    pub fn new(n: usize, k: usize) -> Result<Self, CodeError> {
+        RsCode::with_params(n, k, RsParams::default())
+    }
+
+    pub fn with_params(n: usize, k: usize, params: RsParams) -> Result<Self, CodeError> {
+        if k == 0 || k >= n || n > 255 {
+            return Err(CodeError::InvalidParameters);
+        }
+
+        let map = if params.field_poly == PRIMITIVE_POLYNOMIAL as u16 {
+            None
+        } else {
+            Some(FieldMap::new(params.field_poly).ok_or(CodeError::InvalidParameters)?)
+        };
+        let gamma = Galois::new(
+            map.as_ref()
+                .map_or(params.generator, |m| m.forward(params.generator)),
+        );
+        if !is_primitive(gamma) {
+            return Err(CodeError::InvalidParameters);
+        }
+
+        let mut code = RsCode {
+            n,
+            k,
+            params,
+            gamma,
+            map,
+            generator: Poly::zero(),
+            algorithm: DecoderAlgorithm::default(),
+        };
+        let roots: Vec<Galois> = (0..n - k).map(|i| code.root(i)).collect();
+        code.generator = Poly::from_roots(&roots);
+        Ok(code)
+    }
+
+    // Applies `outer` to every symbol on its way in, ahead of the field map,
+    // so that the code works on symbols in a different basis.
+    pub(crate) fn with_symbol_map(mut self, outer: FieldMap) -> Self {
+        self.map = Some(match &self.map {
+            Some(inner) => outer.then(inner),
+            None => outer,
+        });
+        self
+    }
+
+    /// The code shortened by `by` further symbols: the leading `by` message
+    /// symbols are fixed to zero and never sent.
+    ///
+    /// Leading zeros do not change the parity or the syndromes, so the
+    /// shortened code shares its generator with `self`; an RS(204, 188) code
+    /// is `RsCode::new(255, 239)?.shortened(51)`.
+    pub fn shortened(&self, by: usize) -> Result<Self, CodeError> {
+        if by >= self.k {
+            return Err(CodeError::InvalidParameters);
+        }
+        Ok(RsCode {
+            n: self.n - by,
+            k: self.k - by,
+            ..self.clone()
+        })
+    }
+
+    pub fn with_algorithm(mut self, algorithm: DecoderAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    pub fn algorithm(&self) -> DecoderAlgorithm {
+        self.algorithm
+    }
+
+    pub fn params(&self) -> RsParams {
+        self.params
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn parity_len(&self) -> usize {
+        self.n - self.k
+    }
+
+    /// The number of symbol errors the code can always correct.
+    pub fn capability(&self) -> usize {
+        self.parity_len() / 2
+    }
+
+    pub fn generator(&self) -> &Poly {
+        &self.generator
+    }
+
+    /// Returns the codeword for `msg`: the message followed by its parity.
+    pub fn encode(&self, msg: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if msg.len() != self.k {
+            return Err(CodeError::WrongLength);
+        }
+        let mut codeword = vec![0u8; self.n];
+        codeword[..self.k].copy_from_slice(msg);
+        self.encode_parity(msg, &mut codeword[self.k..])?;
+        Ok(codeword)
+    }
+
+    /// Computes the parity for `msg` as the remainder of `msg(x) * x^(n-k)`
+    /// divided by the generator.
+    pub fn encode_parity(&self, msg: &[u8], parity: &mut [u8]) -> Result<(), CodeError> {
+        if msg.len() != self.k || parity.len() != self.parity_len() {
+            return Err(CodeError::WrongLength);
+        }
+
+        parity.fill(0);
+        for m in msg {
+            self.shift_in(parity, *m);
+        }
+        self.map_from_std(parity);
+        Ok(())
+    }
+
+    // One step of the division by the generator: dividing by a monic
+    // polynomial is a plain LFSR, fed highest degree first.
+    fn shift_in(&self, regs: &mut [u8], symbol: u8) {
+        let g = self.generator.coeffs();
+        let p = regs.len();
+        let feedback = Galois::new(self.to_std(symbol)) + Galois::new(regs[0]);
+        regs.copy_within(1.., 0);
+        regs[p - 1] = 0;
+        if feedback != Galois::zero() {
+            for (j, r) in regs.iter_mut().enumerate() {
+                *r = u8::from(Galois::new(*r) + feedback * g[p - 1 - j]);
+            }
+        }
+    }
+
+    /// `S_j = r(γ^(fcr + j))` for `j in 0..n-k`.
+    pub fn syndromes(&self, codeword: &[u8]) -> Vec<Galois> {
+        (0..self.parity_len())
+            .map(|j| {
+                let x = self.root(j);
+                codeword.iter().fold(Galois::zero(), |acc, c| {
+                    acc * x + Galois::new(self.to_std(*c))
+                })
+            })
+            .collect()
+    }
+
+    /// Corrects `codeword` in place, returning the number of symbols fixed.
+    /// On error the codeword is left as it was.
+    pub fn decode(&self, codeword: &mut [u8]) -> Result<usize, CodeError> {
+        self.decode_with_erasures(codeword, &[])
+    }
+
+    /// Corrects `codeword` in place given the byte positions of known
+    /// erasures, returning the number of symbols fixed.
+    ///
+    /// `e` errors and `s` erasures are corrected whenever `2e + s <= n - k`.
+    /// The erased bytes may hold any value on input.
+    pub fn decode_with_erasures(
+        &self,
+        codeword: &mut [u8],
+        erasures: &[usize],
+    ) -> Result<usize, CodeError> {
+        if codeword.len() != self.n {
+            return Err(CodeError::WrongLength);
+        }
+        if erasures.iter().any(|e| *e >= self.n) {
+            return Err(CodeError::WrongLength);
+        }
+        let mut erasures = erasures.to_vec();
+        erasures.sort_unstable();
+        erasures.dedup();
+        if erasures.len() > self.parity_len() {
+            return Err(CodeError::TooManyErrors);
+        }
+
+        self.map_to_std(codeword);
+        let result = self.decode_std(codeword, &erasures);
+        self.map_from_std(codeword);
+        result
+    }
+
+    // Decodes a codeword that is already in the crate's representation.
+    fn decode_std(&self, codeword: &mut [u8], erasures: &[usize]) -> Result<usize, CodeError> {
+        let syndromes = self.syndromes_std(codeword);
+        if syndromes.iter().all(|s| *s == Galois::zero()) {
+            return Ok(0);
+        }
+
+        // Γ(x) = Π (1 - X_j x) over the erasure locators X_j
+        let mut gamma = Poly::one();
+        for pos in erasures {
+            let x = self.pow((self.n - 1 - pos) as isize);
+            gamma = &gamma * &Poly::new(vec![Galois::identity(), x]);
+        }
+
+        let (locator, omega) = match self.algorithm {
+            DecoderAlgorithm::BerlekampMassey => {
+                let (locator, l) = berlekamp_massey_with(&syndromes, &gamma, erasures.len());
+                // an LFSR longer than its connection polynomial's degree, or
+                // than the parity can pin down, means the errors are too many
+                let errors = l - erasures.len();
+                if locator.degree() != Some(l) || 2 * errors + erasures.len() > self.parity_len() {
+                    return Err(CodeError::TooManyErrors);
+                }
+                let omega = (&Poly::new(syndromes) * &locator).truncate(self.parity_len());
+                (locator, omega)
+            }
+            DecoderAlgorithm::Euclidean => {
+                sugiyama_with(&syndromes, &gamma, erasures.len()).ok_or(CodeError::TooManyErrors)?
+            }
+            DecoderAlgorithm::Gao => return self.decode_gao(codeword, erasures),
+        };
+
+        let degree = locator.degree().unwrap_or(0);
+        let errors = degree.saturating_sub(erasures.len());
+        if degree < erasures.len() || 2 * errors + erasures.len() > self.parity_len() {
+            return Err(CodeError::TooManyErrors);
+        }
+
+        let positions = self.chien_search(&locator);
+        if positions.len() != degree {
+            return Err(CodeError::TooManyErrors);
+        }
+
+        // corrected on a copy, so that a failed check leaves the word as is
+        let magnitudes = self.forney(&omega, &locator, &positions);
+        let mut corrected = codeword.to_vec();
+        let mut fixed = 0;
+        for (pos, e) in positions.iter().zip(magnitudes) {
+            if e != Galois::zero() {
+                corrected[*pos] = u8::from(Galois::new(corrected[*pos]) + e);
+                fixed += 1;
+            }
+        }
+
+        if self
+            .syndromes_std(&corrected)
+            .iter()
+            .any(|s| *s != Galois::zero())
+        {
+            return Err(CodeError::TooManyErrors);
+        }
+        codeword.copy_from_slice(&corrected);
+        Ok(fixed)
+    }
+
+    fn syndromes_std(&self, codeword: &[u8]) -> Vec<Galois> {
+        (0..self.parity_len())
+            .map(|j| {
+                let x = self.root(j);
+                codeword
+                    .iter()
+                    .fold(Galois::zero(), |acc, c| acc * x + Galois::new(*c))
+            })
+            .collect()
+    }
+
+    // The full-length cyclic code with zeros γ^b .. γ^(b+n-k-1) is the
+    // evaluation code { (γ^(j(1-b)) f(γ^j))_j : deg f < 255 - (n - k) },
+    // where `j` is the coefficient index. Shortened positions `j >= n` are
+    // known zeros and erased positions are simply left out of the
+    // interpolation.
+    fn decode_gao(&self, codeword: &mut [u8], erasures: &[usize]) -> Result<usize, CodeError> {
+        let dimension = 255 - self.parity_len();
+        let twist = 1 - self.params.fcr as isize;
+        let mut points = Vec::with_capacity(255);
+        let mut values = Vec::with_capacity(255);
+        for j in 0..255 {
+            let byte = if j < self.n {
+                let i = self.n - 1 - j;
+                if erasures.binary_search(&i).is_ok() {
+                    continue;
+                }
+                Galois::new(codeword[i])
+            } else {
+                Galois::zero()
+            };
+            points.push(self.pow(j as isize));
+            values.push(byte / self.pow(j as isize * twist));
+        }
+
+        let f = gao(&points, &values, dimension).ok_or(CodeError::TooManyErrors)?;
+        if (self.n..255).any(|j| f.eval(self.pow(j as isize)) != Galois::zero()) {
+            return Err(CodeError::TooManyErrors);
+        }
+
+        let mut fixed = 0;
+        for j in 0..self.n {
+            let v = u8::from(self.pow(j as isize * twist) * f.eval(self.pow(j as isize)));
+            let i = self.n - 1 - j;
+            if codeword[i] != v {
+                codeword[i] = v;
+                fixed += 1;
+            }
+        }
+        Ok(fixed)
+    }
+
+    // Byte positions `i` whose locator `X = γ^(n-1-i)` satisfies `Λ(X^-1) = 0`.
+    fn chien_search(&self, locator: &Poly) -> Vec<usize> {
+        (0..self.n)
+            .filter(|i| {
+                let power = (self.n - 1 - i) as isize;
+                locator.eval(self.pow(-power)) == Galois::zero()
+            })
+            .collect()
+    }
+
+    // Forney's formula for the first consecutive root γ^b:
+    // e = X^(1-b) * Ω(X^-1) / Λ'(X^-1), where Ω = S Λ mod x^(n-k).
+    fn forney(&self, omega: &Poly, locator: &Poly, positions: &[usize]) -> Vec<Galois> {
+        let dlocator = locator.derivative();
+        let twist = 1 - self.params.fcr as isize;
+
+        positions
+            .iter()
+            .map(|i| {
+                let power = (self.n - 1 - i) as isize;
+                let x_inv = self.pow(-power);
+                self.pow(power * twist) * omega.eval(x_inv) / dlocator.eval(x_inv)
+            })
+            .collect()
+    }
+
+    // γ^e for any integer exponent.
+    fn pow(&self, e: isize) -> Galois {
+        self.gamma.exp(e.rem_euclid(255) as u32)
+    }
+
+    // The `j`-th generator root, γ^(fcr + j).
+    fn root(&self, j: usize) -> Galois {
+        self.pow(self.params.fcr as isize + j as isize)
+    }
+
+    fn to_std(&self, v: u8) -> u8 {
+        self.map.as_ref().map_or(v, |m| m.forward(v))
+    }
+
+    fn map_to_std(&self, data: &mut [u8]) {
+        if let Some(m) = &self.map {
+            data.iter_mut().for_each(|v| *v = m.forward(*v));
+        }
+    }
+
+    fn map_from_std(&self, data: &mut [u8]) {
+        if let Some(m) = &self.map {
+            data.iter_mut().for_each(|v| *v = m.inverse(*v));
+        }
+    }
+}
+
+/// Computes the parity of an [`RsCode`] message fed one symbol at a time,
+/// with the `n - k` remainder registers of a shift-register encoder.
+#[derive(Debug, Clone)]
+pub struct SystematicEncoder<'a> {
+    code: &'a RsCode,
+    regs: Vec<u8>,
+    consumed: usize,
+}
+
+impl<'a> SystematicEncoder<'a> {
+    pub fn new(code: &'a RsCode) -> Self {
+        SystematicEncoder {
+            code,
+            regs: vec![0; code.parity_len()],
+            consumed: 0,
+        }
+    }
+
+    /// The number of message symbols consumed so far.
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    /// Feeds the next message symbol, failing once `k` have been fed.
+    pub fn push(&mut self, symbol: u8) -> Result<(), CodeError> {
+        if self.consumed == self.code.k {
+            return Err(CodeError::WrongLength);
+        }
+        self.code.shift_in(&mut self.regs, symbol);
+        self.consumed += 1;
+        Ok(())
+    }
+
+    pub fn push_slice(&mut self, symbols: &[u8]) -> Result<(), CodeError> {
+        if symbols.len() > self.code.k - self.consumed {
+            return Err(CodeError::WrongLength);
+        }
+        symbols.iter().try_for_each(|s| self.push(*s))
+    }
+
+    /// Returns the parity once all `k` message symbols have been fed.
+    pub fn finish(mut self) -> Result<Vec<u8>, CodeError> {
+        if self.consumed != self.code.k {
+            return Err(CodeError::WrongLength);
+        }
+        self.code.map_from_std(&mut self.regs);
+        Ok(self.regs)
+    }
+}
+
+// An element is primitive when its order is exactly 255 = 3 * 5 * 17.
+fn is_primitive(g: Galois) -> bool {
+    g != Galois::zero()
+        && [3, 5, 17]
+            .iter()
+            .all(|p| g.exp(255 / p) != Galois::identity())
+}
+
+// Berlekamp-Massey started from the erasure locator `gamma` of degree
+// `erasures`, as for errors-and-erasures decoding. Returns the shortest
+// connection polynomial (with `Λ_0 = 1`) that generates `s` and is a
+// multiple of `gamma`, i.e. the combined errata locator, with the length of
+// its LFSR, which may exceed its degree.
+pub(crate) fn berlekamp_massey_with(s: &[Galois], gamma: &Poly, erasures: usize) -> (Poly, usize) {
+    let mut c = gamma.clone();
+    let mut b = gamma.clone();
+    let mut l = erasures;
+    let mut shift = 1;
+    let mut last = Galois::identity();
+
+    for n in erasures..s.len() {
+        let mut d = Galois::zero();
+        for i in 0..=l.min(n) {
+            d += c.coeff(i) * s[n - i];
+        }
+
+        if d == Galois::zero() {
+            shift += 1;
+        } else if 2 * l <= n + erasures {
+            let t = c.clone();
+            c = &c - &b.scale(d / last).shift(shift);
+            l = n + 1 + erasures - l;
+            b = t;
+            last = d;
+            shift = 1;
+        } else {
+            c = &c - &b.scale(d / last).shift(shift);
+            shift += 1;
+        }
+    }
+    (c, l)
+}
+
+// Solves the key equation with the extended Euclidean algorithm on `x^N` and
+// the erasure-modified syndromes `S Γ mod x^N`, stopping once the remainder
+// degree drops below `(N + erasures) / 2`. Returns the errata locator and
+// evaluator normalized so that `Λ_0 = 1`.
+fn sugiyama_with(s: &[Galois], gamma: &Poly, erasures: usize) -> Option<(Poly, Poly)> {
+    let n = s.len();
+    let mut r_prev = Poly::monomial(Galois::identity(), n);
+    let mut r = (&Poly::new(s.to_vec()) * gamma).truncate(n);
+    let mut t_prev = Poly::zero();
+    let mut t = Poly::one();
+
+    while r.degree().is_some_and(|d| 2 * d >= n + erasures) {
+        let (q, rem) = r_prev.div_rem(&r);
+        let t_next = &t_prev - &(&q * &t);
+        r_prev = std::mem::replace(&mut r, rem);
+        t_prev = std::mem::replace(&mut t, t_next);
+    }
+
+    let t0 = t.coeff(0);
+    if t0 == Galois::zero() {
+        return None;
+    }
+    let inv = t0.inv();
+    Some((&t.scale(inv) * gamma, r.scale(inv)))
+}
+
+/// Gao's decoder for the evaluation code `{ (f(a_i))_i : deg f < k }`:
+/// returns the message polynomial `f` if `values` is within
+/// `(points.len() - k) / 2` errors of a codeword.
+pub(crate) fn gao(points: &[Galois], values: &[Galois], k: usize) -> Option<Poly> {
+    let n = points.len();
+    if k > n {
+        return None;
+    }
+
+    let mut r_prev = Poly::from_roots(points);
+    let mut r = Poly::interpolate(points, values);
+    let mut t_prev = Poly::zero();
+    let mut t = Poly::one();
+
+    // partial extended Euclid on (g0, g1), stopping below degree (n + k) / 2
+    while r.degree().is_some_and(|d| 2 * d >= n + k) {
+        let (q, rem) = r_prev.div_rem(&r);
+        let t_next = &t_prev - &(&q * &t);
+        r_prev = std::mem::replace(&mut r, rem);
+        t_prev = std::mem::replace(&mut t, t_next);
+    }
+
+    let (f, rem) = r.div_rem(&t);
+    if !rem.is_zero() || f.degree().is_some_and(|d| d >= k) {
+        return None;
+    }
+    Some(f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lfsr::LfsrRng;
+
+    fn message(k: usize, seed: usize) -> Vec<u8> {
+        (0..k).map(|i| (i * 73 + seed * 19 + 11) as u8).collect()
+    }
+
+    #[test]
+    fn test_qr_hello_world() {
+        // version 1-M "HELLO WORLD" from the QR code specification walkthrough
+        let code = RsCode::new(26, 16).unwrap();
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        let codeword = code.encode(&data).unwrap();
+        assert_eq!(
+            &codeword[16..],
+            &[196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+        assert!(code
+            .syndromes(&codeword)
+            .iter()
+            .all(|s| *s == Galois::zero()));
+    }
+
+    #[test]
+    fn test_correct_errors() {
+        for (n, k) in [(255, 223), (26, 16), (15, 9), (7, 3)] {
+            let code = RsCode::new(n, k).unwrap();
+            let t = code.capability();
+            for seed in 0..8 {
+                let original = code.encode(&message(k, seed)).unwrap();
+                for errors in 0..=t {
+                    let mut received = original.clone();
+                    for e in 0..errors {
+                        let pos = (seed * 7 + e * (n / t.max(1)) + 1) % n;
+                        received[pos] ^= (e as u8).wrapping_mul(37) | 1;
+                    }
+                    assert_eq!(code.decode(&mut received), Ok(errors));
+                    assert_eq!(received, original);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_euclidean_matches_bm() {
+        let bm = RsCode::new(64, 40).unwrap();
+        let eu = bm.clone().with_algorithm(DecoderAlgorithm::Euclidean);
+        for seed in 0..16 {
+            let original = bm.encode(&message(40, seed)).unwrap();
+            for errors in 0..=bm.capability() {
+                let mut received = original.clone();
+                for e in 0..errors {
+                    received[(seed * 5 + e * 3) % 64] ^= (seed + e + 1) as u8;
+                }
+                let mut a = received.clone();
+                let mut b = received;
+                assert_eq!(bm.decode(&mut a), eu.decode(&mut b));
+                assert_eq!(a, original);
+                assert_eq!(b, original);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gao() {
+        for (n, k) in [(255, 239), (30, 20), (9, 4)] {
+            let code = RsCode::new(n, k)
+                .unwrap()
+                .with_algorithm(DecoderAlgorithm::Gao);
+            for seed in 0..4 {
+                let original = code.encode(&message(k, seed)).unwrap();
+                for errors in 0..=code.capability() {
+                    let mut received = original.clone();
+                    for e in 0..errors {
+                        received[(seed + e * 2) % n] ^= 0x81;
+                    }
+                    assert_eq!(code.decode(&mut received), Ok(errors));
+                    assert_eq!(received, original);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors_and_erasures() {
+        for algorithm in [
+            DecoderAlgorithm::BerlekampMassey,
+            DecoderAlgorithm::Euclidean,
+        ] {
+            let code = RsCode::new(40, 28).unwrap().with_algorithm(algorithm);
+            let original = code.encode(&message(28, 1)).unwrap();
+            for erasures in 0..=12 {
+                let errors = (12 - erasures) / 2;
+                let erased: Vec<usize> = (0..erasures).map(|i| (i * 3 + 1) % 40).collect();
+                let mut received = original.clone();
+                for pos in &erased {
+                    received[*pos] = 0xff;
+                }
+                for e in 0..errors {
+                    received[(e * 7 + 2) % 40] ^= 0x5c;
+                }
+                assert!(code.decode_with_erasures(&mut received, &erased).is_ok());
+                assert_eq!(received, original, "{algorithm:?} s={erasures} e={errors}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        // AES field, a non-trivial generator and first consecutive root
+        let params = RsParams {
+            field_poly: 0x11b,
+            generator: 3,
+            fcr: 120,
+        };
+        for algorithm in [
+            DecoderAlgorithm::BerlekampMassey,
+            DecoderAlgorithm::Euclidean,
+            DecoderAlgorithm::Gao,
+        ] {
+            let code = RsCode::with_params(50, 40, params)
+                .unwrap()
+                .with_algorithm(algorithm);
+            let original = code.encode(&message(40, 2)).unwrap();
+            assert!(code
+                .syndromes(&original)
+                .iter()
+                .all(|s| *s == Galois::zero()));
+
+            let mut received = original.clone();
+            for e in 0..5 {
+                received[e * 9 + 3] ^= 0xa7;
+            }
+            assert_eq!(code.decode(&mut received), Ok(5), "{algorithm:?}");
+            assert_eq!(received, original);
+        }
+
+        // 2 has order 51 in the AES field, and 0x1bb is reducible
+        let bad_generator = RsParams {
+            generator: 2,
+            ..params
+        };
+        assert_eq!(
+            RsCode::with_params(50, 40, bad_generator),
+            Err(CodeError::InvalidParameters)
+        );
+        let bad_field = RsParams {
+            field_poly: 0x1bb,
+            ..params
+        };
+        assert_eq!(
+            RsCode::with_params(50, 40, bad_field),
+            Err(CodeError::InvalidParameters)
+        );
+    }
+
+    #[test]
+    fn test_systematic_encoder() {
+        let aes = RsParams {
+            field_poly: 0x11b,
+            generator: 3,
+            fcr: 120,
+        };
+        for code in [
+            RsCode::new(30, 20).unwrap(),
+            RsCode::with_params(30, 20, aes).unwrap(),
+        ] {
+            let msg = message(20, 5);
+            let mut enc = SystematicEncoder::new(&code);
+            enc.push_slice(&msg[..7]).unwrap();
+            for m in &msg[7..] {
+                enc.push(*m).unwrap();
+            }
+            assert_eq!(enc.consumed(), 20);
+            assert_eq!(enc.push(0), Err(CodeError::WrongLength));
+            assert_eq!(enc.finish().unwrap()[..], code.encode(&msg).unwrap()[20..]);
+        }
+
+        let code = RsCode::new(30, 20).unwrap();
+        let mut enc = SystematicEncoder::new(&code);
+        assert_eq!(enc.push_slice(&[0; 21]), Err(CodeError::WrongLength));
+        enc.push(1).unwrap();
+        assert_eq!(enc.finish(), Err(CodeError::WrongLength));
+    }
+
+    #[test]
+    fn test_shortened() {
+        let full = RsCode::new(255, 239).unwrap();
+        let code = full.shortened(51).unwrap();
+        assert_eq!((code.n(), code.k()), (204, 188));
+        assert_eq!(code, RsCode::new(204, 188).unwrap());
+
+        // a shortened codeword is the full one with its zero prefix dropped
+        let msg = message(188, 4);
+        let mut padded = vec![0u8; 51];
+        padded.extend_from_slice(&msg);
+        let codeword = code.encode(&msg).unwrap();
+        assert_eq!(codeword[..], full.encode(&padded).unwrap()[51..]);
+
+        let mut received = codeword.clone();
+        for e in 0..8 {
+            received[e * 25] ^= 0x3c;
+        }
+        assert_eq!(code.decode(&mut received), Ok(8));
+        assert_eq!(received, codeword);
+        assert_eq!(full.shortened(239), Err(CodeError::InvalidParameters));
+    }
+
+    #[test]
+    fn test_too_many_errors() {
+        let code = RsCode::new(15, 11).unwrap();
+        let original = code.encode(&message(11, 3)).unwrap();
+        let mut corrupt = original.clone();
+        corrupt[0] ^= 1;
+        corrupt[5] ^= 2;
+        corrupt[9] ^= 3;
+        for algorithm in [
+            DecoderAlgorithm::BerlekampMassey,
+            DecoderAlgorithm::Euclidean,
+            DecoderAlgorithm::Gao,
+        ] {
+            let code = code.clone().with_algorithm(algorithm);
+            let mut received = corrupt.clone();
+            assert_eq!(code.decode(&mut received), Err(CodeError::TooManyErrors));
+            assert_eq!(received, corrupt);
+
+            // past t the decoder fails or lands on another codeword
+            let mut rng = LfsrRng::seed_from_u64(algorithm as u64);
+            for _ in 0..200 {
+                let mut received = original.clone();
+                let mut noise = [0u8; 6];
+                rng.fill_bytes(&mut noise);
+                for (i, e) in noise[..3].iter().enumerate() {
+                    received[(noise[3 + i] as usize % 5) * 3 + i] ^= e | 1;
+                }
+                let before = received.clone();
+                match code.decode(&mut received) {
+                    Ok(_) => {
+                        assert_ne!(received, original);
+                        assert_eq!(code.decode(&mut received.clone()), Ok(0));
+                    }
+                    Err(_) => assert_eq!(received, before),
+                }
+            }
+        }
+
+        assert_eq!(code.decode(&mut [0u8; 14]), Err(CodeError::WrongLength));
+        assert_eq!(RsCode::new(256, 200), Err(CodeError::InvalidParameters));
+    }
+}
@@ -0,0 +1,238 @@
+use super::{
+    check_shards, ClayCode, Error, FftReedSolomon, FixedReedSolomon, GenericReedSolomon,
+    ReedSolomon,
+};
+use crate::Field;
+
+/// The operations every `k + m` erasure codec in the crate shares, so that
+/// the codec can be picked at run time, e.g. as a `Box<dyn ErasureCodec>`.
+///
+/// Shards are passed as on [`ReedSolomon`]: `encode` takes the `k` data
+/// shards and returns the `m` parity shards, and `reconstruct` and `verify`
+/// take all `k + m`, data first.
+pub trait ErasureCodec {
+    fn data_shards(&self) -> usize;
+
+    fn parity_shards(&self) -> usize;
+
+    /// The total number of shards, `k + m`.
+    fn shards(&self) -> usize {
+        self.data_shards() + self.parity_shards()
+    }
+
+    /// The storage overhead, `(k + m) / k`.
+    fn overhead(&self) -> f64 {
+        self.shards() as f64 / self.data_shards() as f64
+    }
+
+    /// Computes the parity shards for `data`.
+    fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error>;
+
+    /// Fills in every missing (`None`) shard from the present ones.
+    fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error>;
+
+    /// Checks that the parity shards match the data shards.
+    fn verify(&self, shards: &[&[u8]]) -> Result<bool, Error> {
+        check_shards(shards, self.shards())?;
+        let (data, parity) = shards.split_at(self.data_shards());
+        let expected = self.encode(data)?;
+        Ok(expected.iter().zip(parity).all(|(e, p)| e[..] == p[..]))
+    }
+}
+
+impl ErasureCodec for ReedSolomon {
+    fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        ReedSolomon::encode(self, data)
+    }
+
+    fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        ReedSolomon::reconstruct(self, shards)
+    }
+
+    fn verify(&self, shards: &[&[u8]]) -> Result<bool, Error> {
+        ReedSolomon::verify(self, shards)
+    }
+}
+
+impl ErasureCodec for FftReedSolomon {
+    fn data_shards(&self) -> usize {
+        FftReedSolomon::data_shards(self)
+    }
+
+    fn parity_shards(&self) -> usize {
+        FftReedSolomon::parity_shards(self)
+    }
+
+    fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        FftReedSolomon::encode(self, data)
+    }
+
+    fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        FftReedSolomon::reconstruct(self, shards)
+    }
+}
+
+impl<F: Field> ErasureCodec for GenericReedSolomon<F> {
+    fn data_shards(&self) -> usize {
+        GenericReedSolomon::data_shards(self)
+    }
+
+    fn parity_shards(&self) -> usize {
+        GenericReedSolomon::parity_shards(self)
+    }
+
+    fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        GenericReedSolomon::encode(self, data)
+    }
+
+    fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        GenericReedSolomon::reconstruct(self, shards)
+    }
+}
+
+impl ErasureCodec for ClayCode {
+    fn data_shards(&self) -> usize {
+        ClayCode::data_shards(self)
+    }
+
+    fn parity_shards(&self) -> usize {
+        ClayCode::parity_shards(self)
+    }
+
+    fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        ClayCode::encode(self, data)
+    }
+
+    fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        ClayCode::reconstruct(self, shards)
+    }
+}
+
+impl<const K: usize, const M: usize> ErasureCodec for FixedReedSolomon<K, M> {
+    fn data_shards(&self) -> usize {
+        K
+    }
+
+    fn parity_shards(&self) -> usize {
+        M
+    }
+
+    fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        let data: &[&[u8]; K] = data.try_into().map_err(|_| Error::WrongShardCount)?;
+        let len = data[0].len();
+        if data.iter().any(|d| d.len() != len) {
+            return Err(Error::ShardSizeMismatch);
+        }
+        let mut parity = vec![vec![0u8; len]; M];
+        let mut refs: Vec<&mut [u8]> = parity.iter_mut().map(|p| &mut p[..]).collect();
+        let refs: &mut [&mut [u8]; M] = (&mut refs[..]).try_into().expect("M parity shards");
+        FixedReedSolomon::encode(self, data, refs);
+        Ok(parity)
+    }
+
+    fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        if shards.len() != K + M {
+            return Err(Error::WrongShardCount);
+        }
+        let len = match shards.iter().flatten().next() {
+            Some(s) => s.len(),
+            None => return Err(Error::TooFewShards),
+        };
+        if shards.iter().flatten().any(|s| s.len() != len) {
+            return Err(Error::ShardSizeMismatch);
+        }
+        let present: Vec<bool> = shards.iter().map(Option::is_some).collect();
+        let mut owned: Vec<Vec<u8>> = shards
+            .iter_mut()
+            .map(|s| s.take().unwrap_or_else(|| vec![0; len]))
+            .collect();
+        let (data, parity) = owned.split_at_mut(K);
+        let mut data: Vec<&mut [u8]> = data.iter_mut().map(|d| &mut d[..]).collect();
+        let mut parity: Vec<&mut [u8]> = parity.iter_mut().map(|p| &mut p[..]).collect();
+        let result = FixedReedSolomon::reconstruct(
+            self,
+            (&mut data[..]).try_into().expect("K data shards"),
+            (&mut parity[..]).try_into().expect("M parity shards"),
+            present[..K].try_into().expect("K flags"),
+            present[K..].try_into().expect("M flags"),
+        );
+        // on failure the shards that were missing stay missing
+        for ((slot, shard), was_present) in shards.iter_mut().zip(owned).zip(present) {
+            if result.is_ok() || was_present {
+                *slot = Some(shard);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fountain::FountainCodec;
+    use crate::Gf65536;
+
+    // Encodes, verifies and reconstructs any `m` lost shards through the
+    // trait alone.
+    fn check(codec: &dyn ErasureCodec) {
+        let (k, m) = (codec.data_shards(), codec.parity_shards());
+        let shard_len = 64;
+        let data: Vec<Vec<u8>> = (0..k)
+            .map(|s| (0..shard_len).map(|i| (i * 7 + s * 61 + 1) as u8).collect())
+            .collect();
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = codec.encode(&refs).unwrap();
+        assert_eq!(parity.len(), m);
+        let all: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+        let mut refs: Vec<&[u8]> = all.iter().map(|s| &s[..]).collect();
+        assert_eq!(codec.verify(&refs), Ok(true));
+        let mut bad = all[k].clone();
+        bad[3] ^= 1;
+        refs[k] = &bad;
+        assert_eq!(codec.verify(&refs), Ok(false));
+        refs[k] = &bad[..shard_len - 1];
+        assert_eq!(codec.verify(&refs), Err(Error::ShardSizeMismatch));
+
+        for start in 0..k + m {
+            let mut shards: Vec<Option<Vec<u8>>> = all.iter().cloned().map(Some).collect();
+            for i in 0..m {
+                shards[(start + i * 3) % (k + m)] = None;
+            }
+            codec.reconstruct(&mut shards).unwrap();
+            let shards: Vec<Vec<u8>> = shards.into_iter().map(Option::unwrap).collect();
+            assert_eq!(shards, all, "start {start}");
+        }
+
+        let mut shards: Vec<Option<Vec<u8>>> = all.iter().cloned().map(Some).collect();
+        for s in &mut shards[..m + 1] {
+            *s = None;
+        }
+        assert_eq!(codec.reconstruct(&mut shards), Err(Error::TooFewShards));
+        assert!(shards[..m + 1].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_all_codecs() {
+        let codecs: Vec<Box<dyn ErasureCodec>> = vec![
+            Box::new(ReedSolomon::new(5, 3).unwrap()),
+            Box::new(ReedSolomon::new_cauchy(5, 3).unwrap()),
+            Box::new(FftReedSolomon::new(5, 3).unwrap()),
+            Box::new(GenericReedSolomon::<Gf65536>::new(5, 3).unwrap()),
+            Box::new(ClayCode::new(4, 2).unwrap()),
+            Box::new(FixedReedSolomon::<5, 3>::new()),
+            Box::new(FountainCodec::new(5, 4)),
+        ];
+        for codec in &codecs {
+            check(codec.as_ref());
+        }
+        assert_eq!(codecs[0].overhead(), 1.6);
+    }
+}
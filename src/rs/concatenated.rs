@@ -0,0 +1,263 @@
+use super::code::{CodeError, RsCode};
+
+/// A binary code that protects one byte of an outer code at a time, as the
+/// inner code of a [`ConcatenatedCode`].
+///
+/// Inner codewords hold one bit per byte, each 0 or 1.
+pub trait InnerCode {
+    /// The number of bits each byte is encoded to.
+    fn codeword_len(&self) -> usize;
+
+    /// Appends the codeword for `byte` to `out`.
+    fn encode_byte(&self, byte: u8, out: &mut Vec<u8>);
+
+    /// Decodes one received codeword, or returns `None` when it is
+    /// detectably uncorrectable so that the outer code can treat the byte
+    /// as an erasure.
+    fn decode_byte(&self, bits: &[u8]) -> Option<u8>;
+}
+
+/// Each bit sent `r > 0` times and decoded by majority, with ties (for even
+/// `r`) reported as erasures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Repetition(usize);
+
+impl Repetition {
+    /// Panics if `r` is zero.
+    pub fn new(r: usize) -> Self {
+        assert!(r > 0, "a repetition code sends each bit at least once");
+        Repetition(r)
+    }
+
+    /// The number of times each bit is sent.
+    pub fn r(&self) -> usize {
+        self.0
+    }
+}
+
+impl InnerCode for Repetition {
+    fn codeword_len(&self) -> usize {
+        8 * self.0
+    }
+
+    fn encode_byte(&self, byte: u8, out: &mut Vec<u8>) {
+        for i in 0..8 {
+            out.extend(std::iter::repeat_n(byte >> i & 1, self.0));
+        }
+    }
+
+    fn decode_byte(&self, bits: &[u8]) -> Option<u8> {
+        let mut byte = 0;
+        for (i, chunk) in bits.chunks(self.0).enumerate() {
+            let ones = chunk.iter().filter(|b| **b & 1 == 1).count();
+            if 2 * ones == self.0 {
+                return None;
+            }
+            if 2 * ones > self.0 {
+                byte |= 1 << i;
+            }
+        }
+        Some(byte)
+    }
+}
+
+/// The extended Hamming(8, 4) code on each nibble, low nibble first: it
+/// corrects one bit error per nibble and reports two as an erasure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Hamming84;
+
+impl Hamming84 {
+    // Bits p1 p2 d1 p3 d2 d3 d4 p0: positions 1..=7 are Hamming(7, 4) and
+    // the last bit is the overall parity.
+    fn encode_nibble(d: u8) -> [u8; 8] {
+        let [d1, d2, d3, d4] = [d & 1, d >> 1 & 1, d >> 2 & 1, d >> 3 & 1];
+        let mut c = [d1 ^ d2 ^ d4, d1 ^ d3 ^ d4, d1, d2 ^ d3 ^ d4, d2, d3, d4, 0];
+        c[7] = c[..7].iter().fold(0, |acc, b| acc ^ b);
+        c
+    }
+
+    fn decode_nibble(bits: &[u8]) -> Option<u8> {
+        let mut c: Vec<u8> = bits.iter().map(|b| b & 1).collect();
+        // the syndrome is the 1-based position of a single error
+        let syndrome = (1..=7usize)
+            .filter(|p| c[p - 1] == 1)
+            .fold(0, |acc, p| acc ^ p);
+        let parity = c.iter().fold(0, |acc, b| acc ^ b);
+        match (syndrome, parity) {
+            (0, _) => {}
+            (s, 1) => c[s - 1] ^= 1,
+            // an even number of errors with a nonzero syndrome
+            (_, _) => return None,
+        }
+        Some(c[2] | c[4] << 1 | c[5] << 2 | c[6] << 3)
+    }
+}
+
+impl InnerCode for Hamming84 {
+    fn codeword_len(&self) -> usize {
+        16
+    }
+
+    fn encode_byte(&self, byte: u8, out: &mut Vec<u8>) {
+        out.extend(Hamming84::encode_nibble(byte & 0xf));
+        out.extend(Hamming84::encode_nibble(byte >> 4));
+    }
+
+    fn decode_byte(&self, bits: &[u8]) -> Option<u8> {
+        let lo = Hamming84::decode_nibble(&bits[..8])?;
+        let hi = Hamming84::decode_nibble(&bits[8..])?;
+        Some(lo | hi << 4)
+    }
+}
+
+/// An outer [`RsCode`] whose symbols are each sent through an inner binary
+/// code, the classic scheme of deep-space links.
+///
+/// The inner decoder cleans up scattered bit errors and turns the bytes it
+/// cannot fix into erasures, and the outer decoder then corrects `e` wrong
+/// and `s` erased bytes whenever `2e + s <= n - k`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConcatenatedCode<I> {
+    outer: RsCode,
+    inner: I,
+}
+
+impl<I: InnerCode> ConcatenatedCode<I> {
+    pub fn new(outer: RsCode, inner: I) -> Self {
+        ConcatenatedCode { outer, inner }
+    }
+
+    pub fn outer(&self) -> &RsCode {
+        &self.outer
+    }
+
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    /// The number of message bytes in a block.
+    pub fn message_len(&self) -> usize {
+        self.outer.k()
+    }
+
+    /// The number of bits in a block.
+    pub fn block_len(&self) -> usize {
+        self.outer.n() * self.inner.codeword_len()
+    }
+
+    /// Encodes `msg` with the outer code and every byte of the result with
+    /// the inner code, one bit per output byte.
+    pub fn encode(&self, msg: &[u8]) -> Result<Vec<u8>, CodeError> {
+        let codeword = self.outer.encode(msg)?;
+        let mut bits = Vec::with_capacity(self.block_len());
+        for byte in codeword {
+            self.inner.encode_byte(byte, &mut bits);
+        }
+        Ok(bits)
+    }
+
+    /// Decodes a received block of bits back to the message.
+    pub fn decode(&self, bits: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if bits.len() != self.block_len() {
+            return Err(CodeError::WrongLength);
+        }
+        let mut codeword = Vec::with_capacity(self.outer.n());
+        let mut erasures = Vec::new();
+        for (i, chunk) in bits.chunks(self.inner.codeword_len()).enumerate() {
+            codeword.push(self.inner.decode_byte(chunk).unwrap_or_else(|| {
+                erasures.push(i);
+                0
+            }));
+        }
+        self.outer.decode_with_erasures(&mut codeword, &erasures)?;
+        codeword.truncate(self.outer.k());
+        Ok(codeword)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(k: usize) -> Vec<u8> {
+        (0..k).map(|i| (i * 41 + 9) as u8).collect()
+    }
+
+    #[test]
+    fn test_hamming84() {
+        for byte in 0..=255u8 {
+            let mut c = Vec::new();
+            Hamming84.encode_byte(byte, &mut c);
+            assert_eq!(Hamming84.decode_byte(&c), Some(byte));
+            for i in 0..16 {
+                let mut one = c.clone();
+                one[i] ^= 1;
+                assert_eq!(Hamming84.decode_byte(&one), Some(byte));
+                for j in (i + 1..16).filter(|j| j / 8 == i / 8) {
+                    let mut two = one.clone();
+                    two[j] ^= 1;
+                    assert_eq!(Hamming84.decode_byte(&two), None);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_repetition() {
+        let mut c = Vec::new();
+        Repetition::new(3).encode_byte(0xa5, &mut c);
+        assert_eq!(c.len(), 24);
+        c[0] ^= 1;
+        c[5] ^= 1;
+        assert_eq!(Repetition::new(3).decode_byte(&c), Some(0xa5));
+        c.clear();
+        Repetition::new(2).encode_byte(0xa5, &mut c);
+        c[0] ^= 1;
+        assert_eq!(Repetition::new(2).decode_byte(&c), None);
+    }
+
+    #[test]
+    fn test_concatenated() {
+        let code = ConcatenatedCode::new(RsCode::new(30, 20).unwrap(), Hamming84);
+        assert_eq!(code.block_len(), 30 * 16);
+        let msg = message(20);
+        let sent = code.encode(&msg).unwrap();
+
+        // one bit error in every nibble is cleaned up by the inner code
+        let mut received = sent.clone();
+        for i in (0..received.len()).step_by(8) {
+            received[i + i / 8 % 8] ^= 1;
+        }
+        assert_eq!(code.decode(&received).unwrap(), msg);
+
+        // double errors erase 6 bytes and triple errors corrupt 2 more,
+        // within 2 * 2 + 6 <= 10
+        let mut received = sent.clone();
+        for b in 0..6 {
+            received[b * 16 + 1] ^= 1;
+            received[b * 16 + 2] ^= 1;
+        }
+        for b in [20, 25] {
+            let i = b * 16 + 8;
+            received[i] ^= 1;
+            received[i + 1] ^= 1;
+            received[i + 3] ^= 1;
+        }
+        assert_eq!(code.decode(&received).unwrap(), msg);
+
+        let repetition = ConcatenatedCode::new(RsCode::new(12, 8).unwrap(), Repetition::new(5));
+        let mut received = repetition.encode(&message(8)).unwrap();
+        for i in (0..received.len()).step_by(5) {
+            received[i + 2] ^= 1;
+            received[i + 4] ^= 1;
+        }
+        assert_eq!(repetition.decode(&received).unwrap(), message(8));
+        assert_eq!(repetition.decode(&[0; 10]), Err(CodeError::WrongLength));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_no_repetition() {
+        Repetition::new(0);
+    }
+}
@@ -0,0 +1,158 @@
+use std::io::{self, Read, Write};
+
+use crate::crc::CRC_32;
+
+const MAGIC: [u8; 4] = *b"GF8S";
+const VERSION: u8 = 1;
+/// The length of the header that precedes every shard payload.
+pub const HEADER_LEN: usize = 32;
+
+/// The fields of a shard header, which identify the shard within a stripe
+/// of a `k + m` code.
+///
+/// On the wire the header is [`HEADER_LEN`] little-endian bytes: the magic
+/// `GF8S`, a version byte, a reserved zero byte, `k`, `m` and the shard
+/// index as `u16`, the stripe id and payload length as `u64`, and a CRC-32
+/// of the preceding header bytes followed by the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShardHeader {
+    pub data_shards: usize,
+    pub parity_shards: usize,
+    pub index: usize,
+    pub stripe: u64,
+}
+
+impl ShardHeader {
+    fn to_bytes(self, payload_len: u64) -> io::Result<[u8; HEADER_LEN - 4]> {
+        let total = self.data_shards + self.parity_shards;
+        if self.data_shards == 0 || total > 256 || self.index >= total {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "bad shard header",
+            ));
+        }
+        let mut b = [0u8; HEADER_LEN - 4];
+        b[..4].copy_from_slice(&MAGIC);
+        b[4] = VERSION;
+        b[6..8].copy_from_slice(&(self.data_shards as u16).to_le_bytes());
+        b[8..10].copy_from_slice(&(self.parity_shards as u16).to_le_bytes());
+        b[10..12].copy_from_slice(&(self.index as u16).to_le_bytes());
+        b[12..20].copy_from_slice(&self.stripe.to_le_bytes());
+        b[20..28].copy_from_slice(&payload_len.to_le_bytes());
+        Ok(b)
+    }
+}
+
+/// Writes `payload` to `w`, preceded by its header.
+pub fn write_shard<W: Write>(mut w: W, header: &ShardHeader, payload: &[u8]) -> io::Result<()> {
+    let head = header.to_bytes(payload.len() as u64)?;
+    w.write_all(&head)?;
+    w.write_all(&checksum(&head, payload).to_le_bytes())?;
+    w.write_all(payload)
+}
+
+/// Reads one shard written by [`write_shard`], failing with
+/// `InvalidData` if the header is malformed or the checksum does not match.
+pub fn read_shard<R: Read>(mut r: R) -> io::Result<(ShardHeader, Vec<u8>)> {
+    let mut b = [0u8; HEADER_LEN];
+    r.read_exact(&mut b)?;
+    if b[..4] != MAGIC {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "not a shard"));
+    }
+    if b[4] != VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unsupported shard version",
+        ));
+    }
+    let u16_at = |i: usize| u16::from_le_bytes([b[i], b[i + 1]]) as usize;
+    let u64_at = |i: usize| u64::from_le_bytes(b[i..i + 8].try_into().unwrap());
+    let header = ShardHeader {
+        data_shards: u16_at(6),
+        parity_shards: u16_at(8),
+        index: u16_at(10),
+        stripe: u64_at(12),
+    };
+    let len = u64_at(20);
+    let crc = u32::from_le_bytes(b[28..32].try_into().unwrap());
+
+    // read as the data arrives, so a corrupt length cannot force a huge allocation
+    let mut payload = Vec::new();
+    r.take(len).read_to_end(&mut payload)?;
+    if (payload.len() as u64) < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    if checksum(&b[..28], &payload) != crc {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "shard checksum mismatch",
+        ));
+    }
+    header
+        .to_bytes(len)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "bad shard header"))?;
+    Ok((header, payload))
+}
+
+// The CRC-32 of the header bytes followed by the payload.
+fn checksum(head: &[u8], payload: &[u8]) -> u32 {
+    let mut digest = CRC_32.digest();
+    digest.update(head);
+    digest.update(payload);
+    digest.finalize() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(checksum(b"1234", b"56789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let header = ShardHeader {
+            data_shards: 10,
+            parity_shards: 4,
+            index: 12,
+            stripe: 7,
+        };
+        let mut buf = Vec::new();
+        write_shard(&mut buf, &header, b"payload").unwrap();
+        write_shard(&mut buf, &ShardHeader { index: 0, ..header }, b"").unwrap();
+        assert_eq!(buf.len(), 2 * HEADER_LEN + 7);
+
+        let mut r = &buf[..];
+        assert_eq!(read_shard(&mut r).unwrap(), (header, b"payload".to_vec()));
+        assert_eq!(read_shard(&mut r).unwrap().0.index, 0);
+        assert!(r.is_empty());
+
+        let bad = ShardHeader {
+            index: 14,
+            ..header
+        };
+        assert!(write_shard(Vec::new(), &bad, b"").is_err());
+    }
+
+    #[test]
+    fn test_corruption() {
+        let header = ShardHeader {
+            data_shards: 3,
+            parity_shards: 2,
+            index: 1,
+            stripe: 0,
+        };
+        let mut buf = Vec::new();
+        write_shard(&mut buf, &header, &[5; 40]).unwrap();
+        for pos in [0, 9, 15, 40] {
+            let mut bad = buf.clone();
+            bad[pos] ^= 0x10;
+            let err = read_shard(&bad[..]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "byte {pos}");
+        }
+        let err = read_shard(&buf[..buf.len() - 1]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,146 @@
+use super::RsCode;
+use crate::isomorphism::FieldMap;
+use crate::GaloisField;
+
+/// Conversion between the conventional representation of GF(256) as
+/// GF(2)[x] modulo a field polynomial and Berlekamp's dual basis.
+///
+/// The dual-basis coordinates of `v` are the traces `Tr(λ^k v)` for
+/// `k = 0..8`, the first in the most significant bit: the coordinates in
+/// the basis dual to `1, λ, .., λ^7` under the trace form, for which
+/// Berlekamp's bit-serial encoder needs only XOR gates. CCSDS codes
+/// transmit symbols this way, with `λ = α^117` in the field `0x187`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DualBasis {
+    to_dual: [u8; 256],
+    to_conventional: [u8; 256],
+}
+
+impl DualBasis {
+    /// The dual basis of `1, λ, .., λ^7` in the field `field_poly`, or
+    /// `None` if the polynomial is not irreducible of degree 8 or the powers
+    /// of `λ` are not a basis.
+    pub fn new(field_poly: u16, lambda: u8) -> Option<Self> {
+        let field = GaloisField::new(field_poly)?;
+        let trace = |x: u8| (0..8).fold(0, |t, i| t ^ field.pow(x, 1 << i));
+        let mut to_dual = [0u8; 256];
+        let mut to_conventional = [0u8; 256];
+        let mut seen = [false; 256];
+        for v in 0..=255u8 {
+            let dual = (0..8).fold(0u8, |z, k| {
+                z | trace(field.mul(field.pow(lambda, k), v)) << (7 - k)
+            });
+            if std::mem::replace(&mut seen[dual as usize], true) {
+                return None;
+            }
+            to_dual[v as usize] = dual;
+            to_conventional[dual as usize] = v;
+        }
+        Some(DualBasis {
+            to_dual,
+            to_conventional,
+        })
+    }
+
+    /// The representation of CCSDS 131.0-B, whose conversion matrix is
+    /// Karn's `Tal`.
+    pub fn ccsds() -> Self {
+        DualBasis::new(0x187, 0x4d).expect("α^117 generates GF(256)")
+    }
+
+    pub fn to_dual(&self, v: u8) -> u8 {
+        self.to_dual[v as usize]
+    }
+
+    pub fn to_conventional(&self, v: u8) -> u8 {
+        self.to_conventional[v as usize]
+    }
+
+    /// The table from conventional to dual representation, `Taltab` for
+    /// CCSDS.
+    pub fn to_dual_table(&self) -> &[u8; 256] {
+        &self.to_dual
+    }
+
+    /// The table from dual to conventional representation, `Tal1tab` for
+    /// CCSDS.
+    pub fn to_conventional_table(&self) -> &[u8; 256] {
+        &self.to_conventional
+    }
+}
+
+impl RsCode {
+    /// The same code with every symbol in `basis` on the wire: messages and
+    /// codewords are given and returned in the dual basis, and converted to
+    /// the field of [`RsParams`](super::RsParams) inside.
+    ///
+    /// `basis` must be built on the same field polynomial as the code.
+    pub fn with_dual_basis(self, basis: &DualBasis) -> Self {
+        self.with_symbol_map(FieldMap::from_forward(basis.to_conventional))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rs::presets::{ccsds, ccsds_shortened};
+    use crate::rs::RsParams;
+
+    #[test]
+    fn test_ccsds_tables() {
+        // the rows of Karn's conversion matrix, the image of bit 7 first
+        const TAL: [u8; 8] = [0x8d, 0xef, 0xec, 0x86, 0xfa, 0x99, 0xaf, 0x7b];
+        let basis = DualBasis::ccsds();
+        for k in 0..8 {
+            assert_eq!(basis.to_dual(1 << k), TAL[7 - k]);
+        }
+        for v in 0..=255u8 {
+            assert_eq!(basis.to_conventional(basis.to_dual(v)), v);
+        }
+        // the first entries of Karn's published `Taltab`
+        assert_eq!(
+            basis.to_dual_table()[..16],
+            [
+                0x00, 0x7b, 0xaf, 0xd4, 0x99, 0xe2, 0x36, 0x4d, 0xfa, 0x81, 0x55, 0x2e, 0x63, 0x18,
+                0xcc, 0xb7
+            ]
+        );
+
+        // λ in a proper subfield spans too little
+        assert!(DualBasis::new(0x187, 1).is_none());
+        assert!(DualBasis::new(0x100, 2).is_none());
+    }
+
+    #[test]
+    fn test_dual_basis_codec() {
+        let params = RsParams {
+            field_poly: 0x187,
+            generator: 0xad,
+            fcr: 112,
+        };
+        let code = RsCode::with_params(255, 223, params)
+            .unwrap()
+            .with_dual_basis(&DualBasis::ccsds());
+        assert_eq!(code, ccsds());
+
+        // the codeword of the conventional code, symbol by symbol
+        let basis = DualBasis::ccsds();
+        let conventional = RsCode::with_params(255, 223, params).unwrap();
+        let msg: Vec<u8> = (0..223).map(|i| (i * 31 + 5) as u8).collect();
+        let plain: Vec<u8> = msg.iter().map(|&v| basis.to_conventional(v)).collect();
+        let expected: Vec<u8> = conventional
+            .encode(&plain)
+            .unwrap()
+            .iter()
+            .map(|&v| basis.to_dual(v))
+            .collect();
+        let mut codeword = code.encode(&msg).unwrap();
+        assert_eq!(codeword, expected);
+
+        codeword[7] ^= 0x55;
+        codeword[200] ^= 0x01;
+        assert_eq!(code.decode(&mut codeword), Ok(2));
+        assert_eq!(codeword, expected);
+        assert_eq!(ccsds_shortened(100).unwrap().k(), 100);
+    }
+}
@@ -0,0 +1,242 @@
+use super::{Error, ReedSolomon};
+use crate::slice::{mul_add_slice, mul_slice, xor_slices};
+use crate::{Galois, GfMatrix};
+
+/// [`ReedSolomon`] with the shard counts fixed at compile time.
+///
+/// Shard lists are arrays, so their counts need no checking, and `K + M` is
+/// checked when the codec is instantiated. Shard lengths are still a runtime
+/// property; passing shards of different lengths panics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedReedSolomon<const K: usize, const M: usize> {
+    matrix: GfMatrix,
+}
+
+impl<const K: usize, const M: usize> FixedReedSolomon<K, M> {
+    const VALID: () = assert!(K > 0 && K + M <= 256, "need 0 < K and K + M <= 256");
+
+    pub fn new() -> Self {
+        let () = Self::VALID;
+        let rs = ReedSolomon::new(K, M).expect("shard counts checked at compile time");
+        FixedReedSolomon {
+            matrix: rs.matrix().clone(),
+        }
+    }
+
+    /// Computes the parity shards for `data` into `parity`.
+    pub fn encode(&self, data: &[&[u8]; K], parity: &mut [&mut [u8]; M]) {
+        let len = data[0].len();
+        assert!(
+            data.iter().all(|d| d.len() == len) && parity.iter().all(|p| p.len() == len),
+            "shard size mismatch"
+        );
+        for (j, out) in parity.iter_mut().enumerate() {
+            self.combine(K + j, data, out);
+        }
+    }
+
+    /// Overwrites the shards not marked present with their contents.
+    ///
+    /// Nothing is allocated: the bookkeeping lives in arrays of `K` entries
+    /// and the decoding matrix is inverted into a `K × K` array on the
+    /// stack.
+    pub fn reconstruct(
+        &self,
+        data: &mut [&mut [u8]; K],
+        parity: &mut [&mut [u8]; M],
+        data_present: &[bool; K],
+        parity_present: &[bool; M],
+    ) -> Result<(), Error> {
+        let len = data[0].len();
+        assert!(
+            data.iter().all(|d| d.len() == len) && parity.iter().all(|p| p.len() == len),
+            "shard size mismatch"
+        );
+
+        let mut used = [0usize; K];
+        let mut count = 0;
+        let present = data_present.iter().chain(parity_present);
+        for (i, _) in present.enumerate().filter(|(_, p)| **p) {
+            if count == K {
+                break;
+            }
+            used[count] = i;
+            count += 1;
+        }
+        if count < K {
+            return Err(Error::TooFewShards);
+        }
+
+        if data_present.iter().any(|p| !p) {
+            let decode = self.decode_matrix(&used);
+            for i in (0..K).filter(|i| !data_present[*i]) {
+                // the shard is taken out while the present ones are read
+                let out = std::mem::take(&mut data[i]);
+                for (c, src) in used.iter().enumerate() {
+                    let input: &[u8] = if *src < K {
+                        data[*src]
+                    } else {
+                        parity[*src - K]
+                    };
+                    if c == 0 {
+                        mul_slice(decode[i][c], input, out);
+                    } else {
+                        mul_add_slice(decode[i][c], input, out);
+                    }
+                }
+                data[i] = out;
+            }
+        }
+
+        let data: [&[u8]; K] = std::array::from_fn(|i| &*data[i]);
+        for j in (0..M).filter(|j| !parity_present[*j]) {
+            self.combine(K + j, &data, parity[j]);
+        }
+        Ok(())
+    }
+
+    // The inverse of the encoding matrix rows `used`, by Gauss-Jordan
+    // elimination in place.
+    fn decode_matrix(&self, used: &[usize; K]) -> [[Galois; K]; K] {
+        let mut a: [[Galois; K]; K] =
+            std::array::from_fn(|r| self.matrix.row(used[r]).try_into().unwrap());
+        let mut inv: [[Galois; K]; K] = std::array::from_fn(|r| {
+            std::array::from_fn(|c| {
+                if r == c {
+                    Galois::identity()
+                } else {
+                    Galois::zero()
+                }
+            })
+        });
+        for col in 0..K {
+            let pivot = (col..K)
+                .find(|&r| a[r][col] != Galois::zero())
+                .expect("any k rows of the encoding matrix are invertible");
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+            let scale = a[col][col].inv();
+            for c in 0..K {
+                a[col][c] *= scale;
+                inv[col][c] *= scale;
+            }
+            for r in (0..K).filter(|&r| r != col) {
+                let factor = a[r][col];
+                if factor == Galois::zero() {
+                    continue;
+                }
+                for c in 0..K {
+                    let (x, y) = (a[col][c], inv[col][c]);
+                    a[r][c] += factor * x;
+                    inv[r][c] += factor * y;
+                }
+            }
+        }
+        inv
+    }
+
+    // out = row `r` of the encoding matrix applied to `data`
+    fn combine(&self, r: usize, data: &[&[u8]; K], out: &mut [u8]) {
+        // the first parity row is all ones, a plain XOR
+        if self.matrix.row(r).iter().all(|c| *c == Galois::identity()) {
+            return xor_slices(data, out);
+        }
+        for (c, input) in data.iter().enumerate() {
+            if c == 0 {
+                mul_slice(self.matrix[(r, c)], input, out);
+            } else {
+                mul_add_slice(self.matrix[(r, c)], input, out);
+            }
+        }
+    }
+}
+
+impl<const K: usize, const M: usize> Default for FixedReedSolomon<K, M> {
+    fn default() -> Self {
+        FixedReedSolomon::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_runtime_codec() {
+        let rs = FixedReedSolomon::<4, 2>::new();
+        let data: Vec<Vec<u8>> = (0..4).map(|i| vec![i * 9 + 1; 12]).collect();
+        let refs: [&[u8]; 4] = std::array::from_fn(|i| &data[i][..]);
+        let mut p0 = [0u8; 12];
+        let mut p1 = [0u8; 12];
+        rs.encode(&refs, &mut [&mut p0, &mut p1]);
+
+        let expected = ReedSolomon::new(4, 2).unwrap().encode(&refs).unwrap();
+        assert_eq!(p0[..], expected[0][..]);
+        assert_eq!(p1[..], expected[1][..]);
+    }
+
+    #[test]
+    fn test_xor_parity() {
+        let rs = FixedReedSolomon::<3, 1>::new();
+        let data = [[1u8, 2, 3], [4, 5, 6], [7, 8, 9]];
+        let refs: [&[u8]; 3] = std::array::from_fn(|i| &data[i][..]);
+        let mut p = [0u8; 3];
+        rs.encode(&refs, &mut [&mut p]);
+        assert_eq!(p, [1 ^ 4 ^ 7, 2 ^ 5 ^ 8, 3 ^ 6 ^ 9]);
+    }
+
+    #[test]
+    fn test_reconstruct() {
+        let rs = FixedReedSolomon::<3, 2>::new();
+        let mut d = [[1u8, 2, 3], [4, 5, 6], [7, 8, 9]];
+        let mut p = [[0u8; 3]; 2];
+        {
+            let refs: [&[u8]; 3] = std::array::from_fn(|i| &d[i][..]);
+            let [p0, p1] = &mut p;
+            rs.encode(&refs, &mut [p0, p1]);
+        }
+        let (original_d, original_p) = (d, p);
+
+        d[0] = [0; 3];
+        d[2] = [0; 3];
+        {
+            let [d0, d1, d2] = &mut d;
+            let [p0, p1] = &mut p;
+            rs.reconstruct(
+                &mut [d0, d1, d2],
+                &mut [p0, p1],
+                &[false, true, false],
+                &[true, true],
+            )
+            .unwrap();
+        }
+        assert_eq!((d, p), (original_d, original_p));
+
+        let [d0, d1, d2] = &mut d;
+        let [p0, p1] = &mut p;
+        assert_eq!(
+            rs.reconstruct(
+                &mut [d0, d1, d2],
+                &mut [p0, p1],
+                &[false, true, false],
+                &[true, false]
+            ),
+            Err(Error::TooFewShards)
+        );
+    }
+
+    #[test]
+    fn test_decode_matrix() {
+        // every choice of 3 rows out of 6, against the heap inverse
+        let rs = FixedReedSolomon::<3, 3>::new();
+        for mask in (0u32..64).filter(|m| m.count_ones() == 3) {
+            let rows: Vec<usize> = (0..6).filter(|i| mask >> i & 1 == 1).collect();
+            let used: [usize; 3] = rows.clone().try_into().unwrap();
+            let expected = rs.matrix.select_rows(&rows).invert().unwrap();
+            let decode = rs.decode_matrix(&used);
+            for (r, row) in decode.iter().enumerate() {
+                assert_eq!(row[..], *expected.row(r), "{rows:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use super::{check_shards, Error};
+use crate::field::Field;
+use crate::Gf65536;
+
+/// [`ReedSolomon`](super::ReedSolomon) over any [`Field`], with shards read
+/// as little-endian `F::BYTES`-byte symbols.
+///
+/// Over [`Galois`](crate::Galois) it produces the same parity as
+/// `ReedSolomon::new`; over [`Gf65536`] it supports up to 65536 shards,
+/// whose lengths must then be even.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenericReedSolomon<F: Field> {
+    data_shards: usize,
+    parity_shards: usize,
+    // the m × k parity rows of the systematic matrix, row-major
+    parity_rows: Vec<F>,
+}
+
+/// A codec over GF(2^16) for more than 256 shards.
+pub type ReedSolomon16 = GenericReedSolomon<Gf65536>;
+
+impl<F: Field> GenericReedSolomon<F> {
+    /// The systematic Vandermonde construction of `ReedSolomon::new`: the
+    /// `(k + m) × k` matrix `r^c` times the inverse of its top square, with
+    /// the parity columns scaled so that the first parity row is all ones.
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<Self, Error> {
+        if data_shards == 0 || data_shards + parity_shards > F::ORDER {
+            return Err(Error::InvalidShardCount);
+        }
+
+        let k = data_shards;
+        let power = |r: usize, c: usize| (0..c).fold(F::one(), |acc, _| acc * F::from_index(r));
+        let top: Vec<F> = (0..k * k).map(|i| power(i / k, i % k)).collect();
+        let top_inv = invert(&top, k).expect("vandermonde top square is invertible");
+
+        let mut parity_rows = vec![F::zero(); parity_shards * k];
+        for r in 0..parity_shards {
+            let row: Vec<F> = (0..k).map(|c| power(k + r, c)).collect();
+            for c in 0..k {
+                parity_rows[r * k + c] =
+                    (0..k).fold(F::zero(), |acc, i| acc + row[i] * top_inv[i * k + c]);
+            }
+        }
+        if parity_shards > 0 {
+            for c in 0..k {
+                let scale = parity_rows[c].inv();
+                for r in 0..parity_shards {
+                    parity_rows[r * k + c] *= scale;
+                }
+            }
+        }
+        Ok(GenericReedSolomon {
+            data_shards,
+            parity_shards,
+            parity_rows,
+        })
+    }
+
+    pub fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    pub fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+
+    /// Computes the parity shards for `data`.
+    pub fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        let len = check_shards(data, self.data_shards)?;
+        if !len.is_multiple_of(F::BYTES) {
+            return Err(Error::ShardSizeMismatch);
+        }
+        let mut parity = vec![vec![0u8; len]; self.parity_shards];
+        code_shards::<F>(&self.parity_rows, data, &mut parity);
+        Ok(parity)
+    }
+
+    /// Fills in every missing (`None`) shard from the present ones.
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        if shards.len() != self.total_shards() {
+            return Err(Error::WrongShardCount);
+        }
+        let present: Vec<usize> = (0..shards.len()).filter(|i| shards[*i].is_some()).collect();
+        if present.len() == shards.len() {
+            return Ok(());
+        }
+        if present.len() < self.data_shards {
+            return Err(Error::TooFewShards);
+        }
+        let len = shards[present[0]].as_ref().map_or(0, |s| s.len());
+        if !len.is_multiple_of(F::BYTES)
+            || present
+                .iter()
+                .any(|i| shards[*i].as_ref().map_or(0, |s| s.len()) != len)
+        {
+            return Err(Error::ShardSizeMismatch);
+        }
+
+        let k = self.data_shards;
+        let used = &present[..k];
+        let sub: Vec<F> = used.iter().flat_map(|r| self.row(*r)).collect();
+        let decode = invert(&sub, k).expect("any k rows of the encoding matrix are invertible");
+
+        // missing shard w = row(w) · decode · shards[used]
+        let missing: Vec<usize> = (0..shards.len()).filter(|i| shards[*i].is_none()).collect();
+        let mut rows = Vec::with_capacity(missing.len() * k);
+        for w in &missing {
+            let row = self.row(*w);
+            rows.extend(
+                (0..k).map(|c| (0..k).fold(F::zero(), |acc, i| acc + row[i] * decode[i * k + c])),
+            );
+        }
+
+        let mut recovered = vec![vec![0u8; len]; missing.len()];
+        {
+            let inputs: Vec<&[u8]> = used
+                .iter()
+                .map(|i| shards[*i].as_deref().unwrap_or_default())
+                .collect();
+            code_shards::<F>(&rows, &inputs, &mut recovered);
+        }
+        for (i, r) in missing.iter().zip(recovered) {
+            shards[*i] = Some(r);
+        }
+        Ok(())
+    }
+
+    // Row `r` of the full (k + m) × k encoding matrix.
+    fn row(&self, r: usize) -> Vec<F> {
+        let k = self.data_shards;
+        if r < k {
+            (0..k)
+                .map(|c| if c == r { F::one() } else { F::zero() })
+                .collect()
+        } else {
+            self.parity_rows[(r - k) * k..(r - k + 1) * k].to_vec()
+        }
+    }
+}
+
+// outputs[r] = Σ_c rows[r][c] * inputs[c], symbol by symbol
+fn code_shards<F: Field>(rows: &[F], inputs: &[&[u8]], outputs: &mut [Vec<u8>]) {
+    let k = inputs.len();
+    for (r, out) in outputs.iter_mut().enumerate() {
+        for (pos, sym) in out.chunks_mut(F::BYTES).enumerate() {
+            let off = pos * F::BYTES;
+            let v = inputs
+                .iter()
+                .enumerate()
+                .fold(F::zero(), |acc, (c, input)| {
+                    acc + rows[r * k + c] * F::read(&input[off..])
+                });
+            v.write(sym);
+        }
+    }
+}
+
+// Gauss-Jordan inversion of the n × n row-major matrix `m`.
+fn invert<F: Field>(m: &[F], n: usize) -> Option<Vec<F>> {
+    let mut a = m.to_vec();
+    let mut inv: Vec<F> = (0..n * n)
+        .map(|i| if i / n == i % n { F::one() } else { F::zero() })
+        .collect();
+    for col in 0..n {
+        let pivot = (col..n).find(|r| a[r * n + col] != F::zero())?;
+        for c in 0..n {
+            a.swap(pivot * n + c, col * n + c);
+            inv.swap(pivot * n + c, col * n + c);
+        }
+        let scale = a[col * n + col].inv();
+        for c in 0..n {
+            a[col * n + c] *= scale;
+            inv[col * n + c] *= scale;
+        }
+        for r in (0..n).filter(|r| *r != col) {
+            let f = a[r * n + col];
+            if f == F::zero() {
+                continue;
+            }
+            for c in 0..n {
+                let (x, y) = (a[col * n + c], inv[col * n + c]);
+                a[r * n + c] -= f * x;
+                inv[r * n + c] -= f * y;
+            }
+        }
+    }
+    Some(inv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rs::ReedSolomon;
+    use crate::Galois;
+
+    #[test]
+    fn test_matches_gf256_codec() {
+        let generic = GenericReedSolomon::<Galois>::new(5, 5).unwrap();
+        let rs = ReedSolomon::new(5, 5).unwrap();
+        let data: Vec<Vec<u8>> = (0..5).map(|i| vec![i * 3, i * 7 + 1, 200 - i]).collect();
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        assert_eq!(generic.encode(&refs), rs.encode(&refs));
+    }
+
+    #[test]
+    fn test_many_shards() {
+        let (k, m) = (300, 20);
+        let rs = ReedSolomon16::new(k, m).unwrap();
+        let data: Vec<Vec<u8>> = (0..k)
+            .map(|s| (0..8).map(|i| (s * 17 + i * 5) as u8).collect())
+            .collect();
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        let full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+
+        let mut shards: Vec<Option<Vec<u8>>> = full.iter().cloned().map(Some).collect();
+        for i in (0..k + m).step_by(17).take(m) {
+            shards[i] = None;
+        }
+        rs.reconstruct(&mut shards).unwrap();
+        let shards: Vec<Vec<u8>> = shards.into_iter().map(Option::unwrap).collect();
+        assert_eq!(shards, full);
+
+        assert_eq!(
+            ReedSolomon16::new(65000, 537),
+            Err(Error::InvalidShardCount)
+        );
+        assert_eq!(
+            rs.encode(&vec![&[0u8; 3][..]; k]),
+            Err(Error::ShardSizeMismatch)
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use super::Manifest;
+
+/// Where a run of the original data lives: `len` bytes at `offset` in the
+/// block of data shard `shard` of stripe `stripe`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardRange {
+    pub stripe: u64,
+    pub shard: usize,
+    pub offset: usize,
+    pub len: usize,
+}
+
+/// The layout of `len` bytes of data cut into stripes of `k` blocks of
+/// `shard_size` bytes, for reading part of the data back from only the
+/// shards that hold it.
+///
+/// Block `i` of a stripe holds the stripe's bytes from `i * block_len` on.
+/// [`ShardGeometry::new`] is the layout of [`encode_file`](super::encode_file)
+/// and [`ShardGeometry::short_tail`] that of
+/// [`ReedSolomon::encode_bytes`](super::ReedSolomon::encode_bytes), whose
+/// last stripe has shorter blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardGeometry {
+    data_shards: usize,
+    shard_size: usize,
+    len: u64,
+    short_tail: bool,
+}
+
+impl ShardGeometry {
+    /// Every stripe, the last one zero-padded, has blocks of `shard_size`.
+    pub fn new(data_shards: usize, shard_size: usize, len: u64) -> Self {
+        assert!(data_shards > 0, "need at least one data shard");
+        assert!(shard_size > 0, "shard size must be positive");
+        ShardGeometry {
+            data_shards,
+            shard_size,
+            len,
+            short_tail: false,
+        }
+    }
+
+    /// The blocks of the last stripe are only as long as needed to hold the
+    /// rest of the data, zero-padded to a multiple of `k`.
+    pub fn short_tail(data_shards: usize, shard_size: usize, len: u64) -> Self {
+        ShardGeometry {
+            short_tail: true,
+            ..ShardGeometry::new(data_shards, shard_size, len)
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn stripes(&self) -> u64 {
+        self.len.div_ceil(self.stripe_size())
+    }
+
+    /// The length of the blocks of `stripe`.
+    pub fn block_len(&self, stripe: u64) -> usize {
+        if !self.short_tail || stripe + 1 < self.stripes() {
+            return self.shard_size;
+        }
+        let rest = self.len - stripe * self.stripe_size();
+        rest.div_ceil(self.data_shards as u64) as usize
+    }
+
+    /// The pieces of the data bytes `start .. start + len`, in order,
+    /// clipped to the end of the data.
+    pub fn map_range(&self, start: u64, len: u64) -> Vec<ShardRange> {
+        let end = start.saturating_add(len).min(self.len);
+        let mut out = Vec::new();
+        let mut pos = start;
+        while pos < end {
+            let stripe = pos / self.stripe_size();
+            let block = self.block_len(stripe) as u64;
+            let within = pos - stripe * self.stripe_size();
+            let (shard, offset) = (within / block, within % block);
+            let take = (block - offset).min(end - pos);
+            out.push(ShardRange {
+                stripe,
+                shard: shard as usize,
+                offset: offset as usize,
+                len: take as usize,
+            });
+            pos += take;
+        }
+        out
+    }
+
+    /// The position in the data of byte `offset` of data shard `shard`'s
+    /// block in `stripe`, or `None` if that byte is padding or out of range.
+    pub fn logical_offset(&self, stripe: u64, shard: usize, offset: usize) -> Option<u64> {
+        if stripe >= self.stripes() || shard >= self.data_shards {
+            return None;
+        }
+        let block = self.block_len(stripe);
+        if offset >= block {
+            return None;
+        }
+        let pos = stripe * self.stripe_size() + (shard * block + offset) as u64;
+        (pos < self.len).then_some(pos)
+    }
+
+    fn stripe_size(&self) -> u64 {
+        (self.data_shards * self.shard_size) as u64
+    }
+}
+
+impl Manifest {
+    /// The layout of the archived file over its data shards.
+    pub fn geometry(&self) -> ShardGeometry {
+        ShardGeometry::new(self.data_shards, self.block_size, self.length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rs::ReedSolomon;
+
+    #[test]
+    fn test_map_range() {
+        let g = ShardGeometry::new(3, 10, 75);
+        assert_eq!(g.stripes(), 3);
+        assert_eq!(
+            g.map_range(25, 12),
+            vec![
+                ShardRange {
+                    stripe: 0,
+                    shard: 2,
+                    offset: 5,
+                    len: 5
+                },
+                ShardRange {
+                    stripe: 1,
+                    shard: 0,
+                    offset: 0,
+                    len: 7
+                },
+            ]
+        );
+        assert_eq!(g.map_range(70, 100).iter().map(|r| r.len).sum::<usize>(), 5);
+        assert_eq!(g.logical_offset(2, 1, 4), Some(74));
+        assert_eq!(g.logical_offset(2, 1, 5), None);
+        assert_eq!(g.logical_offset(0, 3, 0), None);
+    }
+
+    #[test]
+    fn test_matches_encode_bytes() {
+        let rs = ReedSolomon::new(3, 2).unwrap();
+        let data: Vec<u8> = (0..71).map(|i| (i * 5 + 1) as u8).collect();
+        let encoded = rs.encode_bytes(&data, 10);
+        let g = ShardGeometry::short_tail(3, 10, 71);
+        assert_eq!(g.stripes(), encoded.stripes.len() as u64);
+
+        for start in 0..71 {
+            for len in [1, 9, 30] {
+                let mut read = Vec::new();
+                for r in g.map_range(start, len) {
+                    let shard = encoded.stripes[r.stripe as usize][r.shard]
+                        .as_ref()
+                        .unwrap();
+                    read.extend_from_slice(&shard[r.offset..r.offset + r.len]);
+                    assert_eq!(
+                        g.logical_offset(r.stripe, r.shard, r.offset),
+                        Some(start + (read.len() - r.len) as u64)
+                    );
+                }
+                let end = (start + len).min(71) as usize;
+                assert_eq!(read, data[start as usize..end]);
+            }
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use super::{check_shard_counts, check_shards, code_row, Error};
+use crate::GfMatrix;
+
+/// Rabin's information dispersal algorithm: `n` fragments of
+/// `ceil(len / k)` bytes, any `k` of which rebuild the data.
+///
+/// Every `k` consecutive bytes of the data are a vector that is multiplied
+/// by an `n × k` Vandermonde matrix, fragment `i` taking the `i`-th entry of
+/// each product. Unlike [`ReedSolomon`](super::ReedSolomon) the code is not
+/// systematic, so recovery always inverts a `k × k` matrix. It provides
+/// availability only: fragments are linear combinations of the data, and
+/// fragment 0 holds every `k`-th byte as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ida {
+    k: usize,
+    // n × k, every k rows are independent
+    matrix: GfMatrix,
+}
+
+impl Ida {
+    /// Creates a dispersal into `n` fragments with threshold `k`, for
+    /// `0 < k <= n <= 256`.
+    pub fn new(k: usize, n: usize) -> Result<Self, Error> {
+        if k > n {
+            return Err(Error::InvalidShardCount);
+        }
+        check_shard_counts(k, n - k)?;
+        Ok(Ida {
+            k,
+            matrix: GfMatrix::vandermonde(n, k),
+        })
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.k
+    }
+
+    pub fn fragment_count(&self) -> usize {
+        self.matrix.rows()
+    }
+
+    /// The length of each fragment of `len` bytes of data.
+    pub fn fragment_len(&self, len: usize) -> usize {
+        len.div_ceil(self.k)
+    }
+
+    /// Splits `data` into all `n` fragments.
+    pub fn disperse(&self, data: &[u8]) -> Vec<Vec<u8>> {
+        let len = self.fragment_len(data.len());
+        // column t holds bytes t, t + k, t + 2k, .. of the data
+        let mut columns = vec![vec![0u8; len]; self.k];
+        for (i, b) in data.iter().enumerate() {
+            columns[i % self.k][i / self.k] = *b;
+        }
+        (0..self.fragment_count())
+            .map(|i| {
+                let mut fragment = vec![0u8; len];
+                code_row(self.matrix.row(i), &columns, &mut fragment);
+                fragment
+            })
+            .collect()
+    }
+
+    /// Rebuilds `len` bytes of data from the fragments, given in order with
+    /// the missing ones `None`, using the first `k` present.
+    pub fn recover(&self, fragments: &[Option<Vec<u8>>], len: usize) -> Result<Vec<u8>, Error> {
+        if fragments.len() != self.fragment_count() {
+            return Err(Error::WrongShardCount);
+        }
+        let (rows, present): (Vec<usize>, Vec<&[u8]>) = fragments
+            .iter()
+            .enumerate()
+            .filter_map(|(i, f)| Some((i, f.as_deref()?)))
+            .take(self.k)
+            .unzip();
+        if present.len() < self.k {
+            return Err(Error::TooFewShards);
+        }
+        let fragment_len = check_shards(&present, self.k)?;
+        if fragment_len < self.fragment_len(len) {
+            return Err(Error::ShardSizeMismatch);
+        }
+
+        let inv = self
+            .matrix
+            .select_rows(&rows)
+            .invert()
+            .expect("any k rows of a vandermonde matrix are independent");
+        let mut column = vec![0u8; fragment_len];
+        let mut data = vec![0u8; len];
+        for t in 0..self.k {
+            code_row(inv.row(t), &present, &mut column);
+            for (j, b) in column.iter().enumerate() {
+                if let Some(d) = data.get_mut(j * self.k + t) {
+                    *d = *b;
+                }
+            }
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 53 + 1) as u8).collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let ida = Ida::new(3, 5).unwrap();
+        for len in [0, 1, 3, 100, 1001] {
+            let data = data(len);
+            let fragments = ida.disperse(&data);
+            assert_eq!(fragments.len(), 5);
+            assert!(fragments.iter().all(|f| f.len() == len.div_ceil(3)));
+
+            // every choice of three fragments
+            for missing in [[0, 1], [0, 4], [1, 3], [3, 4]] {
+                let mut received: Vec<Option<Vec<u8>>> =
+                    fragments.iter().cloned().map(Some).collect();
+                for m in missing {
+                    received[m] = None;
+                }
+                assert_eq!(ida.recover(&received, len).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_not_systematic() {
+        let ida = Ida::new(4, 6).unwrap();
+        let data = data(400);
+        for fragment in &ida.disperse(&data)[1..] {
+            assert!(!data.windows(8).any(|w| fragment.windows(8).any(|f| f == w)));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Ida::new(0, 3), Err(Error::InvalidShardCount));
+        assert_eq!(Ida::new(4, 3), Err(Error::InvalidShardCount));
+        assert_eq!(Ida::new(2, 257), Err(Error::InvalidShardCount));
+
+        let ida = Ida::new(2, 4).unwrap();
+        let mut fragments: Vec<Option<Vec<u8>>> =
+            ida.disperse(&data(10)).into_iter().map(Some).collect();
+        assert_eq!(
+            ida.recover(&fragments[..3], 10),
+            Err(Error::WrongShardCount)
+        );
+        assert_eq!(ida.recover(&fragments, 11), Err(Error::ShardSizeMismatch));
+        fragments[1].as_mut().unwrap().pop();
+        assert_eq!(ida.recover(&fragments, 10), Err(Error::ShardSizeMismatch));
+        fragments[0] = None;
+        fragments[2] = None;
+        fragments[3] = None;
+        assert_eq!(ida.recover(&fragments, 10), Err(Error::TooFewShards));
+    }
+}
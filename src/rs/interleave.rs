@@ -0,0 +1,146 @@
+use super::code::{CodeError, RsCode};
+
+/// `depth` codewords of an [`RsCode`] sent symbol by symbol in turn, so that
+/// consecutive bytes of a frame belong to different codewords.
+///
+/// A frame holds byte `j` of codeword `i` at `j * depth + i`, which spreads
+/// any burst of up to `depth * t` bytes into at most `t` errors per codeword.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interleaved {
+    code: RsCode,
+    depth: usize,
+}
+
+impl Interleaved {
+    pub fn new(code: RsCode, depth: usize) -> Result<Self, CodeError> {
+        if depth == 0 {
+            return Err(CodeError::InvalidParameters);
+        }
+        Ok(Interleaved { code, depth })
+    }
+
+    pub fn code(&self) -> &RsCode {
+        &self.code
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The number of message bytes in a frame.
+    pub fn message_len(&self) -> usize {
+        self.depth * self.code.k()
+    }
+
+    /// The number of bytes in a frame.
+    pub fn frame_len(&self) -> usize {
+        self.depth * self.code.n()
+    }
+
+    /// The longest burst of byte errors a frame always survives.
+    pub fn burst_capability(&self) -> usize {
+        self.depth * self.code.capability()
+    }
+
+    /// Splits `msg` into `depth` consecutive messages of `k` bytes, encodes
+    /// each and interleaves the codewords into a frame.
+    pub fn encode(&self, msg: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if msg.len() != self.message_len() {
+            return Err(CodeError::WrongLength);
+        }
+        let mut frame = vec![0u8; self.frame_len()];
+        for (i, chunk) in msg.chunks(self.code.k()).enumerate() {
+            let codeword = self.code.encode(chunk)?;
+            for (j, v) in codeword.into_iter().enumerate() {
+                frame[j * self.depth + i] = v;
+            }
+        }
+        Ok(frame)
+    }
+
+    /// Corrects `frame` in place, returning the number of bytes fixed.
+    ///
+    /// The frame is left untouched if any codeword fails to decode.
+    pub fn decode(&self, frame: &mut [u8]) -> Result<usize, CodeError> {
+        if frame.len() != self.frame_len() {
+            return Err(CodeError::WrongLength);
+        }
+        let mut codewords = self.deinterleave(frame);
+        let mut fixed = 0;
+        for codeword in &mut codewords {
+            fixed += self.code.decode(codeword)?;
+        }
+        self.interleave(&codewords, frame);
+        Ok(fixed)
+    }
+
+    /// Strips the parity from a decoded frame, returning the original message.
+    pub fn message(&self, frame: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if frame.len() != self.frame_len() {
+            return Err(CodeError::WrongLength);
+        }
+        Ok(self
+            .deinterleave(frame)
+            .into_iter()
+            .flat_map(|mut c| {
+                c.truncate(self.code.k());
+                c
+            })
+            .collect())
+    }
+
+    fn deinterleave(&self, frame: &[u8]) -> Vec<Vec<u8>> {
+        (0..self.depth)
+            .map(|i| frame.iter().skip(i).step_by(self.depth).copied().collect())
+            .collect()
+    }
+
+    fn interleave(&self, codewords: &[Vec<u8>], frame: &mut [u8]) {
+        for (i, codeword) in codewords.iter().enumerate() {
+            for (j, v) in codeword.iter().enumerate() {
+                frame[j * self.depth + i] = *v;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst() {
+        let code = Interleaved::new(RsCode::new(40, 30).unwrap(), 6).unwrap();
+        assert_eq!(code.burst_capability(), 30);
+        let msg: Vec<u8> = (0..code.message_len()).map(|i| (i * 13) as u8).collect();
+        let frame = code.encode(&msg).unwrap();
+
+        for start in [0, 17, frame.len() - 30] {
+            let mut received = frame.clone();
+            for v in &mut received[start..start + 30] {
+                *v ^= 0xc3;
+            }
+            assert_eq!(code.decode(&mut received), Ok(30));
+            assert_eq!(received, frame);
+            assert_eq!(code.message(&received).unwrap(), msg);
+        }
+    }
+
+    #[test]
+    fn test_burst_too_long() {
+        let code = Interleaved::new(RsCode::new(20, 16).unwrap(), 3).unwrap();
+        let msg = vec![7u8; code.message_len()];
+        let frame = code.encode(&msg).unwrap();
+
+        // nine consecutive bytes hit every codeword three times
+        let mut received = frame.clone();
+        for v in &mut received[3..12] {
+            *v ^= 1;
+        }
+        let copy = received.clone();
+        if code.decode(&mut received).is_err() {
+            assert_eq!(received, copy);
+        }
+        assert_eq!(code.decode(&mut [0; 59]), Err(CodeError::WrongLength));
+    }
+}
@@ -0,0 +1,236 @@
+use super::{check_shard_counts, check_shards, Error};
+use crate::constants::{EXP_TABLE, LOG_TABLE};
+use crate::fft::{fft, formal_derivative, ifft};
+use crate::slice::{add_slice, mul_slice};
+
+/// A Reed-Solomon erasure codec built on the additive FFT, in the manner of
+/// Leopard-RS, which encodes and reconstructs in `O(n log n)` per byte
+/// rather than the `O(k m)` of a matrix codec.
+///
+/// Parity shard `j` is the evaluation at point `j` and data shard `i` at
+/// point `T + i`, where `T` is `m` rounded up to a power of two, of a
+/// polynomial whose novel-basis coefficients vanish from degree `N - T`
+/// up (`N` any power of two covering all points). The code is MDS, so any
+/// `k` shards recover the rest, but its parity differs from
+/// [`ReedSolomon`](super::ReedSolomon)'s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FftReedSolomon {
+    data_shards: usize,
+    parity_shards: usize,
+    // parity_shards rounded up to a power of two
+    chunk: usize,
+}
+
+impl FftReedSolomon {
+    /// Fails unless `k > 0` and `k + T <= 256`.
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<Self, Error> {
+        check_shard_counts(data_shards, parity_shards)?;
+        let chunk = parity_shards.next_power_of_two();
+        if data_shards + chunk > 256 {
+            return Err(Error::InvalidShardCount);
+        }
+        Ok(FftReedSolomon {
+            data_shards,
+            parity_shards,
+            chunk,
+        })
+    }
+
+    pub fn data_shards(&self) -> usize {
+        self.data_shards
+    }
+
+    pub fn parity_shards(&self) -> usize {
+        self.parity_shards
+    }
+
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+
+    /// Computes the parity shards for `data`.
+    ///
+    /// Each run of `T` data shards is interpolated on its coset and the sum
+    /// of these polynomials is evaluated on the parity points.
+    pub fn encode(&self, data: &[&[u8]]) -> Result<Vec<Vec<u8>>, Error> {
+        let len = check_shards(data, self.data_shards)?;
+
+        let t = self.chunk;
+        let mut parity = vec![vec![0u8; len]; t];
+        let mut work = vec![vec![0u8; len]; t];
+        for (b, block) in data.chunks(t).enumerate() {
+            for (i, w) in work.iter_mut().enumerate() {
+                match block.get(i) {
+                    Some(d) => w.copy_from_slice(d),
+                    None => w.fill(0),
+                }
+            }
+            ifft(&mut work, ((b + 1) * t) as u8);
+            for (p, w) in parity.iter_mut().zip(&work) {
+                add_slice(w, p);
+            }
+        }
+        fft(&mut parity, 0);
+        parity.truncate(self.parity_shards);
+        Ok(parity)
+    }
+
+    /// Fills in every missing (`None`) shard from the present ones.
+    ///
+    /// With the erasure locator `e(x) = Π (x - u)` over the missing points
+    /// (punctured parity included), `f e` vanishes at every missing point and
+    /// `(f e)' = f e'` there, so one IFFT, a formal derivative and one FFT
+    /// recover all missing values at once.
+    pub fn reconstruct(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), Error> {
+        if shards.len() != self.total_shards() {
+            return Err(Error::WrongShardCount);
+        }
+        let present: Vec<usize> = (0..shards.len()).filter(|i| shards[*i].is_some()).collect();
+        if present.len() == shards.len() {
+            return Ok(());
+        }
+        if present.len() < self.data_shards {
+            return Err(Error::TooFewShards);
+        }
+        let len = shards[present[0]].as_ref().map_or(0, |s| s.len());
+        if present
+            .iter()
+            .any(|i| shards[*i].as_ref().map_or(0, |s| s.len()) != len)
+        {
+            return Err(Error::ShardSizeMismatch);
+        }
+
+        let (k, m, t) = (self.data_shards, self.parity_shards, self.chunk);
+        let n = (t + k).next_power_of_two();
+        let point = |shard: usize| if shard < k { t + shard } else { shard - k };
+
+        let mut erased = vec![false; n];
+        erased[m..t].iter_mut().for_each(|e| *e = true);
+        for (i, s) in shards.iter().enumerate() {
+            if s.is_none() {
+                erased[point(i)] = true;
+            }
+        }
+        let locator = erasure_locator_logs(&erased);
+
+        let mut work = vec![vec![0u8; len]; n];
+        for (i, s) in shards.iter().enumerate() {
+            if let Some(s) = s {
+                let p = point(i);
+                mul_slice(EXP_TABLE[locator[p] as usize], s, &mut work[p]);
+            }
+        }
+        ifft(&mut work, 0);
+        formal_derivative(&mut work);
+        fft(&mut work, 0);
+
+        for (i, s) in shards.iter_mut().enumerate() {
+            if s.is_none() {
+                let p = point(i);
+                let mut out = vec![0u8; len];
+                mul_slice(
+                    EXP_TABLE[(255 - locator[p]) as usize % 255],
+                    &work[p],
+                    &mut out,
+                );
+                *s = Some(out);
+            }
+        }
+        Ok(())
+    }
+}
+
+// For each point `x`, the log of `e(x)` if `x` is not erased and of `e'(x)`
+// if it is, where `e` vanishes on the erased points.
+//
+// Both are `Σ log(x + u)` over the erased `u`, skipping `u = x`; as a XOR
+// convolution of the erasure indicator with the log table (taking
+// `log 0 = 0`) this is three Walsh-Hadamard transforms mod 255.
+fn erasure_locator_logs(erased: &[bool]) -> Vec<u32> {
+    let n = erased.len();
+    let mut indicator: Vec<u32> = erased.iter().map(|e| *e as u32).collect();
+    let mut logs: Vec<u32> = (0..n).map(|x| LOG_TABLE[x] as u32).collect();
+    walsh_hadamard(&mut indicator);
+    walsh_hadamard(&mut logs);
+    for (a, b) in indicator.iter_mut().zip(&logs) {
+        *a = *a * b % 255;
+    }
+    walsh_hadamard(&mut indicator);
+
+    // the inverse transform is the forward one divided by n
+    let n_inv = (1..255)
+        .find(|x| x * n as u32 % 255 == 1)
+        .expect("n is odd mod 255");
+    indicator.iter().map(|v| v * n_inv % 255).collect()
+}
+
+fn walsh_hadamard(data: &mut [u32]) {
+    let mut half = 1;
+    while half < data.len() {
+        for block in (0..data.len()).step_by(2 * half) {
+            for i in block..block + half {
+                let (a, b) = (data[i], data[i + half]);
+                data[i] = (a + b) % 255;
+                data[i + half] = (a + 255 - b) % 255;
+            }
+        }
+        half *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_shards(k: usize, len: usize) -> Vec<Vec<u8>> {
+        (0..k)
+            .map(|s| (0..len).map(|i| (i * 53 + s * 101 + 7) as u8).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_reconstruct() {
+        for (k, m) in [(1, 1), (5, 3), (10, 4), (100, 50), (128, 128)] {
+            let rs = FftReedSolomon::new(k, m).unwrap();
+            let data = sample_shards(k, 20);
+            let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+            let parity = rs.encode(&refs).unwrap();
+            let full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+
+            for lost_start in [0, k / 2, k] {
+                let mut shards: Vec<Option<Vec<u8>>> = full.iter().cloned().map(Some).collect();
+                for s in shards.iter_mut().skip(lost_start).take(m) {
+                    *s = None;
+                }
+                rs.reconstruct(&mut shards).unwrap();
+                let shards: Vec<Vec<u8>> = shards.into_iter().map(Option::unwrap).collect();
+                assert_eq!(shards, full, "k={k} m={m} start={lost_start}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_mds() {
+        // every choice of 3 shards out of 6 recovers the rest
+        let rs = FftReedSolomon::new(3, 3).unwrap();
+        let data = sample_shards(3, 8);
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        let full: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+        for mask in 0u32..64 {
+            if mask.count_ones() != 3 {
+                continue;
+            }
+            let mut shards: Vec<Option<Vec<u8>>> = full
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (mask & (1 << i) != 0).then(|| s.clone()))
+                .collect();
+            rs.reconstruct(&mut shards).unwrap();
+            assert!(shards.iter().zip(&full).all(|(s, f)| s.as_ref() == Some(f)));
+        }
+
+        assert_eq!(FftReedSolomon::new(200, 57), Err(Error::InvalidShardCount));
+        assert!(FftReedSolomon::new(192, 64).is_ok());
+    }
+}
@@ -0,0 +1,154 @@
+use std::fs::File;
+use std::io;
+
+use memmap2::{Mmap, MmapMut};
+
+use super::{code_shards, Error, ReedSolomon};
+use crate::GfMatrix;
+
+impl ReedSolomon {
+    /// Computes the parity shard files for `k` data shard files of equal
+    /// length, resizing the parity files to match.
+    ///
+    /// All files are memory-mapped and coded `window` bytes of every shard at
+    /// a time, so each page of input is read once while it is resident and
+    /// nothing file-sized is allocated.
+    pub fn encode_mapped(&self, data: &[File], parity: &[File], window: usize) -> io::Result<()> {
+        assert!(window > 0, "window must be positive");
+        if data.len() != self.data_shards || parity.len() != self.parity_shards {
+            return Err(to_io(Error::WrongShardCount));
+        }
+        let inputs = map(data.iter())?;
+        let mut outputs = map_mut(parity.iter(), shard_len(&inputs)?)?;
+
+        let rows = self
+            .matrix
+            .submatrix(self.data_shards, 0, self.parity_shards, self.data_shards);
+        code_windows(&rows, &inputs, &mut outputs, window);
+        outputs.iter().try_for_each(|o| o.flush())
+    }
+
+    /// Rewrites the shard files whose indices are in `missing` from the
+    /// others, like [`encode_mapped`](ReedSolomon::encode_mapped) does for
+    /// parity. `shards` holds all `k + m` files, data first.
+    pub fn reconstruct_mapped(
+        &self,
+        shards: &[File],
+        missing: &[usize],
+        window: usize,
+    ) -> io::Result<()> {
+        assert!(window > 0, "window must be positive");
+        if shards.len() != self.total_shards() {
+            return Err(to_io(Error::WrongShardCount));
+        }
+        if missing.iter().any(|i| *i >= shards.len()) {
+            return Err(to_io(Error::InvalidShardIndex));
+        }
+        let mut missing = missing.to_vec();
+        missing.sort_unstable();
+        missing.dedup();
+
+        let present: Vec<usize> = (0..shards.len()).filter(|i| !missing.contains(i)).collect();
+        if present.len() < self.data_shards {
+            return Err(to_io(Error::TooFewShards));
+        }
+        let used = &present[..self.data_shards];
+        let inputs = map(used.iter().map(|i| &shards[*i]))?;
+        let mut outputs = map_mut(missing.iter().map(|i| &shards[*i]), shard_len(&inputs)?)?;
+
+        let rows = self.recovery_rows(&missing, used);
+        code_windows(&rows, &inputs, &mut outputs, window);
+        outputs.iter().try_for_each(|o| o.flush())
+    }
+}
+
+fn to_io(e: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, e)
+}
+
+fn map<'a>(files: impl Iterator<Item = &'a File>) -> io::Result<Vec<Mmap>> {
+    // SAFETY: the files must not be truncated or written by others while
+    // mapped, as for any memory map.
+    files.map(|f| unsafe { Mmap::map(f) }).collect()
+}
+
+fn map_mut<'a>(files: impl Iterator<Item = &'a File>, len: usize) -> io::Result<Vec<MmapMut>> {
+    files
+        .map(|f| {
+            f.set_len(len as u64)?;
+            // SAFETY: as for `map`.
+            unsafe { MmapMut::map_mut(f) }
+        })
+        .collect()
+}
+
+fn shard_len(maps: &[Mmap]) -> io::Result<usize> {
+    let len = maps.first().map_or(0, |m| m.len());
+    if maps.iter().any(|m| m.len() != len) {
+        return Err(to_io(Error::ShardSizeMismatch));
+    }
+    Ok(len)
+}
+
+fn code_windows(rows: &GfMatrix, inputs: &[Mmap], outputs: &mut [MmapMut], window: usize) {
+    let len = inputs.first().map_or(0, |m| m.len());
+    for start in (0..len).step_by(window) {
+        let end = (start + window).min(len);
+        let ins: Vec<&[u8]> = inputs.iter().map(|m| &m[start..end]).collect();
+        let mut outs: Vec<&mut [u8]> = outputs.iter_mut().map(|m| &mut m[start..end]).collect();
+        code_shards(rows, &ins, &mut outs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, OpenOptions};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_mapped_round_trip() {
+        let dir = std::env::temp_dir().join(format!("gf256-lite-mapped-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let paths: Vec<PathBuf> = (0..6).map(|i| dir.join(format!("{i}.shard"))).collect();
+        let open = |p: &PathBuf| {
+            OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(p)
+                .unwrap()
+        };
+
+        let rs = ReedSolomon::new(4, 2).unwrap();
+        let data: Vec<Vec<u8>> = (0..4)
+            .map(|s| (0..10_000).map(|i| (i * 3 + s * 71) as u8).collect())
+            .collect();
+        for (p, d) in paths.iter().zip(&data) {
+            fs::write(p, d).unwrap();
+        }
+        let files: Vec<File> = paths.iter().map(open).collect();
+        rs.encode_mapped(&files[..4], &files[4..], 4096).unwrap();
+        drop(files);
+
+        let refs: Vec<&[u8]> = data.iter().map(|d| &d[..]).collect();
+        let parity = rs.encode(&refs).unwrap();
+        assert_eq!(fs::read(&paths[4]).unwrap(), parity[0]);
+        assert_eq!(fs::read(&paths[5]).unwrap(), parity[1]);
+
+        fs::write(&paths[1], b"").unwrap();
+        fs::write(&paths[5], b"junk").unwrap();
+        let files: Vec<File> = paths.iter().map(open).collect();
+        rs.reconstruct_mapped(&files, &[1, 5], 1000).unwrap();
+        drop(files);
+        assert_eq!(fs::read(&paths[1]).unwrap(), data[1]);
+        assert_eq!(fs::read(&paths[5]).unwrap(), parity[1]);
+
+        let files: Vec<File> = paths.iter().map(open).collect();
+        let err = rs.reconstruct_mapped(&files, &[0, 1, 2], 1000).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
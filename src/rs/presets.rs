@@ -0,0 +1,89 @@
+//! Ready-made [`RsCode`] configurations for common standards.
+//!
+//! PAR2 is not covered: it works on 16-bit symbols.
+
+use super::{CodeError, DualBasis, RsCode, RsParams};
+
+/// QR code error correction: field `0x11d`, `α = 2`, first root `α^0`.
+///
+/// `n` and `k` are the total and data codeword counts of one block, e.g.
+/// `qr(26, 16)` for a version 1-M symbol.
+pub fn qr(n: usize, k: usize) -> Result<RsCode, CodeError> {
+    RsCode::with_params(n, k, RsParams::default())
+}
+
+/// Data Matrix ECC 200: field `0x12d`, `α = 2`, first root `α^1`.
+pub fn data_matrix(n: usize, k: usize) -> Result<RsCode, CodeError> {
+    let params = RsParams {
+        field_poly: 0x12d,
+        generator: 2,
+        fcr: 1,
+    };
+    RsCode::with_params(n, k, params)
+}
+
+/// CCSDS RS(255, 223) as in CCSDS 131.0-B: field `0x187`, generator
+/// `γ = α^11`, first root `γ^112`, with symbols in Berlekamp's dual basis.
+pub fn ccsds() -> RsCode {
+    ccsds_shortened(223).expect("valid CCSDS parameters")
+}
+
+/// CCSDS RS(255, 223) shortened to `k` data symbols (virtual fill), for
+/// `1 <= k <= 223`.
+pub fn ccsds_shortened(k: usize) -> Result<RsCode, CodeError> {
+    let params = RsParams {
+        field_poly: 0x187,
+        generator: 0xad,
+        fcr: 112,
+    };
+    Ok(RsCode::with_params(k + 32, k, params)?.with_dual_basis(&DualBasis::ccsds()))
+}
+
+/// DVB-T / DVB-S outer code RS(204, 188), shortened from RS(255, 239):
+/// field `0x11d`, `α = 2`, first root `α^0`.
+pub fn dvb_t() -> RsCode {
+    RsCode::with_params(204, 188, RsParams::default()).expect("valid DVB parameters")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_round_trip() {
+        let codes = [
+            qr(26, 16).unwrap(),
+            data_matrix(18, 8).unwrap(),
+            ccsds(),
+            ccsds_shortened(100).unwrap(),
+            dvb_t(),
+        ];
+        for code in codes {
+            let msg: Vec<u8> = (0..code.k()).map(|i| (i * 13 + 7) as u8).collect();
+            let original = code.encode(&msg).unwrap();
+            assert_eq!(&original[..code.k()], &msg[..]);
+
+            let mut received = original.clone();
+            for e in 0..code.capability() {
+                received[(e * 11) % code.n()] ^= 0x3c;
+            }
+            assert!(code.decode(&mut received).is_ok());
+            assert_eq!(received, original);
+        }
+    }
+
+    #[test]
+    fn test_ccsds_shortened() {
+        assert_eq!(ccsds_shortened(223), Ok(ccsds()));
+        assert_eq!(ccsds_shortened(0), Err(CodeError::InvalidParameters));
+        assert_eq!(ccsds_shortened(224), Err(CodeError::InvalidParameters));
+    }
+
+    #[test]
+    fn test_data_matrix_vector() {
+        // the worked "123456" example from ISO/IEC 16022
+        let code = data_matrix(8, 3).unwrap();
+        let codeword = code.encode(&[142, 164, 186]).unwrap();
+        assert_eq!(codeword, [142, 164, 186, 114, 25, 5, 88, 102]);
+    }
+}
@@ -0,0 +1,221 @@
+use super::code::{CodeError, RsCode};
+
+// Passes after which a block that still changes is given up on.
+const MAX_PASSES: usize = 16;
+
+/// The product of two [`RsCode`]s: a message of `k1` rows by `k2` columns
+/// whose rows are encoded with `row` and whose columns are encoded with
+/// `col`, giving a block of `n1` rows by `n2` columns.
+///
+/// A block is stored row by row. Both codes must share a field, which makes
+/// the parity rows codewords of `row` as well, so every row and every column
+/// of a block is a codeword.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductCode {
+    row: RsCode,
+    col: RsCode,
+}
+
+impl ProductCode {
+    pub fn new(row: RsCode, col: RsCode) -> Result<Self, CodeError> {
+        if row.params().field_poly != col.params().field_poly {
+            return Err(CodeError::InvalidParameters);
+        }
+        Ok(ProductCode { row, col })
+    }
+
+    pub fn row_code(&self) -> &RsCode {
+        &self.row
+    }
+
+    pub fn col_code(&self) -> &RsCode {
+        &self.col
+    }
+
+    /// The number of message bytes in a block.
+    pub fn message_len(&self) -> usize {
+        self.col.k() * self.row.k()
+    }
+
+    /// The number of bytes in a block.
+    pub fn block_len(&self) -> usize {
+        self.col.n() * self.row.n()
+    }
+
+    /// Encodes `k1` message rows of `k2` bytes, given one after the other.
+    pub fn encode(&self, msg: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if msg.len() != self.message_len() {
+            return Err(CodeError::WrongLength);
+        }
+        let width = self.row.n();
+        let mut block = vec![0u8; self.block_len()];
+        for (i, chunk) in msg.chunks(self.row.k()).enumerate() {
+            self.row
+                .encode_parity(chunk, &mut block[i * width + chunk.len()..(i + 1) * width])?;
+            block[i * width..i * width + chunk.len()].copy_from_slice(chunk);
+        }
+        for j in 0..width {
+            let column = self.column(&block, j);
+            let codeword = self.col.encode(&column[..self.col.k()])?;
+            self.set_column(&mut block, j, &codeword);
+        }
+        Ok(block)
+    }
+
+    /// Corrects `block` in place, returning the number of bytes fixed.
+    ///
+    /// Rows and columns are decoded in turn, each pass clearing errors that
+    /// were too dense for the other direction. Rows that fail to decode are
+    /// passed to the column decoder as erasures, so whole lost rows are
+    /// recovered as long as no more than `n1 - k1` of them are gone. The
+    /// block is left untouched if it cannot be fully corrected.
+    pub fn decode(&self, block: &mut [u8]) -> Result<usize, CodeError> {
+        if block.len() != self.block_len() {
+            return Err(CodeError::WrongLength);
+        }
+        let width = self.row.n();
+        let mut work = block.to_vec();
+        let mut fixed = 0;
+
+        for _ in 0..MAX_PASSES {
+            let mut failed_rows = Vec::new();
+            let mut pass_fixed = 0;
+            for (i, row) in work.chunks_mut(width).enumerate() {
+                let mut codeword = row.to_vec();
+                match self.row.decode(&mut codeword) {
+                    Ok(n) => {
+                        row.copy_from_slice(&codeword);
+                        pass_fixed += n;
+                    }
+                    Err(_) => failed_rows.push(i),
+                }
+            }
+
+            let erasures: &[usize] = if failed_rows.len() <= self.col.parity_len() {
+                &failed_rows
+            } else {
+                &[]
+            };
+            let mut failed_cols = 0;
+            let mut col_fixed = 0;
+            for j in 0..width {
+                let mut column = self.column(&work, j);
+                match self.col.decode_with_erasures(&mut column, erasures) {
+                    Ok(n) => {
+                        self.set_column(&mut work, j, &column);
+                        col_fixed += n;
+                    }
+                    Err(_) => failed_cols += 1,
+                }
+            }
+            fixed += pass_fixed + col_fixed;
+
+            // the rows were codewords and the columns needed nothing
+            if failed_rows.is_empty() && failed_cols == 0 && col_fixed == 0 {
+                block.copy_from_slice(&work);
+                return Ok(fixed);
+            }
+            if pass_fixed + col_fixed == 0 {
+                break;
+            }
+        }
+        Err(CodeError::TooManyErrors)
+    }
+
+    /// Strips the parity from a decoded block, returning the original
+    /// message.
+    pub fn message(&self, block: &[u8]) -> Result<Vec<u8>, CodeError> {
+        if block.len() != self.block_len() {
+            return Err(CodeError::WrongLength);
+        }
+        Ok(block
+            .chunks(self.row.n())
+            .take(self.col.k())
+            .flat_map(|row| &row[..self.row.k()])
+            .copied()
+            .collect())
+    }
+
+    fn column(&self, block: &[u8], j: usize) -> Vec<u8> {
+        block
+            .iter()
+            .skip(j)
+            .step_by(self.row.n())
+            .copied()
+            .collect()
+    }
+
+    fn set_column(&self, block: &mut [u8], j: usize, column: &[u8]) {
+        for (i, v) in column.iter().enumerate() {
+            block[i * self.row.n() + j] = *v;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code() -> ProductCode {
+        ProductCode::new(RsCode::new(20, 16).unwrap(), RsCode::new(12, 8).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_product_codewords() {
+        let code = code();
+        let msg: Vec<u8> = (0..code.message_len())
+            .map(|i| (i * 31 + 5) as u8)
+            .collect();
+        let block = code.encode(&msg).unwrap();
+        for row in block.chunks(20) {
+            assert!(code
+                .row_code()
+                .syndromes(row)
+                .iter()
+                .all(|s| u8::from(*s) == 0));
+        }
+        for j in 0..20 {
+            let column = code.column(&block, j);
+            assert!(code
+                .col_code()
+                .syndromes(&column)
+                .iter()
+                .all(|s| u8::from(*s) == 0));
+        }
+        assert_eq!(code.message(&block).unwrap(), msg);
+    }
+
+    #[test]
+    fn test_iterative_decode() {
+        let code = code();
+        let msg: Vec<u8> = (0..code.message_len()).map(|i| (i * 7) as u8).collect();
+        let block = code.encode(&msg).unwrap();
+
+        // three errors in each of rows 0..5 and a 3x3 burst are too many for
+        // the rows, and with the lost row 10 too many erasures for the
+        // columns, so the burst only falls to the columns on a second pass
+        let mut received = block.clone();
+        for i in 0..5 {
+            for j in 3 * i..3 * i + 3 {
+                received[i * 20 + j] ^= 0x5a;
+            }
+        }
+        for i in 6..9 {
+            for j in 16..19 {
+                received[i * 20 + j] ^= 0xa5;
+            }
+        }
+        received[200..220].fill(0);
+        let errors = received.iter().zip(&block).filter(|(a, b)| a != b).count();
+        assert_eq!(code.decode(&mut received), Ok(errors));
+        assert_eq!(received, block);
+
+        let mut hopeless = block.clone();
+        for v in &mut hopeless[..100] {
+            *v ^= 0xff;
+        }
+        let copy = hopeless.clone();
+        assert_eq!(code.decode(&mut hopeless), Err(CodeError::TooManyErrors));
+        assert_eq!(hopeless, copy);
+    }
+}
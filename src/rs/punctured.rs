@@ -0,0 +1,147 @@
+use super::code::{CodeError, RsCode};
+
+/// An [`RsCode`] with some of its parity symbols left out of every
+/// transmitted word.
+///
+/// The encoder computes the full parity and drops the punctured symbols; the
+/// decoder puts placeholders back and decodes them as erasures, so `e` errors
+/// and `s` erasures in the transmitted word are corrected whenever
+/// `2e + s <= n - k - punctured`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuncturedCode {
+    code: RsCode,
+    // codeword positions of the punctured symbols, sorted
+    punctured: Vec<usize>,
+}
+
+impl PuncturedCode {
+    /// Punctures `code` at the given parity indices, each in `0..n - k`.
+    pub fn new(code: RsCode, parity_positions: &[usize]) -> Result<Self, CodeError> {
+        let mut punctured: Vec<usize> = parity_positions.iter().map(|p| code.k() + p).collect();
+        punctured.sort_unstable();
+        punctured.dedup();
+        if punctured.len() >= code.parity_len() || punctured.iter().any(|p| *p >= code.n()) {
+            return Err(CodeError::InvalidParameters);
+        }
+        Ok(PuncturedCode { code, punctured })
+    }
+
+    /// The unpunctured code.
+    pub fn code(&self) -> &RsCode {
+        &self.code
+    }
+
+    /// The length of a transmitted word.
+    pub fn n(&self) -> usize {
+        self.code.n() - self.punctured.len()
+    }
+
+    pub fn k(&self) -> usize {
+        self.code.k()
+    }
+
+    /// The number of symbol errors the code can always correct.
+    pub fn capability(&self) -> usize {
+        (self.n() - self.k()) / 2
+    }
+
+    /// Returns the transmitted word for `msg`: the message followed by the
+    /// parity that survives puncturing.
+    pub fn encode(&self, msg: &[u8]) -> Result<Vec<u8>, CodeError> {
+        let codeword = self.code.encode(msg)?;
+        Ok(codeword
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| self.punctured.binary_search(i).is_err())
+            .map(|(_, v)| v)
+            .collect())
+    }
+
+    /// Corrects `word` in place, returning the number of transmitted symbols
+    /// fixed.
+    pub fn decode(&self, word: &mut [u8]) -> Result<usize, CodeError> {
+        self.decode_with_erasures(word, &[])
+    }
+
+    /// Corrects `word` in place given the positions of known erasures within
+    /// the transmitted word.
+    pub fn decode_with_erasures(
+        &self,
+        word: &mut [u8],
+        erasures: &[usize],
+    ) -> Result<usize, CodeError> {
+        if word.len() != self.n() || erasures.iter().any(|e| *e >= self.n()) {
+            return Err(CodeError::WrongLength);
+        }
+
+        let mut codeword = vec![0u8; self.code.n()];
+        let mut all_erasures = self.punctured.clone();
+        let mut sent = Vec::with_capacity(word.len());
+        for i in 0..codeword.len() {
+            if self.punctured.binary_search(&i).is_err() {
+                sent.push(i);
+            }
+        }
+        for (i, v) in sent.iter().zip(word.iter()) {
+            codeword[*i] = *v;
+        }
+        all_erasures.extend(erasures.iter().map(|e| sent[*e]));
+
+        self.code
+            .decode_with_erasures(&mut codeword, &all_erasures)?;
+        let mut fixed = 0;
+        for (i, v) in sent.iter().zip(word.iter_mut()) {
+            if *v != codeword[*i] {
+                *v = codeword[*i];
+                fixed += 1;
+            }
+        }
+        Ok(fixed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puncture_round_trip() {
+        let code = PuncturedCode::new(RsCode::new(60, 40).unwrap(), &[0, 7, 19, 3]).unwrap();
+        assert_eq!((code.n(), code.k(), code.capability()), (56, 40, 8));
+
+        let msg: Vec<u8> = (0..40).map(|i| i * 5 + 1).collect();
+        let sent = code.encode(&msg).unwrap();
+        assert_eq!(sent.len(), 56);
+        assert_eq!(sent[..40], msg[..]);
+
+        let mut received = sent.clone();
+        for e in 0..8 {
+            received[e * 7] ^= 0x9e;
+        }
+        assert_eq!(code.decode(&mut received), Ok(8));
+        assert_eq!(received, sent);
+
+        // two erasures cost one error of capability
+        let mut received = sent.clone();
+        for e in 0..7 {
+            received[e * 7 + 1] ^= 0x11;
+        }
+        received[50] = 0;
+        received[55] = 0;
+        assert!(code.decode_with_erasures(&mut received, &[50, 55]).is_ok());
+        assert_eq!(received, sent);
+    }
+
+    #[test]
+    fn test_invalid_puncture() {
+        let code = RsCode::new(20, 16).unwrap();
+        assert_eq!(
+            PuncturedCode::new(code.clone(), &[0, 1, 2, 3]),
+            Err(CodeError::InvalidParameters)
+        );
+        assert_eq!(
+            PuncturedCode::new(code, &[4]),
+            Err(CodeError::InvalidParameters)
+        );
+    }
+}
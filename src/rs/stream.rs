@@ -0,0 +1,309 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use super::ReedSolomon;
+
+// A progress hook, called with the number of stream bytes processed so far
+// after every stripe. Returning `false` cancels the operation, which then
+// fails with an `io::ErrorKind::Other` error.
+struct Progress(Box<dyn FnMut(u64) -> bool + Send>);
+
+impl Progress {
+    fn new(f: impl FnMut(u64) -> bool + Send + 'static) -> Self {
+        Progress(Box::new(f))
+    }
+
+    fn report(&mut self, done: u64) -> io::Result<()> {
+        report(&mut self.0, done)
+    }
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Progress")
+    }
+}
+
+// Calls a progress hook, turning a cancel into an error.
+pub(crate) fn report(hook: &mut impl FnMut(u64) -> bool, done: u64) -> io::Result<()> {
+    if hook(done) {
+        Ok(())
+    } else {
+        Err(io::Error::other("operation cancelled"))
+    }
+}
+
+/// Erasure-codes a byte stream into `k + m` shard streams.
+///
+/// The stream is cut into stripes of `k * block_size` bytes; every stripe
+/// appends one block to each data shard and one block of parity to each
+/// parity shard. Call [`RsWriter::finish`] to write the last, zero-padded
+/// stripe, and keep the returned length for [`RsReader`].
+#[derive(Debug)]
+pub struct RsWriter<W: Write> {
+    rs: ReedSolomon,
+    shards: Vec<W>,
+    block_size: usize,
+    // k * block_size, the bytes in a full stripe
+    stripe_len: usize,
+    stripe: Vec<u8>,
+    written: u64,
+    progress: Option<Progress>,
+}
+
+impl<W: Write> RsWriter<W> {
+    /// Panics unless there is one writer per shard and `block_size > 0`.
+    pub fn new(rs: ReedSolomon, shards: Vec<W>, block_size: usize) -> Self {
+        assert_eq!(shards.len(), rs.total_shards(), "one writer per shard");
+        assert!(block_size > 0, "block size must be positive");
+        let stripe_len = rs.data_shards() * block_size;
+        RsWriter {
+            rs,
+            shards,
+            block_size,
+            stripe_len,
+            stripe: Vec::with_capacity(stripe_len),
+            written: 0,
+            progress: None,
+        }
+    }
+
+    /// Reports the bytes written after every stripe. If `f` returns `false`
+    /// the stream is cancelled and fails with an `io::ErrorKind::Other` error.
+    pub fn with_progress(mut self, f: impl FnMut(u64) -> bool + Send + 'static) -> Self {
+        self.progress = Some(Progress::new(f));
+        self
+    }
+
+    /// Pads and writes any buffered partial stripe, flushes the shards and
+    /// returns them along with the stream length.
+    pub fn finish(mut self) -> io::Result<(Vec<W>, u64)> {
+        if !self.stripe.is_empty() {
+            self.stripe.resize(self.stripe_len, 0);
+            self.write_stripe()?;
+        }
+        for shard in &mut self.shards {
+            shard.flush()?;
+        }
+        Ok((self.shards, self.written))
+    }
+
+    fn write_stripe(&mut self) -> io::Result<()> {
+        let data: Vec<&[u8]> = self.stripe.chunks(self.block_size).collect();
+        let parity = self
+            .rs
+            .encode(&data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        for (shard, block) in self.shards.iter_mut().zip(data.iter().copied()) {
+            shard.write_all(block)?;
+        }
+        for (shard, block) in self.shards[self.rs.data_shards()..].iter_mut().zip(&parity) {
+            shard.write_all(block)?;
+        }
+        self.stripe.clear();
+        match &mut self.progress {
+            Some(p) => p.report(self.written),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<W: Write> Write for RsWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let room = self.stripe_len - self.stripe.len();
+        let n = room.min(buf.len());
+        self.stripe.extend_from_slice(&buf[..n]);
+        self.written += n as u64;
+        if self.stripe.len() == self.stripe_len {
+            self.write_stripe()?;
+        }
+        Ok(n)
+    }
+
+    /// Flushes the shard writers. A partial stripe stays buffered until it
+    /// fills up or the writer is finished.
+    fn flush(&mut self) -> io::Result<()> {
+        for shard in &mut self.shards {
+            shard.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads back a stream written by [`RsWriter`] from any `k` of its shards.
+///
+/// Missing shards are passed as `None`; a shard that fails to read is
+/// dropped and reconstructed from the others from then on.
+#[derive(Debug)]
+pub struct RsReader<R: Read> {
+    rs: ReedSolomon,
+    shards: Vec<Option<R>>,
+    block_size: usize,
+    stripe: Vec<u8>,
+    pos: usize,
+    remaining: u64,
+    done: u64,
+    progress: Option<Progress>,
+}
+
+impl<R: Read> RsReader<R> {
+    /// Panics unless there is one entry per shard and `block_size > 0`.
+    /// `len` is the stream length returned by [`RsWriter::finish`].
+    pub fn new(rs: ReedSolomon, shards: Vec<Option<R>>, block_size: usize, len: u64) -> Self {
+        assert_eq!(shards.len(), rs.total_shards(), "one entry per shard");
+        assert!(block_size > 0, "block size must be positive");
+        RsReader {
+            rs,
+            shards,
+            block_size,
+            stripe: Vec::new(),
+            pos: 0,
+            remaining: len,
+            done: 0,
+            progress: None,
+        }
+    }
+
+    /// Reports the bytes decoded after every stripe. If `f` returns `false`
+    /// the stream is cancelled and fails with an `io::ErrorKind::Other` error.
+    pub fn with_progress(mut self, f: impl FnMut(u64) -> bool + Send + 'static) -> Self {
+        self.progress = Some(Progress::new(f));
+        self
+    }
+
+    fn read_stripe(&mut self) -> io::Result<()> {
+        let mut blocks: Vec<Option<Vec<u8>>> = Vec::with_capacity(self.shards.len());
+        for slot in &mut self.shards {
+            let block = slot.as_mut().and_then(|shard| {
+                let mut block = vec![0u8; self.block_size];
+                shard.read_exact(&mut block).ok().map(|_| block)
+            });
+            if block.is_none() {
+                *slot = None;
+            }
+            blocks.push(block);
+        }
+        self.rs
+            .reconstruct(&mut blocks)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let stripe_len = (self.rs.data_shards() * self.block_size) as u64;
+        let keep = stripe_len.min(self.remaining) as usize;
+        self.stripe = blocks
+            .into_iter()
+            .take(self.rs.data_shards())
+            .flatten()
+            .flatten()
+            .take(keep)
+            .collect();
+        self.pos = 0;
+        self.remaining -= keep as u64;
+        self.done += keep as u64;
+        match &mut self.progress {
+            Some(p) => p.report(self.done),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<R: Read> Read for RsReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.stripe.len() {
+            if self.remaining == 0 {
+                return Ok(0);
+            }
+            self.read_stripe()?;
+        }
+        let n = buf.len().min(self.stripe.len() - self.pos);
+        buf[..n].copy_from_slice(&self.stripe[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_stream(rs: &ReedSolomon, input: &[u8], block_size: usize) -> (Vec<Vec<u8>>, u64) {
+        let shards = vec![Vec::new(); rs.total_shards()];
+        let mut writer = RsWriter::new(rs.clone(), shards, block_size);
+        for chunk in input.chunks(37) {
+            writer.write_all(chunk).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rs = ReedSolomon::new(4, 2).unwrap();
+        let input: Vec<u8> = (0..1000u32).map(|i| (i * 7 + i / 255) as u8).collect();
+        let (shards, len) = encode_stream(&rs, &input, 64);
+        assert_eq!(len, 1000);
+        // four stripes of 256 bytes, the last one padded
+        assert!(shards.iter().all(|s| s.len() == 4 * 64));
+
+        for lost in [[0usize, 1], [2, 5], [4, 5]] {
+            let readers: Vec<Option<&[u8]>> = shards
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (!lost.contains(&i)).then_some(&s[..]))
+                .collect();
+            let mut output = Vec::new();
+            RsReader::new(rs.clone(), readers, 64, len)
+                .read_to_end(&mut output)
+                .unwrap();
+            assert_eq!(output, input);
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        use std::sync::{Arc, Mutex};
+
+        let rs = ReedSolomon::new(2, 1).unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let shards = vec![Vec::new(); 3];
+        let mut writer = RsWriter::new(rs.clone(), shards, 10).with_progress(move |n| {
+            log.lock().unwrap().push(n);
+            true
+        });
+        writer.write_all(&[1; 45]).unwrap();
+        let (shards, len) = writer.finish().unwrap();
+        assert_eq!(*seen.lock().unwrap(), [20, 40, 45]);
+
+        // cancel after the first stripe
+        let readers: Vec<Option<&[u8]>> = shards.iter().map(|s| Some(&s[..])).collect();
+        let mut reader = RsReader::new(rs, readers, 10, len).with_progress(|n| n <= 20);
+        let mut buf = [0u8; 20];
+        reader.read_exact(&mut buf).unwrap();
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_truncated_shard() {
+        let rs = ReedSolomon::new(3, 1).unwrap();
+        let input = vec![0xabu8; 300];
+        let (shards, len) = encode_stream(&rs, &input, 50);
+
+        // shard 1 ends early and is reconstructed from the others
+        let mut readers: Vec<Option<&[u8]>> = shards.iter().map(|s| Some(&s[..])).collect();
+        readers[1] = Some(&shards[1][..70]);
+        let mut output = Vec::new();
+        RsReader::new(rs.clone(), readers, 50, len)
+            .read_to_end(&mut output)
+            .unwrap();
+        assert_eq!(output, input);
+
+        let mut readers: Vec<Option<&[u8]>> = shards.iter().map(|s| Some(&s[..])).collect();
+        readers[0] = None;
+        readers[3] = None;
+        let err = RsReader::new(rs, readers, 50, len)
+            .read_to_end(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,369 @@
+//! Additive and multiplicative scramblers over GF(2).
+//!
+//! A scrambler keeps the last `n` bits of a register and taps it with a
+//! polynomial `1 + x^(d_1) + .. + x^n`, each term `x^d` being the bit from
+//! `d` steps ago. It sees the data one bit at a time, in a configurable
+//! order within each byte.
+//!
+//! - An additive (synchronous) scrambler runs the register as an LFSR on
+//!   its own feedback and XORs that sequence into the data; descrambling is
+//!   the same operation from the same seed.
+//! - A multiplicative (self-synchronizing) scrambler feeds its own output
+//!   back: `y_t = x_t + Σ y_(t-d)`. The descrambler computes
+//!   `x_t = y_t + Σ y_(t-d)` and falls into step after `n` bits whatever
+//!   its seed, at the cost of multiplying each bit error by the number of
+//!   terms.
+//!
+//! Seeds hold the bit from `d` steps ago in bit `d - 1`.
+
+use std::io::{self, Read, Write};
+
+/// The kind of scrambler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScramblerKind {
+    Additive,
+    Multiplicative,
+}
+
+/// The parameters of a scrambler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScramblerParams {
+    pub kind: ScramblerKind,
+    /// The degree `n` of the polynomial, from 1 to 64.
+    pub degree: u8,
+    /// The polynomial without its constant term, bit `d - 1` for `x^d`.
+    pub taps: u64,
+    /// The register before the first bit.
+    pub seed: u64,
+    /// Whether each byte is processed least significant bit first.
+    pub lsb_first: bool,
+}
+
+/// The IEEE 802.11 data scrambler, `1 + x^4 + x^7`, from the all-ones
+/// state.
+pub const IEEE_802_11: ScramblerParams = ScramblerParams {
+    kind: ScramblerKind::Additive,
+    degree: 7,
+    taps: 1 << 3 | 1 << 6,
+    seed: 0x7f,
+    lsb_first: true,
+};
+
+/// The DVB-S/T energy dispersal PRBS, `1 + x^14 + x^15`, from its
+/// initialization sequence `100101010000000`. The standard restarts it
+/// every eight transport packets and does not scramble the sync bytes;
+/// that framing is left to the caller.
+pub const DVB: ScramblerParams = ScramblerParams {
+    kind: ScramblerKind::Additive,
+    degree: 15,
+    taps: 1 << 13 | 1 << 14,
+    seed: 0xa9,
+    lsb_first: false,
+};
+
+/// The SONET/SDH frame-synchronous scrambler, `1 + x^6 + x^7`. The
+/// standard resets it to all ones and outputs its last stage, which runs
+/// seven bits ahead of the feedback used here; the seed is the state seven
+/// steps earlier, so the sequence starts `fe 04 18 51` as specified.
+pub const SONET: ScramblerParams = ScramblerParams {
+    kind: ScramblerKind::Additive,
+    degree: 7,
+    taps: 1 << 5 | 1 << 6,
+    seed: 0x2a,
+    lsb_first: false,
+};
+
+/// The ITU-T V.34 self-synchronizing scrambler of the call mode modem,
+/// `1 + x^-18 + x^-23`.
+pub const V34_CALL: ScramblerParams = ScramblerParams {
+    kind: ScramblerKind::Multiplicative,
+    degree: 23,
+    taps: 1 << 17 | 1 << 22,
+    seed: 0,
+    lsb_first: true,
+};
+
+/// The ITU-T V.34 self-synchronizing scrambler of the answer mode modem,
+/// `1 + x^-5 + x^-23`.
+pub const V34_ANSWER: ScramblerParams = ScramblerParams {
+    kind: ScramblerKind::Multiplicative,
+    degree: 23,
+    taps: 1 << 4 | 1 << 22,
+    seed: 0,
+    lsb_first: true,
+};
+
+/// The IEEE 802.3 64B/66B self-synchronizing scrambler, `1 + x^39 + x^58`,
+/// which leaves the seed unspecified; all ones here.
+pub const IEEE_802_3_64B66B: ScramblerParams = ScramblerParams {
+    kind: ScramblerKind::Multiplicative,
+    degree: 58,
+    taps: 1 << 38 | 1 << 57,
+    seed: (1 << 58) - 1,
+    lsb_first: true,
+};
+
+/// A scrambler and its register.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scrambler {
+    params: ScramblerParams,
+    state: u64,
+}
+
+impl Scrambler {
+    /// Panics if the degree is not in `1..=64`, the taps lack `x^n` or have
+    /// bits above it, or the seed has bits above it.
+    pub fn new(params: ScramblerParams) -> Self {
+        let n = params.degree;
+        assert!((1..=64).contains(&n), "bad scrambler degree");
+        assert!(params.taps >> (n - 1) == 1, "bad scrambler taps");
+        assert!(params.seed & !mask(n) == 0, "bad scrambler seed");
+        Scrambler {
+            params,
+            state: params.seed,
+        }
+    }
+
+    pub fn params(&self) -> ScramblerParams {
+        self.params
+    }
+
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Restores the seed.
+    pub fn reset(&mut self) {
+        self.state = self.params.seed;
+    }
+
+    /// Scrambles `data` in place.
+    pub fn scramble(&mut self, data: &mut [u8]) {
+        self.process(data, false);
+    }
+
+    /// Descrambles `data` in place.
+    pub fn descramble(&mut self, data: &mut [u8]) {
+        self.process(data, true);
+    }
+
+    fn process(&mut self, data: &mut [u8], descramble: bool) {
+        let p = self.params;
+        for byte in data {
+            let mut out = 0u8;
+            for i in 0..8 {
+                let pos = if p.lsb_first { i } else { 7 - i };
+                let x = *byte >> pos & 1;
+                let feedback = ((self.state & p.taps).count_ones() & 1) as u8;
+                let y = x ^ feedback;
+                let shifted_in = match p.kind {
+                    ScramblerKind::Additive => feedback,
+                    ScramblerKind::Multiplicative if descramble => x,
+                    ScramblerKind::Multiplicative => y,
+                };
+                self.state = (self.state << 1 | shifted_in as u64) & mask(p.degree);
+                out |= y << pos;
+            }
+            *byte = out;
+        }
+    }
+}
+
+/// Whether a stream adaptor scrambles or descrambles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Scramble,
+    Descramble,
+}
+
+/// Scrambles or descrambles everything written through it.
+#[derive(Debug)]
+pub struct ScramblerWriter<W: Write> {
+    scrambler: Scrambler,
+    direction: Direction,
+    inner: W,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> ScramblerWriter<W> {
+    pub fn new(scrambler: Scrambler, direction: Direction, inner: W) -> Self {
+        ScramblerWriter {
+            scrambler,
+            direction,
+            inner,
+            buf: Vec::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for ScramblerWriter<W> {
+    /// Processes and writes all of `buf`, since the register has already
+    /// moved past it.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.clear();
+        self.buf.extend_from_slice(buf);
+        let s = &mut self.scrambler;
+        s.process(&mut self.buf, self.direction == Direction::Descramble);
+        self.inner.write_all(&self.buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Scrambles or descrambles everything read through it.
+#[derive(Debug)]
+pub struct ScramblerReader<R: Read> {
+    scrambler: Scrambler,
+    direction: Direction,
+    inner: R,
+}
+
+impl<R: Read> ScramblerReader<R> {
+    pub fn new(scrambler: Scrambler, direction: Direction, inner: R) -> Self {
+        ScramblerReader {
+            scrambler,
+            direction,
+            inner,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ScramblerReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        let s = &mut self.scrambler;
+        s.process(&mut buf[..n], self.direction == Direction::Descramble);
+        Ok(n)
+    }
+}
+
+fn mask(degree: u8) -> u64 {
+    if degree == 64 {
+        !0
+    } else {
+        (1 << degree) - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRESETS: [ScramblerParams; 6] = [
+        IEEE_802_11,
+        DVB,
+        SONET,
+        V34_CALL,
+        V34_ANSWER,
+        IEEE_802_3_64B66B,
+    ];
+
+    #[test]
+    fn test_sequences() {
+        // the 127-bit sequence of IEEE 802.11, clause 17.3.5.5, from zeros
+        let expected = concat!(
+            "00001110111100101100100100000010001001100010111010110110000011",
+            "00110101001110011110110100001010101111101001010001101110001111111"
+        );
+        let mut data = [0u8; 16];
+        Scrambler::new(IEEE_802_11).scramble(&mut data);
+        let bits: String = (0..127)
+            .map(|i| {
+                if data[i / 8] >> (i % 8) & 1 == 1 {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect();
+        assert_eq!(bits, expected);
+
+        let mut data = [0u8; 8];
+        Scrambler::new(SONET).scramble(&mut data);
+        assert_eq!(data, [0xfe, 0x04, 0x18, 0x51, 0xe4, 0x59, 0xd4, 0xfa]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let data: Vec<u8> = (0..500u32).map(|i| (i * 37 + i / 3) as u8).collect();
+        for params in PRESETS {
+            let mut scrambled = data.clone();
+            Scrambler::new(params).scramble(&mut scrambled);
+            assert_ne!(scrambled, data);
+            let mut descrambled = scrambled.clone();
+            Scrambler::new(params).descramble(&mut descrambled);
+            assert_eq!(descrambled, data);
+
+            // the register carries over between calls
+            let mut s = Scrambler::new(params);
+            let mut split = data.clone();
+            s.scramble(&mut split[..123]);
+            s.scramble(&mut split[123..]);
+            assert_eq!(split, scrambled);
+        }
+    }
+
+    #[test]
+    fn test_self_synchronizing() {
+        let data: Vec<u8> = (0..64u8).collect();
+        for params in [V34_CALL, IEEE_802_3_64B66B] {
+            let mut scrambled = data.clone();
+            Scrambler::new(params).scramble(&mut scrambled);
+            // a descrambler with the wrong seed recovers after n bits
+            let mut descrambled = scrambled.clone();
+            let wrong = ScramblerParams {
+                seed: 0x1234,
+                ..params
+            };
+            Scrambler::new(wrong).descramble(&mut descrambled);
+            let skip = (params.degree as usize).div_ceil(8);
+            assert_eq!(descrambled[skip..], data[skip..]);
+
+            // a bit error spreads to three bits, one per term
+            let mut corrupted = scrambled.clone();
+            corrupted[20] ^= 0x10;
+            Scrambler::new(params).descramble(&mut corrupted);
+            let errors: u32 = corrupted
+                .iter()
+                .zip(&data)
+                .map(|(a, b)| (a ^ b).count_ones())
+                .sum();
+            assert_eq!(errors, 3);
+        }
+    }
+
+    #[test]
+    fn test_streams() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7) as u8).collect();
+        for params in PRESETS {
+            let mut w = ScramblerWriter::new(Scrambler::new(params), Direction::Scramble, vec![]);
+            for chunk in data.chunks(77) {
+                w.write_all(chunk).unwrap();
+            }
+            let scrambled = w.into_inner();
+            let mut expected = data.clone();
+            Scrambler::new(params).scramble(&mut expected);
+            assert_eq!(scrambled, expected);
+
+            let mut r = ScramblerReader::new(
+                Scrambler::new(params),
+                Direction::Descramble,
+                &scrambled[..],
+            );
+            let mut out = Vec::new();
+            r.read_to_end(&mut out).unwrap();
+            assert_eq!(out, data);
+        }
+    }
+}
@@ -0,0 +1,538 @@
+//! Shamir secret sharing over GF(256).
+//!
+//! Each byte of the secret is the constant term of its own random
+//! polynomial of degree `k - 1`, and share `x` holds the value of every
+//! polynomial at `x`. Any `k` shares determine the polynomials and so the
+//! secret by Lagrange interpolation at zero, while fewer reveal nothing
+//! about it.
+//!
+//! Randomness comes from the caller as a function that fills a buffer, so
+//! that any cryptographically secure generator can be plugged in, e.g.
+//! `|buf| rand::rng().fill_bytes(buf)`.
+//!
+//! [`split_ramp`] packs several secrets into one sharing, trading the gap
+//! between the privacy and recovery thresholds for smaller shares.
+//!
+//! The [`hardened`] module repeats [`split`] and [`combine`] with
+//! constant-time arithmetic for key material.
+//!
+//! [`ShareRecord`] stores a share with its threshold and an optional label
+//! in a versioned wire format.
+//!
+//! [`split_verifiable`] adds pairwise information-theoretic MACs, with
+//! which [`combine_verified`] rejects tampered shares.
+
+use std::fmt;
+
+pub mod hardened;
+mod mac;
+mod wire;
+pub use mac::{combine_verified, split_verifiable, MacKey, VerifiableShare};
+pub use wire::ShareRecord;
+
+use crate::poly::Poly;
+use crate::slice::{add_slice, mul_add_slice, scale_slice};
+use crate::Galois;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShamirError {
+    /// `k` is zero, `k > n` or `n` exceeds 255.
+    InvalidThreshold,
+    /// No shares were given.
+    NotEnoughShares,
+    /// A share has x-coordinate zero or repeats another's.
+    DuplicateShare,
+    /// The shares do not all have the same length.
+    WrongLength,
+    /// More shares are wrong than [`combine_robust`] can correct.
+    TooManyBadShares,
+    /// Encoded share bytes are truncated or inconsistent.
+    Malformed,
+    /// Encoded share bytes are of a format version this crate cannot read.
+    UnsupportedVersion,
+    /// Encoded share bytes do not match their checksum.
+    ChecksumMismatch,
+}
+
+impl fmt::Display for ShamirError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShamirError::InvalidThreshold => write!(f, "invalid threshold"),
+            ShamirError::NotEnoughShares => write!(f, "not enough shares"),
+            ShamirError::DuplicateShare => write!(f, "duplicate or zero share index"),
+            ShamirError::WrongLength => write!(f, "share length mismatch"),
+            ShamirError::TooManyBadShares => write!(f, "too many bad shares"),
+            ShamirError::Malformed => write!(f, "malformed share"),
+            ShamirError::UnsupportedVersion => write!(f, "unsupported share version"),
+            ShamirError::ChecksumMismatch => write!(f, "share checksum mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for ShamirError {}
+
+/// One share: the x-coordinate and the value of each byte's polynomial
+/// there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    pub x: u8,
+    pub y: Vec<u8>,
+}
+
+/// Splits `secret` into `n` shares at `x = 1..=n`, any `k` of which recover
+/// it, drawing the random coefficients from `rng`.
+pub fn split<R>(secret: &[u8], k: usize, n: usize, mut rng: R) -> Result<Vec<Share>, ShamirError>
+where
+    R: FnMut(&mut [u8]),
+{
+    if k == 0 || k > n || n > 255 {
+        return Err(ShamirError::InvalidThreshold);
+    }
+
+    // coeffs[j] holds the coefficient of x^j of every byte's polynomial
+    let mut coeffs = vec![secret.to_vec()];
+    for _ in 1..k {
+        let mut c = vec![0u8; secret.len()];
+        rng(&mut c);
+        coeffs.push(c);
+    }
+
+    let shares = (1..=n as u8)
+        .map(|x| Share {
+            x,
+            y: eval(&coeffs, Galois::new(x)),
+        })
+        .collect();
+    Ok(shares)
+}
+
+/// Recovers the secret from at least `k` shares of a [`split`].
+///
+/// Every share given is used, so with fewer than `k` shares the result is
+/// simply wrong; the threshold is not recorded in the shares.
+pub fn combine(shares: &[Share]) -> Result<Vec<u8>, ShamirError> {
+    let xs = check_shares(shares)?;
+    let mut secret = vec![0u8; shares[0].y.len()];
+    for (share, l) in shares.iter().zip(lagrange_at(&xs, Galois::zero())) {
+        mul_add_slice(l, &share.y, &mut secret);
+    }
+    Ok(secret)
+}
+
+/// Splits `secrets`, all of the same length, into `n` shares at
+/// `x = 1..=n` as a ramp scheme: any `privacy` shares reveal nothing about
+/// them, and any `secrets.len() + privacy` shares recover them all.
+///
+/// The secrets are the low coefficients of one polynomial per byte and the
+/// `privacy` coefficients above them are random, so each share is the size
+/// of a single secret rather than of all of them. Between the two
+/// thresholds, shares leak partial information.
+pub fn split_ramp<R>(
+    secrets: &[&[u8]],
+    privacy: usize,
+    n: usize,
+    mut rng: R,
+) -> Result<Vec<Share>, ShamirError>
+where
+    R: FnMut(&mut [u8]),
+{
+    let k = secrets.len() + privacy;
+    if secrets.is_empty() || k > n || n > 255 {
+        return Err(ShamirError::InvalidThreshold);
+    }
+    let len = secrets[0].len();
+    if secrets.iter().any(|s| s.len() != len) {
+        return Err(ShamirError::WrongLength);
+    }
+
+    let mut coeffs: Vec<Vec<u8>> = secrets.iter().map(|s| s.to_vec()).collect();
+    for _ in 0..privacy {
+        let mut c = vec![0u8; len];
+        rng(&mut c);
+        coeffs.push(c);
+    }
+    let shares = (1..=n as u8)
+        .map(|x| Share {
+            x,
+            y: eval(&coeffs, Galois::new(x)),
+        })
+        .collect();
+    Ok(shares)
+}
+
+/// Recovers `count` secrets from at least `count + privacy` shares of a
+/// [`split_ramp`], by interpolating the whole polynomial through every
+/// share given.
+pub fn combine_ramp(shares: &[Share], count: usize) -> Result<Vec<Vec<u8>>, ShamirError> {
+    let xs = check_shares(shares)?;
+    if shares.len() < count {
+        return Err(ShamirError::NotEnoughShares);
+    }
+    let mut secrets = vec![vec![0u8; shares[0].y.len()]; count];
+    for (i, share) in shares.iter().enumerate() {
+        // the Lagrange basis polynomial that is one at x_i and zero at the
+        // other points
+        let others: Vec<Galois> = (0..xs.len()).filter(|j| *j != i).map(|j| xs[j]).collect();
+        let basis = Poly::from_roots(&others);
+        let basis = basis.scale(basis.eval(xs[i]).inv());
+        for (j, secret) in secrets.iter_mut().enumerate() {
+            mul_add_slice(basis.coeff(j), &share.y, secret);
+        }
+    }
+    Ok(secrets)
+}
+
+/// Recovers the secret from `m` shares of a [`split`] with threshold `k` of
+/// which up to `(m - k) / 2` may hold wrong values, returning it with the
+/// indices into `shares` of the wrong ones.
+///
+/// The shares of each byte form a codeword of a Reed-Solomon code with the
+/// shares' x-coordinates as evaluation points, which Gao's algorithm
+/// decodes. A share is bad if any of its bytes is wrong.
+pub fn combine_robust(shares: &[Share], k: usize) -> Result<(Vec<u8>, Vec<usize>), ShamirError> {
+    let xs = check_shares(shares)?;
+    if k == 0 {
+        return Err(ShamirError::InvalidThreshold);
+    }
+    let m = shares.len();
+    if m < k {
+        return Err(ShamirError::NotEnoughShares);
+    }
+
+    let g0 = Poly::from_roots(&xs);
+    let mut secret = Vec::with_capacity(shares[0].y.len());
+    let mut bad = vec![false; m];
+    for b in 0..shares[0].y.len() {
+        let ys: Vec<Galois> = shares.iter().map(|s| Galois::new(s.y[b])).collect();
+        let f = gao_decode(&g0, &xs, &ys, k).ok_or(ShamirError::TooManyBadShares)?;
+        for (i, (x, y)) in xs.iter().zip(&ys).enumerate() {
+            bad[i] |= f.eval(*x) != *y;
+        }
+        secret.push(u8::from(f.coeff(0)));
+    }
+
+    // every byte decoded within its own capacity, but together the shares
+    // disagree in more places than a unique decoding allows
+    let bad: Vec<usize> = (0..m).filter(|i| bad[*i]).collect();
+    if 2 * bad.len() > m - k {
+        return Err(ShamirError::TooManyBadShares);
+    }
+    Ok((secret, bad))
+}
+
+// Finds the polynomial of degree below `k` that agrees with all but at most
+// `(m - k) / 2` of the `m` points, where `g0 = Π (x - x_i)`: run the
+// extended Euclidean algorithm on `g0` and the interpolating polynomial
+// until the remainder has degree below `(m + k) / 2`, and divide it by the
+// cofactor.
+fn gao_decode(g0: &Poly, xs: &[Galois], ys: &[Galois], k: usize) -> Option<Poly> {
+    let m = xs.len();
+    let (mut r0, mut r1) = (g0.clone(), Poly::interpolate(xs, ys));
+    let (mut v0, mut v1) = (Poly::zero(), Poly::one());
+    while r1.degree().is_some_and(|d| 2 * d >= m + k) {
+        let (q, r) = r0.div_rem(&r1);
+        r0 = std::mem::replace(&mut r1, r);
+        let v = &v0 - &(&q * &v1);
+        v0 = std::mem::replace(&mut v1, v);
+    }
+    let (f, rem) = r1.div_rem(&v1);
+    if !rem.is_zero() || f.degree().is_some_and(|d| d >= k) {
+        return None;
+    }
+    Some(f)
+}
+
+/// Turns at least `k` shares of a secret into `n_new` fresh shares with
+/// threshold `k_new`, as happens when the policy for a key changes.
+///
+/// Each old holder splits its own share with the new threshold and sends
+/// one sub-share to each new holder, who weights what it receives by the
+/// sender's Lagrange coefficient at zero. The new shares lie on a random
+/// polynomial with the same constant term, so the secret itself is never
+/// assembled. The old shares are unrelated to the new ones and cannot be
+/// mixed with them.
+pub fn reshare<R>(
+    shares: &[Share],
+    k_new: usize,
+    n_new: usize,
+    mut rng: R,
+) -> Result<Vec<Share>, ShamirError>
+where
+    R: FnMut(&mut [u8]),
+{
+    let xs = check_shares(shares)?;
+    if k_new == 0 || k_new > n_new || n_new > 255 {
+        return Err(ShamirError::InvalidThreshold);
+    }
+    let len = shares[0].y.len();
+    let mut fresh: Vec<Share> = (1..=n_new as u8)
+        .map(|x| Share {
+            x,
+            y: vec![0u8; len],
+        })
+        .collect();
+    for (share, l) in shares.iter().zip(lagrange_at(&xs, Galois::zero())) {
+        let sub = split(&share.y, k_new, n_new, &mut rng)?;
+        for (new, s) in fresh.iter_mut().zip(sub) {
+            mul_add_slice(l, &s.y, &mut new.y);
+        }
+    }
+    Ok(fresh)
+}
+
+/// Adds a random sharing of zero with threshold `k` to every share in
+/// place, so that the secret is unchanged but the old shares no longer
+/// combine with the new ones.
+///
+/// Refreshing periodically means an attacker must collect `k` shares within
+/// one period. Every outstanding share must be refreshed together.
+pub fn refresh<R>(shares: &mut [Share], k: usize, mut rng: R) -> Result<(), ShamirError>
+where
+    R: FnMut(&mut [u8]),
+{
+    check_shares(shares)?;
+    if k == 0 || k > 255 {
+        return Err(ShamirError::InvalidThreshold);
+    }
+    let mut coeffs = vec![vec![0u8; shares[0].y.len()]];
+    for _ in 1..k {
+        let mut c = coeffs[0].clone();
+        rng(&mut c);
+        coeffs.push(c);
+    }
+    for share in shares {
+        add_slice(&eval(&coeffs, Galois::new(share.x)), &mut share.y);
+    }
+    Ok(())
+}
+
+// Validates `shares`, returning their x-coordinates.
+pub(crate) fn check_shares(shares: &[Share]) -> Result<Vec<Galois>, ShamirError> {
+    let first = shares.first().ok_or(ShamirError::NotEnoughShares)?;
+    if shares.iter().any(|s| s.y.len() != first.y.len()) {
+        return Err(ShamirError::WrongLength);
+    }
+    let mut seen = [false; 256];
+    for s in shares {
+        if s.x == 0 || seen[s.x as usize] {
+            return Err(ShamirError::DuplicateShare);
+        }
+        seen[s.x as usize] = true;
+    }
+    Ok(shares.iter().map(|s| Galois::new(s.x)).collect())
+}
+
+// Evaluates the byte-wise polynomials with coefficients `coeffs` at `x`.
+pub(crate) fn eval(coeffs: &[Vec<u8>], x: Galois) -> Vec<u8> {
+    let mut y = coeffs.last().cloned().unwrap_or_default();
+    for c in coeffs.iter().rev().skip(1) {
+        scale_slice(x, &mut y);
+        add_slice(c, &mut y);
+    }
+    y
+}
+
+// The Lagrange basis polynomials through the points `xs`, evaluated at `at`:
+// `l_i(at) = Π (at - x_j) / (x_i - x_j)` over `j != i`.
+pub(crate) fn lagrange_at(xs: &[Galois], at: Galois) -> Vec<Galois> {
+    xs.iter()
+        .enumerate()
+        .map(|(i, xi)| {
+            xs.iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(Galois::identity(), |acc, (_, xj)| {
+                    acc * (at - *xj) / (*xi - *xj)
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::fountain::{random_bytes, SplitMix};
+
+    // A deterministic stand-in for a secure generator.
+    pub(crate) fn test_rng(seed: u64) -> impl FnMut(&mut [u8]) {
+        let mut rng = SplitMix(seed);
+        move |buf: &mut [u8]| buf.copy_from_slice(&random_bytes(&mut rng, buf.len()))
+    }
+
+    #[test]
+    fn test_split_combine() {
+        let secret = b"correct horse battery staple";
+        for (k, n) in [(1, 1), (2, 3), (3, 5), (5, 5), (10, 255)] {
+            let shares = split(secret, k, n, test_rng(k as u64)).unwrap();
+            assert_eq!(shares.len(), n);
+            // any k shares, here the last k and every other one
+            assert_eq!(combine(&shares[n - k..]).unwrap(), secret);
+            let spread: Vec<Share> = shares.iter().step_by(2).cloned().collect();
+            if spread.len() >= k {
+                assert_eq!(combine(&spread).unwrap(), secret);
+            }
+            if k > 1 {
+                assert_ne!(combine(&shares[..k - 1]).unwrap(), secret);
+            }
+        }
+    }
+
+    #[test]
+    fn test_combine_robust() {
+        let secret = b"stored on untrusted media";
+        let shares = split(secret, 3, 9, test_rng(6)).unwrap();
+        assert_eq!(
+            combine_robust(&shares, 3).unwrap(),
+            (secret.to_vec(), vec![])
+        );
+
+        // up to (9 - 3) / 2 = 3 bad shares, wrong in one byte or in all
+        let mut corrupted = shares.clone();
+        corrupted[1].y[0] ^= 1;
+        corrupted[4].y.iter_mut().for_each(|b| *b = !*b);
+        corrupted[8].y[20] ^= 0x80;
+        assert_eq!(
+            combine_robust(&corrupted, 3).unwrap(),
+            (secret.to_vec(), vec![1, 4, 8])
+        );
+        assert_ne!(combine(&corrupted[..3]).unwrap(), secret);
+
+        // with fewer shares the capacity drops
+        assert_eq!(
+            combine_robust(&corrupted[1..8], 3).unwrap(),
+            (secret.to_vec(), vec![0, 3])
+        );
+        assert_eq!(
+            combine_robust(&corrupted[1..7], 3),
+            Err(ShamirError::TooManyBadShares)
+        );
+
+        // four bad shares, each byte with at most three errors
+        corrupted[6].y[1] ^= 1;
+        assert_eq!(
+            combine_robust(&corrupted, 3),
+            Err(ShamirError::TooManyBadShares)
+        );
+        assert_eq!(
+            combine_robust(&shares[..2], 3),
+            Err(ShamirError::NotEnoughShares)
+        );
+    }
+
+    #[test]
+    fn test_ramp() {
+        let secrets: [&[u8]; 3] = [b"first key", b"other key", b"third key"];
+        let shares = split_ramp(&secrets, 2, 8, test_rng(7)).unwrap();
+        assert_eq!(shares.len(), 8);
+        assert!(shares.iter().all(|s| s.y.len() == 9));
+        assert_eq!(combine_ramp(&shares[..5], 3).unwrap(), secrets);
+        assert_eq!(combine_ramp(&shares[3..], 3).unwrap(), secrets);
+        assert_eq!(combine_ramp(&shares, 3).unwrap(), secrets);
+        assert_ne!(combine_ramp(&shares[..4], 3).unwrap(), secrets);
+
+        // one secret is plain Shamir
+        let single = split_ramp(&[b"only"], 2, 4, test_rng(8)).unwrap();
+        assert_eq!(combine(&single[1..]).unwrap(), b"only");
+
+        assert_eq!(
+            split_ramp(&secrets, 6, 8, test_rng(9)),
+            Err(ShamirError::InvalidThreshold)
+        );
+        assert_eq!(
+            split_ramp(&[], 2, 8, test_rng(9)),
+            Err(ShamirError::InvalidThreshold)
+        );
+        assert_eq!(
+            split_ramp(&[b"ab", b"c"], 2, 8, test_rng(9)),
+            Err(ShamirError::WrongLength)
+        );
+        assert_eq!(
+            combine_ramp(&shares[..2], 3),
+            Err(ShamirError::NotEnoughShares)
+        );
+    }
+
+    #[test]
+    fn test_reshare() {
+        let secret = b"rotate me";
+        let shares = split(secret, 3, 5, test_rng(1)).unwrap();
+        let mut rng = test_rng(2);
+
+        // to a larger threshold from a quorum, then down again
+        let larger = reshare(&shares[1..4], 4, 7, &mut rng).unwrap();
+        assert_eq!(larger.len(), 7);
+        assert_eq!(combine(&larger[3..]).unwrap(), secret);
+        assert_ne!(combine(&larger[..3]).unwrap(), secret);
+        let smaller = reshare(&larger[..4], 2, 3, &mut rng).unwrap();
+        assert_eq!(combine(&smaller[1..]).unwrap(), secret);
+        assert_ne!(combine(&smaller[..1]).unwrap(), secret);
+
+        // the new shares are fresh: same x, different y
+        let same = reshare(&shares, 3, 5, &mut rng).unwrap();
+        assert_eq!(combine(&same[2..]).unwrap(), secret);
+        assert!(same
+            .iter()
+            .zip(&shares)
+            .all(|(a, b)| a.x == b.x && a.y != b.y));
+        let mixed = [same[0].clone(), shares[1].clone(), same[2].clone()];
+        assert_ne!(combine(&mixed).unwrap(), secret);
+
+        assert_eq!(
+            reshare(&shares, 3, 2, &mut rng),
+            Err(ShamirError::InvalidThreshold)
+        );
+        assert_eq!(
+            reshare(&[], 2, 3, &mut rng),
+            Err(ShamirError::NotEnoughShares)
+        );
+    }
+
+    #[test]
+    fn test_refresh() {
+        let secret = b"long lived";
+        let old = split(secret, 3, 5, test_rng(3)).unwrap();
+        let mut shares = old.clone();
+        refresh(&mut shares, 3, test_rng(4)).unwrap();
+        assert!(shares.iter().zip(&old).all(|(a, b)| a.y != b.y));
+        assert_eq!(combine(&shares[..3]).unwrap(), secret);
+        assert_eq!(combine(&shares[2..]).unwrap(), secret);
+        let mixed = [old[0].clone(), old[1].clone(), shares[2].clone()];
+        assert_ne!(combine(&mixed).unwrap(), secret);
+        assert_ne!(combine(&shares[..2]).unwrap(), secret);
+
+        assert_eq!(
+            refresh(&mut shares, 0, test_rng(5)),
+            Err(ShamirError::InvalidThreshold)
+        );
+        assert_eq!(
+            refresh(&mut [], 2, test_rng(5)),
+            Err(ShamirError::NotEnoughShares)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            split(b"s", 0, 3, test_rng(0)),
+            Err(ShamirError::InvalidThreshold)
+        );
+        assert_eq!(
+            split(b"s", 4, 3, test_rng(0)),
+            Err(ShamirError::InvalidThreshold)
+        );
+        assert_eq!(
+            split(b"s", 2, 256, test_rng(0)),
+            Err(ShamirError::InvalidThreshold)
+        );
+        assert_eq!(combine(&[]), Err(ShamirError::NotEnoughShares));
+
+        let mut shares = split(b"secret", 2, 3, test_rng(1)).unwrap();
+        shares[1].x = shares[0].x;
+        assert_eq!(combine(&shares), Err(ShamirError::DuplicateShare));
+        shares[1].x = 0;
+        assert_eq!(combine(&shares), Err(ShamirError::DuplicateShare));
+        shares[1].x = 2;
+        shares[2].y.pop();
+        assert_eq!(combine(&shares), Err(ShamirError::WrongLength));
+    }
+}
@@ -0,0 +1,106 @@
+//! [`split`] and [`combine`] for key material under side-channel threat
+//! models.
+//!
+//! Every operation on secret bytes goes through [`Galois::mul_ct`], which
+//! neither branches on nor indexes tables by its operands, and the
+//! polynomial coefficients are zeroized before their memory is freed. The
+//! x-coordinates, thresholds and lengths are treated as public. The shares
+//! and the secret returned are the caller's to [`zeroize`].
+//!
+//! The results are identical to those of the plain functions given the same
+//! randomness.
+
+use std::sync::atomic::{compiler_fence, Ordering};
+
+use super::{check_shares, lagrange_at, ShamirError, Share};
+use crate::Galois;
+
+/// Like [`shamir::split`](super::split).
+pub fn split<R>(secret: &[u8], k: usize, n: usize, mut rng: R) -> Result<Vec<Share>, ShamirError>
+where
+    R: FnMut(&mut [u8]),
+{
+    if k == 0 || k > n || n > 255 {
+        return Err(ShamirError::InvalidThreshold);
+    }
+
+    let mut coeffs = vec![secret.to_vec()];
+    for _ in 1..k {
+        let mut c = vec![0u8; secret.len()];
+        rng(&mut c);
+        coeffs.push(c);
+    }
+
+    let shares = (1..=n as u8)
+        .map(|x| {
+            let x = Galois::new(x);
+            let y = (0..secret.len())
+                .map(|j| {
+                    let top = Galois::new(coeffs[k - 1][j]);
+                    let y = coeffs[..k - 1]
+                        .iter()
+                        .rev()
+                        .fold(top, |acc, c| acc.mul_ct(x) + Galois::new(c[j]));
+                    u8::from(y)
+                })
+                .collect();
+            Share { x: u8::from(x), y }
+        })
+        .collect();
+
+    for c in &mut coeffs {
+        zeroize(c);
+    }
+    Ok(shares)
+}
+
+/// Like [`shamir::combine`](super::combine).
+pub fn combine(shares: &[Share]) -> Result<Vec<u8>, ShamirError> {
+    let xs = check_shares(shares)?;
+    let mut secret = vec![0u8; shares[0].y.len()];
+    for (share, l) in shares.iter().zip(lagrange_at(&xs, Galois::zero())) {
+        for (s, y) in secret.iter_mut().zip(&share.y) {
+            *s ^= u8::from(l.mul_ct(Galois::new(*y)));
+        }
+    }
+    Ok(secret)
+}
+
+/// Overwrites `buf` with zeros in a way the compiler does not remove as a
+/// dead store.
+pub fn zeroize(buf: &mut [u8]) {
+    for b in buf.iter_mut() {
+        // SAFETY: `b` is a valid, aligned reference to a byte.
+        unsafe { std::ptr::write_volatile(b, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::{self, tests::test_rng};
+
+    #[test]
+    fn test_matches_plain() {
+        let secret = b"0123456789abcdef0123456789abcdef";
+        for (k, n) in [(1, 1), (2, 3), (5, 8)] {
+            let shares = split(secret, k, n, test_rng(10)).unwrap();
+            assert_eq!(shares, shamir::split(secret, k, n, test_rng(10)).unwrap());
+            assert_eq!(combine(&shares[n - k..]).unwrap(), secret);
+            assert_eq!(shamir::combine(&shares[..k]).unwrap(), secret);
+        }
+        assert_eq!(
+            split(secret, 3, 2, test_rng(0)),
+            Err(ShamirError::InvalidThreshold)
+        );
+        assert_eq!(combine(&[]), Err(ShamirError::NotEnoughShares));
+    }
+
+    #[test]
+    fn test_zeroize() {
+        let mut key = b"very secret".to_vec();
+        zeroize(&mut key);
+        assert!(key.iter().all(|b| *b == 0));
+    }
+}
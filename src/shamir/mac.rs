@@ -0,0 +1,184 @@
+use super::{check_shares, combine, split, ShamirError, Share};
+use crate::polyhash::poly_hash;
+
+/// A one-time key of the information-theoretic MAC on shares: the tag of
+/// `y` is `poly_hash(hash_key, y) + mask` in GF(2^64).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MacKey {
+    pub hash_key: u64,
+    pub mask: u64,
+}
+
+impl MacKey {
+    pub fn tag(&self, y: &[u8]) -> u64 {
+        poly_hash(self.hash_key, y) ^ self.mask
+    }
+}
+
+/// A share with pairwise MACs, as in Rabin and Ben-Or's verifiable secret
+/// sharing: the holder of share `x` keeps one tag for every other holder
+/// and one key to check every other holder's share.
+///
+/// A share altered by anyone without the verifier's key passes with
+/// probability at most `(len / 8 + 2) / 2^64`, whatever their computing
+/// power, so a verifier can sort out tampered shares and combine only good
+/// ones, needing only `k` of them rather than the `k + 2e` shares of
+/// [`combine_robust`](super::combine_robust) for `e` bad ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiableShare {
+    pub share: Share,
+    /// `tags[j - 1]` authenticates this share to the holder of share `j`.
+    pub tags: Vec<u64>,
+    /// `keys[i - 1]` checks the tag of share `i` presented to this holder.
+    pub keys: Vec<MacKey>,
+}
+
+impl VerifiableShare {
+    /// The tag to present along with the share to the holder of share
+    /// `verifier`.
+    pub fn tag_for(&self, verifier: u8) -> Option<u64> {
+        let j = (verifier as usize).checked_sub(1)?;
+        self.tags.get(j).copied()
+    }
+
+    /// Whether `share` carries a valid tag for this holder.
+    pub fn verify(&self, share: &Share, tag: u64) -> bool {
+        let key = (share.x as usize)
+            .checked_sub(1)
+            .and_then(|i| self.keys.get(i));
+        key.is_some_and(|key| key.tag(&share.y) == tag)
+    }
+}
+
+/// Like [`split`], with a MAC key for every ordered pair of shares drawn
+/// from `rng` as well.
+pub fn split_verifiable<R>(
+    secret: &[u8],
+    k: usize,
+    n: usize,
+    mut rng: R,
+) -> Result<Vec<VerifiableShare>, ShamirError>
+where
+    R: FnMut(&mut [u8]),
+{
+    let shares = split(secret, k, n, &mut rng)?;
+    // keys[j][i]: held by share j + 1 to check share i + 1
+    let keys: Vec<Vec<MacKey>> = (0..n)
+        .map(|_| {
+            (0..n)
+                .map(|_| {
+                    let mut b = [0u8; 16];
+                    rng(&mut b);
+                    MacKey {
+                        hash_key: u64::from_le_bytes(b[..8].try_into().unwrap()),
+                        mask: u64::from_le_bytes(b[8..].try_into().unwrap()),
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let verifiable = shares
+        .into_iter()
+        .enumerate()
+        .map(|(i, share)| VerifiableShare {
+            tags: (0..n).map(|j| keys[j][i].tag(&share.y)).collect(),
+            keys: keys[i].clone(),
+            share,
+        })
+        .collect();
+    Ok(verifiable)
+}
+
+/// Recovers the secret as the holder of `me`, from `me` and the shares of
+/// others with the tags they present to it.
+///
+/// Shares whose tags do not verify are left out and their x-coordinates
+/// returned; the rest must number at least `k`, or the combine fails with
+/// [`ShamirError::NotEnoughShares`].
+pub fn combine_verified(
+    me: &VerifiableShare,
+    others: &[(Share, u64)],
+    k: usize,
+) -> Result<(Vec<u8>, Vec<u8>), ShamirError> {
+    let mut good = vec![me.share.clone()];
+    let mut rejected = Vec::new();
+    for (share, tag) in others {
+        if share.x != me.share.x && me.verify(share, *tag) {
+            good.push(share.clone());
+        } else {
+            rejected.push(share.x);
+        }
+    }
+    check_shares(&good)?;
+    if good.len() < k {
+        return Err(ShamirError::NotEnoughShares);
+    }
+    Ok((combine(&good)?, rejected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::tests::test_rng;
+
+    fn presented(shares: &[VerifiableShare], to: u8) -> Vec<(Share, u64)> {
+        shares
+            .iter()
+            .filter(|s| s.share.x != to)
+            .map(|s| (s.share.clone(), s.tag_for(to).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_verified_combine() {
+        let secret = b"launch codes";
+        let shares = split_verifiable(secret, 2, 5, test_rng(21)).unwrap();
+        for me in &shares {
+            let others = presented(&shares, me.share.x);
+            assert_eq!(
+                combine_verified(me, &others, 2).unwrap(),
+                (secret.to_vec(), vec![])
+            );
+        }
+
+        // with three of five tampered, robust decoding would need eight
+        // shares, but the MACs leave one honest share besides our own
+        let me = &shares[0];
+        let mut others = presented(&shares, 1);
+        others[0].0.y[0] ^= 1;
+        others[1].0.y.iter_mut().for_each(|b| *b = !*b);
+        // a tag recomputed without the key does not help
+        others[2].0.y[5] ^= 0x40;
+        others[2].1 ^= 0x40;
+        assert_eq!(
+            combine_verified(me, &others, 2).unwrap(),
+            (secret.to_vec(), vec![2, 3, 4])
+        );
+        others[3].0.y[11] ^= 2;
+        assert_eq!(
+            combine_verified(me, &others, 2),
+            Err(ShamirError::NotEnoughShares)
+        );
+
+        // a tag meant for someone else is rejected
+        let mut misdirected = presented(&shares, 1);
+        misdirected[0].1 = shares[1].tag_for(3).unwrap();
+        let (_, rejected) = combine_verified(me, &misdirected, 2).unwrap();
+        assert_eq!(rejected, [2]);
+    }
+
+    #[test]
+    fn test_tags() {
+        let shares = split_verifiable(b"x", 2, 3, test_rng(22)).unwrap();
+        assert_eq!(shares[0].tags.len(), 3);
+        assert_eq!(shares[0].tag_for(0), None);
+        assert_eq!(shares[0].tag_for(4), None);
+        assert!(shares[1].verify(&shares[2].share, shares[2].tag_for(2).unwrap()));
+        assert!(!shares[1].verify(&shares[2].share, shares[2].tag_for(1).unwrap()));
+        assert_eq!(
+            split_verifiable(b"x", 4, 3, test_rng(23)),
+            Err(ShamirError::InvalidThreshold)
+        );
+    }
+}
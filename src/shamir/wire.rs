@@ -0,0 +1,200 @@
+use crate::crc::CRC_32;
+
+use super::{ShamirError, Share};
+
+const VERSION: u8 = 1;
+const FLAG_LABEL: u8 = 1;
+const FLAG_CHECKSUM: u8 = 2;
+
+/// A share with the metadata needed to recover from it later, in a compact
+/// versioned wire format.
+///
+/// The encoding is a version byte (currently 1), a flags byte (bit 0: a
+/// label follows, bit 1: a checksum ends the record), the share's `x`, the
+/// threshold, then if flagged a length byte and the label, the payload
+/// length as a little-endian `u32` and the payload, and if flagged a
+/// little-endian CRC-32 of all the preceding bytes. Parsers reject unknown
+/// versions and flags, so a later format can only add to this one under a
+/// new version number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShareRecord {
+    pub share: Share,
+    /// The number of shares needed to recover the secret, from 1 to 255.
+    pub threshold: usize,
+    /// Identifies the secret, at most 255 bytes.
+    pub label: Option<Vec<u8>>,
+    /// Whether the encoding carries a CRC-32.
+    pub checksum: bool,
+}
+
+impl ShareRecord {
+    /// A record of `share` without label, with a checksum.
+    pub fn new(share: Share, threshold: usize) -> Self {
+        ShareRecord {
+            share,
+            threshold,
+            label: None,
+            checksum: true,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ShamirError> {
+        if self.share.x == 0 || self.threshold == 0 || self.threshold > 255 {
+            return Err(ShamirError::Malformed);
+        }
+        let payload_len = u32::try_from(self.share.y.len()).map_err(|_| ShamirError::Malformed)?;
+        let mut flags = 0;
+        if self.label.is_some() {
+            flags |= FLAG_LABEL;
+        }
+        if self.checksum {
+            flags |= FLAG_CHECKSUM;
+        }
+
+        let mut out = vec![VERSION, flags, self.share.x, self.threshold as u8];
+        if let Some(label) = &self.label {
+            out.push(u8::try_from(label.len()).map_err(|_| ShamirError::Malformed)?);
+            out.extend_from_slice(label);
+        }
+        out.extend_from_slice(&payload_len.to_le_bytes());
+        out.extend_from_slice(&self.share.y);
+        if self.checksum {
+            let crc = CRC_32.checksum(&out) as u32;
+            out.extend_from_slice(&crc.to_le_bytes());
+        }
+        Ok(out)
+    }
+
+    /// Parses a record written by [`to_bytes`](ShareRecord::to_bytes), which
+    /// must span all of `bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ShamirError> {
+        let (&[version, flags, x, threshold], mut rest) = bytes
+            .split_first_chunk::<4>()
+            .ok_or(ShamirError::Malformed)?;
+        if version != VERSION {
+            return Err(ShamirError::UnsupportedVersion);
+        }
+        if flags & !(FLAG_LABEL | FLAG_CHECKSUM) != 0 || x == 0 || threshold == 0 {
+            return Err(ShamirError::Malformed);
+        }
+
+        let label = if flags & FLAG_LABEL != 0 {
+            let (&len, tail) = rest.split_first().ok_or(ShamirError::Malformed)?;
+            let (label, tail) = tail
+                .split_at_checked(len as usize)
+                .ok_or(ShamirError::Malformed)?;
+            rest = tail;
+            Some(label.to_vec())
+        } else {
+            None
+        };
+        let (len, tail) = rest
+            .split_first_chunk::<4>()
+            .ok_or(ShamirError::Malformed)?;
+        let (payload, tail) = tail
+            .split_at_checked(u32::from_le_bytes(*len) as usize)
+            .ok_or(ShamirError::Malformed)?;
+
+        let checksum = flags & FLAG_CHECKSUM != 0;
+        if checksum {
+            let crc: [u8; 4] = tail.try_into().map_err(|_| ShamirError::Malformed)?;
+            let covered = &bytes[..bytes.len() - 4];
+            if CRC_32.checksum(covered) as u32 != u32::from_le_bytes(crc) {
+                return Err(ShamirError::ChecksumMismatch);
+            }
+        } else if !tail.is_empty() {
+            return Err(ShamirError::Malformed);
+        }
+
+        Ok(ShareRecord {
+            share: Share {
+                x,
+                y: payload.to_vec(),
+            },
+            threshold: threshold as usize,
+            label,
+            checksum,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> ShareRecord {
+        ShareRecord {
+            label: Some(b"backup key".to_vec()),
+            ..ShareRecord::new(
+                Share {
+                    x: 3,
+                    y: vec![9, 8, 7, 6, 5],
+                },
+                2,
+            )
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let full = record();
+        let bytes = full.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 4 + 1 + 10 + 4 + 5 + 4);
+        assert_eq!(bytes[..4], [1, 3, 3, 2]);
+        assert_eq!(ShareRecord::from_bytes(&bytes).unwrap(), full);
+
+        let bare = ShareRecord {
+            label: None,
+            checksum: false,
+            ..full
+        };
+        let bytes = bare.to_bytes().unwrap();
+        assert_eq!(bytes, [1, 0, 3, 2, 5, 0, 0, 0, 9, 8, 7, 6, 5]);
+        assert_eq!(ShareRecord::from_bytes(&bytes).unwrap(), bare);
+    }
+
+    #[test]
+    fn test_rejects() {
+        let bytes = record().to_bytes().unwrap();
+        for pos in [6, 14, 20, bytes.len() - 1] {
+            let mut bad = bytes.clone();
+            bad[pos] ^= 0x40;
+            assert_eq!(
+                ShareRecord::from_bytes(&bad),
+                Err(ShamirError::ChecksumMismatch),
+                "byte {pos}"
+            );
+        }
+        let mut bad = bytes.clone();
+        bad[0] = 2;
+        assert_eq!(
+            ShareRecord::from_bytes(&bad),
+            Err(ShamirError::UnsupportedVersion)
+        );
+        for (pos, v) in [(1, 7), (2, 0), (3, 0)] {
+            let mut bad = bytes.clone();
+            bad[pos] = v;
+            assert_eq!(ShareRecord::from_bytes(&bad), Err(ShamirError::Malformed));
+        }
+        for len in [0, 3, 12, bytes.len() - 1] {
+            assert_eq!(
+                ShareRecord::from_bytes(&bytes[..len]),
+                Err(ShamirError::Malformed)
+            );
+        }
+        let mut long = bytes.clone();
+        long.push(0);
+        assert_eq!(ShareRecord::from_bytes(&long), Err(ShamirError::Malformed));
+
+        let bad = ShareRecord {
+            threshold: 256,
+            ..record()
+        };
+        assert_eq!(bad.to_bytes(), Err(ShamirError::Malformed));
+        let bad = ShareRecord {
+            label: Some(vec![0; 256]),
+            ..record()
+        };
+        assert_eq!(bad.to_bytes(), Err(ShamirError::Malformed));
+    }
+}
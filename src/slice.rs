@@ -0,0 +1,149 @@
+use crate::Galois;
+
+/// Returns the 256-entry multiplication table for `c`, i.e. `table[x] = c * x`.
+pub fn mul_table(c: Galois) -> [u8; 256] {
+    let mut table = [0u8; 256];
+    for (x, t) in table.iter_mut().enumerate() {
+        *t = (c * Galois(x as u8)).0;
+    }
+    table
+}
+
+/// `out[i] ^= input[i]`
+pub fn add_slice(input: &[u8], out: &mut [u8]) {
+    assert_eq!(input.len(), out.len(), "slice length mismatch");
+    for (o, i) in out.iter_mut().zip(input) {
+        *o ^= *i;
+    }
+}
+
+/// `out[i] = inputs[0][i] ^ inputs[1][i] ^ ..`, a word at a time and in a
+/// single pass over `out`.
+pub fn xor_slices<S: AsRef<[u8]>>(inputs: &[S], out: &mut [u8]) {
+    assert!(
+        inputs.iter().all(|i| i.as_ref().len() == out.len()),
+        "slice length mismatch"
+    );
+    let words = out.len() / 8 * 8;
+    for (w, o) in out[..words].chunks_exact_mut(8).enumerate() {
+        let acc = inputs.iter().fold(0u64, |acc, i| {
+            let word = &i.as_ref()[w * 8..w * 8 + 8];
+            acc ^ u64::from_ne_bytes(word.try_into().unwrap())
+        });
+        o.copy_from_slice(&acc.to_ne_bytes());
+    }
+    for (j, o) in out.iter_mut().enumerate().skip(words) {
+        *o = inputs.iter().fold(0, |acc, i| acc ^ i.as_ref()[j]);
+    }
+}
+
+/// `out[i] = c * input[i]`
+pub fn mul_slice(c: Galois, input: &[u8], out: &mut [u8]) {
+    assert_eq!(input.len(), out.len(), "slice length mismatch");
+    if c == Galois::zero() {
+        out.fill(0);
+        return;
+    }
+    if c == Galois::identity() {
+        out.copy_from_slice(input);
+        return;
+    }
+
+    let table = mul_table(c);
+    for (o, i) in out.iter_mut().zip(input) {
+        *o = table[*i as usize];
+    }
+}
+
+/// `out[i] ^= c * input[i]`
+pub fn mul_add_slice(c: Galois, input: &[u8], out: &mut [u8]) {
+    assert_eq!(input.len(), out.len(), "slice length mismatch");
+    if c == Galois::zero() {
+        return;
+    }
+    if c == Galois::identity() {
+        add_slice(input, out);
+        return;
+    }
+
+    let table = mul_table(c);
+    for (o, i) in out.iter_mut().zip(input) {
+        *o ^= table[*i as usize];
+    }
+}
+
+/// `data[i] = c * data[i]`
+pub fn scale_slice(c: Galois, data: &mut [u8]) {
+    if c == Galois::identity() {
+        return;
+    }
+
+    let table = mul_table(c);
+    for d in data.iter_mut() {
+        *d = table[*d as usize];
+    }
+}
+
+pub fn as_bytes(v: &[Galois]) -> &[u8] {
+    // SAFETY: `Galois` is `repr(transparent)` over `u8`.
+    unsafe { std::slice::from_raw_parts(v.as_ptr() as *const u8, v.len()) }
+}
+
+pub fn as_bytes_mut(v: &mut [Galois]) -> &mut [u8] {
+    // SAFETY: `Galois` is `repr(transparent)` over `u8`.
+    unsafe { std::slice::from_raw_parts_mut(v.as_mut_ptr() as *mut u8, v.len()) }
+}
+
+pub fn from_bytes(v: &[u8]) -> &[Galois] {
+    // SAFETY: `Galois` is `repr(transparent)` over `u8`, and every byte is a valid element.
+    unsafe { std::slice::from_raw_parts(v.as_ptr() as *const Galois, v.len()) }
+}
+
+pub fn from_bytes_mut(v: &mut [u8]) -> &mut [Galois] {
+    // SAFETY: `Galois` is `repr(transparent)` over `u8`, and every byte is a valid element.
+    unsafe { std::slice::from_raw_parts_mut(v.as_mut_ptr() as *mut Galois, v.len()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_add_slice() {
+        let input: Vec<u8> = (0..=255).collect();
+        for c in [0u8, 1, 2, 29, 255] {
+            let mut out = vec![7u8; 256];
+            mul_add_slice(Galois(c), &input, &mut out);
+            for (x, o) in out.iter().enumerate() {
+                assert_eq!(*o, (Galois(c) * Galois(x as u8) + Galois(7)).0);
+            }
+
+            mul_slice(Galois(c), &input, &mut out);
+            for (x, o) in out.iter().enumerate() {
+                assert_eq!(*o, (Galois(c) * Galois(x as u8)).0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_xor_slices() {
+        let inputs: Vec<Vec<u8>> = (0..3)
+            .map(|s| (0..21).map(|i| (i * 17 + s * 89) as u8).collect())
+            .collect();
+        let mut out = vec![0xffu8; 21];
+        xor_slices(&inputs, &mut out);
+        for (i, o) in out.iter().enumerate() {
+            assert_eq!(*o, inputs[0][i] ^ inputs[1][i] ^ inputs[2][i]);
+        }
+        xor_slices::<&[u8]>(&[], &mut out);
+        assert_eq!(out, vec![0; 21]);
+    }
+
+    #[test]
+    fn test_byte_views() {
+        let mut v = vec![Galois(3), Galois(200)];
+        assert_eq!(as_bytes(&v), &[3, 200]);
+        as_bytes_mut(&mut v)[0] = 9;
+        assert_eq!(from_bytes(&[9, 200]), &v[..]);
+    }
+}
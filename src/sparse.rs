@@ -0,0 +1,149 @@
+use crate::{Galois, GfMatrix};
+
+/// A sparse matrix over GF(256), stored as the nonzero entries of each row
+/// in column order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseMatrix {
+    cols: usize,
+    rows: Vec<Vec<(usize, Galois)>>,
+}
+
+impl SparseMatrix {
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        SparseMatrix {
+            cols,
+            rows: vec![Vec::new(); rows],
+        }
+    }
+
+    pub fn from_dense(m: &GfMatrix) -> Self {
+        let mut s = SparseMatrix::zero(m.rows(), m.cols());
+        for (r, row) in s.rows.iter_mut().enumerate() {
+            row.extend(
+                m.row(r)
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, v)| **v != Galois::zero())
+                    .map(|(c, v)| (c, *v)),
+            );
+        }
+        s
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The number of nonzero entries.
+    pub fn nnz(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum()
+    }
+
+    /// The nonzero entries of row `r` as `(column, value)`, in column order.
+    pub fn row(&self, r: usize) -> &[(usize, Galois)] {
+        &self.rows[r]
+    }
+
+    pub fn get(&self, r: usize, c: usize) -> Galois {
+        assert!(r < self.rows() && c < self.cols, "index out of bounds");
+        let row = &self.rows[r];
+        row.binary_search_by_key(&c, |e| e.0)
+            .map_or(Galois::zero(), |i| row[i].1)
+    }
+
+    /// Sets entry `(r, c)`, removing it when `v` is zero.
+    pub fn set(&mut self, r: usize, c: usize, v: Galois) {
+        assert!(r < self.rows() && c < self.cols, "index out of bounds");
+        let row = &mut self.rows[r];
+        match row.binary_search_by_key(&c, |e| e.0) {
+            Ok(i) if v == Galois::zero() => {
+                row.remove(i);
+            }
+            Ok(i) => row[i].1 = v,
+            Err(i) if v != Galois::zero() => row.insert(i, (c, v)),
+            Err(_) => {}
+        }
+    }
+
+    /// For each column, the rows with a nonzero entry in it.
+    pub fn column_rows(&self) -> Vec<Vec<usize>> {
+        let mut cols = vec![Vec::new(); self.cols];
+        for (r, row) in self.rows.iter().enumerate() {
+            for (c, _) in row {
+                cols[*c].push(r);
+            }
+        }
+        cols
+    }
+
+    /// Computes `self * x`.
+    pub fn mul_vec(&self, x: &[Galois]) -> Vec<Galois> {
+        assert_eq!(x.len(), self.cols, "dimension mismatch");
+        self.rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .fold(Galois::zero(), |acc, (c, v)| acc + *v * x[*c])
+            })
+            .collect()
+    }
+
+    pub fn to_dense(&self) -> GfMatrix {
+        let mut m = GfMatrix::zero(self.rows(), self.cols);
+        for (r, row) in self.rows.iter().enumerate() {
+            for (c, v) in row {
+                m[(r, *c)] = *v;
+            }
+        }
+        m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dense_roundtrip() {
+        let dense = GfMatrix::from_fn(5, 7, |r, c| {
+            Galois::new(if (r * 3 + c) % 4 == 0 {
+                (r * 7 + c + 1) as u8
+            } else {
+                0
+            })
+        });
+        let sparse = SparseMatrix::from_dense(&dense);
+        assert_eq!(
+            sparse.nnz(),
+            dense
+                .as_slice()
+                .iter()
+                .filter(|v| u8::from(**v) != 0)
+                .count()
+        );
+        assert_eq!(sparse.to_dense(), dense);
+
+        let x: Vec<Galois> = (1..=7).map(|v| Galois::new(v * 19)).collect();
+        let column = GfMatrix::from_vec(7, 1, x.clone());
+        assert_eq!(sparse.mul_vec(&x), (&dense * &column).into_vec());
+    }
+
+    #[test]
+    fn test_set() {
+        let mut m = SparseMatrix::zero(2, 4);
+        m.set(0, 3, Galois::new(9));
+        m.set(0, 1, Galois::new(4));
+        m.set(1, 2, Galois::new(5));
+        assert_eq!(m.row(0), &[(1, Galois::new(4)), (3, Galois::new(9))]);
+        assert_eq!(m.get(0, 3), Galois::new(9));
+        assert_eq!(m.column_rows(), vec![vec![], vec![0], vec![1], vec![0]]);
+
+        m.set(0, 1, Galois::zero());
+        assert_eq!(m.get(0, 1), Galois::zero());
+        assert_eq!(m.nnz(), 2);
+    }
+}
@@ -0,0 +1,223 @@
+//! GF(256) as the composite field GF((2^4)^2).
+//!
+//! The subfield GF(16) is GF(2)[y] modulo `y^4 + y + 1`, and GF(256) is
+//! GF(16)[z] modulo `z^2 + z + λ` with `λ = y^3`. An element `h z + l` is
+//! stored as the byte `h << 4 | l`. Inversion then needs only one GF(16)
+//! inversion and a few GF(16) products, which is why compact and masked
+//! AES implementations compute the S-box in this representation.
+//!
+//! The basis change to and from the crate's [`Galois`] representation is
+//! linear over GF(2) and given as 8×8 bit matrices.
+
+use std::ops::{Add, Mul};
+use std::sync::OnceLock;
+
+use crate::constants::PRIMITIVE_POLYNOMIAL;
+use crate::Galois;
+
+/// `λ` in `z^2 + z + λ`.
+pub const LAMBDA: u8 = 0x8;
+
+/// The product of two GF(16) elements, in the low nibbles, computed
+/// without branches.
+pub fn gf16_mul(a: u8, b: u8) -> u8 {
+    let (a, b) = (a & 0xf, b & 0xf);
+    let mut product = 0u8;
+    for i in 0..4 {
+        product ^= (a << i) & 0u8.wrapping_sub(b >> i & 1);
+    }
+    for i in (4..7).rev() {
+        product ^= (0b10011 << (i - 4)) & 0u8.wrapping_sub(product >> i & 1);
+    }
+    product
+}
+
+/// The inverse of a GF(16) element as `a^14`, with zero mapped to zero.
+pub fn gf16_inv(a: u8) -> u8 {
+    let a2 = gf16_mul(a, a);
+    let a4 = gf16_mul(a2, a2);
+    let a8 = gf16_mul(a4, a4);
+    gf16_mul(gf16_mul(a8, a4), a2)
+}
+
+/// An element of GF(256) in the tower representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+#[repr(transparent)]
+pub struct Tower(u8);
+
+impl Tower {
+    pub fn new(v: u8) -> Self {
+        Tower(v)
+    }
+
+    /// The element `hi z + lo`.
+    pub fn from_parts(hi: u8, lo: u8) -> Self {
+        Tower((hi & 0xf) << 4 | lo & 0xf)
+    }
+
+    pub fn zero() -> Self {
+        Tower(0)
+    }
+
+    pub fn identity() -> Self {
+        Tower(1)
+    }
+
+    /// The coefficient of `z`.
+    pub fn hi(self) -> u8 {
+        self.0 >> 4
+    }
+
+    /// The constant coefficient.
+    pub fn lo(self) -> u8 {
+        self.0 & 0xf
+    }
+
+    /// The inverse, with zero mapped to zero: `(h z + l)^-1 = (h z + h + l) / Δ`
+    /// for the norm `Δ = h^2 λ + h l + l^2` in GF(16).
+    pub fn inv(self) -> Self {
+        let (h, l) = (self.hi(), self.lo());
+        let norm = gf16_mul(gf16_mul(h, h), LAMBDA) ^ gf16_mul(h, l) ^ gf16_mul(l, l);
+        let norm_inv = gf16_inv(norm);
+        Tower::from_parts(gf16_mul(h, norm_inv), gf16_mul(h ^ l, norm_inv))
+    }
+
+    pub fn from_galois(g: Galois) -> Self {
+        Tower(apply(&basis().to_tower, u8::from(g)))
+    }
+
+    pub fn to_galois(self) -> Galois {
+        Galois::new(apply(&basis().from_tower, self.0))
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Add for Tower {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Tower(self.0 ^ rhs.0)
+    }
+}
+
+impl Mul for Tower {
+    type Output = Self;
+
+    // (a z + b)(c z + d) = (ac + ad + bc) z + (ac λ + bd), as z^2 = z + λ
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (a, b, c, d) = (self.hi(), self.lo(), rhs.hi(), rhs.lo());
+        let ac = gf16_mul(a, c);
+        Tower::from_parts(
+            ac ^ gf16_mul(a, d) ^ gf16_mul(b, c),
+            gf16_mul(ac, LAMBDA) ^ gf16_mul(b, d),
+        )
+    }
+}
+
+impl From<u8> for Tower {
+    fn from(v: u8) -> Self {
+        Tower(v)
+    }
+}
+
+impl From<Tower> for u8 {
+    fn from(t: Tower) -> Self {
+        t.0
+    }
+}
+
+struct Basis {
+    to_tower: [u8; 8],
+    from_tower: [u8; 8],
+}
+
+// The images of the crate's basis α^i are the powers of a tower root of the
+// crate's field polynomial.
+fn basis() -> &'static Basis {
+    static BASIS: OnceLock<Basis> = OnceLock::new();
+    BASIS.get_or_init(|| {
+        let powers = |g: Tower| -> [Tower; 9] {
+            let mut p = [Tower::identity(); 9];
+            for i in 1..9 {
+                p[i] = p[i - 1] * g;
+            }
+            p
+        };
+        let root = (2..=255u8)
+            .map(Tower)
+            .find(|g| {
+                let p = powers(*g);
+                (0..9)
+                    .filter(|i| PRIMITIVE_POLYNOMIAL >> i & 1 == 1)
+                    .fold(Tower::zero(), |acc, i| acc + p[i])
+                    == Tower::zero()
+            })
+            .expect("the field polynomial has a root in every GF(256)");
+        let p = powers(root);
+        let to_tower = std::array::from_fn(|i| p[i].0);
+
+        let mut table = [0u8; 256];
+        for v in 0..=255u8 {
+            table[apply(&to_tower, v) as usize] = v;
+        }
+        let from_tower = std::array::from_fn(|j| table[1 << j]);
+        Basis {
+            to_tower,
+            from_tower,
+        }
+    })
+}
+
+/// The basis change from [`Galois`] to [`Tower`] as an 8×8 matrix over
+/// GF(2), column `j` being the image of bit `j`.
+pub fn to_tower_matrix() -> [u8; 8] {
+    basis().to_tower
+}
+
+/// The inverse of [`to_tower_matrix`].
+pub fn from_tower_matrix() -> [u8; 8] {
+    basis().from_tower
+}
+
+fn apply(columns: &[u8; 8], v: u8) -> u8 {
+    (0..8)
+        .filter(|j| v >> j & 1 == 1)
+        .fold(0, |acc, j| acc ^ columns[j])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gf16() {
+        for a in 1..16u8 {
+            assert_eq!(gf16_mul(a, gf16_inv(a)), 1);
+        }
+        assert_eq!(gf16_inv(0), 0);
+        // y * y^3 = y^4 = y + 1
+        assert_eq!(gf16_mul(0b0010, 0b1000), 0b0011);
+    }
+
+    #[test]
+    fn test_isomorphism() {
+        for a in 0..=255u8 {
+            let ga = Galois::new(a);
+            let ta = Tower::from_galois(ga);
+            assert_eq!(ta.to_galois(), ga);
+            let expected = if a == 0 { ga } else { ga.inv() };
+            assert_eq!(ta.inv().to_galois(), expected);
+            for b in (0..=255u8).step_by(7) {
+                let gb = Galois::new(b);
+                let tb = Tower::from_galois(gb);
+                assert_eq!((ta * tb).to_galois(), ga * gb);
+                assert_eq!((ta + tb).to_galois(), ga + gb);
+            }
+        }
+        let (to, from) = (to_tower_matrix(), from_tower_matrix());
+        for v in 0..=255u8 {
+            assert_eq!(apply(&from, apply(&to, v)), v);
+        }
+        assert_eq!(Tower::identity().to_galois(), Galois::identity());
+    }
+}
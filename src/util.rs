@@ -0,0 +1,111 @@
+//! Number theory shared by the field and sequence constructions.
+
+// The distinct prime factors of n, by trial division and Pollard's rho.
+pub(crate) fn prime_factors(n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    let mut stack = vec![n];
+    while let Some(m) = stack.pop() {
+        if m == 1 {
+            continue;
+        }
+        if is_prime(m) {
+            if !factors.contains(&m) {
+                factors.push(m);
+            }
+            continue;
+        }
+        if let Some(p) = (2..1000).find(|&p| m.is_multiple_of(p)) {
+            stack.extend([p, m / p]);
+        } else {
+            let d = rho(m);
+            stack.extend([d, m / d]);
+        }
+    }
+    factors.sort_unstable();
+    factors
+}
+
+fn mul_mod_u64(a: u64, b: u64, n: u64) -> u64 {
+    (a as u128 * b as u128 % n as u128) as u64
+}
+
+fn pow_mod_u64(mut a: u64, mut e: u64, n: u64) -> u64 {
+    let mut result = 1 % n;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mul_mod_u64(result, a, n);
+        }
+        a = mul_mod_u64(a, a, n);
+        e >>= 1;
+    }
+    result
+}
+
+// Miller-Rabin with the bases that are deterministic below 2^64.
+fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = BASES.iter().find(|&&p| n.is_multiple_of(p)) {
+        return n == p;
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in BASES {
+        let mut x = pow_mod_u64(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod_u64(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// A nontrivial factor of the odd composite n.
+fn rho(n: u64) -> u64 {
+    for c in 1.. {
+        let f = |x: u64| ((x as u128 * x as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!()
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prime_factors() {
+        assert_eq!(
+            prime_factors(u64::MAX),
+            [3, 5, 17, 257, 641, 65537, 6_700_417]
+        );
+        assert_eq!(prime_factors((1 << 61) - 1), [(1 << 61) - 1]);
+        assert_eq!(prime_factors(1), []);
+        assert_eq!(prime_factors(256), [2]);
+        assert_eq!(prime_factors(65536), [2]);
+        assert_eq!(prime_factors(3 * 3 * 7 * 1009), [3, 7, 1009]);
+    }
+}
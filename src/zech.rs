@@ -0,0 +1,188 @@
+//! Arithmetic in the exponent representation, with Zech logarithms.
+//!
+//! A [`LogGalois`] stores the discrete logarithm `n` of a nonzero element
+//! `α^n`, so products and quotients are additions and subtractions of
+//! exponents modulo 255. Addition uses the Zech logarithm `Z(n)`, defined by
+//! `α^Z(n) = 1 + α^n`:
+//!
+//! `α^a + α^b = α^a (1 + α^(b-a)) = α^(a + Z(b - a))`
+//!
+//! which keeps a whole computation in exponents, as in some decoder inner
+//! loops, with one table lookup per sum. In characteristic 2, `Z(0)` is the
+//! logarithm of zero, and `Z` is an involution.
+
+use std::fmt;
+use std::ops::{Add, Div, Mul};
+use std::sync::OnceLock;
+
+use crate::constants::{EXP_TABLE, LOG_TABLE};
+use crate::Galois;
+
+// the stored exponent of zero
+const ZERO: u8 = 255;
+
+/// An element of GF(256) as its logarithm to the base `α = 2`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LogGalois(u8);
+
+impl LogGalois {
+    /// `α^n`, for any `n`, reduced modulo 255.
+    pub fn from_exponent(n: u32) -> Self {
+        LogGalois((n % 255) as u8)
+    }
+
+    pub fn zero() -> Self {
+        LogGalois(ZERO)
+    }
+
+    pub fn identity() -> Self {
+        LogGalois(0)
+    }
+
+    /// The exponent `n` of `α^n` in `0..255`, or `None` for zero.
+    pub fn exponent(self) -> Option<u8> {
+        (self.0 != ZERO).then_some(self.0)
+    }
+
+    pub fn to_galois(self) -> Galois {
+        match self.exponent() {
+            Some(n) => EXP_TABLE[n as usize],
+            None => Galois::zero(),
+        }
+    }
+
+    /// The inverse, panicking on zero like [`Galois::inv`].
+    pub fn inv(self) -> Self {
+        LogGalois::identity() / self
+    }
+
+    pub fn pow(self, n: u32) -> Self {
+        match self.exponent() {
+            None if n == 0 => LogGalois::identity(),
+            None => self,
+            Some(e) => LogGalois::from_exponent((e as u64 * n as u64 % 255) as u32),
+        }
+    }
+}
+
+/// The Zech logarithm `Z(n)` with `α^Z(n) = 1 + α^n`.
+pub fn zech(n: u8) -> LogGalois {
+    LogGalois(zech_table()[n as usize % 255])
+}
+
+fn zech_table() -> &'static [u8; 255] {
+    static ZECH: OnceLock<[u8; 255]> = OnceLock::new();
+    ZECH.get_or_init(|| {
+        std::array::from_fn(|n| {
+            let sum = EXP_TABLE[n] + Galois::identity();
+            LogGalois::from(sum).0
+        })
+    })
+}
+
+impl From<Galois> for LogGalois {
+    fn from(g: Galois) -> Self {
+        if g == Galois::zero() {
+            LogGalois::zero()
+        } else {
+            LogGalois(LOG_TABLE[u8::from(g) as usize])
+        }
+    }
+}
+
+impl From<LogGalois> for Galois {
+    fn from(l: LogGalois) -> Self {
+        l.to_galois()
+    }
+}
+
+impl fmt::Debug for LogGalois {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.exponent() {
+            Some(n) => write!(f, "α^{n}"),
+            None => f.write_str("0"),
+        }
+    }
+}
+
+impl Add for LogGalois {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        match (self.exponent(), rhs.exponent()) {
+            (None, _) => rhs,
+            (_, None) => self,
+            (Some(a), Some(b)) => {
+                let z = zech(((b as u32 + 255 - a as u32) % 255) as u8);
+                z * self
+            }
+        }
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Mul for LogGalois {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        match (self.exponent(), rhs.exponent()) {
+            (Some(a), Some(b)) => LogGalois::from_exponent(a as u32 + b as u32),
+            _ => LogGalois::zero(),
+        }
+    }
+}
+
+impl Div for LogGalois {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        let b = rhs.exponent().expect("divide by zero");
+        match self.exponent() {
+            Some(a) => LogGalois::from_exponent(a as u32 + 255 - b as u32),
+            None => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zech() {
+        assert_eq!(zech(0), LogGalois::zero());
+        // 1 + α = 3 = α^25
+        assert_eq!(zech(1), LogGalois::from_exponent(25));
+        for n in 1..255u8 {
+            let z = zech(n).exponent().unwrap();
+            assert_eq!(zech(z).exponent(), Some(n));
+            // Z(-n) = Z(n) - n
+            assert_eq!(
+                zech(255 - n),
+                LogGalois::from_exponent(z as u32 + 255 - n as u32)
+            );
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        for a in 0..=255u8 {
+            let (ga, la) = (Galois::new(a), LogGalois::from(Galois::new(a)));
+            assert_eq!(la.to_galois(), ga);
+            assert_eq!(la.pow(3).to_galois(), ga.exp(3));
+            if a != 0 {
+                assert_eq!(la.inv().to_galois(), ga.inv());
+            }
+            for b in 0..=255u8 {
+                let (gb, lb) = (Galois::new(b), LogGalois::from(Galois::new(b)));
+                assert_eq!((la + lb).to_galois(), ga + gb);
+                assert_eq!(Galois::from(la * lb), ga * gb);
+                if b != 0 {
+                    assert_eq!((la / lb).to_galois(), ga / gb);
+                }
+            }
+        }
+        assert_eq!(format!("{:?}", LogGalois::from(Galois::new(2))), "α^1");
+        assert_eq!(format!("{:?}", LogGalois::zero()), "0");
+    }
+}
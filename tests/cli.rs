@@ -0,0 +1,85 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn gf256_rs() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_gf256-rs"))
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gf256-rs-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn split_and_join() {
+    let dir = scratch_dir("split");
+    let input = dir.join("photo.jpg");
+    let contents: Vec<u8> = (0..100_000u32).map(|i| (i * 13 % 256) as u8).collect();
+    fs::write(&input, &contents).unwrap();
+
+    let status = gf256_rs()
+        .args(["split", "--data", "4", "--parity", "2", "--block", "4096"])
+        .arg(&input)
+        .current_dir(&dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    // any two shard files may go
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(dir.join("photo.jpg.0.shard")).unwrap();
+    fs::remove_file(dir.join("photo.jpg.5.shard")).unwrap();
+
+    let status = gf256_rs()
+        .args(["join", "photo.jpg.manifest"])
+        .current_dir(&dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(fs::read(&input).unwrap(), contents);
+
+    fs::remove_file(dir.join("photo.jpg.1.shard")).unwrap();
+    let output = gf256_rs()
+        .args(["join", "--out", "again.jpg", "photo.jpg.manifest"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn join_rejects_unsafe_names() {
+    let dir = scratch_dir("names");
+    let work = dir.join("work");
+    fs::create_dir_all(&work).unwrap();
+    for name in ["../escaped", "/tmp/gf256-rs-escaped", "sub/file", ".."] {
+        let manifest =
+            format!("gf256-lite archive 1\nname={name}\nlength=1\ndata=1\nparity=1\nblock=16\n");
+        fs::write(work.join("evil.manifest"), manifest).unwrap();
+        let output = gf256_rs()
+            .args(["join", "evil.manifest"])
+            .current_dir(&work)
+            .output()
+            .unwrap();
+        assert!(!output.status.success(), "{name}");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("not a plain file name"), "{stderr}");
+    }
+    assert!(!dir.join("escaped").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn usage_errors() {
+    for args in [&[][..], &["split", "file"], &["frobnicate", "file"]] {
+        let output = gf256_rs().args(args).output().unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).starts_with("gf256-rs: "));
+    }
+}